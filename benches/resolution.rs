@@ -0,0 +1,106 @@
+// Benchmarked components are `Send + Sync`, which the `unsync` feature doesn't support
+#[cfg(not(feature = "unsync"))]
+mod resolution {
+    use std::sync::Arc;
+
+    use criterion::{black_box, criterion_group, Criterion};
+    use dill::*;
+
+    /////////////////////////////////////////////////////////////////////////////////////////
+
+    trait Handler: Send + Sync {
+        fn handle(&self) -> usize;
+    }
+
+    #[component]
+    struct Leaf;
+
+    #[component]
+    #[scope(Singleton)]
+    struct Config {
+        #[dill(skip)]
+        retries: usize,
+    }
+
+    #[component]
+    struct Repo {
+        leaf: Arc<Leaf>,
+        config: Arc<Config>,
+    }
+
+    #[component]
+    #[interface(dyn Handler)]
+    struct MessageHandler {
+        repo: Arc<Repo>,
+        config: Arc<Config>,
+    }
+
+    impl Handler for MessageHandler {
+        fn handle(&self) -> usize {
+            self.config.retries
+        }
+    }
+
+    #[component]
+    #[interface(dyn Handler)]
+    struct AuditHandler {
+        leaf: Arc<Leaf>,
+    }
+
+    impl Handler for AuditHandler {
+        fn handle(&self) -> usize {
+            0
+        }
+    }
+
+    fn catalog() -> Catalog {
+        CatalogBuilder::new()
+            .add::<Leaf>()
+            .add::<Config>()
+            .add::<Repo>()
+            .add::<MessageHandler>()
+            .build()
+    }
+
+    /////////////////////////////////////////////////////////////////////////////////////////
+
+    fn bench_resolution(c: &mut Criterion) {
+        let cat = catalog();
+
+        c.bench_function("transient_no_deps", |b| {
+            b.iter(|| black_box(cat.get_one::<Leaf>().unwrap()))
+        });
+
+        c.bench_function("singleton_cached", |b| {
+            b.iter(|| black_box(cat.get_one::<Config>().unwrap()))
+        });
+
+        c.bench_function("transient_chain", |b| {
+            b.iter(|| black_box(cat.get_one::<MessageHandler>().unwrap()))
+        });
+
+        c.bench_function("interface_one_of", |b| {
+            b.iter(|| black_box(cat.get_one::<dyn Handler>().unwrap().handle()))
+        });
+
+        let cat = CatalogBuilder::new()
+            .add::<Leaf>()
+            .add::<Config>()
+            .add::<Repo>()
+            .add::<MessageHandler>()
+            .add::<AuditHandler>()
+            .build();
+
+        c.bench_function("interface_all_of", |b| {
+            b.iter(|| black_box(cat.get::<AllOf<dyn Handler>>().unwrap()))
+        });
+    }
+
+    criterion_group!(benches, bench_resolution);
+}
+
+#[cfg(not(feature = "unsync"))]
+criterion::criterion_main!(resolution::benches);
+
+#[cfg(feature = "unsync")]
+fn main() {}
@@ -0,0 +1,40 @@
+use std::{env, process::Command};
+
+/// Captures the version of the compiler and the features that change the layout of the types
+/// for the plugins feature, as the plugins have to be built by the same compiler and with the same
+/// features as the application, see `CatalogBuilder::load_plugin()`
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_PLUGINS").is_none() {
+        return;
+    }
+
+    let mut features = vec![if env::var_os("CARGO_FEATURE_UNSYNC").is_some() {
+        "unsync"
+    } else {
+        "sync"
+    }];
+    for (var, feature) in [
+        ("CARGO_FEATURE_CONFIG", "config"),
+        ("CARGO_FEATURE_PARKING_LOT", "parking-lot"),
+    ] {
+        if env::var_os(var).is_some() {
+            features.push(feature);
+        }
+    }
+    println!(
+        "cargo:rustc-env=DILL_LAYOUT_FEATURES={}",
+        features.join(".")
+    );
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_default();
+
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    println!("cargo:rustc-env=DILL_RUSTC_VERSION={}", version.trim());
+}
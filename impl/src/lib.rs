@@ -2,356 +2,3381 @@ extern crate proc_macro;
 
 use darling::FromMeta;
 use proc_macro::TokenStream;
-use quote::{format_ident, quote, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use syn;
+use syn::spanned::Spanned;
 
-#[derive(FromMeta, Debug)]
+#[derive(FromMeta, Debug, Default)]
 struct ComponentOptions {
     #[darling(default)]
     scope: Option<syn::Path>,
+    #[darling(default)]
+    tags: StringList,
+    #[darling(default)]
+    profiles: StringList,
+    #[darling(default)]
+    eager: bool,
+    #[darling(default)]
+    register: bool,
+    #[darling(default)]
+    priority: i32,
+    #[darling(default)]
+    after_build: Option<syn::Ident>,
+    #[darling(default)]
+    validate: bool,
+    #[darling(default)]
+    ctor: Option<syn::Ident>,
+}
+
+/// Options of a component argument specified via `#[dill(...)]` attribute,
+/// e.g. `#[dill(named = "foo", lazy)]`
+#[derive(FromMeta, Debug, Default)]
+struct ArgOptions {
+    #[darling(default)]
+    skip: bool,
+    #[darling(default)]
+    arg: bool,
+    #[darling(default)]
+    named: Option<String>,
+    #[darling(default)]
+    lazy: bool,
+    #[darling(default)]
+    concrete: Option<String>,
+    #[darling(default)]
+    from: Option<String>,
+    #[darling(default)]
+    validate: Option<syn::Path>,
+    #[darling(default)]
+    qualifier: Option<syn::Path>,
+}
+
+/// Options of an enum component specified via `#[dill(...)]` attribute,
+/// e.g. `#[dill(variant_selector = "select_storage")]`
+#[derive(FromMeta, Debug, Default)]
+struct EnumOptions {
+    #[darling(default)]
+    variant_selector: Option<String>,
+}
+
+/// List of string literals, e.g. `tags("foo", "bar")`
+#[derive(Debug, Default)]
+struct StringList(Vec<String>);
+
+impl FromMeta for StringList {
+    fn from_list(items: &[syn::NestedMeta]) -> darling::Result<Self> {
+        items
+            .iter()
+            .map(|item| match item {
+                syn::NestedMeta::Lit(syn::Lit::Str(s)) => Ok(s.value()),
+                _ => Err(darling::Error::unexpected_type("non-string literal").with_span(item)),
+            })
+            .collect::<darling::Result<Vec<_>>>()
+            .map(StringList)
+    }
+}
+
+/// Arguments of the `#[component]` attribute: an optional visibility of the generated builder
+/// followed by [`ComponentOptions`], e.g. `#[component(pub, tags("foo"))]`
+struct ComponentArgs {
+    vis: syn::Visibility,
+    options: ComponentOptions,
+}
+
+impl syn::parse::Parse for ComponentArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let vis: syn::Visibility = input.parse()?;
+        if !matches!(vis, syn::Visibility::Inherited) && !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+        }
+
+        let metas: Vec<syn::NestedMeta> = syn::punctuated::Punctuated::<
+            syn::NestedMeta,
+            syn::Token![,],
+        >::parse_terminated(input)?
+        .into_iter()
+        .collect();
+
+        let options = ComponentOptions::from_list(&metas)
+            .map_err(|e| syn::Error::new(input.span(), e.to_string()))?;
+
+        Ok(Self { vis, options })
+    }
+}
+
+/// Generates a builder for a component from a struct definition or from an impl block containing
+/// a `new()` function.
+///
+/// Arguments (struct fields or `new()` parameters) are resolved from the catalog depending on their type:
+/// - `Arc<T>` - resolves `OneOf<T>`
+/// - `&T` - resolves `OneOf<T>` and passes the instance by reference, which is kept alive (and
+///   cached according to its scope) the same way as `Arc<T>` for the duration of the constructor call
+/// - `Box<T>` - resolves `OneOf<T>` and moves a clone of the instance into a new box (requires `T: Clone`)
+/// - `T` - resolves `OneOf<T>` and passes a clone of the instance, which applies to any type not
+///   listed here, e.g. arrays, tuples or `Buffer<1024>`. Types that are not `Clone` are resolved
+///   via `Owned<T>` instead, i.e. a fresh instance is moved into the argument
+/// - `Vec<Arc<T>>` - resolves `AllOf<T>`, i.e. all implementations of the interface
+/// - `HashMap<String, Arc<T>>` - resolves `AllOfNamed<T>`, i.e. all implementations bound under
+///   a name, keyed by that name
+/// - `Option<Arc<T>>` - resolves `Maybe<OneOf<T>>`, i.e. `None` if no implementations were registered
+/// - `Lazy<Arc<T>>` - defers resolving `OneOf<T>` until `Lazy::get()` is called
+/// - `Factory<T>` - resolves `OneOf<T>` upon every `Factory::create()` call, e.g. to build
+///   a fresh `Transient` instance per unit of work
+/// - `Weak<T>` - resolves `OneOf<T>` and downgrades the instance, e.g. for a child component to
+///   refer to a `Singleton` parent without a reference cycle (a `Transient` instance is dropped
+///   right away, as nothing else holds it)
+/// - `Catalog` and `Arc<Catalog>` - receives a handle to the resolving catalog, e.g. for components
+///   acting as service locators, see `Catalog::detached()`
+/// - `FromContext<V>` - receives the value attached to the resolving catalog handle via
+///   `Catalog::with()`, e.g. the authenticated principal of a request
+/// - `ComponentContext` - receives the identity of the component being constructed, e.g. to
+///   name its tracing spans
+///
+/// Trait objects keep their bounds, e.g. `Arc<dyn Store + Send + Sync>` and
+/// `&(dyn Store + Send + Sync)` resolve `OneOf<dyn Store + Send + Sync>`. Note that this is a
+/// different type than `dyn Store`, so the implementations have to be bound to it as such,
+/// e.g. via `#[interface(dyn Store + Send + Sync)]`, which allows injecting the traits that
+/// don't have `Send + Sync` supertraits.
+///
+/// `Box<dyn Trait>` arguments resolve `Owned<dyn Trait>`, i.e. a fresh instance moved into the box,
+/// which requires the implementation to be `Transient` - use `Arc<dyn Trait>` for shared instances.
+///
+/// Components must be `Send + Sync` - violating this is reported at the component definition.
+/// With the `unsync` feature of `dill` this requirement is dropped and the shared instances are
+/// `Rc<T>` instead of `Arc<T>`.
+///
+/// The scope is selected via `#[scope(Singleton)]` and defaults to `Transient`. Scopes that take
+/// parameters receive them as arguments of their `new()` constructor, e.g.
+/// `#[scope(Cached(Duration::from_secs(300)))]`, while generic scopes take their parameters
+/// as usual, e.g. `#[scope(Keyed<TenantId>)]`. Applications can override the scope at
+/// registration time via `with_scope()` of the generated builder or
+/// `CatalogBuilder::add_with_scope()`.
+///
+/// Components marked with `#[component(profiles("test", "dev"))]` are registered only when one
+/// of the profiles is active, see `CatalogBuilder::with_profile()`.
+///
+/// Components marked with `#[component(register)]` are discovered at link time and registered
+/// via `CatalogBuilder::auto_register()`, which requires the `auto-register` feature of `dill`.
+///
+/// Components marked with `#[component(eager)]` are instantiated when the catalog is built
+/// instead of upon first use, see `CatalogBuilder::try_build()`.
+///
+/// When multiple implementations of an interface are registered, the one marked with the highest
+/// `#[component(priority = 10)]` is resolved via `OneOf`, see `CatalogBuilder::add_with_priority()`.
+/// Priority defaults to `0`.
+///
+/// Components marked with `#[component(after_build = "init")]` have the named method invoked
+/// on every new instance once all dependencies are injected, before the instance is cached by the
+/// scope or handed out, e.g. to subscribe it to an injected event bus. The method takes `&self`
+/// or `&mut self` and the resolving catalog, returning `Result<(), InjectionError>`.
+///
+/// Components marked with `#[component(validate)]` implement `dill::Validate`, which checks the
+/// invariants of every new instance after the `after_build` hook, failing the resolution with
+/// `InjectionError::Validation` instead of handing out an invalid instance.
+///
+/// The default conversion of an owned argument can be replaced by annotating it with
+/// `#[inject(with = path::to::fn)]`, where the function takes `Arc<U>` resolved from the catalog
+/// and returns the argument type. Such arguments don't require `Clone` and therefore don't get
+/// the `with_<arg>(val)` setter.
+///
+/// Every argument also gets the `with_<arg>_value(val)` setter, which hands the value to the first
+/// instance built and fails the subsequent builds with `InjectionError::ValueConsumed`, so that
+/// the values that are not `Clone` can be supplied to the components built once, e.g. `Singleton`s.
+/// This includes the arguments injected via `#[inject(with)]` and the owned trait objects, which
+/// don't get the `with_<arg>(val)` setter, while the setter of a value that is not `Clone`
+/// behaves the same as `with_<arg>_value(val)`.
+///
+/// The `with_<arg>(val)` setters of the value arguments accept `impl Into<T>`, e.g.
+/// `.with_host("localhost")` for a `String` argument or `.with_port(8080)` for a newtype
+/// implementing `From<u16>`. Numbers, `bool`, `char` and smart pointers are taken as is, so that
+/// the literals are not ambiguous and the unsized coercion applies, e.g. `.with_db(Arc::new(PgDb))`
+/// for an `Arc<dyn Db>` argument.
+///
+/// Values of multiple arguments can be applied at once via `with_overrides()` of the builder, which
+/// takes the generated `<Builder>Overrides` struct with an `Option` field per argument that has
+/// the `with_<arg>(val)` setter, e.g.
+/// `HttpServerBuilder::new().with_overrides(HttpServerBuilderOverrides { port: Some(8080), ..Default::default() })`.
+/// The struct is `Clone` as long as all the values are. Generic components don't get the struct.
+///
+/// The `with_<arg>` setters are also implemented for the `Registration` handle returned by
+/// `CatalogBuilder::add()` via the generated `<Type>Registration` trait, which has to be in scope,
+/// e.g. `cat.add::<HttpServer>().with_port(8080)`. Generic components don't get the trait.
+///
+/// An impl block with multiple constructors can select the one used by the builder via
+/// `#[component(ctor = "with_config")]`, in which case `new()` doesn't have to be defined.
+///
+/// Constructors can be fallible by returning `Result<Self, E>`, in which case the error is
+/// propagated as `InjectionError::ConstructorFailed`.
+///
+/// Constructors can also return the shared instance, i.e. `Arc<Self>` (or `Result<Arc<Self>, E>`),
+/// which is then handed out as is instead of being wrapped into a new `Arc`, e.g. to keep a weak
+/// reference to itself created via `Arc::new_cyclic()`. Such components can't have
+/// the `after_build` hook, as it may require `&mut self`, and `build_standalone()` of their
+/// builders returns an `Arc<Self>`.
+///
+/// Components with `async fn new()` can only be resolved via `Catalog::get_async()`, which
+/// resolves the whole dependency chain asynchronously. Synchronous resolution of such components
+/// fails with `InjectionError::AsyncConstructor`.
+///
+/// Arguments annotated with `#[named("...")]` are resolved from the binding registered under
+/// the specified name, see `CatalogBuilder::bind_named()`.
+///
+/// Arguments annotated with `#[dill::default]` fall back to `Default::default()` when the dependency
+/// is not registered, while `#[dill::default = expr]` falls back to the specified expression.
+///
+/// Arguments also accept the `#[dill(...)]` attribute with the following options:
+/// - `skip` - initializes the argument with `Default::default()` instead of resolving it, e.g. for
+///   the internal state of a struct component like a cache or a counter
+/// - `named = "..."` - same as `#[named("...")]`
+/// - `qualifier = Q` - resolves the argument from the binding qualified by the marker type `Q`
+///   (see `dill::Qualifier` and `CatalogBuilder::bind_qualified()`), e.g.
+///   `#[dill(qualifier = Primary)] db: Arc<dyn Db>`
+/// - `lazy` - on a `Lazy<T>` argument resolves `T` upon the first `Lazy::get()` call as if it was
+///   the argument type, e.g. `Lazy<Vec<Arc<dyn Trait>>>` or `Lazy<Option<Arc<T>>>`
+/// - `concrete = "..."` - resolves the argument of an `impl Trait` type or of a type depending on
+///   the generic parameters of the constructor as the specified type, e.g.
+///   `#[dill(concrete = "Arc<PgDb>")] db: Arc<impl Db>`, leaving the compiler to infer
+///   the generic arguments of the constructor call. Such arguments are rejected otherwise,
+///   as the type to resolve them as is unknown.
+/// - `from = "..."` - resolves the specified type and converts a clone of it into the argument
+///   type via `TryFrom` (and thus via `From`), e.g. `#[dill(from = "AppConfig")] db: DbConfig`
+///   to inject a narrowed view of a larger config. Conversion errors are propagated as
+///   `InjectionError::ConstructorFailed` of the argument type.
+/// - `arg` - excludes the argument from the resolution, taking its value from the runtime arguments
+///   passed via `Catalog::get_with()` instead, e.g. `#[dill(arg)] order_id: u64` of a transient
+///   component built per request (a.k.a. assisted injection). Values are passed as a tuple
+///   of all such arguments in the order of declaration.
+/// - `validate = "..."` - checks the resolved (or overridden) value before the constructor is
+///   called via the specified function, which takes a reference to the value and returns either
+///   `bool` or `Result<(), E>` with a displayable error, e.g.
+///   `#[dill(validate = "is_valid_port")] port: u16`. Failures are reported as
+///   `InjectionError::Validation` naming the component and the argument.
+///
+/// A component can be bound to multiple interfaces upon registration via `#[interface(dyn Trait)]`
+/// attributes, which is equivalent to calling `CatalogBuilder::bind()` for every interface.
+/// All bindings share the same builder and thus respect the scope of the component.
+///
+/// Generic components are supported - every concrete instantiation is registered separately,
+/// e.g. `cat.add::<Repo<User>>()`, or instantiated on demand for every type argument by
+/// implementing `dill::OpenGeneric` for the interface. Every instantiation gets its own builder
+/// and thus its own scope, so e.g. a `#[scope(Singleton)]` `Cache<T>` caches one instance
+/// of `Cache<User>` and another one of `Cache<Order>`.
+///
+/// Enums are constructed by picking one of the variants and resolving its fields the same way
+/// as the fields of a struct, e.g. for strategies selected by the configuration. The macro
+/// generates the `<Enum>Variant` enum with the same fieldless variants, which is either resolved
+/// from the catalog (e.g. registered via `CatalogBuilder::add_value()`) or returned by the function
+/// specified via `#[dill(variant_selector = "select")]` on the enum, which takes `&Catalog` and
+/// returns `Result<<Enum>Variant, InjectionError>`. Only the fields of the picked variant are
+/// resolved, so the dependencies of other variants don't have to be registered. Setters of the
+/// builder are prefixed with the variant name, e.g. `with_local_dir()` for the `dir` field
+/// of the `Local` variant, while the fields of tuple variants are named by their index,
+/// e.g. `with_s3_0()`.
+///
+/// Types defined in other crates can be constructed by annotating a free function returning
+/// the type, e.g. `fn http_client(config: Arc<Config>) -> Client`, which generates
+/// `HttpClientBuilder` (named after the function) that resolves the function arguments the same
+/// way as for `new()`. Since such types can't implement `BuilderLike`, the builder is registered
+/// explicitly via `cat.add_builder(HttpClientBuilder::new())`.
+#[proc_macro_attribute]
+pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(item as syn::Item);
+    let args = syn::parse_macro_input!(attr as ComponentArgs);
+    let gen = match ast {
+        syn::Item::Struct(struct_ast) => component_from_struct(args.options, struct_ast),
+        syn::Item::Enum(enum_ast) => component_from_enum(args.options, enum_ast),
+        syn::Item::Impl(impl_ast) => component_from_impl(args.vis, args.options, impl_ast),
+        syn::Item::Fn(fn_ast) => component_from_fn(args.options, fn_ast),
+        ast => Err(syn::Error::new_spanned(
+            ast,
+            "The #[component] macro can only be used on struct or enum definiton, an impl block or \
+            a function",
+        )),
+    };
+    gen.unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+#[proc_macro_attribute]
+pub fn scope(_args: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+/// On a trait definition generates the shorthands for resolving its implementations, e.g. for
+/// `#[interface] trait UserRepo` the `UserRepoRef` alias of `Arc<dyn UserRepo>` and
+/// the `UserRepoCatalogExt` trait implemented for `Catalog` with the `get_user_repo()` method,
+/// which is equivalent to `cat.get_one::<dyn UserRepo>()`. The extension trait has to be in scope
+/// to call the method. Generic traits are not supported.
+///
+/// Note that the alias can't be used as a component argument type, as the macro can't see through
+/// it - arguments have to be declared as `Arc<dyn UserRepo>`.
+///
+/// On a component `#[interface(dyn Trait)]` binds it to the specified interface, see
+/// `#[component]`.
+#[proc_macro_attribute]
+pub fn interface(args: TokenStream, item: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return item;
+    }
+    match syn::parse_macro_input!(item as syn::Item) {
+        syn::Item::Trait(trait_ast) => interface_from_trait(trait_ast),
+        ast => Err(syn::Error::new_spanned(
+            ast,
+            "The #[interface] macro without arguments can only be used on a trait definition",
+        )),
+    }
+    .unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn interface_from_trait(ast: syn::ItemTrait) -> syn::Result<TokenStream> {
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.generics,
+            "#[interface] is not supported on generic traits",
+        ));
+    }
+
+    let vis = &ast.vis;
+    let ident = &ast.ident;
+    let ref_name = format_ident!("{}Ref", ident);
+    let ext_name = format_ident!("{}CatalogExt", ident);
+    let getter = format_ident!("get_{}", to_snake_case(&ident.to_string()));
+    let ref_doc = format!("Shared pointer to an implementation of [`{}`]", ident);
+    let getter_doc = format!("Resolves the implementation of [`{}`]", ident);
+    let spy = implement_spy(&ast);
+
+    let gen = quote! {
+        #ast
+
+        #spy
+
+        #[doc = #ref_doc]
+        #[allow(dead_code)]
+        #vis type #ref_name = ::dill::Shared<dyn #ident>;
+
+        /// Shorthands for resolving the interface available on `Catalog`
+        #[allow(dead_code)]
+        #vis trait #ext_name {
+            #[doc = #getter_doc]
+            fn #getter(&self) -> Result<#ref_name, ::dill::InjectionError>;
+        }
+
+        impl #ext_name for ::dill::Catalog {
+            fn #getter(&self) -> Result<#ref_name, ::dill::InjectionError> {
+                self.get_one::<dyn #ident>()
+            }
+        }
+    };
+
+    Ok(gen.into())
+}
+
+#[cfg(not(feature = "testing"))]
+fn implement_spy(_ast: &syn::ItemTrait) -> proc_macro2::TokenStream {
+    proc_macro2::TokenStream::new()
+}
+
+/// Generates the proxy that records the calls and delegates them to the real implementation,
+/// see `CatalogBuilder::spy()`. Traits that can't be proxied silently get no proxy, as they are
+/// valid interfaces otherwise.
+#[cfg(feature = "testing")]
+fn implement_spy(ast: &syn::ItemTrait) -> proc_macro2::TokenStream {
+    let is_sized_only = |sig: &syn::Signature| {
+        sig.generics.where_clause.as_ref().map_or(false, |w| {
+            w.predicates.iter().any(|p| match p {
+                syn::WherePredicate::Type(t) => {
+                    matches!(&t.bounded_ty, syn::Type::Path(p) if p.path.is_ident("Self"))
+                        && t.bounds.iter().any(|b| match b {
+                            syn::TypeParamBound::Trait(t) => t.path.is_ident("Sized"),
+                            _ => false,
+                        })
+                }
+                _ => false,
+            })
+        })
+    };
+
+    // The proxy can only implement the marker supertraits
+    for bound in &ast.supertraits {
+        if let syn::TypeParamBound::Trait(t) = bound {
+            let name = t.path.segments.last().unwrap().ident.to_string();
+            if !["Send", "Sync", "MaybeSendSync"].contains(&name.as_str()) {
+                return proc_macro2::TokenStream::new();
+            }
+        }
+    }
+
+    let mut methods = Vec::new();
+    for item in &ast.items {
+        let method = match item {
+            syn::TraitItem::Method(method) => method,
+            // Associated types and constants
+            _ => return proc_macro2::TokenStream::new(),
+        };
+
+        // Methods excluded from the trait object
+        if is_sized_only(&method.sig) {
+            if method.default.is_some() {
+                continue;
+            }
+            return proc_macro2::TokenStream::new();
+        }
+
+        match method.sig.receiver() {
+            Some(syn::FnArg::Receiver(r)) if r.reference.is_some() && r.mutability.is_none() => {}
+            _ => return proc_macro2::TokenStream::new(),
+        }
+        if method.sig.asyncness.is_some() {
+            return proc_macro2::TokenStream::new();
+        }
+
+        let mut sig = method.sig.clone();
+        let mut args = Vec::new();
+        for (i, arg) in sig.inputs.iter_mut().enumerate() {
+            if let syn::FnArg::Typed(arg) = arg {
+                let name = format_ident!("arg{}", i);
+                arg.pat = Box::new(syn::parse_quote!(#name));
+                args.push(name);
+            }
+        }
+
+        let ident = &sig.ident;
+        let method_name = ident.to_string();
+        let call = if sig.unsafety.is_some() {
+            quote! { unsafe { self.inner.#ident(#(#args),*) } }
+        } else {
+            quote! { self.inner.#ident(#(#args),*) }
+        };
+        methods.push(quote! {
+            #sig {
+                self.recorder.record(#method_name);
+                #call
+            }
+        });
+    }
+
+    let vis = &ast.vis;
+    let ident = &ast.ident;
+    let spy_name = format_ident!("{}Spy", ident);
+
+    quote! {
+        #[doc(hidden)]
+        #vis struct #spy_name {
+            inner: ::dill::Shared<dyn #ident>,
+            recorder: ::dill::CallRecorder,
+        }
+
+        impl #ident for #spy_name {
+            #(#methods)*
+        }
+
+        impl ::dill::Spyable for dyn #ident {
+            fn spy(
+                inner: ::dill::Shared<Self>,
+                recorder: ::dill::CallRecorder,
+            ) -> ::dill::Shared<Self> {
+                ::dill::Shared::new(#spy_name { inner, recorder })
+            }
+        }
+    }
+}
+
+/// Declares a catalog of the listed components, checking at compile time that every argument
+/// of each component is satisfiable by the other entries of the list, e.g.
+/// `catalog! { MyService, PgDb as dyn Db }`.
+///
+/// Entries are either component types, which are registered via `CatalogBuilder::add()`, or
+/// `Impl as dyn Trait` pairs, which register the component bound only to the listed interfaces
+/// (instead of its `#[interface]` attributes). The same component can be listed several times to
+/// bind it to multiple interfaces.
+///
+/// Listed types must implement `BuilderLike`. Arguments resolved by name (see `#[named("...")]`),
+/// the ones with a default value and collections of implementations (e.g. `Vec<Arc<T>>`) are not
+/// checked, so such dependencies can be registered afterwards on the returned `CatalogBuilder`.
+/// An unsatisfied dependency is reported as an unimplemented `Provides<T>` trait at the entry
+/// of the component that requires it.
+#[proc_macro]
+pub fn catalog(input: TokenStream) -> TokenStream {
+    let entries = syn::parse_macro_input!(input with CatalogEntries::parse_terminated);
+
+    // Components along with the interfaces they are bound to, in the order of declaration
+    let mut components: Vec<(&syn::Type, Vec<&syn::Type>)> = Vec::new();
+    let mut provided: Vec<&syn::Type> = Vec::new();
+    let key = |typ: &syn::Type| typ.to_token_stream().to_string();
+
+    for entry in entries.iter() {
+        let pos = match components
+            .iter()
+            .position(|(t, _)| key(t) == key(&entry.typ))
+        {
+            Some(pos) => pos,
+            None => {
+                components.push((&entry.typ, Vec::new()));
+                components.len() - 1
+            }
+        };
+        for typ in std::iter::once(&entry.typ).chain(entry.iface.as_ref()) {
+            if !provided.iter().any(|p| key(p) == key(typ)) {
+                provided.push(typ);
+            }
+        }
+        if let Some(iface) = &entry.iface {
+            components[pos].1.push(iface);
+        }
+    }
+
+    let checks = components.iter().map(|(typ, _)| {
+        quote_spanned! { typ.span() =>
+            __dill_check_dependencies::<<#typ as ::dill::BuilderLike>::Builder>();
+        }
+    });
+
+    let registrations = components.iter().map(|(typ, ifaces)| {
+        if ifaces.is_empty() {
+            quote! { cat.add::<#typ>(); }
+        } else {
+            quote! {
+                cat.add_builder(<#typ as ::dill::BuilderLike>::builder());
+                #( cat.bind::<#ifaces, #typ>(); )*
+            }
+        }
+    });
+
+    let gen = quote! {
+        {
+            struct __DillCatalog;
+
+            #( impl ::dill::Provides<#provided> for __DillCatalog {} )*
+            impl ::dill::Provides<::dill::ShutdownToken> for __DillCatalog {}
+
+            fn __dill_check_dependencies<B: ::dill::StaticDependencies<__DillCatalog>>() {}
+
+            #[allow(dead_code)]
+            fn __dill_check_all() {
+                #( #checks )*
+            }
+
+            let mut cat = ::dill::CatalogBuilder::new();
+            #( #registrations )*
+            cat
+        }
+    };
+
+    gen.into()
+}
+
+type CatalogEntries = syn::punctuated::Punctuated<CatalogEntry, syn::Token![,]>;
+
+/// Entry of the `catalog!` macro: a component type optionally bound to an interface,
+/// e.g. `PgDb as dyn Db`
+struct CatalogEntry {
+    typ: syn::Type,
+    iface: Option<syn::Type>,
+}
+
+impl syn::parse::Parse for CatalogEntry {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let typ = input.parse()?;
+        let iface = if input.peek(syn::Token![as]) {
+            input.parse::<syn::Token![as]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { typ, iface })
+    }
+}
+
+/// Generates a builder for a struct, similarly to `#[component]`, but without re-emitting the struct definition.
+///
+/// The scope can be specified via `#[builder(scope = Singleton)]` attribute.
+#[proc_macro_derive(Builder, attributes(builder, inject, named, dill))]
+pub fn derive_builder(item: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(item as syn::ItemStruct);
+    derive_builder_impl(ast).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn derive_builder_impl(ast: syn::ItemStruct) -> syn::Result<TokenStream> {
+    let impl_name = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let impl_type = syn::parse2(quote! { #impl_name #ty_generics }).unwrap();
+
+    let args = get_struct_args(&ast)?;
+
+    let explicit_scope = get_builder_scope(&ast.attrs)?.map(ScopeSpec::from);
+    let scope_was_explicit = explicit_scope.is_some();
+    let scope = explicit_scope.unwrap_or_else(ScopeSpec::transient);
+
+    implement_builder(
+        &ast.vis,
+        &impl_type,
+        &ast.attrs,
+        &ast.generics,
+        scope,
+        scope_was_explicit,
+        &ComponentOptions::default(),
+        Vec::new(),
+        args,
+        CtorKind::Fields,
+    )
+}
+
+/// Implements `Bundle` for a struct, resolving all of its fields at once via `Catalog::get_bundle()`,
+/// e.g. for a handler that needs many services. Fields are resolved the same way as the arguments
+/// of a `#[component]`, except for the references and `#[dill::default]`.
+#[proc_macro_derive(Inject, attributes(inject, named, dill))]
+pub fn derive_inject(item: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(item as syn::ItemStruct);
+    derive_inject_impl(ast).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn derive_inject_impl(ast: syn::ItemStruct) -> syn::Result<TokenStream> {
+    let impl_name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut field_names = Vec::new();
+    let mut field_values = Vec::new();
+    for arg in get_struct_args(&ast)? {
+        if is_reference(&arg.typ) {
+            return Err(syn::Error::new_spanned(
+                &arg.typ,
+                "Reference fields are not supported in bundles",
+            ));
+        }
+        if let Some(default) = &arg.default {
+            return Err(syn::Error::new_spanned(
+                default,
+                "#[dill::default] attribute is not supported in bundles",
+            ));
+        }
+        if let Some(validate) = &arg.validate {
+            return Err(syn::Error::new_spanned(
+                validate,
+                "#[dill(validate)] attribute is not supported in bundles",
+            ));
+        }
+        if arg.runtime {
+            return Err(syn::Error::new(
+                arg.name.span(),
+                "#[dill(arg)] attribute is not supported in bundles",
+            ));
+        }
+        let value = if arg.skip {
+            quote! { ::std::default::Default::default() }
+        } else {
+            implement_arg(&arg)?.resolve
+        };
+        field_names.push(arg.name);
+        field_values.push(value);
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::dill::Bundle for #impl_name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn resolve(cat: &::dill::Catalog) -> Result<Self, ::dill::InjectionError> {
+                Ok(Self {
+                    #( #field_names: #field_values, )*
+                })
+            }
+        }
+    }
+    .into())
+}
+
+/// Generates a variant of a free function that resolves its parameters annotated with `#[dill]`
+/// from the catalog, e.g. for the CLI subcommands and the job runners that are functions rather
+/// than components.
+///
+/// The variant is named after the function with the `_injected` suffix and takes the catalog
+/// followed by the remaining parameters in their original order, e.g.
+/// `#[dill::inject] fn handle(req: Request, #[dill] svc: Arc<dyn Service>) -> Response`
+/// generates `handle_injected(cat: &Catalog, req: Request) -> Result<Response, InjectionError>`.
+///
+/// Injected parameters are resolved the same way as the arguments of a `#[component]`
+/// (including the `#[dill(...)]` options), except for `#[dill::default]` and
+/// `#[dill(validate)]`. Variants of `async` functions resolve the parameters asynchronously.
+#[proc_macro_attribute]
+pub fn inject(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(item as syn::ItemFn);
+    if !attr.is_empty() {
+        return syn::Error::new_spanned(
+            proc_macro2::TokenStream::from(attr),
+            "#[dill::inject] doesn't accept any options",
+        )
+        .to_compile_error()
+        .into();
+    }
+    inject_fn(ast).unwrap_or_else(|e| e.to_compile_error().into())
+}
+
+fn inject_fn(mut ast: syn::ItemFn) -> syn::Result<TokenStream> {
+    if !ast.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.sig.generics,
+            "#[dill::inject] is not supported on generic functions",
+        ));
+    }
+
+    let is_async = ast.sig.asyncness.is_some();
+    let mut params = Vec::new();
+    let mut resolve = Vec::new();
+    let mut call_args = Vec::new();
+    for input in ast.sig.inputs.iter_mut() {
+        let input = match input {
+            syn::FnArg::Typed(input) => input,
+            syn::FnArg::Receiver(recv) => {
+                return Err(syn::Error::new_spanned(
+                    recv,
+                    "#[dill::inject] is only supported on free functions",
+                ))
+            }
+        };
+        let name = match input.pat.as_ref() {
+            syn::Pat::Ident(ident) => ident.ident.clone(),
+            pat => {
+                return Err(syn::Error::new_spanned(
+                    pat,
+                    format!(
+                        "Unexpected format of arguments in {}() function, expected an identifier",
+                        ast.sig.ident
+                    ),
+                ))
+            }
+        };
+
+        if !input.attrs.iter().any(is_arg_attr) {
+            let typ = &input.ty;
+            params.push(quote! { #name: #typ });
+            call_args.push(quote! { #name });
+            continue;
+        }
+
+        let arg = get_arg(name, input.ty.as_ref().clone(), &input.attrs, &[])?;
+        strip_arg_attrs(&mut input.attrs);
+        if let Some(default) = &arg.default {
+            return Err(syn::Error::new_spanned(
+                default,
+                "#[dill::default] attribute is not supported on injected parameters",
+            ));
+        }
+        if let Some(validate) = &arg.validate {
+            return Err(syn::Error::new_spanned(
+                validate,
+                "#[dill(validate)] attribute is not supported on injected parameters",
+            ));
+        }
+        // Parameters without the attributes are already passed by the caller
+        if arg.runtime {
+            return Err(syn::Error::new(
+                arg.name.span(),
+                "#[dill(arg)] attribute is not supported on injected parameters",
+            ));
+        }
+
+        let name = &arg.name;
+        if arg.skip {
+            resolve.push(quote! { let #name = ::std::default::Default::default(); });
+            call_args.push(quote! { #name });
+        } else {
+            let arg_impl = implement_arg(&arg)?;
+            let value = if is_async {
+                arg_impl.resolve_async
+            } else {
+                arg_impl.resolve
+            };
+            resolve.push(quote! { let #name = #value; });
+            call_args.push(arg_impl.provide_dependency);
+        }
+    }
+
+    let vis = &ast.vis;
+    let fn_name = &ast.sig.ident;
+    let injected_name = format_ident!("{}_injected", fn_name);
+    let asyncness = &ast.sig.asyncness;
+    let unsafety = &ast.sig.unsafety;
+    let ret = match &ast.sig.output {
+        syn::ReturnType::Default => quote! { () },
+        syn::ReturnType::Type(_, typ) => quote! { #typ },
+    };
+    let wait = if is_async {
+        quote! { .await }
+    } else {
+        quote! {}
+    };
+    let doc = format!(
+        "Calls [`{}()`] with the parameters annotated with `#[dill]` resolved from the catalog",
+        fn_name
+    );
+
+    Ok(quote! {
+        #ast
+
+        #[doc = #doc]
+        #vis #asyncness #unsafety fn #injected_name(
+            cat: &::dill::Catalog,
+            #( #params, )*
+        ) -> Result<#ret, ::dill::InjectionError> {
+            #( #resolve )*
+            Ok(#fn_name( #( #call_args ),* ) #wait)
+        }
+    }
+    .into())
+}
+
+fn component_from_struct(
+    options: ComponentOptions,
+    mut ast: syn::ItemStruct,
+) -> syn::Result<TokenStream> {
+    if let Some(ctor) = &options.ctor {
+        return Err(syn::Error::new(
+            ctor.span(),
+            "#[component(ctor)] is only supported on impl blocks",
+        ));
+    }
+
+    let impl_name = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+    let impl_type = syn::parse2(quote! { #impl_name #ty_generics }).unwrap();
+
+    let args = get_struct_args(&ast)?;
+
+    for field in ast.fields.iter_mut() {
+        strip_arg_attrs(&mut field.attrs);
+    }
+
+    let explicit_scope =
+        get_scope(&ast.attrs)?.or_else(|| options.scope.clone().map(ScopeSpec::from));
+    let scope_was_explicit = explicit_scope.is_some();
+    let scope = explicit_scope.unwrap_or_else(ScopeSpec::transient);
+    let interfaces = get_interfaces(&ast.attrs)?;
+
+    let mut gen: TokenStream = quote! { #ast }.into();
+    let builder: TokenStream = implement_builder(
+        &ast.vis,
+        &impl_type,
+        &ast.attrs,
+        &ast.generics,
+        scope,
+        scope_was_explicit,
+        &options,
+        interfaces,
+        args,
+        CtorKind::Fields,
+    )?;
+
+    gen.extend(builder.into_iter());
+    Ok(gen)
+}
+
+fn component_from_enum(
+    options: ComponentOptions,
+    mut ast: syn::ItemEnum,
+) -> syn::Result<TokenStream> {
+    if let Some(ctor) = &options.ctor {
+        return Err(syn::Error::new(
+            ctor.span(),
+            "#[component(ctor)] is only supported on impl blocks",
+        ));
+    }
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.generics,
+            "#[component] is not supported on generic enums",
+        ));
+    }
+    if ast.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "#[component] enum has to have at least one variant",
+        ));
+    }
+
+    let impl_name = &ast.ident;
+    let impl_type: syn::Type = syn::parse_quote! { #impl_name };
+    let variant_enum = format_ident!("{}Variant", impl_name);
+
+    let enum_options: EnumOptions = get_dill_options(&ast.attrs)?;
+    let selector = enum_options
+        .variant_selector
+        .map(|selector| {
+            syn::parse_str::<syn::Path>(&selector).map_err(|e| {
+                syn::Error::new(
+                    impl_name.span(),
+                    format!("Invalid variant selector of {}: {}", impl_name, e),
+                )
+            })
+        })
+        .transpose()?;
+    ast.attrs.retain(|a| !a.path.is_ident("dill"));
+
+    // Arguments of all variants are collected into a single list, prefixed with the variant name
+    // to keep them unique in the builder
+    let mut args = Vec::new();
+    let mut variants = Vec::new();
+    for variant in ast.variants.iter_mut() {
+        let prefix = to_snake_case(&variant.ident.to_string());
+        let start = args.len();
+        for (i, field) in variant.fields.iter().enumerate() {
+            let name = match &field.ident {
+                Some(ident) => format_ident!("{}_{}", prefix, ident),
+                None => format_ident!("{}_{}", prefix, i),
+            };
+            args.push(get_arg(name, field.ty.clone(), &field.attrs, &[])?);
+        }
+
+        let fields = match &variant.fields {
+            syn::Fields::Named(named) => VariantFields::Named(
+                named
+                    .named
+                    .iter()
+                    .map(|f| f.ident.clone().unwrap())
+                    .collect(),
+            ),
+            syn::Fields::Unnamed(_) => VariantFields::Unnamed,
+            syn::Fields::Unit => VariantFields::Unit,
+        };
+
+        for field in variant.fields.iter_mut() {
+            strip_arg_attrs(&mut field.attrs);
+        }
+
+        variants.push(EnumVariant {
+            ident: variant.ident.clone(),
+            fields,
+            args: start..args.len(),
+        });
+    }
+
+    let explicit_scope =
+        get_scope(&ast.attrs)?.or_else(|| options.scope.clone().map(ScopeSpec::from));
+    let scope_was_explicit = explicit_scope.is_some();
+    let scope = explicit_scope.unwrap_or_else(ScopeSpec::transient);
+    let interfaces = get_interfaces(&ast.attrs)?;
+
+    let vis = &ast.vis;
+    let variant_idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let variant_enum_doc = format!(
+        "Variants of [`{}`], selecting the one constructed by its builder",
+        impl_name
+    );
+
+    let mut gen: TokenStream = quote! {
+        #ast
+
+        #[doc = #variant_enum_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #variant_enum {
+            #( #variant_idents, )*
+        }
+    }
+    .into();
+
+    let builder: TokenStream = implement_builder(
+        &ast.vis,
+        &impl_type,
+        &ast.attrs,
+        &ast.generics,
+        scope,
+        scope_was_explicit,
+        &options,
+        interfaces,
+        args,
+        CtorKind::Variants {
+            selector,
+            variant_enum,
+            variants,
+        },
+    )?;
+
+    gen.extend(builder.into_iter());
+    Ok(gen)
+}
+
+fn component_from_impl(
+    vis: syn::Visibility,
+    options: ComponentOptions,
+    mut ast: syn::ItemImpl,
+) -> syn::Result<TokenStream> {
+    let impl_type = ast.self_ty.as_ref().clone();
+    let ctor_name = options.ctor.clone().unwrap_or_else(|| format_ident!("new"));
+    let new = get_ctor(&mut ast.items, &ctor_name).ok_or_else(|| {
+        syn::Error::new_spanned(
+            &impl_type,
+            format!(
+                "When using #[component] macro on the impl block it's expected to contain a {}() \
+                function. Select another constructor via #[component(ctor = \"...\")] or use \
+                #[derive(Builder)] on the struct.",
+                ctor_name
+            ),
+        )
+    })?;
+
+    let is_fallible = is_result(&new.sig.output);
+    let instance_type = match &new.sig.output {
+        syn::ReturnType::Type(_, typ) if is_fallible => get_type_arg(typ, "Result").unwrap(),
+        syn::ReturnType::Type(_, typ) => typ.as_ref().clone(),
+        syn::ReturnType::Default => {
+            return Err(syn::Error::new_spanned(
+                &new.sig,
+                format!(
+                    "{}() has to return Self, Result<Self, E> or Arc<Self> to be used as \
+                    the constructor of the component",
+                    ctor_name
+                ),
+            ))
+        }
+    };
+    let is_shared =
+        is_smart_ptr(&instance_type) || get_type_arg(&instance_type, "Shared").is_some();
+    if let (true, Some(after_build)) = (is_shared, &options.after_build) {
+        return Err(syn::Error::new_spanned(
+            after_build,
+            "#[component(after_build)] is not supported with constructors returning Arc<Self>",
+        ));
+    }
+
+    let args = get_fn_args(&mut new.sig)?;
+
+    let ctor_kind = CtorKind::New {
+        name: ctor_name,
+        is_async: new.sig.asyncness.is_some(),
+        is_fallible,
+        is_shared,
+    };
+
+    let explicit_scope =
+        get_scope(&ast.attrs)?.or_else(|| options.scope.clone().map(ScopeSpec::from));
+    let scope_was_explicit = explicit_scope.is_some();
+    let scope = explicit_scope.unwrap_or_else(ScopeSpec::transient);
+    let interfaces = get_interfaces(&ast.attrs)?;
+
+    let mut gen: TokenStream = quote! { #ast }.into();
+    let builder: TokenStream = implement_builder(
+        &vis,
+        &impl_type,
+        &ast.attrs,
+        &ast.generics,
+        scope,
+        scope_was_explicit,
+        &options,
+        interfaces,
+        args,
+        ctor_kind,
+    )?;
+
+    gen.extend(builder.into_iter());
+    Ok(gen)
+}
+
+fn component_from_fn(options: ComponentOptions, mut ast: syn::ItemFn) -> syn::Result<TokenStream> {
+    if !ast.sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ast.sig.generics,
+            "#[component] is not supported on generic functions",
+        ));
+    }
+    // Options of the attribute don't carry their spans, so they are reported at the function name
+    if options.register {
+        return Err(syn::Error::new(
+            ast.sig.ident.span(),
+            "#[component(register)] is not supported on functions",
+        ));
+    }
+    if let Some(ctor) = &options.ctor {
+        return Err(syn::Error::new(
+            ctor.span(),
+            "#[component(ctor)] is only supported on impl blocks",
+        ));
+    }
+    if let Some(iface) = get_interfaces(&ast.attrs)?.first() {
+        return Err(syn::Error::new_spanned(
+            iface,
+            "#[interface] is not supported on functions, use CatalogBuilder::bind() instead",
+        ));
+    }
+
+    let is_fallible = is_result(&ast.sig.output);
+    let impl_type = match &ast.sig.output {
+        syn::ReturnType::Type(_, typ) if is_fallible => get_type_arg(typ, "Result").unwrap(),
+        syn::ReturnType::Type(_, typ) => typ.as_ref().clone(),
+        syn::ReturnType::Default => {
+            return Err(syn::Error::new(
+                ast.sig.ident.span(),
+                "#[component] function has to return the component",
+            ))
+        }
+    };
+
+    let args = get_fn_args(&mut ast.sig)?;
+
+    let ctor_kind = CtorKind::Fn {
+        name: ast.sig.ident.clone(),
+        is_async: ast.sig.asyncness.is_some(),
+        is_fallible,
+    };
+
+    let explicit_scope =
+        get_scope(&ast.attrs)?.or_else(|| options.scope.clone().map(ScopeSpec::from));
+    let scope_was_explicit = explicit_scope.is_some();
+    let scope = explicit_scope.unwrap_or_else(ScopeSpec::transient);
+
+    // Scope attribute is not valid on functions
+    ast.attrs.retain(|a| !a.path.is_ident("scope"));
+
+    let mut gen: TokenStream = quote! { #ast }.into();
+    let builder: TokenStream = implement_builder(
+        &ast.vis,
+        &impl_type,
+        &ast.attrs,
+        &syn::Generics::default(),
+        scope,
+        scope_was_explicit,
+        &options,
+        Vec::new(),
+        args,
+        ctor_kind,
+    )?;
+
+    gen.extend(builder.into_iter());
+    Ok(gen)
+}
+
+fn implement_builder(
+    impl_vis: &syn::Visibility,
+    impl_type: &syn::Type,
+    impl_attrs: &[syn::Attribute],
+    generics: &syn::Generics,
+    scope: ScopeSpec,
+    scope_was_explicit: bool,
+    options: &ComponentOptions,
+    interfaces: Vec<syn::Type>,
+    args: Vec<ComponentArg>,
+    ctor_kind: CtorKind,
+) -> syn::Result<TokenStream> {
+    let builder_name = match &ctor_kind {
+        CtorKind::Fn { name, .. } => format_ident!("{}Builder", to_camel_case(&name.to_string())),
+        _ => format_ident!("{}Builder", get_type_ident(impl_type)?),
+    };
+    reject_borrowing_type(generics, impl_type)?;
+    let generics = get_component_generics(generics, impl_type);
+
+    // Docs of the component follow the generated summary, as the builder is its public face
+    let builder_doc = format!(
+        "Builder of `{}`, with the setters overriding its dependencies",
+        type_to_string(impl_type)
+    );
+    let separator: syn::Attribute = syn::parse_quote! { #[doc = ""] };
+    let mut impl_docs: Vec<_> = impl_attrs
+        .iter()
+        .filter(|a| a.path.is_ident("doc"))
+        .collect();
+    if !impl_docs.is_empty() {
+        impl_docs.insert(0, &separator);
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let impl_path = get_expr_path(impl_type);
+    let scope_type = &scope.typ;
+    let scope_args = &scope.args;
+    let tags = &options.tags.0;
+    let profiles = &options.profiles.0;
+    let eager = options.eager;
+    let priority = options.priority;
+
+    let arg_name: Vec<_> = args.iter().map(|arg| &arg.name).collect();
+    let arg_impls = args
+        .iter()
+        .map(|arg| implement_arg(arg))
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let arg_override_fn_field: Vec<_> = arg_impls.iter().map(|a| &a.override_fn_field).collect();
+    let arg_override_fn_field_ctor: Vec<_> = arg_impls
+        .iter()
+        .map(|a| &a.override_fn_field_ctor)
+        .collect();
+    let arg_override_fn_field_clone: Vec<_> = arg_impls
+        .iter()
+        .map(|a| &a.override_fn_field_clone)
+        .collect();
+    let arg_override_setters: Vec<_> = arg_impls.iter().map(|a| &a.override_setters).collect();
+    let arg_registration_setters: Vec<_> =
+        arg_impls.iter().map(|a| &a.registration_setters).collect();
+    let arg_registration_setter_impls: Vec<_> = arg_impls
+        .iter()
+        .map(|a| &a.registration_setter_impls)
+        .collect();
+    let arg_overrides_field: Vec<_> = arg_impls.iter().map(|a| &a.overrides_field).collect();
+    let arg_overrides_apply: Vec<_> = arg_impls.iter().map(|a| &a.overrides_apply).collect();
+    let arg_overrides_clone: Vec<_> = arg_impls.iter().map(|a| &a.overrides_clone).collect();
+    let arg_overrides_clone_bound: Vec<_> =
+        arg_impls.iter().map(|a| &a.overrides_clone_bound).collect();
+    let arg_missing_override: Vec<_> = arg_impls.iter().map(|a| &a.missing_override).collect();
+    // Enum variants resolve only the arguments of the selected variant, see `variants_ctor`
+    let is_variants = matches!(ctor_kind, CtorKind::Variants { .. });
+
+    // Runtime arguments are taken before resolving the dependencies, so that the ones passed
+    // to `Catalog::get_with()` reach the requested component rather than its dependencies
+    let runtime_args: Vec<_> = args.iter().filter(|arg| arg.runtime).collect();
+    let take_runtime_args = match runtime_args.first() {
+        None => proc_macro2::TokenStream::new(),
+        Some(arg) if is_variants => {
+            return Err(syn::Error::new(
+                arg.name.span(),
+                "#[dill(arg)] attribute is not supported on enum components",
+            ))
+        }
+        Some(_) => {
+            let names = runtime_args.iter().map(|arg| &arg.name);
+            let types = runtime_args.iter().map(|arg| &arg.typ);
+            quote! {
+                let ( #( #names, )* ) = ::dill::RuntimeArgs::<( #( #types, )* )>::take(cat)?;
+            }
+        }
+    };
+
+    let arg_prepare_dependency: Vec<_> = std::iter::once(&take_runtime_args)
+        .chain(
+            arg_impls
+                .iter()
+                .filter(|_| !is_variants)
+                .map(|a| &a.prepare_dependency),
+        )
+        .collect();
+    let arg_prepare_dependency_async: Vec<_> = std::iter::once(&take_runtime_args)
+        .chain(
+            arg_impls
+                .iter()
+                .filter(|_| !is_variants)
+                .map(|a| &a.prepare_dependency_async),
+        )
+        .collect();
+    let arg_provide_dependency: Vec<_> = arg_impls.iter().map(|a| &a.provide_dependency).collect();
+
+    let (arg_dependency_info, arg_required): (Vec<_>, Vec<syn::Type>) = match &ctor_kind {
+        CtorKind::Variants {
+            selector,
+            variant_enum,
+            ..
+        } => {
+            // Dependencies of the variants that are not selected don't have to be registered
+            let mut infos: Vec<_> = args
+                .iter()
+                .zip(&arg_impls)
+                .filter_map(|(arg, a)| {
+                    let (iface, _) = a.dependency.as_ref()?;
+                    let override_fn_name = format_ident!("arg_{}_fn", arg.name);
+                    let named = match &arg.named {
+                        None => quote! { None },
+                        Some(named) => quote! { Some(#named) },
+                    };
+                    Some(quote! {
+                        if self.#override_fn_name.is_none() {
+                            deps.push(::dill::DependencyInfo::of::<#iface>(
+                                ::dill::DependencyKind::Maybe,
+                                #named,
+                            ));
+                        }
+                    })
+                })
+                .collect();
+            match selector {
+                Some(_) => (infos, Vec::new()),
+                None => {
+                    infos.push(quote! {
+                        deps.push(::dill::DependencyInfo::of::<#variant_enum>(
+                            ::dill::DependencyKind::One,
+                            None,
+                        ));
+                    });
+                    (infos, vec![syn::parse_quote! { #variant_enum }])
+                }
+            }
+        }
+        _ => (
+            arg_impls
+                .iter()
+                .map(|a| a.dependency_info.clone())
+                .collect(),
+            arg_impls
+                .iter()
+                .filter_map(|a| a.required.clone())
+                .collect(),
+        ),
+    };
+
+    // Picks the variant and resolves only its fields
+    let variants_ctor = |is_async: bool| match &ctor_kind {
+        CtorKind::Variants {
+            selector,
+            variant_enum,
+            variants,
+        } => {
+            let select = match (selector, is_async) {
+                (Some(selector), _) => quote! { #selector(cat)? },
+                (None, false) => quote! { *cat.get::<::dill::OneOf<#variant_enum>>()? },
+                (None, true) => {
+                    quote! { *cat.get_async::<::dill::OneOf<#variant_enum>>().await? }
+                }
+            };
+            let arms = variants.iter().map(|v| {
+                let variant = &v.ident;
+                let impls = &arg_impls[v.args.clone()];
+                let prepare = impls.iter().map(|a| {
+                    if is_async {
+                        &a.prepare_dependency_async
+                    } else {
+                        &a.prepare_dependency
+                    }
+                });
+                let provide = impls.iter().map(|a| &a.provide_dependency);
+                let construct = match &v.fields {
+                    VariantFields::Named(fields) => quote! {
+                        #impl_path::#variant { #( #fields: #provide, )* }
+                    },
+                    VariantFields::Unnamed => quote! { #impl_path::#variant( #( #provide, )* ) },
+                    VariantFields::Unit => quote! { #impl_path::#variant },
+                };
+                quote! {
+                    #variant_enum::#variant => {
+                        #( #prepare )*
+                        #construct
+                    }
+                }
+            });
+            quote! {
+                match #select {
+                    #( #arms )*
+                }
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    let ctor = match &ctor_kind {
+        CtorKind::Fields => quote! {
+            #impl_path {
+                #( #arg_name: #arg_provide_dependency, )*
+            }
+        },
+        CtorKind::New {
+            is_async,
+            is_fallible,
+            ..
+        }
+        | CtorKind::Fn {
+            is_async,
+            is_fallible,
+            ..
+        } => {
+            let wait = if *is_async {
+                quote! { .await }
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+            let check = if *is_fallible {
+                quote! { .map_err(|e| ::dill::InjectionError::constructor_failed::<#impl_type>(e))? }
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+            let ctor_path = match &ctor_kind {
+                CtorKind::Fn { name, .. } => quote! { #name },
+                CtorKind::New { name, .. } => quote! { #impl_path::#name },
+                CtorKind::Fields | CtorKind::Variants { .. } => unreachable!(),
+            };
+            quote! {
+                #ctor_path(#( #arg_provide_dependency, )*) #wait #check
+            }
+        }
+        CtorKind::Variants { .. } => variants_ctor(false),
+    };
+
+    let ctor_async = match &ctor_kind {
+        CtorKind::Variants { .. } => variants_ctor(true),
+        _ => ctor.clone(),
+    };
+
+    let is_async = matches!(
+        ctor_kind,
+        CtorKind::New { is_async: true, .. } | CtorKind::Fn { is_async: true, .. }
+    );
+
+    // Constructors returning `Arc<Self>` build the shared instance themselves
+    let is_shared = matches!(
+        ctor_kind,
+        CtorKind::New {
+            is_shared: true,
+            ..
+        }
+    );
+    let built_type = if is_shared {
+        quote! { ::dill::Shared<#impl_type> }
+    } else {
+        quote! { #impl_type }
+    };
+    let share = |inst: proc_macro2::TokenStream| {
+        if is_shared {
+            inst
+        } else {
+            quote! { ::dill::Shared::new(#inst) }
+        }
+    };
+    let inst_ref = if is_shared {
+        quote! { &*inst }
+    } else {
+        quote! { &inst }
+    };
+
+    // Post-construction hook and validation run before the instance is shared with anyone
+    let finish = |ctor: &proc_macro2::TokenStream| match (&options.after_build, options.validate) {
+        (None, false) => quote! { Ok(#ctor) },
+        (after_build, validate) => {
+            let after_build = after_build.iter();
+            let validate = if validate {
+                quote! {
+                    ::dill::Validate::validate(#inst_ref).map_err(|reason| {
+                        ::dill::InjectionError::invalid_instance::<#impl_type>(reason)
+                    })?;
+                }
+            } else {
+                proc_macro2::TokenStream::new()
+            };
+            quote! {
+                #[allow(unused_mut)]
+                let mut inst = #ctor;
+                #( inst.#after_build(cat)?; )*
+                #validate
+                Ok(inst)
+            }
+        }
+    };
+    let finish_async = finish(&ctor_async);
+    let finish = finish(&ctor);
+
+    let build_body = if is_async {
+        quote! { Err(::dill::InjectionError::async_constructor::<#impl_type>()) }
+    } else {
+        quote! {
+            #( #arg_prepare_dependency )*
+            #finish
+        }
+    };
+    let share_built = share(quote! { self.build(cat)? });
+    let share_built_async = share(quote! {
+        ::dill::instrument::build_async(self, cat, self.build_async(cat)).await?
+    });
+
+    // Catalog stores instances as `Shared<SharedAny>`, requiring them to be `Send + Sync`
+    // unless the `unsync` feature is enabled - assert this upfront so that
+    // the error points at the component instead of the generated code.
+    // Generic components carry this requirement in their bounds instead.
+    let assert_send_sync = if generics.params.is_empty() {
+        quote_spanned! { impl_type.span() =>
+            const _: () = {
+                fn assert_component_is_send_and_sync<T: ?Sized + ::dill::MaybeSendSync>() {}
+                fn assert_all() {
+                    assert_component_is_send_and_sync::<#impl_type>();
+                }
+            };
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // Generic components don't have a single builder to submit
+    let auto_register = match (options.register, generics.params.is_empty()) {
+        (false, _) => proc_macro2::TokenStream::new(),
+        (true, true) => quote! {
+            ::dill::inventory::submit! {
+                ::dill::ComponentRegistration::new(<#impl_type as ::dill::BuilderLike>::register)
+            }
+        },
+        (true, false) => {
+            return Err(syn::Error::new_spanned(
+                &generics.params,
+                "#[component(register)] is not supported on generic components",
+            ))
+        }
+    };
+
+    // Types constructed by functions are usually defined in other crates, so the trait can't be
+    // implemented for them
+    let builder_like = if let CtorKind::Fn { .. } = ctor_kind {
+        proc_macro2::TokenStream::new()
+    } else {
+        quote! {
+            impl #impl_generics ::dill::BuilderLike for #impl_type #where_clause {
+                type Builder = #builder_name #ty_generics;
+                fn register(cat: &mut ::dill::CatalogBuilder) {
+                    cat.add_builder(Self::builder());
+                    #(
+                        cat.bind::<#interfaces, #impl_type>();
+                    )*
+                }
+                fn builder() -> Self::Builder {
+                    #builder_name::new()
+                }
+            }
+        }
+    };
+
+    // Setters are provided for `Registration` returned by `CatalogBuilder::add()` via a trait,
+    // as the type is defined in another crate. Generic components are skipped, as the trait would
+    // have to replicate their generic parameters.
+    let registration = match (&ctor_kind, generics.params.is_empty()) {
+        (CtorKind::Fn { .. }, _) | (_, false) => proc_macro2::TokenStream::new(),
+        (_, true) => {
+            let trait_name = format_ident!("{}Registration", get_type_ident(impl_type)?);
+            quote! {
+                /// Setters of the builder available on `Registration` of the component
+                #[allow(dead_code)]
+                #impl_vis trait #trait_name {
+                    #( #arg_registration_setters )*
+                }
+
+                impl<'a> #trait_name for ::dill::Registration<'a, #impl_type> {
+                    #( #arg_registration_setter_impls )*
+                }
+            }
+        }
+    };
+
+    // Generic components are skipped for the same reason as with `Registration`
+    let overrides = if generics.params.is_empty() {
+        let overrides_name = format_ident!("{}Overrides", builder_name);
+        let overrides_doc = format!(
+            "Values of the arguments to apply to [`{}`] at once, see `with_overrides()`",
+            builder_name
+        );
+        quote! {
+            #[doc = #overrides_doc]
+            #[derive(Default)]
+            #[allow(dead_code)]
+            #impl_vis struct #overrides_name {
+                #( #arg_overrides_field )*
+            }
+
+            // Cloneable only if all values are, the higher-ranked bounds let the impl compile
+            // when some of them are not
+            impl Clone for #overrides_name
+            where
+                #( #arg_overrides_clone_bound )*
+            {
+                fn clone(&self) -> Self {
+                    Self {
+                        #( #arg_overrides_clone )*
+                    }
+                }
+            }
+
+            impl #builder_name {
+                /// Applies all specified values as if by calling the `with_<arg>(val)` setters,
+                /// e.g. with the values read from the configuration
+                #[allow(unused_mut, unused_variables)]
+                pub fn with_overrides(mut self, overrides: #overrides_name) -> Self {
+                    #( #arg_overrides_apply )*
+                    self
+                }
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // Catalogs declared via `catalog!` have to provide every required dependency
+    let static_dependencies = {
+        let mut generics = generics.clone();
+        let lifetimes = generics.lifetimes().count();
+        generics
+            .params
+            .insert(lifetimes, syn::parse_quote! { __DillCatalog });
+        let where_clause = generics.make_where_clause();
+        for req in &arg_required {
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { __DillCatalog: ::dill::Provides<#req> });
+        }
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::dill::StaticDependencies<__DillCatalog> for #builder_name #ty_generics #where_clause {}
+        }
+    };
+
+    let gen = quote! {
+        #assert_send_sync
+
+        #auto_register
+
+        #builder_like
+
+        #registration
+
+        #overrides
+
+        #static_dependencies
+
+        #[doc = #builder_doc]
+        #( #impl_docs )*
+        #impl_vis struct #builder_name #impl_generics #where_clause {
+            // Shared by the clones of the builder, which keeps the instances separate per catalog
+            // the same way as for the catalog snapshots
+            scope: std::sync::Arc<dyn ::dill::Scope>,
+            scope_name: &'static str,
+            scope_was_explicit: bool,
+            #(
+                #arg_override_fn_field
+            )*
+            _phantom: std::marker::PhantomData<fn() -> #impl_type>,
+        }
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            pub fn new() -> Self {
+                Self {
+                    scope: std::sync::Arc::new(<#scope_type>::new(#( #scope_args ),*)),
+                    scope_name: std::any::type_name::<#scope_type>(),
+                    scope_was_explicit: #scope_was_explicit,
+                    #(
+                        #arg_override_fn_field_ctor
+                    )*
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+
+            /// Overrides the scope of the component, e.g. to let the application decide
+            /// whether a component defined in a library is a singleton
+            pub fn with_scope<S: ::dill::Scope + 'static>(mut self, scope: S) -> Self {
+                self.scope = std::sync::Arc::new(scope);
+                self.scope_name = std::any::type_name::<S>();
+                self.scope_was_explicit = true;
+                self
+            }
+
+            #( #arg_override_setters )*
+
+            // Components without dependencies don't use the catalog
+            #[allow(unused_variables)]
+            fn build(&self, cat: &::dill::Catalog) -> Result<#built_type, ::dill::InjectionError> {
+                #build_body
+            }
+
+            #[allow(unused_variables)]
+            async fn build_async(&self, cat: &::dill::Catalog) -> Result<#built_type, ::dill::InjectionError> {
+                #( #arg_prepare_dependency_async )*
+                #finish_async
+            }
+
+            /// Builds a new instance regardless of the scope, without caching it in the scope
+            pub fn build_fresh(&self, cat: &::dill::Catalog) -> Result<::dill::Shared<#impl_type>, ::dill::InjectionError> {
+                Ok(#share_built)
+            }
+
+            /// Builds a new instance without a catalog (e.g. in unit tests) from the arguments
+            /// overridden via the setters, failing with `InjectionError::MissingOverrides` that lists
+            /// the ones that are not. Arguments that don't require a registration are resolved as if
+            /// nothing was registered, e.g. `Option<Arc<T>>` into `None`.
+            pub fn build_standalone(&self) -> Result<#built_type, ::dill::InjectionError> {
+                #[allow(unused_mut)]
+                let mut missing: Vec<&'static str> = Vec::new();
+                #( #arg_missing_override )*
+                if !missing.is_empty() {
+                    return Err(::dill::InjectionError::missing_overrides::<#impl_type>(missing));
+                }
+                self.build(&::dill::Catalog::standalone(self)?)
+            }
+        }
+
+        impl #impl_generics Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        // Derived implementation would require the generic arguments to be `Clone`
+        impl #impl_generics Clone for #builder_name #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                Self {
+                    scope: self.scope.clone(),
+                    scope_name: self.scope_name,
+                    scope_was_explicit: self.scope_was_explicit,
+                    #(
+                        #arg_override_fn_field_clone
+                    )*
+                    _phantom: std::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #impl_generics ::dill::Builder for #builder_name #ty_generics #where_clause {
+            fn instance_type_id(&self) -> std::any::TypeId {
+                std::any::TypeId::of::<#impl_type>()
+            }
+
+            fn instance_type_name(&self) -> &'static str {
+                std::any::type_name::<#impl_type>()
+            }
+
+            fn scope_name(&self) -> &'static str {
+                self.scope_name
+            }
+
+            fn scope_was_explicit(&self) -> bool {
+                self.scope_was_explicit
+            }
+
+            fn is_catalog_wide(&self) -> bool {
+                self.scope.is_catalog_wide()
+            }
+
+            fn tags(&self) -> &[&'static str] {
+                &[#( #tags ),*]
+            }
+
+            fn profiles(&self) -> &[&'static str] {
+                &[#( #profiles ),*]
+            }
+
+            fn is_eager(&self) -> bool {
+                #eager
+            }
+
+            fn priority(&self) -> i32 {
+                #priority
+            }
+
+            fn dependencies(&self) -> Vec<::dill::DependencyInfo> {
+                #[allow(unused_mut)]
+                let mut deps = Vec::new();
+                #( #arg_dependency_info )*
+                deps
+            }
+
+            fn get(&self, cat: &::dill::Catalog) -> Result<::dill::Shared<::dill::SharedAny>, ::dill::InjectionError> {
+                Ok(::dill::TypedBuilder::get(self, cat)?)
+            }
+
+            fn cached(&self, cat: &::dill::Catalog) -> Option<::dill::Shared<::dill::SharedAny>> {
+                use dill::Scope;
+                self.scope.get(cat)
+            }
+
+            fn invalidate(&self, cat: &::dill::Catalog) {
+                use dill::Scope;
+                self.scope.invalidate(cat)
+            }
+
+            fn get_async<'a>(
+                &'a self,
+                cat: &'a ::dill::Catalog,
+            ) -> ::dill::BoxFuture<'a, Result<::dill::Shared<::dill::SharedAny>, ::dill::InjectionError>> {
+                Box::pin(async move {
+                    let inst: ::dill::Shared<::dill::SharedAny> =
+                        ::dill::AsyncTypedBuilder::get_async(self, cat).await?;
+                    Ok(inst)
+                })
+            }
+        }
+
+        impl #impl_generics ::dill::TypedBuilder<#impl_type> for #builder_name #ty_generics #where_clause {
+            fn get(&self, cat: &::dill::Catalog) -> Result<::dill::Shared<#impl_type>, ::dill::InjectionError> {
+                let inst = ::dill::instrument::get_or_build(self, self.scope.as_ref(), cat, &|| {
+                    let inst: ::dill::Shared<::dill::SharedAny> = #share_built;
+                    Ok(inst)
+                })?;
+
+                Ok(inst.downcast().unwrap())
+            }
+        }
+
+        impl #impl_generics ::dill::AsyncTypedBuilder<#impl_type> for #builder_name #ty_generics #where_clause {
+            fn get_async<'a>(
+                &'a self,
+                cat: &'a ::dill::Catalog,
+            ) -> ::dill::BoxFuture<'a, Result<::dill::Shared<#impl_type>, ::dill::InjectionError>> {
+                Box::pin(async move {
+                    use dill::Scope;
+
+                    if let Some(inst) = self.scope.get(cat) {
+                        ::dill::instrument::cache_hit(self, cat);
+                        return Ok(inst.downcast().unwrap());
+                    }
+
+                    // Unlike synchronous resolution this does not guarantee exactly-once
+                    // construction, only that all callers get the instance that was cached first
+                    let inst: ::dill::Shared<::dill::SharedAny> = #share_built_async;
+                    Ok(self.scope.set_if_absent(cat, inst)?.downcast().unwrap())
+                })
+            }
+        }
+    };
+
+    Ok(gen.into())
+}
+
+/// Pieces of the generated builder code related to a single argument
+#[derive(Default)]
+struct ArgImpl {
+    override_fn_field: proc_macro2::TokenStream,
+    override_fn_field_ctor: proc_macro2::TokenStream,
+    override_fn_field_clone: proc_macro2::TokenStream,
+    override_setters: proc_macro2::TokenStream,
+    /// Setters declared by the `<Type>Registration` trait, see `Registration`
+    registration_setters: proc_macro2::TokenStream,
+    registration_setter_impls: proc_macro2::TokenStream,
+    /// Field of the `<Builder>Overrides` struct and the code applying it to the builder
+    overrides_field: proc_macro2::TokenStream,
+    overrides_apply: proc_macro2::TokenStream,
+    /// Cloning of the field along with the bound that makes the struct `Clone` only if it is
+    overrides_clone: proc_macro2::TokenStream,
+    overrides_clone_bound: proc_macro2::TokenStream,
+    /// Records the argument that has to be overridden, see `build_standalone()`
+    missing_override: proc_macro2::TokenStream,
+    prepare_dependency: proc_macro2::TokenStream,
+    prepare_dependency_async: proc_macro2::TokenStream,
+    provide_dependency: proc_macro2::TokenStream,
+    dependency_info: proc_macro2::TokenStream,
+    /// Type that has to be registered for the argument to be resolved, see `catalog!`
+    required: Option<syn::Type>,
+    /// Expression resolving the argument from the catalog synchronously, ignoring the overrides
+    resolve: proc_macro2::TokenStream,
+    /// Asynchronous counterpart of `resolve`, to be awaited in an `async` context
+    resolve_async: proc_macro2::TokenStream,
+    /// Interface the argument depends on along with the `DependencyKind` variant
+    dependency: Option<(syn::Type, proc_macro2::TokenStream)>,
+}
+
+fn implement_arg(arg: &ComponentArg) -> syn::Result<ArgImpl> {
+    let name = &arg.name;
+    let typ = &arg.typ;
+
+    if let (Some(validate), true) = (&arg.validate, arg.skip || arg.lazy) {
+        return Err(syn::Error::new_spanned(
+            validate,
+            "#[dill(validate)] can't be combined with #[dill(skip)] and #[dill(lazy)]",
+        ));
+    }
+
+    if arg.runtime
+        && (arg.skip
+            || arg.lazy
+            || arg.inject_with.is_some()
+            || arg.named.is_some()
+            || arg.default.is_some()
+            || arg.from.is_some()
+            || arg.validate.is_some())
+    {
+        return Err(syn::Error::new(
+            name.span(),
+            "#[dill(arg)] can't be combined with other options of the argument",
+        ));
+    }
+
+    // Runtime arguments are taken upfront by the builder, see `#[dill(arg)]`
+    if arg.runtime {
+        return Ok(ArgImpl {
+            provide_dependency: quote! { #name },
+            ..Default::default()
+        });
+    }
+
+    // Skipped fields hold the internal state of the component rather than its dependencies
+    if arg.skip {
+        let init = quote! { let #name = ::std::default::Default::default(); };
+        return Ok(ArgImpl {
+            prepare_dependency: init.clone(),
+            prepare_dependency_async: init,
+            provide_dependency: quote! { #name },
+            ..Default::default()
+        });
+    }
+
+    let injection_type = if arg.lazy {
+        if arg.inject_with.is_some() || arg.default.is_some() {
+            return Err(syn::Error::new(
+                name.span(),
+                "#[dill(lazy)] can't be combined with #[inject(with)] and #[dill::default]",
+            ));
+        }
+        match get_type_arg(typ, "Lazy") {
+            Some(inner) => InjectionType::LazyOf { inner },
+            None => {
+                return Err(syn::Error::new_spanned(
+                    typ,
+                    "#[dill(lazy)] attribute is only supported on Lazy<T> arguments",
+                ))
+            }
+        }
+    } else {
+        deduce_injection_type(typ)
+    };
+
+    if arg.from.is_some()
+        && (arg.inject_with.is_some()
+            || arg.lazy
+            || matches!(injection_type, InjectionType::Reference { .. }))
+    {
+        return Err(syn::Error::new(
+            name.span(),
+            "#[dill(from)] can't be combined with #[inject(with)], #[dill(lazy)] and reference \
+            arguments",
+        ));
+    }
+
+    // Argument resolved lazily is handled the same way as its inner type, see `#[dill(lazy)]`
+    let lazy_inner = match &injection_type {
+        InjectionType::LazyOf { inner } => Some(implement_arg(&ComponentArg {
+            name: name.clone(),
+            typ: inner.clone(),
+            inject_with: None,
+            named: arg.named.clone(),
+            default: None,
+            skip: false,
+            lazy: false,
+            runtime: false,
+            from: None,
+            validate: None,
+        })?),
+        _ => None,
+    };
+
+    let is_reference = matches!(injection_type, InjectionType::Reference { .. });
+    let override_fn_name = format_ident!("arg_{}_fn", name);
+
+    // Reference arguments are overridden with shared instances that are borrowed for the
+    // duration of the constructor call, the same way as the ones resolved from the catalog
+    let override_typ = match &injection_type {
+        InjectionType::Reference { inner } => quote! { ::dill::Shared<#inner> },
+        _ => quote! { #typ },
+    };
+
+    let override_fn_field = quote! {
+        #override_fn_name: Option<std::sync::Arc<dyn ::dill::OverrideFn<#override_typ>>>,
+    };
+
+    let override_fn_field_ctor = quote! { #override_fn_name: None, };
+
+    let override_fn_field_clone = quote! { #override_fn_name: self.#override_fn_name.clone(), };
+
+    let (
+        override_setters,
+        registration_setters,
+        registration_setter_impls,
+        overrides_field,
+        overrides_apply,
+        overrides_clone,
+        overrides_clone_bound,
+    ) = {
+        let setter_val_name = format_ident!("with_{}", name);
+        let setter_fn_name = format_ident!("with_{}_fn", name);
+        let setter_from_name = format_ident!("with_{}_from", name);
+        let setter_once_name = format_ident!("with_{}_value", name);
+
+        let overrides = format!(
+            "Overrides the `{}: {}` dependency",
+            name,
+            type_to_string(typ)
+        );
+        let doc_val = format!("{} with the value", overrides);
+        let doc_once = format!(
+            "{} with the value moved into the first instance built",
+            overrides
+        );
+        let doc_fn = format!("{} with the value returned by the function", overrides);
+        let doc_from = format!(
+            "{} with the conversion of the `U` instance resolved from the catalog",
+            overrides
+        );
+
+        // Values are converted to spare the callers e.g. `.to_owned()` for `String` arguments.
+        // Smart pointers are passed as is for the unsized coercion to apply, while numbers
+        // are for the literals not to be ambiguous.
+        let val_typ = match &injection_type {
+            InjectionType::Value { typ } if !is_primitive(typ) => quote! { impl Into<#typ> },
+            _ => quote! { #override_typ },
+        };
+
+        let sig_val = quote! {
+            #[doc = #doc_val]
+            fn #setter_val_name(self, val: #val_typ) -> Self
+        };
+        let sig_once = quote! {
+            #[doc = #doc_once]
+            fn #setter_once_name(self, val: #override_typ) -> Self
+        };
+        let sig_fn = quote! {
+            #[doc = #doc_fn]
+            fn #setter_fn_name(
+                self,
+                fun: impl Fn(&::dill::Catalog) -> Result<#override_typ, ::dill::InjectionError> + 'static + ::dill::MaybeSendSync
+            ) -> Self
+        };
+        let sig_from = quote! {
+            #[doc = #doc_from]
+            fn #setter_from_name<U>(
+                self,
+                adapter: impl Fn(::dill::Shared<U>) -> #override_typ + 'static + ::dill::MaybeSendSync
+            ) -> Self
+            where
+                U: 'static + ?Sized + ::dill::MaybeSendSync
+        };
+
+        // Types injected via custom conversion and owned trait objects might not be `Clone`
+        let is_owned = matches!(injection_type, InjectionType::Owned { .. });
+        let has_setter_val = arg.inject_with.is_none() && !is_owned;
+
+        // Values that are not `Clone` are handed to the first build, see `with_<arg>_value()`
+        let setter_val = match &injection_type {
+            InjectionType::Value { .. } if has_setter_val => quote! {
+                #[doc = #doc_val]
+                pub fn #setter_val_name(mut self, val: #val_typ) -> Self {
+                    #[allow(clippy::useless_conversion)]
+                    let val: #override_typ = val.into();
+                    let cloner = {
+                        #[allow(unused_imports)]
+                        use ::dill::{CloneValue as _, MoveValue as _};
+                        (&&::dill::ValueProbe::<#override_typ>::default()).cloner()
+                    };
+                    match cloner {
+                        Some(clone) => {
+                            self.#override_fn_name = Some(std::sync::Arc::new(move |_| Ok(clone(&val))));
+                            self
+                        }
+                        None => self.#setter_once_name(val),
+                    }
+                }
+            },
+            _ if has_setter_val => quote! {
+                #[doc = #doc_val]
+                pub fn #setter_val_name(mut self, val: #val_typ) -> Self {
+                    #[allow(clippy::useless_conversion)]
+                    let val: #override_typ = val.into();
+                    self.#override_fn_name = Some(std::sync::Arc::new(move |_| Ok(val.clone())));
+                    self
+                }
+            },
+            _ => proc_macro2::TokenStream::new(),
+        };
+
+        let arg_name = name.to_string();
+        let override_setters = quote! {
+            #setter_val
+
+            #[doc = #doc_once]
+            pub fn #setter_once_name(mut self, val: #override_typ) -> Self {
+                let type_name = ::dill::Builder::instance_type_name(&self);
+                let val = std::sync::Mutex::new(Some(val));
+                self.#override_fn_name = Some(std::sync::Arc::new(move |_| {
+                    val.lock().unwrap().take().ok_or_else(|| {
+                        ::dill::InjectionError::value_consumed(type_name, #arg_name)
+                    })
+                }));
+                self
+            }
+
+            #[doc = #doc_fn]
+            pub fn #setter_fn_name(
+                mut self,
+                fun: impl Fn(&::dill::Catalog) -> Result<#override_typ, ::dill::InjectionError> + 'static + ::dill::MaybeSendSync
+            ) -> Self {
+                self.#override_fn_name = Some(std::sync::Arc::new(fun));
+                self
+            }
+
+            #[doc = #doc_from]
+            pub fn #setter_from_name<U>(
+                mut self,
+                adapter: impl Fn(::dill::Shared<U>) -> #override_typ + 'static + ::dill::MaybeSendSync
+            ) -> Self
+            where
+                U: 'static + ?Sized + ::dill::MaybeSendSync,
+            {
+                self.#override_fn_name = Some(std::sync::Arc::new(move |cat: &::dill::Catalog| {
+                    Ok(adapter(cat.get_one::<U>()?))
+                }));
+                self
+            }
+        };
+
+        // Same setters forwarded to the builder held by `Registration`
+        let (registration_val, registration_val_impl) = if has_setter_val {
+            (
+                quote! { #sig_val; },
+                quote! {
+                    #sig_val {
+                        #[allow(clippy::useless_conversion)]
+                        let val: #override_typ = val.into();
+                        self.configure(move |b| b.#setter_val_name(val))
+                    }
+                },
+            )
+        } else {
+            Default::default()
+        };
+
+        let registration_setters = quote! {
+            #registration_val
+            #sig_once;
+            #sig_fn;
+            #sig_from;
+        };
+
+        let registration_setter_impls = quote! {
+            #registration_val_impl
+
+            #sig_once {
+                self.configure(move |b| b.#setter_once_name(val))
+            }
+
+            #sig_fn {
+                self.configure(move |b| b.#setter_fn_name(fun))
+            }
+
+            #sig_from {
+                self.configure(move |b| b.#setter_from_name(adapter))
+            }
+        };
+
+        // Values applied in bulk via `with_overrides()` of the builder
+        let (overrides_field, overrides_apply, overrides_clone, overrides_clone_bound) =
+            if has_setter_val {
+                (
+                    quote! { pub #name: Option<#override_typ>, },
+                    quote! {
+                        if let Some(val) = overrides.#name {
+                            self = self.#setter_val_name(val);
+                        }
+                    },
+                    quote! { #name: self.#name.clone(), },
+                    quote! { for<'__dill> Option<#override_typ>: Clone, },
+                )
+            } else {
+                Default::default()
+            };
+
+        (
+            override_setters,
+            registration_setters,
+            registration_setter_impls,
+            overrides_field,
+            overrides_apply,
+            overrides_clone,
+            overrides_clone_bound,
+        )
+    };
+
+    // Type that has to be registered for the argument not to fall back to the default value
+    let default_iface = match (&arg.default, &injection_type, &arg.inject_with) {
+        (None, _, _) => None,
+        (Some(_), _, None) if arg.from.is_some() => arg.from.as_ref(),
+        (Some(_), InjectionType::Arc { inner }, None)
+        | (Some(_), InjectionType::Box { inner }, None)
+        | (Some(_), InjectionType::Owned { inner }, None) => Some(inner),
+        (Some(_), InjectionType::Value { typ }, None) => Some(typ),
+        (Some(default), _, _) => {
+            return Err(syn::Error::new_spanned(
+                default,
+                "#[dill::default] attribute is only supported on T, Arc<T>, Box<T> and \
+                Box<dyn Trait> arguments without #[inject(with)]",
+            ))
+        }
+    };
+
+    // Arguments that can't be resolved from an empty catalog, see `build_standalone()`
+    let needs_override = arg.default.is_none()
+        && match (&injection_type, &arg.inject_with) {
+            _ if arg.from.is_some() => true,
+            (_, Some(_)) => true,
+            (InjectionType::Reference { .. }, None)
+            | (InjectionType::Arc { .. }, None)
+            | (InjectionType::Box { .. }, None)
+            | (InjectionType::Owned { .. }, None)
+            | (InjectionType::Weak { .. }, None)
+            | (InjectionType::Value { .. }, None)
+            | (InjectionType::Context { .. }, None) => true,
+            _ => false,
+        };
+
+    let missing_override = if needs_override {
+        let arg_name = name.to_string();
+        quote! {
+            if self.#override_fn_name.is_none() {
+                missing.push(#arg_name);
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    // Named bindings and the arguments that fall back to a default value or don't depend
+    // on the registrations are not checked by `catalog!`
+    let required = match (&injection_type, &arg.inject_with) {
+        _ if arg.named.is_some() || arg.default.is_some() => None,
+        _ if arg.from.is_some() => arg.from.clone(),
+        (InjectionType::Reference { inner }, _) => Some(inner.clone()),
+        (_, Some(_)) => None,
+        (InjectionType::Arc { inner }, None)
+        | (InjectionType::Box { inner }, None)
+        | (InjectionType::Owned { inner }, None)
+        | (InjectionType::Lazy { inner }, None)
+        | (InjectionType::Factory { inner }, None)
+        | (InjectionType::Weak { inner }, None) => Some(inner.clone()),
+        (InjectionType::Value { typ }, None) => Some(typ.clone()),
+        (InjectionType::LazyOf { .. }, None) => lazy_inner.as_ref().unwrap().required.clone(),
+        _ => None,
+    };
+
+    // Reports `#[named("...")]` on the arguments that are not resolved from a single binding
+    let unsupported_named = |what: &str| match &arg.named {
+        None => Ok(()),
+        Some(named) => Err(syn::Error::new(
+            named.span(),
+            format!(
+                "{} attribute is not supported on {} arguments",
+                named.attr(),
+                what
+            ),
+        )),
+    };
+
+    let resolve_arg = |is_async: bool| -> syn::Result<proc_macro2::TokenStream> {
+        // Resolves the spec either as is or by the binding name, see `#[named("...")]`
+        let resolve = |spec: proc_macro2::TokenStream| match (&arg.named, is_async) {
+            (None, false) => quote! { cat.get::<#spec>() },
+            (None, true) => quote! { cat.get_async::<#spec>().await },
+            (Some(named), false) => quote! { cat.get_named::<#spec>(#named) },
+            (Some(named), true) => quote! { cat.get_named_async::<#spec>(#named).await },
+        };
+
+        // Source is cloned out of the catalog, as conversions consume the value
+        if let Some(from) = &arg.from {
+            let get = match (&arg.named, is_async) {
+                (None, false) => quote! { cat.get_one::<#from>()? },
+                (None, true) => quote! { cat.get_one_async::<#from>().await? },
+                (Some(named), false) => quote! { cat.get_one_named::<#from>(#named)? },
+                (Some(named), true) => {
+                    quote! { cat.get_one_named_async::<#from>(#named).await? }
+                }
+            };
+            return Ok(quote! {
+                <#typ as ::std::convert::TryFrom<#from>>::try_from(#get.as_ref().clone())
+                    .map_err(|e| ::dill::InjectionError::constructor_failed::<#typ>(e))?
+            });
+        }
+
+        Ok(match (&injection_type, &arg.inject_with) {
+            (InjectionType::Reference { inner }, _) => {
+                let get = resolve(quote! { OneOf<#inner> });
+                quote! { #get? }
+            }
+            (_, Some(with)) => match (&arg.named, is_async) {
+                (None, false) => quote! { #with(cat.get_one()?) },
+                (None, true) => quote! { #with(cat.get_one_async().await?) },
+                (Some(named), false) => quote! { #with(cat.get_one_named(#named)?) },
+                (Some(named), true) => quote! { #with(cat.get_one_named_async(#named).await?) },
+            },
+            (InjectionType::Arc { inner }, None) => {
+                let get = resolve(quote! { OneOf<#inner> });
+                quote! { #get? }
+            }
+            (InjectionType::Box { inner }, None) => {
+                let get = resolve(quote! { OneOf<#inner> });
+                quote! { #get.map(|v| Box::new(v.as_ref().clone()))? }
+            }
+            (InjectionType::Weak { inner }, None) => {
+                let get = resolve(quote! { OneOf<#inner> });
+                quote! { #get.map(|v| ::dill::Shared::downgrade(&v))? }
+            }
+            // Values that are not `Clone` are moved out of a fresh instance instead
+            (InjectionType::Value { typ }, None) => {
+                let get = resolve(quote! { OneOf<#typ> });
+                let get_owned = resolve(quote! { ::dill::Owned<#typ> });
+                quote! {
+                    {
+                        #[allow(unused_imports)]
+                        use ::dill::{CloneValue as _, MoveValue as _};
+                        match (&&::dill::ValueProbe::<#typ>::default()).cloner() {
+                            Some(clone) => #get.map(|v| clone(v.as_ref()))?,
+                            None => #get_owned.map(|v| *v)?,
+                        }
+                    }
+                }
+            }
+            (InjectionType::All { inner }, None) => {
+                unsupported_named("Vec<Arc<T>>")?;
+                if is_async {
+                    quote! { cat.get_async::<::dill::AllOf<#inner>>().await? }
+                } else {
+                    quote! { cat.get::<::dill::AllOf<#inner>>()? }
+                }
+            }
+            (InjectionType::AllNamed { inner }, None) => {
+                unsupported_named("HashMap<String, Arc<T>>")?;
+                if is_async {
+                    quote! { cat.get_async::<::dill::AllOfNamed<#inner>>().await? }
+                } else {
+                    quote! { cat.get::<::dill::AllOfNamed<#inner>>()? }
+                }
+            }
+            (InjectionType::AllLazy { inner }, None) => {
+                unsupported_named("Vec<LazyBinding<T>>")?;
+                quote! { cat.get::<::dill::AllOfLazy<#inner>>()? }
+            }
+            (InjectionType::Maybe { inner }, None) => {
+                let get = resolve(quote! { ::dill::Maybe<::dill::OneOf<#inner>> });
+                quote! { #get? }
+            }
+            (InjectionType::Lazy { inner }, None) => {
+                unsupported_named("Lazy<Arc<T>>")?;
+                quote! { ::dill::Lazy::<::dill::Shared<#inner>>::one_of(cat) }
+            }
+            (InjectionType::Factory { inner }, None) => {
+                unsupported_named("Factory<T>")?;
+                quote! { ::dill::Factory::<#inner>::new(cat) }
+            }
+            (InjectionType::LazyOf { .. }, None) => {
+                let get = &lazy_inner.as_ref().unwrap().resolve;
+                quote! {
+                    ::dill::Lazy::new(cat, |cat: &::dill::Catalog| -> Result<_, ::dill::InjectionError> {
+                        Ok(#get)
+                    })
+                }
+            }
+            (InjectionType::Catalog { is_arc }, None) => {
+                unsupported_named("Catalog")?;
+                if *is_arc {
+                    quote! { ::dill::Shared::new(cat.detached()) }
+                } else {
+                    quote! { cat.detached() }
+                }
+            }
+            (InjectionType::Context { inner }, None) => {
+                unsupported_named("FromContext<T>")?;
+                if is_async {
+                    quote! { cat.get_async::<::dill::FromContext<#inner>>().await? }
+                } else {
+                    quote! { cat.get::<::dill::FromContext<#inner>>()? }
+                }
+            }
+            (InjectionType::Owned { inner }, None) => {
+                let get = resolve(quote! { ::dill::Owned<#inner> });
+                quote! { #get? }
+            }
+            (InjectionType::Component, None) => {
+                unsupported_named("ComponentContext")?;
+                if is_async {
+                    quote! { cat.get_async::<::dill::ComponentContext>().await? }
+                } else {
+                    quote! { cat.get::<::dill::ComponentContext>()? }
+                }
+            }
+        })
+    };
+
+    let from_catalog = |is_async: bool| -> syn::Result<proc_macro2::TokenStream> {
+        let get = resolve_arg(is_async)?;
+        match (&arg.default, default_iface) {
+            (Some(default), Some(iface)) => {
+                let is_registered = match &arg.named {
+                    None => quote! { cat.builders_for::<#iface>().next().is_some() },
+                    Some(named) => quote! { cat.builder_named::<#iface>(#named).is_some() },
+                };
+                Ok(quote! {
+                    if #is_registered {
+                        #get
+                    } else {
+                        #default
+                    }
+                })
+            }
+            _ => Ok(get),
+        }
+    };
+
+    // Overridden values are validated too, as they might come from the configuration
+    let validate = match &arg.validate {
+        None => proc_macro2::TokenStream::new(),
+        Some(validate) => {
+            let arg_name = name.to_string();
+            let value = if is_reference {
+                quote! { #name.as_ref() }
+            } else {
+                quote! { &#name }
+            };
+            quote! {
+                if let Err(reason) = ::dill::ValidationOutcome::into_result(#validate(#value)) {
+                    return Err(::dill::InjectionError::invalid_argument(
+                        ::dill::Builder::instance_type_name(self),
+                        #arg_name,
+                        reason,
+                    ));
+                }
+            }
+        }
+    };
+
+    let prepare = |from_catalog: proc_macro2::TokenStream| {
+        quote! {
+            let #name = match self.#override_fn_name {
+                Some(ref fun) => fun(cat)?,
+                _ => #from_catalog,
+            };
+            #validate
+        }
+    };
+
+    let prepare_dependency = prepare(from_catalog(false)?);
+    let prepare_dependency_async = prepare(from_catalog(true)?);
+
+    let provide_dependency = if is_reference {
+        quote! { #name.as_ref() }
+    } else {
+        quote! { #name }
+    };
+
+    let (dependency_info, dependency) = {
+        let info = |iface: &syn::Type, kind: proc_macro2::TokenStream| {
+            let named = match &arg.named {
+                None => quote! { None },
+                Some(named) => quote! { Some(#named) },
+            };
+            quote! { ::dill::DependencyInfo::of::<#iface>(::dill::DependencyKind::#kind, #named) }
+        };
+
+        // Arguments with a default value don't require the dependency to be registered
+        let one = if arg.default.is_some() {
+            quote! { Maybe }
+        } else {
+            quote! { One }
+        };
+
+        let lazy = quote! { Lazy };
+        let dependency = match (&injection_type, &arg.inject_with) {
+            _ if arg.from.is_some() => Some((arg.from.clone().unwrap(), one)),
+            (InjectionType::Reference { inner }, _) => Some((inner.clone(), quote! { One })),
+            // Type of the dependency is inferred from the conversion function, so it's unknown here
+            (_, Some(_)) => None,
+            (InjectionType::Arc { inner }, None)
+            | (InjectionType::Box { inner }, None)
+            | (InjectionType::Weak { inner }, None) => Some((inner.clone(), one)),
+            (InjectionType::Value { typ }, None) => Some((typ.clone(), one)),
+            (InjectionType::All { inner }, None)
+            | (InjectionType::AllNamed { inner }, None)
+            | (InjectionType::AllLazy { inner }, None) => Some((inner.clone(), quote! { All })),
+            (InjectionType::Maybe { inner }, None) => Some((inner.clone(), quote! { Maybe })),
+            (InjectionType::Lazy { inner }, None) | (InjectionType::Factory { inner }, None) => {
+                Some((inner.clone(), lazy))
+            }
+            (InjectionType::LazyOf { .. }, None) => lazy_inner
+                .as_ref()
+                .unwrap()
+                .dependency
+                .clone()
+                .map(|(iface, _)| (iface, lazy)),
+            // Catalog and context values don't depend on any registration
+            (InjectionType::Catalog { .. }, None)
+            | (InjectionType::Context { .. }, None)
+            | (InjectionType::Component, None) => None,
+            (InjectionType::Owned { inner }, None) => Some((inner.clone(), one)),
+        };
+
+        let tokens = match &dependency {
+            None => proc_macro2::TokenStream::new(),
+            // Overridden arguments are not resolved from the catalog
+            Some((iface, kind)) => {
+                let info = info(iface, kind.clone());
+                quote! {
+                    if self.#override_fn_name.is_none() {
+                        deps.push(#info);
+                    }
+                }
+            }
+        };
+
+        (tokens, dependency)
+    };
+
+    Ok(ArgImpl {
+        override_fn_field,
+        override_fn_field_ctor,
+        override_fn_field_clone,
+        override_setters,
+        registration_setters,
+        registration_setter_impls,
+        overrides_field,
+        overrides_apply,
+        overrides_clone,
+        overrides_clone_bound,
+        missing_override,
+        prepare_dependency,
+        prepare_dependency_async,
+        provide_dependency,
+        dependency_info,
+        required,
+        resolve: resolve_arg(false)?,
+        resolve_async: resolve_arg(true)?,
+        dependency,
+    })
+}
+
+/// How the instance of a component is constructed
+enum CtorKind {
+    /// Struct literal with all fields injected
+    Fields,
+    /// `fn new(...) -> Self` or the constructor selected via `#[component(ctor = "...")]`,
+    /// where async constructors can only be resolved via `Catalog::get_async()`, fallible ones
+    /// return `Result<Self, E>` and shared ones return `Arc<Self>` to be handed out as is
+    New {
+        name: syn::Ident,
+        is_async: bool,
+        is_fallible: bool,
+        is_shared: bool,
+    },
+    /// Free function `fn name(...) -> T`, with the same kinds as `New`
+    Fn {
+        name: syn::Ident,
+        is_async: bool,
+        is_fallible: bool,
+    },
+    /// Enum variant picked by the selector function or by the injected `<Enum>Variant`
+    Variants {
+        selector: Option<syn::Path>,
+        variant_enum: syn::Ident,
+        variants: Vec<EnumVariant>,
+    },
+}
+
+/// Variant of an enum component along with the range of its arguments in the argument list
+struct EnumVariant {
+    ident: syn::Ident,
+    fields: VariantFields,
+    args: std::ops::Range<usize>,
+}
+
+/// Shape of an enum variant, which determines how it is constructed from the arguments
+enum VariantFields {
+    Named(Vec<syn::Ident>),
+    Unnamed,
+    Unit,
+}
+
+/// Binding that an argument is resolved from, expands into the binding name
+#[derive(Clone)]
+enum BindingName {
+    /// See `#[named("...")]`
+    Name(syn::LitStr),
+    /// See `#[dill(qualifier = Q)]`
+    Qualifier(syn::Path),
+}
+
+impl BindingName {
+    fn span(&self) -> proc_macro2::Span {
+        match self {
+            Self::Name(name) => name.span(),
+            Self::Qualifier(qualifier) => qualifier.span(),
+        }
+    }
+
+    /// Attribute that specified the binding, for the error messages
+    fn attr(&self) -> &'static str {
+        match self {
+            Self::Name(_) => "#[named]",
+            Self::Qualifier(_) => "#[dill(qualifier)]",
+        }
+    }
+}
+
+impl ToTokens for BindingName {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            Self::Name(name) => name.to_tokens(tokens),
+            Self::Qualifier(qualifier) => {
+                tokens.extend(quote! { <#qualifier as ::dill::Qualifier>::name() })
+            }
+        }
+    }
+}
+
+/// Argument of a component (struct field or a parameter of `new()`)
+struct ComponentArg {
+    name: syn::Ident,
+    typ: syn::Type,
+    /// Function converting the resolved `Arc<T>` into the argument type, see `#[inject(with = f)]`
+    inject_with: Option<syn::Path>,
+    /// Name of the binding to resolve the argument from, see `#[named("...")]`
+    named: Option<BindingName>,
+    /// Value to use when the dependency is not registered, see `#[dill::default]`
+    default: Option<syn::Expr>,
+    /// Whether the argument is initialized with `Default::default()`, see `#[dill(skip)]`
+    skip: bool,
+    /// Whether the argument is resolved upon first use, see `#[dill(lazy)]`
+    lazy: bool,
+    /// Whether the argument is passed upon resolution, see `#[dill(arg)]`
+    runtime: bool,
+    /// Type to resolve and convert into the argument type, see `#[dill(from = "...")]`
+    from: Option<syn::Type>,
+    /// Function checking the resolved value, see `#[dill(validate = "...")]`
+    validate: Option<syn::Path>,
+}
+
+/// Describes how the argument of a component is resolved from the catalog
+enum InjectionType {
+    /// `&T` - resolves `OneOf<T>` and passes instance by reference
+    Reference { inner: syn::Type },
+    /// `Arc<T>` - resolves `OneOf<T>`
+    Arc { inner: syn::Type },
+    /// `Box<T>` - resolves `OneOf<T>` and moves a clone of the instance into a new box
+    Box { inner: syn::Type },
+    /// `Box<dyn T>` - resolves `Owned<dyn T>`, moving a fresh instance into the box
+    Owned { inner: syn::Type },
+    /// `Vec<Arc<T>>` - resolves `AllOf<T>`
+    All { inner: syn::Type },
+    /// `HashMap<String, Arc<T>>` - resolves `AllOfNamed<T>`
+    AllNamed { inner: syn::Type },
+    /// `Vec<LazyBinding<T>>` - resolves `AllOfLazy<T>`, building the implementations on demand
+    AllLazy { inner: syn::Type },
+    /// `Option<Arc<T>>` - resolves `Maybe<OneOf<T>>`
+    Maybe { inner: syn::Type },
+    /// `Lazy<Arc<T>>` - resolves `OneOf<T>` upon first use
+    Lazy { inner: syn::Type },
+    /// `Factory<T>` - resolves `OneOf<T>` upon every `create()` call
+    Factory { inner: syn::Type },
+    /// `Weak<T>` - resolves `OneOf<T>` and downgrades the instance
+    Weak { inner: syn::Type },
+    /// `Lazy<T>` marked with `#[dill(lazy)]` - resolves `T` as if it was the argument type
+    /// upon first use
+    LazyOf { inner: syn::Type },
+    /// `T` - resolves `OneOf<T>` and passes a clone of the instance by value
+    Value { typ: syn::Type },
+    /// `Catalog` or `Arc<Catalog>` - passes a handle to the resolving catalog
+    Catalog { is_arc: bool },
+    /// `FromContext<V>` - resolves the value attached to the resolving catalog handle
+    Context { inner: syn::Type },
+    /// `ComponentContext` - passes the identity of the component being constructed
+    Component,
+}
+
+fn deduce_injection_type(typ: &syn::Type) -> InjectionType {
+    if is_reference(typ) {
+        InjectionType::Reference {
+            inner: strip_reference(typ),
+        }
+    } else if is_catalog(typ) {
+        InjectionType::Catalog { is_arc: false }
+    } else if is_smart_ptr(typ) && is_catalog(&strip_smart_ptr(typ)) {
+        InjectionType::Catalog { is_arc: true }
+    } else if is_component_context(typ) {
+        InjectionType::Component
+    } else if is_smart_ptr(typ) {
+        InjectionType::Arc {
+            inner: strip_smart_ptr(typ),
+        }
+    } else if let Some(inner) =
+        get_type_arg(typ, "Vec").and_then(|t| get_type_arg(&t, "LazyBinding"))
+    {
+        InjectionType::AllLazy { inner }
+    } else if let Some(inner) = get_type_arg(typ, "Vec").filter(is_smart_ptr) {
+        InjectionType::All {
+            inner: strip_smart_ptr(&inner),
+        }
+    } else if let Some(inner) = get_type_arg_at(typ, "HashMap", 1).filter(is_smart_ptr) {
+        InjectionType::AllNamed {
+            inner: strip_smart_ptr(&inner),
+        }
+    } else if let Some(inner) = get_type_arg(typ, "Lazy").filter(is_smart_ptr) {
+        InjectionType::Lazy {
+            inner: strip_smart_ptr(&inner),
+        }
+    } else if let Some(inner) = get_type_arg(typ, "Option").filter(is_smart_ptr) {
+        InjectionType::Maybe {
+            inner: strip_smart_ptr(&inner),
+        }
+    } else if let Some(inner) = get_type_arg(typ, "Factory") {
+        InjectionType::Factory { inner }
+    } else if let Some(inner) = get_type_arg(typ, "Weak") {
+        InjectionType::Weak { inner }
+    } else if let Some(inner) = get_type_arg(typ, "FromContext") {
+        InjectionType::Context { inner }
+    } else if let Some(inner) = get_type_arg(typ, "Box") {
+        if is_trait_object(&inner) {
+            InjectionType::Owned { inner }
+        } else {
+            InjectionType::Box { inner }
+        }
+    } else {
+        // Any other type (e.g. an array, a tuple or a type with const generic arguments)
+        // is resolved as a value
+        InjectionType::Value {
+            typ: ungroup(typ).clone(),
+        }
+    }
 }
 
-#[proc_macro_attribute]
-pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let ast: syn::Item = syn::parse(item).unwrap();
-    let vis: syn::Visibility = syn::parse(attr).unwrap();
-    match ast {
-        syn::Item::Struct(struct_ast) => component_from_struct(struct_ast),
-        syn::Item::Impl(impl_ast) => component_from_impl(vis, impl_ast),
-        _ => panic!("The #[component] macro can only be used on struct definiton or an impl block"),
+/// Renders the type for the generated docs, e.g. `Arc<dyn Db>` rather than `Arc < dyn Db >`
+fn type_to_string(typ: &syn::Type) -> String {
+    typ.to_token_stream()
+        .to_string()
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" , ", ", ")
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace("& ", "&")
+}
+
+/// Returns the name of the component type without the generic arguments, e.g. `Repo` for `Repo<T>`
+fn get_type_ident(typ: &syn::Type) -> syn::Result<syn::Ident> {
+    match typ {
+        syn::Type::Path(tp) => Ok(tp.path.segments.last().unwrap().ident.clone()),
+        _ => Err(syn::Error::new_spanned(typ, "Unsupported component type")),
     }
 }
 
-#[proc_macro_attribute]
-pub fn scope(_args: TokenStream, item: TokenStream) -> TokenStream {
-    item
+/// Returns the path of the component type usable in expressions, e.g. `Repo::<T>` for `Repo<T>`
+fn get_expr_path(typ: &syn::Type) -> proc_macro2::TokenStream {
+    match typ {
+        syn::Type::Path(tp) if tp.qself.is_none() => {
+            let mut path = tp.path.clone();
+            for seg in path.segments.iter_mut() {
+                if let syn::PathArguments::AngleBracketed(args) = &mut seg.arguments {
+                    args.colon2_token = Some(Default::default());
+                }
+            }
+            quote! { #path }
+        }
+        _ => quote! { <#typ> },
+    }
 }
 
-fn component_from_struct(ast: syn::ItemStruct) -> TokenStream {
-    let impl_name = &ast.ident;
-    let impl_type = syn::parse2(quote! { #impl_name }).unwrap();
+/// Catalogs only hold `'static` instances, so the types borrowing data (e.g. `Parser<'a>`)
+/// can't be components. Reports this upfront, as otherwise the generated builder fails
+/// with confusing errors about the lifetimes.
+fn reject_borrowing_type(generics: &syn::Generics, impl_type: &syn::Type) -> syn::Result<()> {
+    let lifetime = generics
+        .lifetimes()
+        .map(|ld| &ld.lifetime)
+        .chain(get_type_lifetimes(impl_type))
+        .find(|lt| lt.ident != "static");
 
-    let args: Vec<_> = ast
-        .fields
-        .iter()
-        .map(|f| (f.ident.clone().unwrap(), f.ty.clone()))
-        .collect();
+    match lifetime {
+        None => Ok(()),
+        Some(lifetime) => Err(syn::Error::new_spanned(
+            lifetime,
+            format!(
+                "#[component] is not supported on types with lifetime parameters, as the \
+                catalog can only hold 'static instances. Store owned values in the fields \
+                instead, e.g. String or Arc<str> in place of &{} str, and pass the borrowed \
+                data to the methods of the component.",
+                lifetime
+            ),
+        )),
+    }
+}
 
-    let scope_type =
-        get_scope(&ast.attrs).unwrap_or_else(|| syn::parse_str("::dill::Transient").unwrap());
+/// Returns the lifetimes passed as generic arguments of a type, e.g. `'a` of `Parser<'a>`
+fn get_type_lifetimes(typ: &syn::Type) -> impl Iterator<Item = &syn::Lifetime> {
+    let args = match typ {
+        syn::Type::Path(tp) => tp
+            .path
+            .segments
+            .last()
+            .and_then(|seg| match &seg.arguments {
+                syn::PathArguments::AngleBracketed(ab) => Some(&ab.args),
+                _ => None,
+            }),
+        _ => None,
+    };
 
-    let mut gen: TokenStream = quote! { #ast }.into();
-    let builder: TokenStream = implement_builder(&ast.vis, &impl_type, scope_type, args, false);
+    args.into_iter().flatten().filter_map(|arg| match arg {
+        syn::GenericArgument::Lifetime(lt) => Some(lt),
+        _ => None,
+    })
+}
 
-    gen.extend(builder.into_iter());
-    gen
+/// Extends the generic parameters of a component with the bounds required to register it
+/// in the catalog, i.e. the instances being `'static + MaybeSendSync`
+fn get_component_generics(generics: &syn::Generics, impl_type: &syn::Type) -> syn::Generics {
+    let mut generics = generics.clone();
+    if generics.params.is_empty() {
+        return generics;
+    }
+
+    let params: Vec<_> = generics.params.iter().cloned().collect();
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        match param {
+            syn::GenericParam::Type(tp) => {
+                let ident = tp.ident;
+                where_clause
+                    .predicates
+                    .push(syn::parse_quote! { #ident: 'static });
+            }
+            // Lifetimes are rejected by `reject_borrowing_type()`
+            syn::GenericParam::Lifetime(_) | syn::GenericParam::Const(_) => {}
+        }
+    }
+    where_clause
+        .predicates
+        .push(syn::parse_quote! { #impl_type: ::dill::MaybeSendSync });
+    generics
 }
 
-fn component_from_impl(vis: syn::Visibility, ast: syn::ItemImpl) -> TokenStream {
-    let impl_type = &ast.self_ty;
-    let new = get_new(&ast.items).expect(
-        "When using #[component] macro on the impl block it's expected to contain a new() function. \
-        Otherwise use #[derive(Builder)] on the struct."
-    );
+/// Scope of a component along with the arguments to construct it with, e.g. `Singleton`
+/// or `Cached(Duration::from_secs(60))`
+struct ScopeSpec {
+    typ: syn::Path,
+    args: Vec<syn::Expr>,
+}
 
-    let args: Vec<_> = new
-        .sig
-        .inputs
+impl ScopeSpec {
+    fn transient() -> Self {
+        Self::from(syn::parse_str::<syn::Path>("::dill::Transient").unwrap())
+    }
+}
+
+impl From<syn::Path> for ScopeSpec {
+    fn from(typ: syn::Path) -> Self {
+        Self {
+            typ,
+            args: Vec::new(),
+        }
+    }
+}
+
+/// Searches for `#[scope(X)]`, `#[scope(X<T>)]` or `#[scope(X(args...))]` attribute and returns
+/// the scope
+fn get_scope(attrs: &Vec<syn::Attribute>) -> syn::Result<Option<ScopeSpec>> {
+    const EXPECTED: &str = "Invalid scope attribute, expected #[scope(X)] or #[scope(X(args...))]";
+
+    attrs
         .iter()
-        .map(|arg| match arg {
-            syn::FnArg::Typed(targ) => targ,
-            _ => panic!("Unexpected argument in new() function"),
-        })
-        .map(|arg| {
-            (
-                match arg.pat.as_ref() {
-                    syn::Pat::Ident(ident) => ident.ident.clone(),
-                    _ => panic!("Unexpected format of arguments in new() function"),
+        .filter(|a| a.path.is_ident("scope"))
+        .next()
+        .map(|a| {
+            // Generic arguments of a type path are not a valid expression
+            if let Ok(typ) = a.parse_args::<syn::Path>() {
+                return Ok(ScopeSpec::from(typ));
+            }
+            match a.parse_args()? {
+                syn::Expr::Call(syn::ExprCall { func, args, .. }) => match *func {
+                    syn::Expr::Path(p) => Ok(ScopeSpec {
+                        typ: p.path,
+                        args: args.into_iter().collect(),
+                    }),
+                    func => Err(syn::Error::new_spanned(func, EXPECTED)),
                 },
-                arg.ty.as_ref().clone(),
-            )
+                expr => Err(syn::Error::new_spanned(expr, EXPECTED)),
+            }
         })
-        .collect();
+        .transpose()
+}
 
-    let scope_type =
-        get_scope(&ast.attrs).unwrap_or_else(|| syn::parse_str("::dill::Transient").unwrap());
+/// Collects types from all `#[interface(X)]` attributes
+fn get_interfaces(attrs: &Vec<syn::Attribute>) -> syn::Result<Vec<syn::Type>> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("interface"))
+        .map(|a| a.parse_args())
+        .collect()
+}
 
-    let mut gen: TokenStream = quote! { #ast }.into();
-    let builder: TokenStream = implement_builder(&vis, impl_type, scope_type, args, true);
+/// Searches for `#[builder(scope = X)]` attribute and returns `X`
+fn get_builder_scope(attrs: &Vec<syn::Attribute>) -> syn::Result<Option<syn::Path>> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("builder"))
+        .next()
+        .map(|a| {
+            a.parse_args_with(|input: syn::parse::ParseStream| {
+                let key: syn::Ident = input.parse()?;
+                if key != "scope" {
+                    return Err(syn::Error::new(key.span(), "Unknown builder option"));
+                }
+                input.parse::<syn::Token![=]>()?;
+                input.parse::<syn::Path>()
+            })
+        })
+        .transpose()
+}
 
-    gen.extend(builder.into_iter());
-    gen
+/// Returns arguments corresponding to the struct fields
+fn get_struct_args(ast: &syn::ItemStruct) -> syn::Result<Vec<ComponentArg>> {
+    ast.fields
+        .iter()
+        .map(|f| match &f.ident {
+            Some(ident) => get_arg(ident.clone(), f.ty.clone(), &f.attrs, &[]),
+            None => Err(syn::Error::new_spanned(
+                f,
+                "Tuple struct fields are not supported, expected a named field",
+            )),
+        })
+        .collect()
 }
 
-fn implement_builder(
-    impl_vis: &syn::Visibility,
-    impl_type: &syn::Type,
-    scope_type: syn::Path,
-    args: Vec<(syn::Ident, syn::Type)>,
-    has_new: bool,
-) -> TokenStream {
-    let builder_name = format_ident!("{}Builder", quote! { #impl_type }.to_string());
-
-    let arg_name: Vec<_> = args.iter().map(|(name, _)| name).collect();
-    let arg_impls: Vec<_> = args
+/// Returns arguments corresponding to the function parameters, stripping the attributes
+/// processed by the macro
+fn get_fn_args(sig: &mut syn::Signature) -> syn::Result<Vec<ComponentArg>> {
+    // Arguments depending on these have to specify the concrete type to resolve
+    let generic_params: Vec<_> = sig
+        .generics
+        .params
         .iter()
-        .map(|(name, typ)| implement_arg(name, typ, &builder_name))
+        .filter_map(|param| match param {
+            syn::GenericParam::Type(tp) => Some(tp.ident.clone()),
+            syn::GenericParam::Const(cp) => Some(cp.ident.clone()),
+            syn::GenericParam::Lifetime(_) => None,
+        })
         .collect();
 
-    // Unzip
-    let mut arg_override_fn_field = Vec::new();
-    let mut arg_override_fn_field_ctor = Vec::new();
-    let mut arg_override_setters = Vec::new();
-    let mut arg_prepare_dependency = Vec::new();
-    let mut arg_provide_dependency = Vec::new();
-    for (
-        override_fn_field,
-        override_fn_field_ctor,
-        override_setters,
-        prepare_dependency,
-        provide_dependency,
-    ) in arg_impls
-    {
-        arg_override_fn_field.push(override_fn_field);
-        arg_override_fn_field_ctor.push(override_fn_field_ctor);
-        arg_override_setters.push(override_setters);
-        arg_prepare_dependency.push(prepare_dependency);
-        arg_provide_dependency.push(provide_dependency);
-    }
+    let args = sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            syn::FnArg::Typed(targ) => Ok(targ),
+            syn::FnArg::Receiver(recv) => Err(syn::Error::new_spanned(
+                recv,
+                format!("Unexpected argument in {}() function", sig.ident),
+            )),
+        })
+        .map(|arg| {
+            let arg = arg?;
+            let name = match arg.pat.as_ref() {
+                syn::Pat::Ident(ident) => ident.ident.clone(),
+                pat => {
+                    return Err(syn::Error::new_spanned(
+                        pat,
+                        format!(
+                        "Unexpected format of arguments in {}() function, expected an identifier",
+                        sig.ident
+                    ),
+                    ))
+                }
+            };
+            get_arg(name, arg.ty.as_ref().clone(), &arg.attrs, &generic_params)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
 
-    let ctor = if !has_new {
-        quote! {
-            #impl_type {
-                #( #arg_name: #arg_provide_dependency, )*
-            }
-        }
-    } else {
-        quote! {
-            #impl_type::new(#( #arg_provide_dependency, )*)
+    for arg in sig.inputs.iter_mut() {
+        if let syn::FnArg::Typed(targ) = arg {
+            strip_arg_attrs(&mut targ.attrs);
         }
-    };
+    }
 
-    let gen = quote! {
-        impl ::dill::BuilderLike for #impl_type {
-            type Builder = #builder_name;
-            fn register(cat: &mut ::dill::CatalogBuilder) {
-                cat.add_builder(Self::builder());
-            }
-            fn builder() -> Self::Builder {
-                #builder_name::new()
-            }
-        }
+    Ok(args)
+}
 
-        #impl_vis struct #builder_name {
-            scope: #scope_type,
-            #(
-                #arg_override_fn_field
-            )*
+/// Returns the first `impl` keyword or the generic parameter that the type tokens refer to
+fn find_generic(
+    tokens: proc_macro2::TokenStream,
+    generic_params: &[syn::Ident],
+) -> Option<proc_macro2::Ident> {
+    tokens.into_iter().find_map(|tt| match tt {
+        proc_macro2::TokenTree::Ident(ident)
+            if ident == "impl" || generic_params.contains(&ident) =>
+        {
+            Some(ident)
         }
+        proc_macro2::TokenTree::Group(group) => find_generic(group.stream(), generic_params),
+        _ => None,
+    })
+}
 
-        impl #builder_name {
-            pub fn new() -> Self {
-                Self {
-                    scope: #scope_type::new(),
-                    #(
-                        #arg_override_fn_field_ctor
-                    )*
-                }
+/// Converts `snake_case` function name into `UpperCamelCase`, e.g. `HttpClient` for `http_client`
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
             }
+        })
+        .collect()
+}
 
-            #( #arg_override_setters )*
-
-            fn build(&self, cat: &::dill::Catalog) -> Result<#impl_type, ::dill::InjectionError> {
-                #( #arg_prepare_dependency )*
-                Ok(#ctor)
+/// Converts `UpperCamelCase` type name into `snake_case`, e.g. `http_client` for `HttpClient`
+/// and `db_pool` for `DBPool`
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut res = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).map_or(false, |n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_lower) {
+                res.push('_');
             }
         }
+        res.extend(c.to_lowercase());
+    }
+    res
+}
 
-        impl ::dill::Builder for #builder_name {
-            fn instance_type_id(&self) -> std::any::TypeId {
-                std::any::TypeId::of::<#impl_type>()
-            }
+/// Searches for `#[inject(with = path::to::fn)]` attribute and returns the function path
+fn get_inject_with(attrs: &Vec<syn::Attribute>) -> syn::Result<Option<syn::Path>> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("inject"))
+        .next()
+        .map(|a| {
+            a.parse_args_with(|input: syn::parse::ParseStream| {
+                let key: syn::Ident = input.parse()?;
+                if key != "with" {
+                    return Err(syn::Error::new(key.span(), "Unknown inject option"));
+                }
+                input.parse::<syn::Token![=]>()?;
+                input.parse::<syn::Path>()
+            })
+        })
+        .transpose()
+}
 
-            fn instance_type_name(&self) -> &'static str {
-                std::any::type_name::<#impl_type>()
-            }
+/// Collects the argument attributes processed by the macro
+fn get_arg(
+    name: syn::Ident,
+    typ: syn::Type,
+    attrs: &Vec<syn::Attribute>,
+    generic_params: &[syn::Ident],
+) -> syn::Result<ComponentArg> {
+    let options: ArgOptions = get_dill_options(attrs)?;
 
-            fn get(&self, cat: &::dill::Catalog) -> Result<std::sync::Arc<dyn std::any::Any + Send + Sync>, ::dill::InjectionError> {
-                Ok(::dill::TypedBuilder::get(self, cat)?)
+    let typ = match &options.concrete {
+        Some(concrete) => syn::parse_str(concrete).map_err(|e| {
+            syn::Error::new(
+                name.span(),
+                format!("Invalid concrete type of argument {}: {}", name, e),
+            )
+        })?,
+        None => match find_generic(typ.to_token_stream(), generic_params) {
+            Some(ident) => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "Argument {} has a generic type that can't be resolved from the catalog, \
+                        use a trait object (e.g. Arc<dyn Trait>) or specify the type to resolve \
+                        via #[dill(concrete = \"...\")]",
+                        name
+                    ),
+                ))
             }
-        }
+            None => typ,
+        },
+    };
 
-        impl ::dill::TypedBuilder<#impl_type> for #builder_name {
-            fn get(&self, cat: &::dill::Catalog) -> Result<std::sync::Arc<#impl_type>, ::dill::InjectionError> {
-                use dill::Scope;
+    let mut names = get_named(attrs)?
+        .into_iter()
+        .chain(
+            options
+                .named
+                .map(|named| syn::LitStr::new(&named, name.span())),
+        )
+        .map(BindingName::Name)
+        .chain(options.qualifier.map(BindingName::Qualifier));
+    let named = names.next();
+    if let Some(other) = names.next() {
+        return Err(syn::Error::new(
+            other.span(),
+            format!("Argument {} has multiple binding names specified", name),
+        ));
+    }
 
-                if let Some(inst) = self.scope.get() {
-                    return Ok(inst.downcast().unwrap());
-                }
+    let from = options
+        .from
+        .map(|from| {
+            syn::parse_str(&from).map_err(|e| {
+                syn::Error::new(
+                    name.span(),
+                    format!("Invalid source type of argument {}: {}", name, e),
+                )
+            })
+        })
+        .transpose()?;
 
-                let inst = std::sync::Arc::new(self.build(cat)?);
+    Ok(ComponentArg {
+        inject_with: get_inject_with(attrs)?,
+        from,
+        named,
+        default: get_default(attrs)?,
+        skip: options.skip,
+        lazy: options.lazy,
+        runtime: options.arg,
+        validate: options.validate,
+        name,
+        typ,
+    })
+}
 
-                self.scope.set(inst.clone());
-                Ok(inst)
+/// Searches for `#[dill(...)]` attribute and parses the options, see [`ArgOptions`]
+/// and [`EnumOptions`]
+fn get_dill_options<T: FromMeta + Default>(attrs: &Vec<syn::Attribute>) -> syn::Result<T> {
+    attrs
+        .iter()
+        .filter(|a| a.path.is_ident("dill"))
+        // Bare `#[dill]` marks an injected parameter without options, see `#[dill::inject]`
+        .filter(|a| !a.tokens.is_empty())
+        .next()
+        .map(|a| syn::Attribute {
+            tokens: quote_qualifier(a.tokens.clone()),
+            ..a.clone()
+        })
+        .map(|a| match a.parse_meta()? {
+            syn::Meta::List(list) => {
+                let items: Vec<_> = list.nested.iter().cloned().collect();
+                T::from_list(&items)
+                    .map_err(|e| syn::Error::new_spanned(&list.nested, e.to_string()))
             }
-        }
-    };
-
-    gen.into()
+            meta => Err(syn::Error::new_spanned(
+                meta,
+                "Invalid dill attribute, expected #[dill(...)]",
+            )),
+        })
+        .unwrap_or_else(|| Ok(T::default()))
 }
 
-fn implement_arg(
-    name: &syn::Ident,
-    typ: &syn::Type,
-    builder: &syn::Ident,
-) -> (
-    proc_macro2::TokenStream,
-    proc_macro2::TokenStream,
-    proc_macro2::TokenStream,
-    proc_macro2::TokenStream,
-    proc_macro2::TokenStream,
-) {
-    let override_fn_name = format_ident!("arg_{}_fn", name);
-
-    let override_fn_field = if is_reference(typ) {
-        proc_macro2::TokenStream::new()
-    } else {
-        quote! {
-            #override_fn_name: Option<Box<dyn Fn(&::dill::Catalog) -> Result<#typ, ::dill::InjectionError> + Send + Sync>>,
-        }
-    };
-
-    let override_fn_field_ctor = if is_reference(typ) {
-        proc_macro2::TokenStream::new()
-    } else {
-        quote! { #override_fn_name: None, }
-    };
+/// Quotes the type in `qualifier = Q` option, as attributes only accept literal values,
+/// e.g. `#[dill(qualifier = Primary)]` is parsed as `#[dill(qualifier = "Primary")]`
+fn quote_qualifier(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    use proc_macro2::{Delimiter, Group, Literal, TokenTree};
 
-    let override_setters = if is_reference(typ) {
-        proc_macro2::TokenStream::new()
-    } else {
-        let setter_val_name = format_ident!("with_{}", name);
-        let setter_fn_name = format_ident!("with_{}_fn", name);
-        quote! {
-            pub fn #setter_val_name(mut self, val: #typ) -> #builder {
-                self.#override_fn_name = Some(Box::new(move |_| Ok(val.clone())));
-                self
-            }
+    let is_punct =
+        |tt: &TokenTree, ch: char| matches!(tt, TokenTree::Punct(p) if p.as_char() == ch);
 
-            pub fn #setter_fn_name(
-                mut self,
-                fun: impl Fn(&::dill::Catalog) -> Result<#typ, ::dill::InjectionError> + 'static + Send + Sync
-            ) -> #builder {
-                self.#override_fn_name = Some(Box::new(fun));
-                self
-            }
-        }
-    };
+    tokens
+        .into_iter()
+        .map(|tt| match tt {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                let mut quoted = Vec::new();
+                let mut items = group.stream().into_iter().peekable();
+                while let Some(tt) = items.next() {
+                    let is_qualifier = matches!(&tt, TokenTree::Ident(i) if i == "qualifier");
+                    quoted.push(tt);
+                    if !is_qualifier || !items.peek().map_or(false, |tt| is_punct(tt, '=')) {
+                        continue;
+                    }
+                    quoted.extend(items.next());
 
-    let from_catalog = if is_reference(typ) {
-        let stripped = strip_reference(typ);
-        quote! { cat.get::<OneOf<#stripped>>()? }
-    } else if is_smart_ptr(typ) {
-        let stripped = strip_smart_ptr(typ);
-        quote! { cat.get::<OneOf<#stripped>>()? }
-    } else {
-        quote! { cat.get::<OneOf<#typ>>().map(|v| v.as_ref().clone())? }
-    };
+                    // Types that are already quoted, as well as the missing ones, are left as is
+                    let span = match items.peek() {
+                        Some(TokenTree::Literal(_)) | None => continue,
+                        Some(tt) => tt.span(),
+                    };
 
-    let prepare_dependency = if is_reference(typ) {
-        quote! { let #name = #from_catalog; }
-    } else {
-        quote! {
-            let #name = match self.#override_fn_name {
-                Some(ref fun) => fun(cat)?,
-                _ => #from_catalog,
-            };
-        }
-    };
+                    // Collects the type up to the next option, skipping the commas of its generics
+                    let mut typ = proc_macro2::TokenStream::new();
+                    let mut depth = 0;
+                    while let Some(tt) = items.peek() {
+                        if is_punct(tt, ',') && depth == 0 {
+                            break;
+                        } else if is_punct(tt, '<') {
+                            depth += 1;
+                        } else if is_punct(tt, '>') {
+                            depth -= 1;
+                        }
+                        typ.extend(items.next());
+                    }
 
-    let provide_dependency = if is_reference(typ) {
-        quote! { #name.as_ref() }
-    } else {
-        quote! { #name }
-    };
+                    let mut lit = Literal::string(&typ.to_string());
+                    lit.set_span(span);
+                    quoted.push(TokenTree::Literal(lit));
+                }
+                let mut quoted_group =
+                    Group::new(Delimiter::Parenthesis, quoted.into_iter().collect());
+                quoted_group.set_span(group.span());
+                TokenTree::Group(quoted_group)
+            }
+            tt => tt,
+        })
+        .collect()
+}
 
-    (
-        override_fn_field,
-        override_fn_field_ctor,
-        override_setters,
-        prepare_dependency,
-        provide_dependency,
-    )
+/// Searches for `#[named("...")]` (or `#[dill::named("...")]`) attribute and returns the name
+fn get_named(attrs: &Vec<syn::Attribute>) -> syn::Result<Option<syn::LitStr>> {
+    attrs
+        .iter()
+        .filter(|a| is_dill_attr(a, "named"))
+        .next()
+        .map(|a| a.parse_args())
+        .transpose()
 }
 
-/// Searches for `#[scope(X)]` attribute and returns `X`
-fn get_scope(attrs: &Vec<syn::Attribute>) -> Option<syn::Path> {
+/// Searches for `#[dill::default]` (or `#[default]`) attribute and returns the default value,
+/// which is either `Default::default()` or the expression from `#[dill::default = expr]`
+fn get_default(attrs: &Vec<syn::Attribute>) -> syn::Result<Option<syn::Expr>> {
+    use syn::parse::Parser;
+
     attrs
         .iter()
-        .filter_map(|a| a.parse_meta().ok())
-        .filter_map(|m| match m {
-            syn::Meta::List(ml) => Some(ml),
-            _ => None,
-        })
-        .filter(|ml| ml.path.is_ident("scope"))
+        .filter(|a| is_dill_attr(a, "default"))
         .next()
-        .and_then(|ml| match ml.nested.into_iter().next() {
-            Some(syn::NestedMeta::Meta(syn::Meta::Path(p))) => Some(p),
-            _ => panic!("Invalid scope attribute"),
+        .map(|a| {
+            if a.tokens.is_empty() {
+                Ok(syn::parse_quote! { ::std::default::Default::default() })
+            } else {
+                (|input: syn::parse::ParseStream| {
+                    input.parse::<syn::Token![=]>()?;
+                    input.parse::<syn::Expr>()
+                })
+                .parse2(a.tokens.clone())
+            }
         })
+        .transpose()
+}
+
+/// Checks whether the attribute has the specified name, optionally qualified as `dill::<name>`
+fn is_dill_attr(attr: &syn::Attribute, name: &str) -> bool {
+    attr.path.is_ident(name)
+        || (attr.path.segments.len() == 2
+            && attr.path.segments[0].ident == "dill"
+            && attr.path.segments[1].ident == name)
+}
+
+/// Whether the attribute is processed by the macro and is not valid in the emitted code
+fn is_arg_attr(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("inject")
+        || attr.path.is_ident("dill")
+        || is_dill_attr(attr, "named")
+        || is_dill_attr(attr, "default")
+}
+
+/// Removes attributes processed by the macro, see [`is_arg_attr()`]
+fn strip_arg_attrs(attrs: &mut Vec<syn::Attribute>) {
+    attrs.retain(|a| !is_arg_attr(a));
 }
 
-/// Searches `impl` block for `new()` method
-fn get_new(impl_items: &Vec<syn::ImplItem>) -> Option<&syn::ImplItemMethod> {
+/// Searches `impl` block for the constructor method, `new()` unless specified otherwise
+fn get_ctor<'a>(
+    impl_items: &'a mut Vec<syn::ImplItem>,
+    name: &syn::Ident,
+) -> Option<&'a mut syn::ImplItemMethod> {
     impl_items
-        .iter()
+        .iter_mut()
         .filter_map(|i| match i {
             syn::ImplItem::Method(m) => Some(m),
             _ => None,
         })
-        .filter(|m| m.sig.ident == "new")
+        .filter(|m| m.sig.ident == *name)
         .next()
 }
 
-fn is_reference(typ: &syn::Type) -> bool {
+/// Whether function returns `Result<...>`
+fn is_result(output: &syn::ReturnType) -> bool {
+    match output {
+        syn::ReturnType::Type(_, typ) => get_type_arg(typ, "Result").is_some(),
+        syn::ReturnType::Default => false,
+    }
+}
+
+/// Strips the parentheses and the invisible groups (e.g. the ones wrapping `$t:ty` fragments
+/// of `macro_rules!`) around the type, which are otherwise mistaken for the value types
+fn ungroup(typ: &syn::Type) -> &syn::Type {
     match typ {
+        syn::Type::Group(group) => ungroup(&group.elem),
+        syn::Type::Paren(paren) => ungroup(&paren.elem),
+        _ => typ,
+    }
+}
+
+fn is_reference(typ: &syn::Type) -> bool {
+    match ungroup(typ) {
         syn::Type::Reference(_) => true,
         _ => false,
     }
 }
 
+// Parentheses are required around the trait objects with bounds, e.g. `&(dyn Trait + Send)`,
+// but not in the generated specs
 fn strip_reference(typ: &syn::Type) -> syn::Type {
-    match typ {
-        syn::Type::Reference(r) => r.elem.as_ref().clone(),
+    match ungroup(typ) {
+        syn::Type::Reference(r) => ungroup(&r.elem).clone(),
         _ => typ.clone(),
     }
 }
 
-fn is_smart_ptr(typ: &syn::Type) -> bool {
-    match typ {
+/// Whether the type is a number, `bool` or `char`
+fn is_primitive(typ: &syn::Type) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+        "f32", "f64", "bool", "char",
+    ];
+    match ungroup(typ) {
+        syn::Type::Path(typepath) if typepath.qself.is_none() => typepath
+            .path
+            .get_ident()
+            .map_or(false, |ident| PRIMITIVES.iter().any(|p| ident == p)),
+        _ => false,
+    }
+}
+
+/// Whether the type is `Catalog` (or `dill::Catalog`)
+fn is_catalog(typ: &syn::Type) -> bool {
+    match ungroup(typ) {
         syn::Type::Path(typepath) if typepath.qself.is_none() => {
-            match typepath.path.segments.first() {
-                Some(seg) if seg.ident.to_string() == "Arc" => true,
-                _ => false,
+            match typepath.path.segments.last() {
+                Some(seg) => seg.ident == "Catalog" && seg.arguments.is_empty(),
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Whether the type is `ComponentContext` (or `dill::ComponentContext`)
+fn is_component_context(typ: &syn::Type) -> bool {
+    match ungroup(typ) {
+        syn::Type::Path(typepath) if typepath.qself.is_none() => {
+            match typepath.path.segments.last() {
+                Some(seg) => seg.ident == "ComponentContext" && seg.arguments.is_empty(),
+                None => false,
             }
         }
         _ => false,
     }
 }
 
+/// Whether the type is `Arc<T>` (or e.g. `std::sync::Arc<T>`)
+fn is_smart_ptr(typ: &syn::Type) -> bool {
+    get_smart_ptr_arg(typ).is_some()
+}
+
 fn strip_smart_ptr(typ: &syn::Type) -> syn::Type {
-    match typ {
+    get_smart_ptr_arg(typ).unwrap_or_else(|| typ.clone())
+}
+
+/// Shared instances are `Arc<T>`, or `Rc<T>` with the `unsync` feature of `dill`
+fn get_smart_ptr_arg(typ: &syn::Type) -> Option<syn::Type> {
+    get_type_arg(typ, "Arc").or_else(|| get_type_arg(typ, "Rc"))
+}
+
+/// Returns the first type argument of a generic type with specified name, e.g. `T` of `Box<T>`
+fn get_type_arg(typ: &syn::Type, name: &str) -> Option<syn::Type> {
+    get_type_arg_at(typ, name, 0)
+}
+
+/// Returns the type argument at the specified position, e.g. `V` of `HashMap<K, V>` for `1`
+///
+/// Const and lifetime arguments are skipped, so e.g. `T` is the first type argument of `Foo<'a, 3, T>`.
+fn get_type_arg_at(typ: &syn::Type, name: &str, index: usize) -> Option<syn::Type> {
+    match ungroup(typ) {
         syn::Type::Path(typepath) if typepath.qself.is_none() => {
-            match typepath.path.segments.first() {
-                Some(seg) if seg.ident.to_string() == "Arc" => match seg.arguments {
-                    syn::PathArguments::AngleBracketed(ref args) => {
-                        syn::parse2(args.args.to_token_stream()).unwrap()
-                    }
-                    _ => typ.clone(),
+            match typepath.path.segments.last() {
+                Some(seg) if seg.ident == name => match seg.arguments {
+                    syn::PathArguments::AngleBracketed(ref args) => args
+                        .args
+                        .iter()
+                        .filter_map(|arg| match arg {
+                            syn::GenericArgument::Type(inner) => Some(ungroup(inner).clone()),
+                            _ => None,
+                        })
+                        .nth(index),
+                    _ => None,
                 },
-                _ => typ.clone(),
+                _ => None,
             }
         }
-        _ => typ.clone(),
+        _ => None,
+    }
+}
+
+fn is_trait_object(typ: &syn::Type) -> bool {
+    match ungroup(typ) {
+        syn::Type::TraitObject(_) => true,
+        _ => false,
     }
 }
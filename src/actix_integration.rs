@@ -0,0 +1,211 @@
+use std::{
+    future::{ready, Future, Ready},
+    ops::Deref,
+    pin::Pin,
+    sync::Arc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    error::InternalError,
+    web::Data,
+    Error, FromRequest, HttpRequest, HttpResponse,
+};
+
+use crate::{Catalog, CatalogBuilder};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Injected
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// An actix-web extractor that resolves a single implementation of the interface from the
+/// request [`Catalog`] set up by the [`RequestScope`] middleware, falling back to the catalog
+/// registered as the app data (`App::app_data(Data::new(catalog))`).
+///
+/// Responds with `500 Internal Server Error` when the catalog is missing or fails to resolve
+/// the dependency. The response body is generic, while the details are logged with the `tracing`
+/// feature.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// trait Greeter: Send + Sync {
+///     fn greet(&self) -> String;
+/// }
+///
+/// async fn hello(greeter: Injected<dyn Greeter>) -> String {
+///     greeter.greet()
+/// }
+/// ```
+pub struct Injected<Iface>(pub Arc<Iface>)
+where
+    Iface: 'static + ?Sized + Send + Sync;
+
+impl<Iface> Deref for Injected<Iface>
+where
+    Iface: 'static + ?Sized + Send + Sync,
+{
+    type Target = Arc<Iface>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<Iface> FromRequest for Injected<Iface>
+where
+    Iface: 'static + ?Sized + Send + Sync,
+{
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let catalog = req
+            .extensions()
+            .get::<Catalog>()
+            .cloned()
+            .or_else(|| req.app_data::<Data<Catalog>>().map(|c| c.get_ref().clone()));
+
+        Box::pin(async move {
+            let catalog = catalog.ok_or_else(|| {
+                internal_error(
+                    "Catalog is missing from the request, is RequestScope middleware installed?",
+                )
+            })?;
+
+            let instance = catalog
+                .get_one_async::<Iface>()
+                .await
+                .map_err(internal_error)?;
+
+            Ok(Self(instance))
+        })
+    }
+}
+
+/// Responds with a generic `500 Internal Server Error`, as the errors name the internal types
+/// of the application. The cause is kept for the error handlers and logged with the `tracing`
+/// feature.
+fn internal_error<E>(cause: E) -> Error
+where
+    E: std::fmt::Debug + std::fmt::Display + 'static,
+{
+    #[cfg(feature = "tracing")]
+    tracing::error!(error = %cause, "Failed to handle the request");
+    let response = HttpResponse::InternalServerError().body("Internal Server Error");
+    InternalError::from_response(cause, response).into()
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// RequestScope
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Registers the per-request components, see [`RequestScope::with_request_components()`]
+type RequestConfigurer = Arc<dyn Fn(&mut CatalogBuilder, &ServiceRequest) + Send + Sync>;
+
+/// An actix-web middleware that stores a per-request child of the catalog in the request
+/// extensions for the [`Injected`] extractor.
+///
+/// Every request [enters a new scope](Catalog::enter_scope()), so that the [`Scoped`][crate::Scoped]
+/// components are shared within the request only.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[derive(Clone)]
+/// struct RequestId(String);
+///
+/// let catalog = CatalogBuilder::new().build();
+///
+/// let app = actix_web::App::new().wrap(RequestScope::new(catalog).with_request_components(
+///     |b, req| {
+///         let id = req
+///             .headers()
+///             .get("x-request-id")
+///             .and_then(|v| v.to_str().ok())
+///             .unwrap_or_default();
+///         b.add_value(RequestId(id.to_owned()));
+///     },
+/// ));
+/// ```
+#[derive(Clone)]
+pub struct RequestScope {
+    catalog: Catalog,
+    configure: Option<RequestConfigurer>,
+}
+
+impl RequestScope {
+    pub fn new(catalog: Catalog) -> Self {
+        Self {
+            catalog,
+            configure: None,
+        }
+    }
+
+    /// Registers additional components in a child catalog created for every request
+    /// (see [`CatalogBuilder::new_chained()`]), e.g. the values derived from the request headers.
+    ///
+    /// Note that the components of the parent catalog can't depend on the per-request ones.
+    pub fn with_request_components<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(&mut CatalogBuilder, &ServiceRequest) + Send + Sync + 'static,
+    {
+        self.configure = Some(Arc::new(configure));
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestScope
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestScopeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestScopeMiddleware {
+            service,
+            catalog: self.catalog.clone(),
+            configure: self.configure.clone(),
+        }))
+    }
+}
+
+/// The service produced by the [`RequestScope`] middleware
+pub struct RequestScopeMiddleware<S> {
+    service: S,
+    catalog: Catalog,
+    configure: Option<RequestConfigurer>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestScopeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = S::Future;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let catalog = match &self.configure {
+            Some(configure) => {
+                let mut b = self.catalog.builder_chained();
+                configure(&mut b, &req);
+                b.build()
+            }
+            None => self.catalog.clone(),
+        };
+
+        req.extensions_mut().insert(catalog.enter_scope());
+        self.service.call(req)
+    }
+}
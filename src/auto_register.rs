@@ -0,0 +1,30 @@
+use crate::CatalogBuilder;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Registration of a component marked with `#[component(register)]`,
+/// see [`CatalogBuilder::auto_register()`]
+pub struct ComponentRegistration {
+    register: fn(&mut CatalogBuilder),
+}
+
+impl ComponentRegistration {
+    pub fn new(register: fn(&mut CatalogBuilder)) -> Self {
+        Self { register }
+    }
+}
+
+inventory::collect!(ComponentRegistration);
+
+impl CatalogBuilder {
+    /// Registers all components marked with `#[component(register)]` across all crates linked
+    /// into the binary, including their interface bindings.
+    ///
+    /// The order of registration is unspecified.
+    pub fn auto_register(&mut self) -> &mut Self {
+        for registration in inventory::iter::<ComponentRegistration> {
+            (registration.register)(self);
+        }
+        self
+    }
+}
@@ -0,0 +1,205 @@
+use std::{
+    ops::Deref,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use thiserror::Error;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{Catalog, CatalogBuilder, InjectionError};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Inject
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// An axum extractor that resolves a single implementation of the interface from the
+/// [`Catalog`] stored in the request extensions by the [`CatalogLayer`].
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// trait Greeter: Send + Sync {
+///     fn greet(&self) -> String;
+/// }
+///
+/// async fn hello(Inject(greeter): Inject<dyn Greeter>) -> String {
+///     greeter.greet()
+/// }
+/// ```
+pub struct Inject<Iface>(pub Arc<Iface>)
+where
+    Iface: 'static + ?Sized + Send + Sync;
+
+impl<Iface> Deref for Inject<Iface>
+where
+    Iface: 'static + ?Sized + Send + Sync,
+{
+    type Target = Arc<Iface>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S, Iface> FromRequestParts<S> for Inject<Iface>
+where
+    S: Send + Sync,
+    Iface: 'static + ?Sized + Send + Sync,
+{
+    type Rejection = InjectRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let catalog = parts
+            .extensions
+            .get::<Catalog>()
+            .ok_or(InjectRejection::MissingCatalog)?
+            .clone();
+
+        let instance = catalog
+            .get_one_async::<Iface>()
+            .await
+            .map_err(InjectRejection::Injection)?;
+
+        Ok(Self(instance))
+    }
+}
+
+/// Rejection of the [`Inject`] extractor, responds with `500 Internal Server Error`.
+///
+/// The response body is generic, as the error names the internal types of the application,
+/// while the details are logged with the `tracing` feature.
+#[derive(Error, Debug)]
+pub enum InjectRejection {
+    #[error("Catalog is missing from the request extensions, is CatalogLayer installed?")]
+    MissingCatalog,
+    #[error("Failed to inject the dependency: {0:?}")]
+    Injection(#[source] InjectionError),
+}
+
+impl IntoResponse for InjectRejection {
+    fn into_response(self) -> Response {
+        #[cfg(feature = "tracing")]
+        tracing::error!(error = %self, "Failed to handle the request");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// CatalogLayer
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Registers the per-request components, see [`CatalogLayer::with_request_components()`]
+type RequestConfigurer = Arc<dyn Fn(&mut CatalogBuilder, &Parts) + Send + Sync>;
+
+/// A tower layer that stores a per-request child of the catalog in the request extensions
+/// for the [`Inject`] extractor.
+///
+/// Every request [enters a new scope](Catalog::enter_scope()), so that the [`Scoped`][crate::Scoped]
+/// components are shared within the request only.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[derive(Clone)]
+/// struct RequestId(String);
+///
+/// let catalog = CatalogBuilder::new().build();
+///
+/// let layer = CatalogLayer::new(catalog).with_request_components(|b, parts| {
+///     let id = parts
+///         .headers
+///         .get("x-request-id")
+///         .and_then(|v| v.to_str().ok())
+///         .unwrap_or_default();
+///     b.add_value(RequestId(id.to_owned()));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct CatalogLayer {
+    catalog: Catalog,
+    configure: Option<RequestConfigurer>,
+}
+
+impl CatalogLayer {
+    pub fn new(catalog: Catalog) -> Self {
+        Self {
+            catalog,
+            configure: None,
+        }
+    }
+
+    /// Registers additional components in a child catalog created for every request
+    /// (see [`CatalogBuilder::new_chained()`]), e.g. the values derived from the request headers.
+    ///
+    /// Note that the components of the parent catalog can't depend on the per-request ones.
+    pub fn with_request_components<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(&mut CatalogBuilder, &Parts) + Send + Sync + 'static,
+    {
+        self.configure = Some(Arc::new(configure));
+        self
+    }
+}
+
+impl<S> Layer<S> for CatalogLayer {
+    type Service = CatalogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CatalogService {
+            inner,
+            catalog: self.catalog.clone(),
+            configure: self.configure.clone(),
+        }
+    }
+}
+
+/// The service produced by the [`CatalogLayer`]
+#[derive(Clone)]
+pub struct CatalogService<S> {
+    inner: S,
+    catalog: Catalog,
+    configure: Option<RequestConfigurer>,
+}
+
+impl<S, B> Service<Request<B>> for CatalogService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let (parts, body) = req.into_parts();
+
+        let catalog = match &self.configure {
+            Some(configure) => {
+                let mut b = self.catalog.builder_chained();
+                configure(&mut b, &parts);
+                b.build()
+            }
+            None => self.catalog.clone(),
+        };
+
+        let mut req = Request::from_parts(parts, body);
+        req.extensions_mut().insert(catalog.enter_scope());
+        self.inner.call(req)
+    }
+}
@@ -1,7 +1,4 @@
-use std::{
-    any::{Any, TypeId},
-    sync::Arc,
-};
+use std::{any::TypeId, future::Future, marker::PhantomData, pin::Pin, sync::Arc};
 
 use crate::*;
 
@@ -9,14 +6,188 @@ use crate::*;
 
 /// Builders are responsible for resolving dependencies and creating new instances of a certain type.
 /// Builders typically create new instances for every call, delegating the lifetime management to [`Scope`]s,
-pub trait Builder: Send + Sync {
+///
+/// Custom builders can implement this trait directly, see the crate docs for an example and
+/// the parts of the API that remain stable across releases.
+pub trait Builder: MaybeSendSync {
     fn instance_type_id(&self) -> TypeId;
     fn instance_type_name(&self) -> &'static str;
-    fn get(&self, cat: &Catalog) -> Result<Arc<dyn Any + Send + Sync>, InjectionError>;
+
+    /// Type name of the [`Scope`] controlling the lifetime of instances, e.g. `dill::scopes::Singleton`.
+    /// For user-defined scopes this is the name of the custom scope type.
+    /// Defaults to [`Transient`], i.e. for the builders that create a new instance on every call.
+    fn scope_name(&self) -> &'static str {
+        std::any::type_name::<Transient>()
+    }
+
+    /// Whether the scope was explicitly chosen for the component (e.g. via `#[scope(Singleton)]`)
+    /// as opposed to being a default one. Builders with default scope are subject to the catalog-wide
+    /// scope configured via [`CatalogBuilder::default_scope()`]. Defaults to `false`.
+    fn scope_was_explicit(&self) -> bool {
+        false
+    }
+
+    /// Whether the scope keeps a single instance for the entire catalog, see
+    /// [`Scope::is_catalog_wide()`]. Defaults to `false`.
+    fn is_catalog_wide(&self) -> bool {
+        false
+    }
+
+    /// Tags attached to the component, e.g. via `#[component(tags("startup"))]`.
+    /// Untagged components return an empty slice.
+    fn tags(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Profiles the component is registered in, e.g. via `#[component(profiles("test"))]`.
+    /// Components without profiles are registered regardless of the active profiles,
+    /// see [`CatalogBuilder::with_profile()`].
+    fn profiles(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// Whether the instance should be created when the catalog is built rather than upon first use,
+    /// e.g. via `#[component(eager)]`. See [`CatalogBuilder::try_build()`].
+    fn is_eager(&self) -> bool {
+        false
+    }
+
+    /// Priority of the component among the implementations of an interface, e.g. via
+    /// `#[component(priority = 10)]` or [`CatalogBuilder::add_with_priority()`]. [`OneOf`] resolves
+    /// the implementation with the highest priority, while [`AllOf`] returns all of them ordered
+    /// by priority. Defaults to `0`.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Dependencies that the builder will resolve from the catalog, used to validate the
+    /// dependency graph before resolving anything (see [`CatalogBuilder::validate()`]).
+    /// Builders without dependencies return an empty list.
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        Vec::new()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError>;
+
+    /// Returns the instance currently cached by the scope without constructing a new one,
+    /// e.g. to shut it down (see [`Catalog::shutdown()`]). Non-caching builders return `None`.
+    fn cached(&self, _cat: &Catalog) -> Option<Shared<SharedAny>> {
+        None
+    }
+
+    /// Drops the instance cached by the scope, see [`Catalog::invalidate()`].
+    /// Non-caching builders do nothing.
+    fn invalidate(&self, _cat: &Catalog) {}
+
+    /// Resolves dependencies and creates an instance asynchronously, which is required for
+    /// components with `async fn new()`. Defaults to the synchronous [`Builder::get()`].
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(std::future::ready(self.get(cat)))
+    }
+}
+
+/// Whether both pointers refer to the same builder. Compares the data pointers only, as the
+/// vtable addresses of the trait objects are not guaranteed to be unique.
+pub(crate) fn same_builder(a: &Arc<dyn Builder>, b: &Arc<dyn Builder>) -> bool {
+    std::ptr::eq(Arc::as_ptr(a) as *const (), Arc::as_ptr(b) as *const ())
+}
+
+pub trait TypedBuilder<T: MaybeSendSync>: Builder {
+    fn get(&self, cat: &Catalog) -> Result<Shared<T>, InjectionError>;
+}
+
+/// Asynchronous counterpart of [`TypedBuilder`], see [`Catalog::get_async()`]
+pub trait AsyncTypedBuilder<T: MaybeSendSync>: Builder {
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<T>, InjectionError>>;
+}
+
+/// A boxed future returned by asynchronous resolution methods
+#[cfg(not(feature = "unsync"))]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A boxed future returned by asynchronous resolution methods
+#[cfg(feature = "unsync")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// Function overriding an argument of a generated builder, see `with_<arg>_fn()` setters
+pub trait OverrideFn<T>: Fn(&Catalog) -> Result<T, InjectionError> + MaybeSendSync {}
+
+impl<T, F> OverrideFn<T> for F where F: Fn(&Catalog) -> Result<T, InjectionError> + MaybeSendSync {}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Selects how the generated builders take the values of owned arguments: cloned if the type is
+/// `Clone`, or moved otherwise, e.g. out of a fresh instance (see [`Owned`]). Dispatched via
+/// auto-ref, with `(&&ValueProbe::<T>::default()).cloner()` resolving to [`CloneValue`] first.
+#[doc(hidden)]
+pub struct ValueProbe<T>(PhantomData<T>);
+
+impl<T> Default for ValueProbe<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
 }
 
-pub trait TypedBuilder<T: Send + Sync>: Builder {
-    fn get(&self, cat: &Catalog) -> Result<Arc<T>, InjectionError>;
+#[doc(hidden)]
+pub trait CloneValue<T> {
+    fn cloner(&self) -> Option<fn(&T) -> T>;
+}
+
+impl<T: Clone> CloneValue<T> for &ValueProbe<T> {
+    fn cloner(&self) -> Option<fn(&T) -> T> {
+        Some(T::clone)
+    }
+}
+
+#[doc(hidden)]
+pub trait MoveValue<T> {
+    fn cloner(&self) -> Option<fn(&T) -> T>;
+}
+
+impl<T> MoveValue<T> for ValueProbe<T> {
+    fn cloner(&self) -> Option<fn(&T) -> T> {
+        None
+    }
+}
+
+/// Describes a dependency of a [`Builder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DependencyInfo {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub kind: DependencyKind,
+    /// Name of the binding for dependencies resolved via [`Catalog::get_named()`]
+    pub name: Option<&'static str>,
+}
+
+impl DependencyInfo {
+    pub fn of<Iface: 'static + ?Sized>(kind: DependencyKind, name: Option<&'static str>) -> Self {
+        Self {
+            type_id: TypeId::of::<Iface>(),
+            type_name: std::any::type_name::<Iface>(),
+            kind,
+            name,
+        }
+    }
+}
+
+/// Specifies how the dependency is resolved, see [`DependencyInfo`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    /// Exactly one implementation, see [`OneOf`]
+    One,
+    /// Any number of implementations, see [`AllOf`]
+    All,
+    /// At most one implementation, see [`Maybe`]
+    Maybe,
+    /// Exactly one implementation, resolved upon first use, see [`Lazy`] and [`Factory`]
+    Lazy,
 }
 
 /// Allows [`CatalogBuilder::add()`] to accept both impl types with associated builder and custom builders
@@ -26,6 +197,169 @@ pub trait BuilderLike {
     fn builder() -> Self::Builder;
 }
 
+/// Implemented by the catalogs declared via the [`catalog!`] macro for every type they provide
+pub trait Provides<T: ?Sized> {}
+
+/// Implemented by the builders generated via `#[component]` for every catalog that provides
+/// all dependencies the component requires, which allows [`catalog!`] to check them at compile time
+pub trait StaticDependencies<Cat> {}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Typed helpers available on every [`Builder`], including the type-erased ones returned by
+/// [`Catalog::builders()`]
+pub trait BuilderExt: Builder {
+    /// Whether the builder constructs instances of type `T`
+    fn builds<T: 'static + ?Sized>(&self) -> bool {
+        self.instance_type_id() == TypeId::of::<T>()
+    }
+
+    /// Resolves an instance via [`Builder::get()`] and downcasts it to the concrete type.
+    ///
+    /// Panics if the builder constructs instances of a different type, see [`BuilderExt::builds()`].
+    fn get_instance<T: 'static + MaybeSendSync>(
+        &self,
+        cat: &Catalog,
+    ) -> Result<Shared<T>, InjectionError> {
+        assert!(
+            self.builds::<T>(),
+            "Builder of {} can't construct instances of {}",
+            self.instance_type_name(),
+            std::any::type_name::<T>()
+        );
+        Ok(self.get(cat)?.downcast().unwrap())
+    }
+
+    /// Wraps the builder into one that transforms every instance it resolves, e.g. to add
+    /// telemetry to a single component at registration time without declaring a decorator
+    /// for the whole interface (see [`CatalogBuilder::add_decorator()`]).
+    ///
+    /// The function is invoked upon every resolution, while the wrapped instance itself
+    /// is still subject to the scope of the original builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// struct Client;
+    ///
+    /// struct Traced<T>(Arc<T>);
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add_builder(Client::builder().map(|inst| Arc::new(Traced(inst))))
+    ///     .build();
+    ///
+    /// assert!(cat.get_one::<Traced<Client>>().is_ok());
+    /// assert!(cat.get_one::<Client>().is_err());
+    /// ```
+    fn map<T, U, F>(self, fun: F) -> MapBuilder<Self, T, U, F>
+    where
+        Self: Sized + TypedBuilder<T>,
+        T: 'static + MaybeSendSync,
+        U: 'static + MaybeSendSync,
+        F: Fn(Shared<T>) -> Shared<U> + MaybeSendSync,
+    {
+        MapBuilder::new(self, fun)
+    }
+
+    /// Wraps the builder into one that invokes the function before every resolution, e.g. to
+    /// check a precondition or to log the access. An error returned by the function fails
+    /// the resolution without invoking the original builder.
+    fn before_build<F>(self, fun: F) -> BeforeBuildBuilder<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&Catalog) -> Result<(), InjectionError> + MaybeSendSync,
+    {
+        BeforeBuildBuilder::new(self, fun)
+    }
+}
+
+impl<B: Builder + ?Sized> BuilderExt for B {}
+
+/// Caches the instances of a hand-written builder in a [`Scope`] the same way the builders
+/// generated via `#[component]` do, including the notifications of the
+/// [`ResolutionObserver`]s and the `tracing` spans.
+///
+/// See the crate docs for an example of a custom builder.
+pub struct ScopedInstance<S: Scope> {
+    scope: S,
+}
+
+impl<S: Scope> ScopedInstance<S> {
+    pub fn new(scope: S) -> Self {
+        Self { scope }
+    }
+
+    pub fn scope(&self) -> &S {
+        &self.scope
+    }
+
+    /// Type name of the scope, to be returned from [`Builder::scope_name()`]
+    pub fn scope_name(&self) -> &'static str {
+        std::any::type_name::<S>()
+    }
+
+    /// To be returned from [`Builder::is_catalog_wide()`]
+    pub fn is_catalog_wide(&self) -> bool {
+        self.scope.is_catalog_wide()
+    }
+
+    /// Returns the instance cached by the scope or constructs a new one using `build`,
+    /// to be called from [`TypedBuilder::get()`]
+    pub fn get_or_build<T>(
+        &self,
+        builder: &dyn Builder,
+        cat: &Catalog,
+        build: impl Fn() -> Result<T, InjectionError>,
+    ) -> Result<Shared<T>, InjectionError>
+    where
+        T: 'static + MaybeSendSync,
+    {
+        let inst = instrument::get_or_build(builder, &self.scope, cat, &|| {
+            let inst: Shared<SharedAny> = Shared::new(build()?);
+            Ok(inst)
+        })?;
+        Ok(inst.downcast().unwrap())
+    }
+
+    /// Asynchronous counterpart of [`ScopedInstance::get_or_build()`], to be called from
+    /// [`AsyncTypedBuilder::get_async()`]. Unlike the synchronous resolution this does not
+    /// guarantee that the instance is constructed exactly once, only that all callers get the
+    /// same instance, see [`Scope::set_if_absent()`].
+    pub async fn get_or_build_async<T, F>(
+        &self,
+        builder: &dyn Builder,
+        cat: &Catalog,
+        build: F,
+    ) -> Result<Shared<T>, InjectionError>
+    where
+        T: 'static + MaybeSendSync,
+        F: Future<Output = Result<T, InjectionError>>,
+    {
+        if let Some(inst) = self.scope.get(cat) {
+            instrument::cache_hit(builder, cat);
+            return Ok(inst.downcast().unwrap());
+        }
+
+        let inst: Shared<SharedAny> =
+            Shared::new(instrument::build_async(builder, cat, build).await?);
+        Ok(self.scope.set_if_absent(cat, inst)?.downcast().unwrap())
+    }
+
+    /// To be returned from [`Builder::cached()`]
+    pub fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.scope.get(cat)
+    }
+
+    /// To be called from [`Builder::invalidate()`]
+    pub fn invalidate(&self, cat: &Catalog) {
+        self.scope.invalidate(cat)
+    }
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Used to create an instance of a default builder for a component.
@@ -57,29 +391,29 @@ pub fn builder_for<B: BuilderLike>() -> B::Builder {
 
 pub struct Prebuilt<T>
 where
-    T: 'static + Send + Sync,
+    T: 'static + MaybeSendSync,
 {
-    value: Arc<T>,
+    value: Shared<T>,
 }
 
 impl<T> Prebuilt<T>
 where
-    T: 'static + Send + Sync,
+    T: 'static + MaybeSendSync,
 {
     pub fn from_value(value: T) -> Self {
         Self {
-            value: Arc::new(value),
+            value: Shared::new(value),
         }
     }
 
-    pub fn from_shared(value: Arc<T>) -> Self {
+    pub fn from_shared(value: Shared<T>) -> Self {
         Self { value }
     }
 }
 
 impl<T> Builder for Prebuilt<T>
 where
-    T: 'static + Send + Sync,
+    T: 'static + MaybeSendSync,
 {
     fn instance_type_id(&self) -> TypeId {
         TypeId::of::<T>()
@@ -89,16 +423,32 @@ where
         std::any::type_name::<T>()
     }
 
-    fn get(&self, _cat: &Catalog) -> Result<Arc<dyn Any + Send + Sync>, InjectionError> {
+    fn scope_name(&self) -> &'static str {
+        std::any::type_name::<Singleton>()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        true
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        true
+    }
+
+    fn get(&self, _cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
         Ok(self.value.clone())
     }
+
+    fn cached(&self, _cat: &Catalog) -> Option<Shared<SharedAny>> {
+        Some(self.value.clone())
+    }
 }
 
 impl<T> TypedBuilder<T> for Prebuilt<T>
 where
-    T: 'static + Send + Sync,
+    T: 'static + MaybeSendSync,
 {
-    fn get(&self, _cat: &Catalog) -> Result<Arc<T>, InjectionError> {
+    fn get(&self, _cat: &Catalog) -> Result<Shared<T>, InjectionError> {
         Ok(self.value.clone())
     }
 }
@@ -106,28 +456,29 @@ where
 /////////////////////////////////////////////////////////////////////////////////////////
 
 // TODO: Implement Builder trait for any `Fn() -> T` without wrapping?
-pub struct Factory<Fct, Impl>
+/// Builds instances by invoking a closure without dependencies, see [`CatalogBuilder::add_factory()`]
+pub struct FactoryBuilder<Fct, Impl>
 where
     Fct: Fn() -> Impl,
-    Impl: 'static + Send + Sync,
+    Impl: 'static + MaybeSendSync,
 {
     factory: Fct,
 }
 
-impl<Fct, Impl> Factory<Fct, Impl>
+impl<Fct, Impl> FactoryBuilder<Fct, Impl>
 where
     Fct: Fn() -> Impl,
-    Impl: 'static + Send + Sync,
+    Impl: 'static + MaybeSendSync,
 {
     pub fn new(factory: Fct) -> Self {
         Self { factory }
     }
 }
 
-impl<Fct, Impl> Builder for Factory<Fct, Impl>
+impl<Fct, Impl> Builder for FactoryBuilder<Fct, Impl>
 where
-    Fct: Fn() -> Impl + Send + Sync,
-    Impl: 'static + Send + Sync,
+    Fct: Fn() -> Impl + MaybeSendSync,
+    Impl: 'static + MaybeSendSync,
 {
     fn instance_type_id(&self) -> TypeId {
         TypeId::of::<Impl>()
@@ -137,17 +488,496 @@ where
         std::any::type_name::<Impl>()
     }
 
-    fn get(&self, _cat: &Catalog) -> Result<Arc<dyn Any + Send + Sync>, InjectionError> {
-        Ok(Arc::new((self.factory)()))
+    fn scope_name(&self) -> &'static str {
+        std::any::type_name::<Transient>()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        true
+    }
+
+    fn get(&self, _cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        Ok(Shared::new((self.factory)()))
+    }
+}
+
+impl<Fct, Impl> TypedBuilder<Impl> for FactoryBuilder<Fct, Impl>
+where
+    Fct: Fn() -> Impl + MaybeSendSync,
+    Impl: 'static + MaybeSendSync,
+{
+    fn get(&self, _cat: &Catalog) -> Result<Shared<Impl>, InjectionError> {
+        Ok(Shared::new((self.factory)()))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Builds instances by invoking a closure that can resolve dependencies from the [`Catalog`],
+/// see [`CatalogBuilder::add_builder_fn()`]
+pub struct FnBuilder<Fct, Impl>
+where
+    Fct: Fn(&Catalog) -> Result<Impl, InjectionError>,
+    Impl: 'static + MaybeSendSync,
+{
+    fun: Fct,
+}
+
+impl<Fct, Impl> FnBuilder<Fct, Impl>
+where
+    Fct: Fn(&Catalog) -> Result<Impl, InjectionError>,
+    Impl: 'static + MaybeSendSync,
+{
+    pub fn new(fun: Fct) -> Self {
+        Self { fun }
+    }
+}
+
+impl<Fct, Impl> Builder for FnBuilder<Fct, Impl>
+where
+    Fct: Fn(&Catalog) -> Result<Impl, InjectionError> + MaybeSendSync,
+    Impl: 'static + MaybeSendSync,
+{
+    fn instance_type_id(&self) -> TypeId {
+        TypeId::of::<Impl>()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        std::any::type_name::<Impl>()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        std::any::type_name::<Transient>()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        true
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        Ok(Shared::new((self.fun)(cat)?))
+    }
+}
+
+impl<Fct, Impl> TypedBuilder<Impl> for FnBuilder<Fct, Impl>
+where
+    Fct: Fn(&Catalog) -> Result<Impl, InjectionError> + MaybeSendSync,
+    Impl: 'static + MaybeSendSync,
+{
+    fn get(&self, cat: &Catalog) -> Result<Shared<Impl>, InjectionError> {
+        Ok(Shared::new((self.fun)(cat)?))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Transforms the instances of the inner builder, see [`BuilderExt::map()`]
+pub struct MapBuilder<Bld, T, U, F> {
+    inner: Bld,
+    fun: F,
+    _types: PhantomData<fn(Shared<T>) -> Shared<U>>,
+}
+
+impl<Bld, T, U, F> MapBuilder<Bld, T, U, F>
+where
+    Bld: TypedBuilder<T>,
+    T: 'static + MaybeSendSync,
+    U: 'static + MaybeSendSync,
+    F: Fn(Shared<T>) -> Shared<U> + MaybeSendSync,
+{
+    pub fn new(inner: Bld, fun: F) -> Self {
+        Self {
+            inner,
+            fun,
+            _types: PhantomData,
+        }
+    }
+
+    fn map(&self, inst: Shared<SharedAny>) -> Shared<SharedAny> {
+        // SAFETY: the inner builder is guaranteed to construct the instances of `T`
+        (self.fun)(inst.downcast().unwrap())
+    }
+}
+
+impl<Bld, T, U, F> Builder for MapBuilder<Bld, T, U, F>
+where
+    Bld: TypedBuilder<T>,
+    T: 'static + MaybeSendSync,
+    U: 'static + MaybeSendSync,
+    F: Fn(Shared<T>) -> Shared<U> + MaybeSendSync,
+{
+    fn instance_type_id(&self) -> TypeId {
+        TypeId::of::<U>()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        std::any::type_name::<U>()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        self.inner.scope_name()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        self.inner.scope_was_explicit()
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.inner.is_catalog_wide()
+    }
+
+    fn tags(&self) -> &[&'static str] {
+        self.inner.tags()
+    }
+
+    fn profiles(&self) -> &[&'static str] {
+        self.inner.profiles()
+    }
+
+    fn is_eager(&self) -> bool {
+        self.inner.is_eager()
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        self.inner.dependencies()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        Ok(self.map(Builder::get(&self.inner, cat)?))
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.inner.cached(cat).map(|inst| self.map(inst))
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        self.inner.invalidate(cat)
+    }
+
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(async move { Ok(self.map(Builder::get_async(&self.inner, cat).await?)) })
+    }
+}
+
+impl<Bld, T, U, F> TypedBuilder<U> for MapBuilder<Bld, T, U, F>
+where
+    Bld: TypedBuilder<T>,
+    T: 'static + MaybeSendSync,
+    U: 'static + MaybeSendSync,
+    F: Fn(Shared<T>) -> Shared<U> + MaybeSendSync,
+{
+    fn get(&self, cat: &Catalog) -> Result<Shared<U>, InjectionError> {
+        Ok((self.fun)(TypedBuilder::get(&self.inner, cat)?))
+    }
+}
+
+impl<Bld, T, U, F> AsyncTypedBuilder<U> for MapBuilder<Bld, T, U, F>
+where
+    Bld: TypedBuilder<T> + AsyncTypedBuilder<T>,
+    T: 'static + MaybeSendSync,
+    U: 'static + MaybeSendSync,
+    F: Fn(Shared<T>) -> Shared<U> + MaybeSendSync,
+{
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<U>, InjectionError>> {
+        Box::pin(async move {
+            Ok((self.fun)(
+                AsyncTypedBuilder::get_async(&self.inner, cat).await?,
+            ))
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Invokes a function before every resolution via the inner builder,
+/// see [`BuilderExt::before_build()`]
+pub struct BeforeBuildBuilder<Bld, F> {
+    inner: Bld,
+    fun: F,
+}
+
+impl<Bld, F> BeforeBuildBuilder<Bld, F>
+where
+    Bld: Builder,
+    F: Fn(&Catalog) -> Result<(), InjectionError> + MaybeSendSync,
+{
+    pub fn new(inner: Bld, fun: F) -> Self {
+        Self { inner, fun }
+    }
+}
+
+impl<Bld, F> Builder for BeforeBuildBuilder<Bld, F>
+where
+    Bld: Builder,
+    F: Fn(&Catalog) -> Result<(), InjectionError> + MaybeSendSync,
+{
+    fn instance_type_id(&self) -> TypeId {
+        self.inner.instance_type_id()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        self.inner.instance_type_name()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        self.inner.scope_name()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        self.inner.scope_was_explicit()
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.inner.is_catalog_wide()
+    }
+
+    fn tags(&self) -> &[&'static str] {
+        self.inner.tags()
+    }
+
+    fn profiles(&self) -> &[&'static str] {
+        self.inner.profiles()
+    }
+
+    fn is_eager(&self) -> bool {
+        self.inner.is_eager()
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        self.inner.dependencies()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        (self.fun)(cat)?;
+        self.inner.get(cat)
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.inner.cached(cat)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        self.inner.invalidate(cat)
+    }
+
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(async move {
+            (self.fun)(cat)?;
+            self.inner.get_async(cat).await
+        })
+    }
+}
+
+impl<Bld, F, T> TypedBuilder<T> for BeforeBuildBuilder<Bld, F>
+where
+    Bld: TypedBuilder<T>,
+    F: Fn(&Catalog) -> Result<(), InjectionError> + MaybeSendSync,
+    T: MaybeSendSync,
+{
+    fn get(&self, cat: &Catalog) -> Result<Shared<T>, InjectionError> {
+        (self.fun)(cat)?;
+        TypedBuilder::get(&self.inner, cat)
     }
 }
 
-impl<Fct, Impl> TypedBuilder<Impl> for Factory<Fct, Impl>
+impl<Bld, F, T> AsyncTypedBuilder<T> for BeforeBuildBuilder<Bld, F>
 where
-    Fct: Fn() -> Impl + Send + Sync,
-    Impl: 'static + Send + Sync,
+    Bld: AsyncTypedBuilder<T>,
+    F: Fn(&Catalog) -> Result<(), InjectionError> + MaybeSendSync,
+    T: MaybeSendSync,
 {
-    fn get(&self, _cat: &Catalog) -> Result<Arc<Impl>, InjectionError> {
-        Ok(Arc::new((self.factory)()))
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<T>, InjectionError>> {
+        Box::pin(async move {
+            (self.fun)(cat)?;
+            AsyncTypedBuilder::get_async(&self.inner, cat).await
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Overrides the scope of the inner builder, which is expected to be [`Transient`]
+pub(crate) struct ScopedBuilder {
+    inner: Arc<dyn Builder>,
+    scope: Box<dyn Scope>,
+    scope_name: &'static str,
+}
+
+impl ScopedBuilder {
+    pub(crate) fn new(
+        inner: Arc<dyn Builder>,
+        scope: Box<dyn Scope>,
+        scope_name: &'static str,
+    ) -> Self {
+        Self {
+            inner,
+            scope,
+            scope_name,
+        }
+    }
+}
+
+impl Builder for ScopedBuilder {
+    fn instance_type_id(&self) -> TypeId {
+        self.inner.instance_type_id()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        self.inner.instance_type_name()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        self.scope_name
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        true
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.scope.is_catalog_wide()
+    }
+
+    fn tags(&self) -> &[&'static str] {
+        self.inner.tags()
+    }
+
+    fn profiles(&self) -> &[&'static str] {
+        self.inner.profiles()
+    }
+
+    fn is_eager(&self) -> bool {
+        self.inner.is_eager()
+    }
+
+    fn priority(&self) -> i32 {
+        self.inner.priority()
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        self.inner.dependencies()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        // Construction is reported by the inner builder
+        instrument::get_or_init(self, self.scope.as_ref(), cat, &|| self.inner.get(cat))
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.scope.get(cat)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        self.scope.invalidate(cat)
+    }
+
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(async move {
+            if let Some(inst) = self.scope.get(cat) {
+                instrument::cache_hit(self, cat);
+                return Ok(inst);
+            }
+
+            let inst = self.inner.get_async(cat).await?;
+            self.scope.set_if_absent(cat, inst)
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Overrides the priority of the inner builder, see [`CatalogBuilder::add_with_priority()`]
+pub(crate) struct PrioritizedBuilder {
+    inner: Arc<dyn Builder>,
+    priority: i32,
+}
+
+impl PrioritizedBuilder {
+    pub(crate) fn new(inner: Arc<dyn Builder>, priority: i32) -> Self {
+        Self { inner, priority }
+    }
+}
+
+impl Builder for PrioritizedBuilder {
+    fn instance_type_id(&self) -> TypeId {
+        self.inner.instance_type_id()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        self.inner.instance_type_name()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        self.inner.scope_name()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        self.inner.scope_was_explicit()
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.inner.is_catalog_wide()
+    }
+
+    fn tags(&self) -> &[&'static str] {
+        self.inner.tags()
+    }
+
+    fn profiles(&self) -> &[&'static str] {
+        self.inner.profiles()
+    }
+
+    fn is_eager(&self) -> bool {
+        self.inner.is_eager()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        self.inner.dependencies()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        self.inner.get(cat)
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.inner.cached(cat)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        self.inner.invalidate(cat)
+    }
+
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        self.inner.get_async(cat)
     }
 }
@@ -0,0 +1,45 @@
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// A set of related dependencies resolved at once, implemented via `#[derive(Inject)]`,
+/// see [`Catalog::get_bundle()`]
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+/// use std::sync::Arc;
+///
+/// trait Auth: Send + Sync {}
+/// trait Audit: Send + Sync {}
+///
+/// #[component]
+/// #[interface(dyn Auth)]
+/// struct AuthImpl;
+/// impl Auth for AuthImpl {}
+///
+/// #[derive(Inject)]
+/// struct Handlers {
+///     auth: Arc<dyn Auth>,
+///     audits: Vec<Arc<dyn Audit>>,
+/// }
+///
+/// let cat = CatalogBuilder::new().add::<AuthImpl>().build();
+///
+/// let handlers = cat.get_bundle::<Handlers>().unwrap();
+/// assert!(handlers.audits.is_empty());
+/// ```
+pub trait Bundle: Sized {
+    fn resolve(cat: &Catalog) -> Result<Self, InjectionError>;
+}
+
+impl Catalog {
+    /// Resolves all fields of the bundle, failing on the first one that can't be resolved
+    pub fn get_bundle<B>(&self) -> Result<B, InjectionError>
+    where
+        B: Bundle,
+    {
+        B::resolve(self)
+    }
+}
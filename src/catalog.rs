@@ -1,7 +1,20 @@
-use std::{any::TypeId, collections::HashMap, sync::Arc};
+use std::{
+    any::{type_name, TypeId},
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    future::Future,
+    marker::Unsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+    time::Instant,
+};
 
 use multimap::MultiMap;
 
+use crate::sync::{lock, Mutex};
+use crate::type_map::TypeIdMap;
 use crate::*;
 
 /////////////////////////////////////////////////////////////////////////////////////////
@@ -12,27 +25,1066 @@ pub(crate) struct IfaceTypeId(pub TypeId);
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub(crate) struct ImplTypeId(pub TypeId);
 
+/// Whether the dependency is injectable without a registration, like the [`Catalog`] itself
+pub(crate) fn is_builtin_dependency(type_id: TypeId) -> bool {
+    type_id == TypeId::of::<Catalog>() || type_id == TypeId::of::<ShutdownToken>()
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
+/// Cloning the catalog is cheap and results in a handle to the same catalog, sharing all
+/// registrations and instances cached by the [`Scope`]s. The catalog is `Send + Sync`, so
+/// the handles can be moved into threads, async tasks or a web framework's shared state as is
+/// (unless the `unsync` feature is enabled, see [`Shared`]).
 #[derive(Clone)]
-pub struct Catalog(Arc<CatalogInner>);
+pub struct Catalog {
+    inner: Arc<CatalogInner>,
+    /// Type currently being resolved, used to detect dependency cycles
+    frame: Option<Arc<ResolutionFrame>>,
+    /// Instances cached by the [`Scoped`] scope, see [`Catalog::enter_scope()`]
+    scope_context: Option<Arc<ScopeCache>>,
+    /// Instances of [`Transient`] components memoized for the duration of the top-level resolution,
+    /// see [`CatalogBuilder::memoize_transients()`]
+    resolution_context: Option<Arc<ScopeCache>>,
+    /// Values attached to the handle, see [`Catalog::with_context()`]
+    context: Option<Arc<ContextValue>>,
+    /// Frames of the resolution limited in time, see [`Catalog::get_async_timeout()`]
+    watch: Option<Arc<ResolutionWatch>>,
+}
+
+/// Forms a linked list of types that are currently being resolved, from the innermost to the outermost
+struct ResolutionFrame {
+    type_id: TypeId,
+    type_name: &'static str,
+    /// Number of frames in the list, including this one
+    depth: usize,
+    parent: Option<Arc<ResolutionFrame>>,
+}
+
+impl ResolutionFrame {
+    fn iter(&self) -> impl Iterator<Item = &ResolutionFrame> {
+        std::iter::successors(Some(self), |f| f.parent.as_deref())
+    }
+}
+
+/// Tracks the frames of a resolution to report where it got stuck, see [`Catalog::get_async_timeout()`]
+#[derive(Default)]
+pub(crate) struct ResolutionWatch {
+    frames: std::sync::Mutex<Vec<Weak<ResolutionFrame>>>,
+}
+
+impl ResolutionWatch {
+    fn track(&self, frame: &Arc<ResolutionFrame>) {
+        let mut frames = self.frames.lock().unwrap();
+        frames.retain(|f| f.strong_count() != 0);
+        frames.push(Arc::downgrade(frame));
+    }
+
+    /// Returns the deepest chain of types that are still being resolved, from the outermost one
+    pub(crate) fn stuck_chain(&self) -> Vec<&'static str> {
+        let frames = self.frames.lock().unwrap();
+        let deepest = frames
+            .iter()
+            .filter_map(|f| f.upgrade())
+            .max_by_key(|f| f.depth);
+
+        let mut chain: Vec<_> = deepest
+            .iter()
+            .flat_map(|f| f.iter())
+            .map(|f| f.type_name)
+            .collect();
+        chain.reverse();
+        chain
+    }
+}
+
+/// Casts the instance returned by [`CatalogBuilder::on_unresolved()`] handlers, which is
+/// `Shared<Shared<T>>` for unsized types and either `Shared<T>` or `Shared<Shared<T>>` for
+/// the sized ones
+trait FromUnresolved {
+    fn from_unresolved(inst: Shared<SharedAny>) -> Option<Shared<Self>>;
+}
+
+impl<T: 'static + ?Sized + MaybeSendSync> FromUnresolved for T {
+    default fn from_unresolved(inst: Shared<SharedAny>) -> Option<Shared<Self>> {
+        inst.downcast::<Shared<T>>()
+            .ok()
+            .map(|inst| inst.as_ref().clone())
+    }
+}
+
+impl<T: 'static + MaybeSendSync> FromUnresolved for T {
+    fn from_unresolved(inst: Shared<SharedAny>) -> Option<Shared<Self>> {
+        match inst.downcast::<T>() {
+            Ok(inst) => Some(inst),
+            Err(inst) => inst
+                .downcast::<Shared<T>>()
+                .ok()
+                .map(|inst| inst.as_ref().clone()),
+        }
+    }
+}
+
+/// Forms a linked list of the context values, from the most recently attached to the oldest
+struct ContextValue {
+    value: Shared<SharedAny>,
+    parent: Option<Arc<ContextValue>>,
+}
+
+impl ContextValue {
+    fn iter(&self) -> impl Iterator<Item = &ContextValue> {
+        std::iter::successors(Some(self), |v| v.parent.as_deref())
+    }
+}
+
+thread_local! {
+    /// Types resolved synchronously on the current thread, from the outermost to the innermost.
+    /// Unlike [`ResolutionFrame`]s these are not lost by the detached handles, e.g. the ones
+    /// used by [`Lazy::get()`] and [`Factory::create()`] calls inside the constructors.
+    static RESOLUTION_STACK: std::cell::RefCell<Vec<&'static str>> = std::cell::RefCell::new(Vec::new());
+}
+
+/// Keeps the type on the resolution stack of the current thread until dropped,
+/// see [`Catalog::guard_depth()`]
+pub(crate) struct DepthGuard;
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        RESOLUTION_STACK.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+/// Shuts down and drops the instances of the scope entered by [`Catalog::scope()`] when dropped,
+/// unless the scope was already shut down asynchronously
+struct ScopeGuard {
+    cat: Catalog,
+    shut_down: bool,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        if !self.shut_down {
+            self.cat.shutdown_scoped();
+        }
+        if let Some(ctx) = self.cat.scope_context() {
+            ctx.clear();
+        }
+    }
+}
 
-#[derive(Clone)]
 struct CatalogInner {
-    builders: HashMap<ImplTypeId, Arc<dyn Builder>>,
-    bindings: MultiMap<IfaceTypeId, Binding>,
+    /// See [`Catalog::id()`]
+    id: usize,
+    registry: Arc<Registry>,
+    scope_cache: ScopeCache,
+    /// See [`Catalog::shutdown_token()`]
+    shutdown_token: ShutdownToken,
+}
+
+impl CatalogInner {
+    fn new(registry: Arc<Registry>) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let shutdown_token = match &registry.config.parent {
+            Some(parent) => parent.shutdown_token().child(),
+            None => ShutdownToken::new(),
+        };
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            scope_cache: ScopeCache::with_limits(registry.config.cache_limits),
+            registry,
+            shutdown_token,
+        }
+    }
+}
+
+// Frozen upon build, so that the lookups don't require any synchronization
+struct Registry {
+    builders: TypeIdMap<ImplTypeId, Arc<dyn Builder>>,
+    /// See [`DuplicatePolicy::Append`]
+    appended: Vec<Arc<dyn Builder>>,
+    bindings: TypeIdMap<IfaceTypeId, Vec<Binding>>,
+    /// The only mutable part, only consulted for the interfaces that have no bindings,
+    /// see [`OpenGeneric`]
+    open_generics: Mutex<TypeIdMap<IfaceTypeId, Binding>>,
+    config: RegistryConfig,
 }
 
 impl Catalog {
     pub(crate) fn new(
         builders: HashMap<ImplTypeId, Arc<dyn Builder>>,
+        appended: Vec<Arc<dyn Builder>>,
         bindings: MultiMap<IfaceTypeId, Binding>,
+        mut config: RegistryConfig,
     ) -> Self {
-        Self(Arc::new(CatalogInner { builders, bindings }))
+        config.parent = config.parent.map(|p| p.detached());
+        Self {
+            inner: Arc::new(CatalogInner::new(Arc::new(Registry {
+                builders: builders.into_iter().collect(),
+                appended,
+                bindings: bindings.into_iter().collect(),
+                open_generics: Mutex::new(TypeIdMap::default()),
+                config,
+            }))),
+            frame: None,
+            scope_context: None,
+            resolution_context: None,
+            context: None,
+            watch: None,
+        }
+    }
+
+    /// Creates a new catalog that shares all registrations with this one, but has its own
+    /// independent state of [`Scope`]s, so e.g. [`Singleton`] instances are not shared between
+    /// the snapshot and the original.
+    ///
+    /// Registrations are not copied, so taking a snapshot is cheap regardless of catalog size.
+    #[must_use]
+    pub fn snapshot(&self) -> Self {
+        Self {
+            inner: Arc::new(CatalogInner::new(self.inner.registry.clone())),
+            frame: None,
+            scope_context: None,
+            resolution_context: None,
+            context: None,
+            watch: None,
+        }
+    }
+
+    /// Returns a handle to the same catalog that is not associated with any in-flight resolution,
+    /// e.g. for components that receive the catalog as a dependency and use it later.
+    /// The handle stays within the current [`Scoped`] scope and keeps its context values.
+    #[must_use]
+    pub fn detached(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            frame: None,
+            scope_context: self.scope_context.clone(),
+            resolution_context: None,
+            context: self.context.clone(),
+            watch: None,
+        }
+    }
+
+    /// Returns a handle to an empty catalog that resolves the dependencies of the component built
+    /// without a catalog, see `build_standalone()` of the generated builders
+    #[doc(hidden)]
+    pub fn standalone(builder: &dyn Builder) -> Result<Self, InjectionError> {
+        CatalogBuilder::new().build().enter(builder)
+    }
+
+    /// Returns a handle of the `owner` catalog that continues the in-flight resolution and stays
+    /// within the current [`Scoped`] scope and context of this handle
+    pub(crate) fn rebased(&self, owner: &Catalog) -> Self {
+        Self {
+            inner: owner.inner.clone(),
+            frame: self.frame.clone(),
+            scope_context: self.scope_context.clone(),
+            resolution_context: self.resolution_context.clone(),
+            context: self.context.clone(),
+            watch: self.watch.clone(),
+        }
     }
 
+    /// Starts a new logical scope (e.g. an HTTP request or a task), returning a handle that caches
+    /// instances of [`Scoped`] components for as long as it (or any of its clones) is alive.
+    ///
+    /// Entering a scope from a scoped handle starts a new, nested scope that doesn't share instances
+    /// with the outer one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// #[scope(Scoped)]
+    /// struct RequestState;
+    ///
+    /// let cat = CatalogBuilder::new().add::<RequestState>().build();
+    ///
+    /// let request_cat = cat.enter_scope();
+    /// let s1 = request_cat.get_one::<RequestState>().unwrap();
+    /// let s2 = request_cat.get_one::<RequestState>().unwrap();
+    /// assert!(std::sync::Arc::ptr_eq(&s1, &s2));
+    ///
+    /// let s3 = cat.enter_scope().get_one::<RequestState>().unwrap();
+    /// assert!(!std::sync::Arc::ptr_eq(&s1, &s3));
+    /// ```
+    #[must_use]
+    pub fn enter_scope(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            frame: self.frame.clone(),
+            scope_context: Some(Arc::new(ScopeCache::new())),
+            resolution_context: self.resolution_context.clone(),
+            context: self.context.clone(),
+            watch: self.watch.clone(),
+        }
+    }
+
+    /// Runs the closure with a handle of a new scope (see [`Catalog::enter_scope()`]), shutting down
+    /// the instances cached within the scope (see [`Catalog::shutdown()`]) and dropping them from
+    /// the scope when the closure returns or panics. Instances of the outer scopes and singletons
+    /// are left intact.
+    ///
+    /// Clones of the handle that outlive the closure can still be used, but resolve new instances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// static CLOSED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// #[component]
+    /// #[scope(Scoped)]
+    /// #[interface(dyn Shutdown)]
+    /// struct Transaction;
+    ///
+    /// impl Shutdown for Transaction {
+    ///     fn shutdown(&self) {
+    ///         CLOSED.store(true, Ordering::SeqCst);
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add::<Transaction>().build();
+    ///
+    /// let tx = cat.scope(|cat| cat.get_one::<Transaction>().unwrap());
+    /// assert!(CLOSED.load(Ordering::SeqCst));
+    /// assert_eq!(Arc::strong_count(&tx), 1);
+    /// ```
+    pub fn scope<R>(&self, f: impl FnOnce(&Catalog) -> R) -> R {
+        let guard = ScopeGuard {
+            cat: self.enter_scope(),
+            shut_down: false,
+        };
+        f(&guard.cat)
+    }
+
+    /// Asynchronous counterpart of [`Catalog::scope()`] that runs the future returned by the
+    /// closure and shuts down the instances of the scope via [`Catalog::shutdown_async()`].
+    ///
+    /// When the future panics or is dropped before completion (e.g. cancelled by a timeout)
+    /// the instances are still shut down and dropped, but only the [`Shutdown`] hooks are run,
+    /// as [`AsyncShutdown`] can't be awaited on drop.
+    pub async fn scope_async<F, Fut, R>(&self, f: F) -> R
+    where
+        F: FnOnce(Catalog) -> Fut,
+        Fut: Future<Output = R>,
+    {
+        let mut guard = ScopeGuard {
+            cat: self.enter_scope(),
+            shut_down: false,
+        };
+        let res = f(guard.cat.clone()).await;
+
+        guard.cat.shutdown_scoped_async().await;
+        guard.shut_down = true;
+        res
+    }
+
+    /// Returns a handle to the same catalog with the value attached to it, e.g. the id of the
+    /// tenant or the user on whose behalf the components are resolved. The value is visible
+    /// to all resolutions made through the handle (see [`Catalog::context()`]) and selects
+    /// the instances cached by the [`Keyed`] scope.
+    ///
+    /// Attaching a value of the type that is already attached shadows the previous one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    /// struct TenantId(u64);
+    ///
+    /// let cat = CatalogBuilder::new().build();
+    /// assert_eq!(cat.context::<TenantId>(), None);
+    ///
+    /// let tenant_cat = cat.with_context(TenantId(42));
+    /// assert_eq!(tenant_cat.context::<TenantId>(), Some(&TenantId(42)));
+    /// ```
+    #[must_use]
+    pub fn with_context<V>(&self, value: V) -> Self
+    where
+        V: 'static + MaybeSendSync,
+    {
+        Self {
+            inner: self.inner.clone(),
+            frame: self.frame.clone(),
+            scope_context: self.scope_context.clone(),
+            resolution_context: self.resolution_context.clone(),
+            context: Some(Arc::new(ContextValue {
+                value: Shared::new(value),
+                parent: self.context.clone(),
+            })),
+            watch: self.watch.clone(),
+        }
+    }
+
+    /// Shorthand for [`Catalog::with_context()`] that reads naturally when passing request-specific
+    /// data (e.g. the authenticated principal or a correlation id) to a single resolution, where
+    /// the components receive it via [`FromContext`] arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// struct Principal(String);
+    ///
+    /// struct Greeter {
+    ///     greeting: String,
+    /// }
+    ///
+    /// #[component]
+    /// impl Greeter {
+    ///     pub fn new(principal: FromContext<Principal>) -> Self {
+    ///         Self {
+    ///             greeting: format!("Hello, {}", principal.0),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add::<Greeter>().build();
+    ///
+    /// let greeter = cat
+    ///     .with(Principal("alice".to_owned()))
+    ///     .get::<OneOf<Greeter>>()
+    ///     .unwrap();
+    /// assert_eq!(greeter.greeting, "Hello, alice");
+    ///
+    /// assert!(matches!(
+    ///     cat.get_one::<Greeter>(),
+    ///     Err(InjectionError::MissingContext(_))
+    /// ));
+    /// ```
+    #[must_use]
+    pub fn with<V>(&self, value: V) -> Self
+    where
+        V: 'static + MaybeSendSync,
+    {
+        self.with_context(value)
+    }
+
+    /// Returns the most recently attached context value of the type,
+    /// see [`Catalog::with_context()`]
+    pub fn context<V>(&self) -> Option<&V>
+    where
+        V: 'static,
+    {
+        self.context_values().find_map(|v| v.downcast_ref::<V>())
+    }
+
+    /// Same as [`Catalog::context()`], but returns the shared pointer to the value
+    pub(crate) fn context_shared<V>(&self) -> Option<Shared<V>>
+    where
+        V: 'static + MaybeSendSync,
+    {
+        self.context_values()
+            .find(|v| v.is::<V>())
+            .map(|v| v.clone().downcast().unwrap())
+    }
+
+    fn context_values(&self) -> impl Iterator<Item = &Shared<SharedAny>> {
+        self.context.iter().flat_map(|v| v.iter()).map(|v| &v.value)
+    }
+
+    /// Unique id of the catalog within the process, shared by all of its handles (e.g. the ones
+    /// returned by [`Catalog::enter_scope()`]), while every [`Catalog::snapshot()`] gets a new one
+    pub fn id(&self) -> usize {
+        self.inner.id
+    }
+
+    /// Identity of the component whose dependencies are being resolved, see [`ComponentContext`]
+    pub(crate) fn component_context(&self) -> Option<ComponentContext> {
+        self.frame
+            .as_ref()
+            .map(|f| ComponentContext::new(f.type_id, f.type_name, self.id()))
+    }
+
+    pub(crate) fn scope_context(&self) -> Option<&ScopeCache> {
+        self.scope_context.as_deref()
+    }
+
+    pub(crate) fn resolution_context(&self) -> Option<&ScopeCache> {
+        self.resolution_context.as_deref()
+    }
+
+    /// Returns a handle to use when resolving dependencies of the type produced by the builder,
+    /// or an error if the type is already being resolved further up the chain or the chain
+    /// exceeds the maximum depth.
+    pub(crate) fn enter(&self, builder: &dyn Builder) -> Result<Self, InjectionError> {
+        let type_id = builder.instance_type_id();
+        let depth = self.frame.as_ref().map(|f| f.depth).unwrap_or(0) + 1;
+
+        let chain = || {
+            let mut chain: Vec<_> = self
+                .frame
+                .iter()
+                .flat_map(|f| f.iter())
+                .map(|f| f.type_name)
+                .collect();
+            chain.reverse();
+            chain.push(builder.instance_type_name());
+            chain
+        };
+
+        if let Some(frame) = &self.frame {
+            if frame.iter().any(|f| f.type_id == type_id) {
+                return Err(InjectionError::cycle(chain()));
+            }
+        }
+
+        // Guards against the recursion the cycle detection doesn't see, e.g. the one through
+        // detached handles or ever-growing generic types
+        let max_depth = self.inner.registry.config.max_resolution_depth;
+        if depth > max_depth {
+            return Err(InjectionError::max_depth_exceeded(max_depth, chain()));
+        }
+
+        // Top-level resolution starts a new memoization context, which the nested ones share
+        let resolution_context = match &self.frame {
+            None if self.inner.registry.config.memoize_transients => {
+                Some(Arc::new(ScopeCache::new()))
+            }
+            None => None,
+            Some(_) => self.resolution_context.clone(),
+        };
+
+        let frame = Arc::new(ResolutionFrame {
+            type_id,
+            type_name: builder.instance_type_name(),
+            depth,
+            parent: self.frame.clone(),
+        });
+        if let Some(watch) = &self.watch {
+            watch.track(&frame);
+        }
+
+        Ok(Self {
+            inner: self.inner.clone(),
+            frame: Some(frame),
+            scope_context: self.scope_context.clone(),
+            resolution_context,
+            context: self.context.clone(),
+            watch: self.watch.clone(),
+        })
+    }
+
+    /// Tracks the synchronous resolution of the type produced by the builder on the current
+    /// thread, failing if the resolution is nested deeper than the catalog allows
+    pub(crate) fn guard_depth(&self, builder: &dyn Builder) -> Result<DepthGuard, InjectionError> {
+        let max_depth = self.inner.registry.config.max_resolution_depth;
+        RESOLUTION_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if stack.len() >= max_depth {
+                let mut chain = stack.clone();
+                chain.push(builder.instance_type_name());
+                return Err(InjectionError::max_depth_exceeded(max_depth, chain));
+            }
+            stack.push(builder.instance_type_name());
+            Ok(DepthGuard)
+        })
+    }
+
+    /// Types resolved synchronously on the current thread, from the outermost to the innermost,
+    /// including the ones resolved through the detached handles
+    pub(crate) fn thread_resolution_chain() -> Vec<&'static str> {
+        RESOLUTION_STACK.with(|stack| stack.borrow().clone())
+    }
+
+    /// Returns a handle that tracks the frames of the resolutions made through it
+    pub(crate) fn watched(&self, watch: Arc<ResolutionWatch>) -> Self {
+        Self {
+            watch: Some(watch),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a builder of a child catalog, see [`CatalogBuilder::new_chained()`]
+    pub fn builder_chained(&self) -> CatalogBuilder {
+        CatalogBuilder::new_chained(self)
+    }
+
+    /// Creates a builder seeded with all registrations of this catalog (including its parent
+    /// and settings like [`CatalogBuilder::memoize_transients()`]), e.g. to register a few more
+    /// components and build an updated catalog.
+    ///
+    /// Like with [`Catalog::snapshot()`], the new catalog doesn't share the instances cached
+    /// by the [`Scope`]s with this one, and its eager components are instantiated again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// let cat = CatalogBuilder::new().add_value(8080u16).build();
+    ///
+    /// let updated = cat.builder().add_value("localhost".to_owned()).build();
+    ///
+    /// assert_eq!(*updated.get_one::<u16>().unwrap(), 8080);
+    /// assert_eq!(*updated.get_one::<String>().unwrap(), "localhost");
+    /// assert!(cat.get_one::<String>().is_err());
+    /// ```
+    pub fn builder(&self) -> CatalogBuilder {
+        let registry = &self.inner.registry;
+
+        let mut bindings = MultiMap::new();
+        for (iface_type, iface_bindings) in &registry.bindings {
+            bindings.insert_many(*iface_type, iface_bindings.iter().cloned());
+        }
+
+        CatalogBuilder::new_seeded(
+            registry
+                .builders
+                .iter()
+                .map(|(impl_type, b)| (*impl_type, b.clone()))
+                .collect(),
+            registry.appended.clone(),
+            bindings,
+            registry.config.clone(),
+        )
+    }
+
+    /// Returns the bindings of the interface, falling back to the parent catalogs if needed,
+    /// along with the catalog that owns them or `None` if it's this catalog
+    pub(crate) fn bindings_of(
+        &self,
+        iface_type: IfaceTypeId,
+    ) -> (Option<&Vec<Binding>>, Option<&Catalog>) {
+        let mut owner = None;
+        let mut cat = self;
+        loop {
+            let bindings = cat.inner.registry.bindings.get(&iface_type);
+            if bindings.is_some() {
+                return (bindings, owner);
+            }
+            match &cat.inner.registry.config.parent {
+                Some(parent) => {
+                    cat = parent;
+                    owner = Some(parent);
+                }
+                None => return (None, None),
+            }
+        }
+    }
+
+    /// Consults the handlers registered via [`CatalogBuilder::on_unresolved()`] of this catalog
+    /// and then of its parents, failing if the instance provided by a handler has a wrong type
+    pub(crate) fn resolve_unresolved<Iface>(&self) -> Option<Result<Shared<Iface>, InjectionError>>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        let type_id = TypeId::of::<Iface>();
+        let type_name = type_name::<Iface>();
+
+        let mut cat = Some(self);
+        while let Some(c) = cat {
+            for handler in &c.inner.registry.config.unresolved {
+                if let Some(inst) = handler(type_id, type_name, self) {
+                    return Some(
+                        Iface::from_unresolved(inst)
+                            .ok_or_else(InjectionError::unresolved_mismatch::<Iface>),
+                    );
+                }
+            }
+            cat = c.inner.registry.config.parent.as_ref();
+        }
+        None
+    }
+
+    /// Returns the binding of the implementation of the open generic interface, instantiating
+    /// its builder upon the first use and keeping it, so that the scope of the component is
+    /// respected, see [`OpenGeneric`]
+    pub(crate) fn open_generic_binding<Iface, Impl>(&self) -> Binding
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + BuilderLike + MaybeSendSync + Unsize<Iface>,
+        Impl::Builder: 'static,
+    {
+        lock(&self.inner.registry.open_generics)
+            .entry(IfaceTypeId(TypeId::of::<Iface>()))
+            .or_insert_with(|| {
+                CatalogBuilder::new_binding_of::<Iface, Impl>(Arc::new(Impl::builder()))
+            })
+            .clone()
+    }
+
+    /// Creates an error for the interface that has no bindings, capturing the chain of types
+    /// being resolved and the registered types with similar names to help diagnosing the problem
+    pub(crate) fn unregistered<Iface>(&self) -> InjectionError
+    where
+        Iface: 'static + ?Sized,
+    {
+        let type_id = TypeId::of::<Iface>();
+        let type_name = std::any::type_name::<Iface>();
+
+        let mut required_by: Vec<_> = self
+            .frame
+            .iter()
+            .flat_map(|f| f.iter())
+            .map(|f| f.type_name)
+            .collect();
+        required_by.reverse();
+
+        // Types registered without a binding of their own (see `CatalogBuilder::add_as()`) are the
+        // best candidates, followed by the types whose name contains the name of the interface
+        let key = short_type_name(type_name).to_lowercase();
+        let mut suggestions: Vec<_> =
+            std::iter::successors(Some(self), |c| c.inner.registry.config.parent.as_ref())
+                .flat_map(|c| c.builders())
+                .filter(|b| {
+                    b.instance_type_id() == type_id
+                        || (!key.is_empty()
+                            && short_type_name(b.instance_type_name())
+                                .to_lowercase()
+                                .contains(&key))
+                })
+                .map(|b| b.instance_type_name())
+                .collect();
+        suggestions.sort_unstable();
+        suggestions.dedup();
+
+        InjectionError::unregistered::<Iface>().with_context(required_by, suggestions)
+    }
+
+    pub(crate) fn scope_cache(&self) -> &ScopeCache {
+        &self.inner.scope_cache
+    }
+
+    /// Observers of the resolution, see [`CatalogBuilder::with_observer()`]
+    pub(crate) fn observers(&self) -> &[Arc<dyn ResolutionObserver>] {
+        &self.inner.registry.config.observers
+    }
+
+    pub(crate) fn resolution_log(&self) -> Option<&ResolutionLog> {
+        self.inner.registry.config.resolution_log.as_deref()
+    }
+
+    /// See [`CatalogBuilder::max_async_concurrency()`]
+    pub(crate) fn max_async_concurrency(&self) -> usize {
+        self.inner.registry.config.max_async_concurrency
+    }
+
+    /// Components with a caching scope that resolving the builder might construct, i.e. the ones
+    /// it depends on directly or transitively (including itself) that aren't cached yet
+    pub(crate) fn uncached_dependencies<'a>(&'a self, builder: &'a dyn Builder) -> HashSet<TypeId> {
+        let transient = type_name::<Transient>();
+        let mut visited = HashSet::new();
+        let mut uncached = HashSet::new();
+        let mut stack = vec![(builder, self)];
+        while let Some((builder, cat)) = stack.pop() {
+            if !visited.insert(builder.instance_type_id()) {
+                continue;
+            }
+            if builder.scope_name() != transient {
+                // Dependencies of the cached instances are not resolved again
+                if builder.cached(cat).is_some() {
+                    continue;
+                }
+                uncached.insert(builder.instance_type_id());
+            }
+            for dep in builder.dependencies() {
+                let (bindings, owner) = cat.bindings_of(IfaceTypeId(dep.type_id));
+                let owner = owner.unwrap_or(cat);
+                stack.extend(
+                    bindings
+                        .into_iter()
+                        .flatten()
+                        .filter(|b| dep.name.is_none() || b.name.as_deref() == dep.name)
+                        .map(|b| (b.builder.as_ref(), owner)),
+                );
+            }
+        }
+        uncached
+    }
+
+    /// Resolves the dependency while notifying the observers
+    fn observed<T>(
+        &self,
+        spec: &'static str,
+        resolve: impl FnOnce() -> Result<T, InjectionError>,
+    ) -> Result<T, InjectionError> {
+        if self.observers().is_empty() && self.resolution_log().is_none() {
+            return resolve();
+        }
+
+        let started = self.resolve_started(spec);
+        let res = resolve();
+        self.resolve_finished(spec, started, &res);
+        res
+    }
+
+    /// Asynchronous counterpart of [`Catalog::observed()`]
+    async fn observed_async<T>(
+        &self,
+        spec: &'static str,
+        resolve: impl Future<Output = Result<T, InjectionError>>,
+    ) -> Result<T, InjectionError> {
+        if self.observers().is_empty() && self.resolution_log().is_none() {
+            return resolve.await;
+        }
+
+        let started = self.resolve_started(spec);
+        let res = resolve.await;
+        self.resolve_finished(spec, started, &res);
+        res
+    }
+
+    fn resolve_started(&self, spec: &'static str) -> Instant {
+        for observer in self.observers() {
+            observer.on_resolve_start(spec);
+        }
+        Instant::now()
+    }
+
+    fn resolve_finished<T>(
+        &self,
+        spec: &'static str,
+        started: Instant,
+        res: &Result<T, InjectionError>,
+    ) {
+        let elapsed = started.elapsed();
+        for observer in self.observers() {
+            observer.on_resolve_end(spec, elapsed);
+        }
+
+        if let Some(log) = self.resolution_log() {
+            let mut required_by: Vec<_> = self
+                .frame
+                .iter()
+                .flat_map(|f| f.iter())
+                .map(|f| f.type_name)
+                .collect();
+            required_by.reverse();
+
+            log.record(ResolutionRecord {
+                spec,
+                elapsed,
+                error: res.as_ref().err().cloned(),
+                required_by,
+            });
+        }
+
+        // Nested resolutions are performed with the handle that tracks the resolution frame
+        if let (Err(error), None) = (res, &self.frame) {
+            for observer in self.observers() {
+                observer.on_error(spec, error);
+            }
+        }
+    }
+
+    /// Returns builders registered in this catalog, excluding the ones of the parent catalogs
     pub fn builders(&self) -> impl Iterator<Item = &dyn Builder> {
-        self.0.builders.values().map(|b| b.as_ref())
+        let registry = &self.inner.registry;
+        registry
+            .builders
+            .values()
+            .chain(&registry.appended)
+            .map(|b| b.as_ref())
+    }
+
+    /// Describes the components registered in this catalog (excluding the ones of the parent
+    /// catalogs) ordered by type name, along with the interfaces they are bound to and whether
+    /// their instances are currently cached, e.g. to dump the state of the container on a debug
+    /// endpoint. Cached instances are looked up in the current scope, see [`Catalog::enter_scope()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {}
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// #[interface(dyn Db)]
+    /// struct PgDb;
+    /// impl Db for PgDb {}
+    ///
+    /// let cat = CatalogBuilder::new().add::<PgDb>().build();
+    /// cat.get_one::<dyn Db>().unwrap();
+    ///
+    /// let pg = &cat.components()[0];
+    /// assert_eq!(pg.type_name, std::any::type_name::<PgDb>());
+    /// assert_eq!(pg.scope_name, std::any::type_name::<Singleton>());
+    /// assert!(pg.bindings.iter().any(|b| b.type_name == std::any::type_name::<dyn Db>()));
+    /// assert!(pg.is_cached);
+    /// ```
+    pub fn components(&self) -> Vec<ComponentInfo> {
+        let mut components: Vec<_> = self
+            .builders()
+            .map(|builder| {
+                let type_id = builder.instance_type_id();
+
+                let mut bindings: Vec<_> = self
+                    .inner
+                    .registry
+                    .bindings
+                    .iter()
+                    .flat_map(|(iface_type, bindings)| {
+                        bindings
+                            .iter()
+                            .filter(|b| b.builder.instance_type_id() == type_id)
+                            .map(move |b| BindingInfo {
+                                type_id: iface_type.0,
+                                type_name: b.iface_name,
+                                name: b.name.clone(),
+                            })
+                    })
+                    .collect();
+                bindings.sort_by(|a, b| (a.type_name, &a.name).cmp(&(b.type_name, &b.name)));
+
+                ComponentInfo {
+                    type_id,
+                    type_name: builder.instance_type_name(),
+                    scope_name: builder.scope_name(),
+                    bindings,
+                    is_cached: builder.cached(self).is_some(),
+                    priority: builder.priority(),
+                    tags: builder.tags().to_vec(),
+                }
+            })
+            .collect();
+
+        components.sort_by_key(|c| c.type_name);
+        components
+    }
+
+    /// Describes the wiring of the components registered in this catalog (see
+    /// [`Catalog::components()`]), e.g. to assert in tests that the production wiring registers
+    /// exactly the expected implementations, see [`snapshot_diff()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {}
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// #[interface(dyn Db)]
+    /// struct PgDb;
+    /// impl Db for PgDb {}
+    ///
+    /// let cat = CatalogBuilder::new().add::<PgDb>().build();
+    ///
+    /// let golden = format!(
+    ///     "component {pg}\n  scope {singleton}\n  binds {db}\n  binds {pg}\n",
+    ///     pg = std::any::type_name::<PgDb>(),
+    ///     singleton = std::any::type_name::<Singleton>(),
+    ///     db = std::any::type_name::<dyn Db>(),
+    /// );
+    /// assert_eq!(cat.wiring_snapshot().to_string(), golden);
+    /// ```
+    pub fn wiring_snapshot(&self) -> WiringSnapshot {
+        WiringSnapshot {
+            components: self
+                .components()
+                .iter()
+                .map(ComponentWiring::from)
+                .collect(),
+        }
+    }
+
+    /// Describes the effective wiring of this catalog: its components (see
+    /// [`Catalog::wiring_snapshot()`]), the implementations chosen for the interfaces according
+    /// to their priorities and the configuration applied via [`CatalogBuilder::configure_from()`],
+    /// e.g. to be exported along with a deployment and compared to the one of the next release
+    /// via [`Catalog::assert_wiring()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {}
+    ///
+    /// #[component]
+    /// #[interface(dyn Db)]
+    /// struct PgDb;
+    /// impl Db for PgDb {}
+    ///
+    /// #[component]
+    /// #[interface(dyn Db)]
+    /// struct SqliteDb;
+    /// impl Db for SqliteDb {}
+    ///
+    /// let manifest = CatalogBuilder::new()
+    ///     .add_with_priority::<PgDb>(10)
+    ///     .add::<SqliteDb>()
+    ///     .build()
+    ///     .wiring_manifest();
+    ///
+    /// assert_eq!(manifest.components.len(), 2);
+    /// assert_eq!(manifest.interfaces[0].type_name, std::any::type_name::<dyn Db>());
+    /// assert_eq!(
+    ///     manifest.interfaces[0].implementation.as_deref(),
+    ///     Some(std::any::type_name::<PgDb>())
+    /// );
+    /// ```
+    pub fn wiring_manifest(&self) -> WiringManifest {
+        let registry = &self.inner.registry;
+
+        let mut interfaces: Vec<_> = registry
+            .bindings
+            .iter()
+            .filter(|(iface_type, bindings)| {
+                bindings
+                    .iter()
+                    .any(|b| b.builder.instance_type_id() != iface_type.0)
+            })
+            .filter_map(|(_, bindings)| {
+                let first = bindings.first()?;
+                let tied = bindings
+                    .get(1)
+                    .map_or(false, |b| b.builder.priority() == first.builder.priority());
+                Some(InterfaceWiring {
+                    type_name: first.iface_name.to_owned(),
+                    implementation: if tied {
+                        None
+                    } else {
+                        Some(first.builder.instance_type_name().to_owned())
+                    },
+                })
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.type_name.cmp(&b.type_name));
+
+        let mut config: Vec<_> = registry
+            .config
+            .applied_config
+            .iter()
+            .map(|(key, value)| ConfigWiring {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        config.sort_by(|a, b| a.key.cmp(&b.key));
+
+        WiringManifest {
+            components: self.wiring_snapshot().components,
+            interfaces,
+            config,
+        }
+    }
+
+    /// Asserts that the effective wiring of this catalog (see [`Catalog::wiring_manifest()`])
+    /// matches the expected one, e.g. the manifest exported by the previous deployment.
+    ///
+    /// # Panics
+    ///
+    /// If the wirings differ, listing the added, removed and changed components as well as
+    /// the interfaces and configuration keys whose values changed.
+    pub fn assert_wiring(&self, expected: &WiringManifest) {
+        let diff = expected.diff(&self.wiring_manifest());
+        if !diff.is_empty() {
+            panic!("Wiring differs from the expected one:\n{}", diff);
+        }
     }
 
     pub fn builders_for<'a, Iface>(&'a self) -> impl Iterator<Item = TypecastBuilder<'a, Iface>>
@@ -41,22 +1093,888 @@ impl Catalog {
     {
         let iface_type = IfaceTypeId(TypeId::of::<Iface>());
 
-        let bindings = self.0.bindings.get_vec(&&iface_type);
-        TypecastBuilderIterator::new(bindings)
+        let (bindings, owner) = self.bindings_of(iface_type);
+        TypecastBuilderIterator::new(bindings, owner)
     }
 
     pub fn get<Spec>(&self) -> Result<Spec::ReturnType, InjectionError>
     where
         Spec: DependencySpec + 'static,
     {
-        Spec::get(self)
+        self.observed(type_name::<Spec>(), || Spec::get(self))
+    }
+
+    /// Resolves a dependency asynchronously, allowing components with `async fn new()`
+    /// anywhere in the dependency chain.
+    ///
+    /// Note that unlike the synchronous resolution, concurrent async resolution of a
+    /// [`Singleton`] may construct the instance more than once.
+    pub async fn get_async<Spec>(&self) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: AsyncDependencySpec + 'static,
+    {
+        self.observed_async(type_name::<Spec>(), Spec::get_async(self))
+            .await
+    }
+
+    /// A short-hand for `get_async::<OneOf<T>>()`.
+    pub async fn get_one_async<Iface>(&self) -> Result<Shared<Iface>, InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        self.observed_async(type_name::<OneOf<Iface>>(), OneOf::<Iface>::get_async(self))
+            .await
+    }
+
+    /// Resolves a dependency passing the runtime arguments to the requested component, which
+    /// receives them via `#[dill(arg)]` arguments instead of resolving them from the catalog
+    /// (a.k.a. assisted injection). Arguments are passed as a tuple in the order of declaration,
+    /// e.g. `(order_id,)` for a single one, and resolution fails with
+    /// [`InjectionError::MissingContext`] when their types don't match.
+    ///
+    /// Arguments are only consumed when a new instance is built, so such components are
+    /// expected to be [`Transient`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    /// use std::sync::Arc;
+    ///
+    /// #[component]
+    /// struct Pricing;
+    ///
+    /// #[component]
+    /// struct OrderHandler {
+    ///     pricing: Arc<Pricing>,
+    ///     #[dill(arg)]
+    ///     order_id: u64,
+    ///     #[dill(arg)]
+    ///     user: String,
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Pricing>()
+    ///     .add::<OrderHandler>()
+    ///     .build();
+    ///
+    /// let handler = cat
+    ///     .get_with::<OneOf<OrderHandler>>((42u64, "alice".to_owned()))
+    ///     .unwrap();
+    /// assert_eq!(handler.order_id, 42);
+    /// assert_eq!(handler.user, "alice");
+    ///
+    /// assert!(matches!(
+    ///     cat.get_one::<OrderHandler>(),
+    ///     Err(InjectionError::MissingContext(_))
+    /// ));
+    /// ```
+    pub fn get_with<Spec>(
+        &self,
+        args: impl 'static + MaybeSendSync,
+    ) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: DependencySpec + 'static,
+    {
+        self.with_context(RuntimeArgs::new(args)).get::<Spec>()
+    }
+
+    /// Asynchronous counterpart of [`Catalog::get_with()`], see [`Catalog::get_async()`]
+    pub async fn get_with_async<Spec>(
+        &self,
+        args: impl 'static + MaybeSendSync,
+    ) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: AsyncDependencySpec + 'static,
+    {
+        self.with_context(RuntimeArgs::new(args))
+            .get_async::<Spec>()
+            .await
+    }
+
+    /// Resolves a dependency from the binding registered under the specified name,
+    /// see [`CatalogBuilder::bind_named()`].
+    pub fn get_named<Spec>(&self, name: &str) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: NamedDependencySpec + 'static,
+    {
+        self.observed(type_name::<Spec>(), || Spec::get_named(self, name))
+    }
+
+    /// A short-hand for `get_named::<OneOf<T>>(name)`.
+    pub fn get_one_named<Iface>(&self, name: &str) -> Result<Shared<Iface>, InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        self.observed(type_name::<OneOf<Iface>>(), || {
+            OneOf::<Iface>::get_named(self, name)
+        })
+    }
+
+    /// Asynchronous counterpart of [`Catalog::get_named()`].
+    pub async fn get_named_async<Spec>(
+        &self,
+        name: &str,
+    ) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: NamedDependencySpec + 'static,
+    {
+        self.observed_async(type_name::<Spec>(), Spec::get_named_async(self, name))
+            .await
+    }
+
+    /// A short-hand for `get_named_async::<OneOf<T>>(name)`.
+    pub async fn get_one_named_async<Iface>(
+        &self,
+        name: &str,
+    ) -> Result<Shared<Iface>, InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        self.observed_async(
+            type_name::<OneOf<Iface>>(),
+            OneOf::<Iface>::get_named_async(self, name),
+        )
+        .await
+    }
+
+    /// Returns the builder bound to the interface under the specified name
+    pub fn builder_named<'a, Iface>(&'a self, name: &str) -> Option<TypecastBuilder<'a, Iface>>
+    where
+        Iface: 'static + ?Sized,
+    {
+        self.builders_for::<Iface>()
+            .find(|b| b.name() == Some(name))
+    }
+
+    /// Builds all instances that implement a specific interface and carry the specified tag.
+    /// Components without tags never match.
+    pub fn get_tagged<Iface>(&self, tag: &str) -> Result<Vec<Shared<Iface>>, InjectionError>
+    where
+        Iface: 'static + ?Sized,
+    {
+        self.builders_for::<Iface>()
+            .filter(|b| b.tags().iter().any(|t| *t == tag))
+            .map(|b| b.get(self))
+            .collect()
+    }
+
+    /// Builds all instances that implement a specific interface and keeps only those matching the predicate.
+    ///
+    /// Unlike [`Catalog::get_tagged()`] this inspects the constructed instances, so all implementations
+    /// are built (with all their construction side effects) even if they get filtered out.
+    /// Instances are returned ordered by priority, then by registration.
+    pub fn get_all_where<Iface>(
+        &self,
+        pred: impl Fn(&Iface) -> bool,
+    ) -> Result<Vec<Shared<Iface>>, InjectionError>
+    where
+        Iface: 'static + ?Sized,
+    {
+        let mut instances = Vec::new();
+        for builder in self.builders_for::<Iface>() {
+            let inst = builder.get(self)?;
+            if pred(inst.as_ref()) {
+                instances.push(inst);
+            }
+        }
+        Ok(instances)
+    }
+
+    /// Resolves an instance of a concrete type identified by its [`TypeId`] at runtime.
+    ///
+    /// This is an escape hatch for cases where the type is not known statically (e.g. plugin systems
+    /// that map external identifiers to component types). Only concrete instance types can be resolved
+    /// this way, not interfaces. The caller is responsible for downcasting the returned instance.
+    pub fn get_by_type_id(&self, type_id: TypeId) -> Result<Shared<SharedAny>, InjectionError> {
+        match self.inner.registry.builders.get(&ImplTypeId(type_id)) {
+            Some(builder) => {
+                let _guard = self.guard_depth(builder.as_ref())?;
+                builder.get(&self.enter(builder.as_ref())?)
+            }
+            None => match &self.inner.registry.config.parent {
+                Some(parent) => parent.get_by_type_id(type_id),
+                None => Err(InjectionError::unregistered_type_id(type_id)),
+            },
+        }
+    }
+
+    /// Resolves an instance of a concrete type identified by its name at runtime, e.g. a component
+    /// selected by user input in an admin console. The name has to match
+    /// [`Builder::instance_type_name()`] exactly (see [`Catalog::components()`] for the registered
+    /// names), while the caller is responsible for downcasting the returned instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// struct Stats;
+    ///
+    /// let cat = CatalogBuilder::new().add::<Stats>().build();
+    ///
+    /// let inst = cat.get_by_name(std::any::type_name::<Stats>()).unwrap();
+    /// let _stats: Arc<Stats> = inst.downcast().unwrap();
+    ///
+    /// assert!(cat.get_by_name("Stats").is_err());
+    /// ```
+    pub fn get_by_name(&self, type_name: &str) -> Result<Shared<SharedAny>, InjectionError> {
+        let builder = self
+            .inner
+            .registry
+            .builders
+            .values()
+            .find(|b| b.instance_type_name() == type_name);
+
+        match builder {
+            Some(builder) => {
+                let _guard = self.guard_depth(builder.as_ref())?;
+                builder.get(&self.enter(builder.as_ref())?)
+            }
+            None => match &self.inner.registry.config.parent {
+                Some(parent) => parent.get_by_name(type_name),
+                None => Err(InjectionError::unregistered_name(type_name)),
+            },
+        }
+    }
+
+    /// Returns the graph of the components registered in this catalog and their declared
+    /// dependencies (see [`Builder::dependencies()`]), e.g. to render it via [`DependencyGraph::to_dot()`].
+    ///
+    /// Components of the parent catalogs are included only when depended upon.
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+
+        let node = |builder: &dyn Builder| GraphNode {
+            type_id: builder.instance_type_id(),
+            type_name: builder.instance_type_name(),
+            scope_name: builder.scope_name(),
+        };
+
+        graph.nodes.extend(self.builders().map(node));
+
+        for builder in self.builders() {
+            for dep in builder.dependencies() {
+                if is_builtin_dependency(dep.type_id) {
+                    continue;
+                }
+
+                let (bindings, _) = self.bindings_of(IfaceTypeId(dep.type_id));
+                let targets: Vec<_> = bindings
+                    .into_iter()
+                    .flatten()
+                    .filter(|b| dep.name.is_none() || b.name.as_deref() == dep.name)
+                    .map(|b| b.builder.as_ref())
+                    .collect();
+
+                if targets.is_empty() {
+                    graph.edges.push(GraphEdge {
+                        from: builder.instance_type_id(),
+                        to: None,
+                        dependency: dep,
+                    });
+                }
+
+                for target in targets {
+                    let target = node(target);
+                    if !graph.nodes.contains(&target) {
+                        graph.nodes.push(target.clone());
+                    }
+                    graph.edges.push(GraphEdge {
+                        from: builder.instance_type_id(),
+                        to: Some(target.type_id),
+                        dependency: dep,
+                    });
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Returns the components of the [`Catalog::dependency_graph()`] sorted so that every
+    /// component follows its dependencies, e.g. to document the initialization order or to warm up
+    /// the components one by one. Among the components whose dependencies precede them,
+    /// [`Singleton`]s go first, followed by the rest ordered by type name, so the order doesn't
+    /// depend on the registration order. Members of a dependency cycle are ordered by the same
+    /// rules once nothing else can go first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    /// use std::sync::Arc;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Pool;
+    ///
+    /// #[component]
+    /// struct Repo {
+    ///     pool: Arc<Pool>,
+    /// }
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Config;
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Repo>()
+    ///     .add::<Pool>()
+    ///     .add::<Config>()
+    ///     .build();
+    ///
+    /// let order: Vec<_> = cat
+    ///     .construction_order()
+    ///     .into_iter()
+    ///     .map(|node| node.type_name.rsplit("::").next().unwrap())
+    ///     .collect();
+    /// assert_eq!(order, ["Config", "Pool", "Repo"]);
+    /// ```
+    pub fn construction_order(&self) -> Vec<GraphNode> {
+        let graph = self.dependency_graph();
+        let singleton = type_name::<Singleton>();
+
+        // Counts the distinct dependencies of every component that are not constructed yet
+        let mut dependents: HashMap<TypeId, Vec<TypeId>> = HashMap::new();
+        let mut pending: HashMap<TypeId, usize> = HashMap::new();
+        let mut seen = HashSet::new();
+        for edge in &graph.edges {
+            match edge.to {
+                Some(to) if to != edge.from && seen.insert((edge.from, to)) => {
+                    dependents.entry(to).or_default().push(edge.from);
+                    *pending.entry(edge.from).or_default() += 1;
+                }
+                _ => {}
+            }
+        }
+
+        let mut remaining = graph.nodes;
+        let mut order = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let key = |node: &GraphNode| (node.scope_name != singleton, node.type_name);
+            let ready = remaining
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| pending.get(&node.type_id).copied().unwrap_or(0) == 0)
+                .min_by_key(|(_, node)| key(node))
+                .or_else(|| {
+                    remaining
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, node)| key(node))
+                })
+                .map(|(i, _)| i)
+                .unwrap();
+
+            let node = remaining.swap_remove(ready);
+            for dependent in dependents.get(&node.type_id).into_iter().flatten() {
+                if let Some(count) = pending.get_mut(dependent) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+            order.push(node);
+        }
+        order
+    }
+
+    /// Drops the instances of all implementations of the interface cached by their [`Scope`]s,
+    /// so that the next resolution constructs new ones, e.g. to pick up the refreshed credentials.
+    /// Clients that already hold the instances are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Credentials;
+    ///
+    /// let cat = CatalogBuilder::new().add::<Credentials>().build();
+    ///
+    /// let c1 = cat.get_one::<Credentials>().unwrap();
+    /// cat.invalidate::<Credentials>();
+    /// let c2 = cat.get_one::<Credentials>().unwrap();
+    /// assert!(!std::sync::Arc::ptr_eq(&c1, &c2));
+    /// ```
+    pub fn invalidate<Iface>(&self)
+    where
+        Iface: 'static + ?Sized,
+    {
+        for builder in self.builders_for::<Iface>() {
+            Builder::invalidate(&builder, self);
+        }
+    }
+
+    /// Same as [`Catalog::invalidate()`], but also drops the cached instances of the components
+    /// of this catalog that depend on the interface directly or transitively (see
+    /// [`Catalog::dependency_graph()`]), so that e.g. services holding a reloaded configuration
+    /// are reconstructed along with it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Config;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Service {
+    ///     config: Arc<Config>,
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add::<Config>().add::<Service>().build();
+    ///
+    /// let s1 = cat.get_one::<Service>().unwrap();
+    /// cat.invalidate_with_dependents::<Config>();
+    /// let s2 = cat.get_one::<Service>().unwrap();
+    /// assert!(!Arc::ptr_eq(&s1.config, &s2.config));
+    /// ```
+    pub fn invalidate_with_dependents<Iface>(&self)
+    where
+        Iface: 'static + ?Sized,
+    {
+        let graph = self.dependency_graph();
+
+        let mut stale: Vec<_> = self
+            .builders_for::<Iface>()
+            .map(|b| b.instance_type_id())
+            .collect();
+        let mut visited = HashSet::new();
+        while let Some(type_id) = stale.pop() {
+            if visited.insert(type_id) {
+                stale.extend(
+                    graph
+                        .edges
+                        .iter()
+                        .filter(|e| e.to == Some(type_id))
+                        .map(|e| e.from),
+                );
+            }
+        }
+
+        self.invalidate::<Iface>();
+        for type_id in visited {
+            if let Some(builder) = self.inner.registry.builders.get(&ImplTypeId(type_id)) {
+                builder.invalidate(self);
+            }
+        }
+    }
+
+    /// Replaces the implementation of the interface registered via
+    /// [`CatalogBuilder::add_swappable()`], so that the following resolutions get the instances
+    /// of the new one, while the instances handed out so far stay intact. Components that hold
+    /// an instance of the interface have to be resolved again to observe the swap.
+    ///
+    /// The swap is shared by the catalogs seeded from the same registrations, e.g. via
+    /// [`Catalog::builder()`], and by the child catalogs. Returns
+    /// [`InjectionError::Unregistered`] if the interface has no swappable implementation.
+    pub fn swap_builder<Iface, Bld, Impl>(&self, builder: Bld) -> Result<(), InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        let iface_type = IfaceTypeId(TypeId::of::<Iface>());
+        match self.inner.registry.config.swap_slots.get(&iface_type) {
+            Some(slot) => {
+                // SAFETY: the slots are keyed by the type of their interface
+                let slot: &SwapSlot<Iface> = unsafe { cast_erased(slot.as_ref()) };
+                slot.swap(builder);
+                Ok(())
+            }
+            None => match &self.inner.registry.config.parent {
+                Some(parent) => parent.swap_builder::<Iface, Bld, Impl>(builder),
+                None => Err(InjectionError::unregistered::<Iface>()),
+            },
+        }
+    }
+
+    /// Constructs the [`Singleton`]s (and the other components with a catalog-wide scope, see
+    /// [`Scope::is_catalog_wide()`]) of this catalog that are not cached yet, running the
+    /// constructors of independent components concurrently, e.g. to speed up the startup of
+    /// a service with many slow constructors. Constructors run on a pool of as many threads as
+    /// [`std::thread::available_parallelism()`] suggests.
+    ///
+    /// Components are constructed in waves, where every wave only depends on the components of
+    /// the preceding ones. Construction stops after the first wave that failed, returning its
+    /// first error. Components of the parent catalogs are left intact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Db;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Search;
+    ///
+    /// let cat = CatalogBuilder::new().add::<Db>().add::<Search>().build();
+    /// cat.warm_up_parallel().unwrap();
+    ///
+    /// assert!(cat.components().iter().all(|c| c.is_cached));
+    /// ```
+    #[cfg(not(feature = "unsync"))]
+    pub fn warm_up_parallel(&self) -> Result<(), InjectionError> {
+        let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+        for wave in self.construction_waves() {
+            let pending: Vec<_> = wave
+                .into_iter()
+                .filter(
+                    |type_id| match self.inner.registry.builders.get(&ImplTypeId(*type_id)) {
+                        Some(builder) => {
+                            builder.is_catalog_wide() && builder.cached(self).is_none()
+                        }
+                        None => false,
+                    },
+                )
+                .collect();
+
+            // Workers take the components off the shared queue until it's empty
+            let threads = pending.len().min(max_threads);
+            let queue = Arc::new(std::sync::Mutex::new(pending.into_iter().enumerate()));
+            let handles: Vec<_> = (0..threads)
+                .map(|_| {
+                    let cat = self.clone();
+                    let queue = queue.clone();
+                    std::thread::spawn(move || {
+                        let mut errors = Vec::new();
+                        loop {
+                            let next = queue.lock().unwrap().next();
+                            let (pos, type_id) = match next {
+                                Some(next) => next,
+                                None => break errors,
+                            };
+                            if let Err(err) = cat.get_by_type_id(type_id) {
+                                errors.push((pos, err));
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            // Waiting for all threads of the wave so that none outlives the failed warm-up
+            let mut errors = Vec::new();
+            for handle in handles {
+                let thread_errors = handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                errors.extend(thread_errors);
+            }
+            if let Some((_, err)) = errors.into_iter().min_by_key(|(pos, _)| *pos) {
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Shuts down the instances of this catalog that are bound to the [`Shutdown`] interface,
+    /// in the reverse order of construction, so that every component is shut down before its dependencies.
+    ///
+    /// Only instances cached by the scopes (e.g. [`Singleton`]s) are affected - nothing is constructed
+    /// just to be shut down. Components of the parent catalogs are left intact.
+    ///
+    /// Cancels the [`ShutdownToken`] of the catalog first, so that the background tasks of the
+    /// components start winding down before any of them is shut down.
+    pub fn shutdown(&self) {
+        self.inner.shutdown_token.cancel();
+        self.shutdown_cached(false);
+    }
+
+    /// Asynchronous counterpart of [`Catalog::shutdown()`] that also shuts down the instances
+    /// bound to the [`AsyncShutdown`] interface, respecting the same order across both interfaces.
+    pub async fn shutdown_async(&self) {
+        self.inner.shutdown_token.cancel();
+        self.shutdown_cached_async(false).await;
+    }
+
+    /// Token cancelled when the catalog (or its parent) is shut down, the same one that
+    /// the components receive as a dependency, see [`ShutdownToken`]. Snapshots of the catalog
+    /// (see [`Catalog::snapshot()`]) have tokens of their own.
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.inner.shutdown_token.clone()
+    }
+
+    /// Shuts down only the instances cached within the current scope, see [`Catalog::scope()`]
+    fn shutdown_scoped(&self) {
+        self.shutdown_cached(true);
+    }
+
+    fn shutdown_cached(&self, scoped_only: bool) {
+        for (_, inst) in self.cached_for_shutdown::<dyn Shutdown>(scoped_only) {
+            inst.shutdown();
+        }
+    }
+
+    async fn shutdown_scoped_async(&self) {
+        self.shutdown_cached_async(true).await;
+    }
+
+    async fn shutdown_cached_async(&self, scoped_only: bool) {
+        enum Target {
+            Sync(Shared<dyn Shutdown>),
+            Async(Shared<dyn AsyncShutdown>),
+        }
+
+        let mut targets: Vec<_> = self
+            .cached_for_shutdown::<dyn Shutdown>(scoped_only)
+            .into_iter()
+            .map(|(pos, inst)| (pos, Target::Sync(inst)))
+            .chain(
+                self.cached_for_shutdown::<dyn AsyncShutdown>(scoped_only)
+                    .into_iter()
+                    .map(|(pos, inst)| (pos, Target::Async(inst))),
+            )
+            .collect();
+        targets.sort_by_key(|(pos, _)| Reverse(*pos));
+
+        for (_, target) in targets {
+            match target {
+                Target::Sync(inst) => inst.shutdown(),
+                Target::Async(inst) => inst.shutdown_async().await,
+            }
+        }
+    }
+
+    /// Delivers the event to all components bound to the [`EventHandler<E>`] interface, ordered
+    /// by priority, then by registration. Handlers are resolved according to their scopes, so
+    /// transient ones are constructed for every event.
+    ///
+    /// Fails without invoking any handler if one of them could not be resolved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    /// use std::sync::Mutex;
+    ///
+    /// struct UserCreated {
+    ///     name: String,
+    /// }
+    ///
+    /// struct WelcomeMailer {
+    ///     sent: Mutex<Vec<String>>,
+    /// }
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// #[interface(dyn EventHandler<UserCreated>)]
+    /// impl WelcomeMailer {
+    ///     pub fn new() -> Self {
+    ///         Self { sent: Mutex::new(Vec::new()) }
+    ///     }
+    /// }
+    ///
+    /// impl EventHandler<UserCreated> for WelcomeMailer {
+    ///     fn handle(&self, event: &UserCreated) {
+    ///         self.sent.lock().unwrap().push(event.name.clone());
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add::<WelcomeMailer>().build();
+    ///
+    /// cat.publish(&UserCreated { name: "alice".to_owned() }).unwrap();
+    ///
+    /// let mailer = cat.get_one::<WelcomeMailer>().unwrap();
+    /// assert_eq!(*mailer.sent.lock().unwrap(), ["alice"]);
+    /// ```
+    pub fn publish<E>(&self, event: &E) -> Result<(), InjectionError>
+    where
+        E: 'static,
+    {
+        for handler in self.get_all::<dyn EventHandler<E>>()? {
+            handler.handle(event);
+        }
+        Ok(())
+    }
+
+    /// Asynchronous counterpart of [`Catalog::publish()`] that resolves the handlers
+    /// asynchronously and also delivers the event to the components bound to the
+    /// [`AsyncEventHandler<E>`] interface. Synchronous handlers are invoked first.
+    pub async fn publish_async<E>(&self, event: &E) -> Result<(), InjectionError>
+    where
+        E: 'static,
+    {
+        let handlers = self.get_async::<AllOf<dyn EventHandler<E>>>().await?;
+        let async_handlers = self.get_async::<AllOf<dyn AsyncEventHandler<E>>>().await?;
+
+        for handler in handlers {
+            handler.handle(event);
+        }
+        for handler in async_handlers {
+            handler.handle_async(event).await;
+        }
+        Ok(())
+    }
+
+    /// Returns the cached instances of this catalog bound to the interface in the shutdown order,
+    /// along with the position of their type in the construction order. When `scoped_only` is set
+    /// skips the instances that are also cached outside of the current scope (e.g. singletons).
+    fn cached_for_shutdown<Iface>(&self, scoped_only: bool) -> Vec<(usize, Shared<Iface>)>
+    where
+        Iface: 'static + ?Sized,
+    {
+        let unscoped = Self {
+            inner: self.inner.clone(),
+            frame: None,
+            scope_context: None,
+            resolution_context: None,
+            context: self.context.clone(),
+            watch: None,
+        };
+        let is_scoped = |b: &TypecastBuilder<'_, Iface>, inst: &Shared<Iface>| match b
+            .cached(&unscoped)
+        {
+            Some(outer) => Shared::as_ptr(&outer) as *const () != Shared::as_ptr(inst) as *const (),
+            None => true,
+        };
+
+        let order = self.construction_positions();
+        let bindings = self
+            .inner
+            .registry
+            .bindings
+            .get(&IfaceTypeId(TypeId::of::<Iface>()));
+
+        let mut instances: Vec<_> = TypecastBuilderIterator::<Iface>::new(bindings, None)
+            .filter_map(|b| {
+                let pos = order[&b.instance_type_id()];
+                b.cached(self)
+                    .filter(|inst| !scoped_only || is_scoped(&b, inst))
+                    .map(|inst| (pos, inst))
+            })
+            .collect();
+        instances.sort_by_key(|(pos, _)| Reverse(*pos));
+        instances
+    }
+
+    /// Returns positions of the component types of this catalog in the construction order,
+    /// see [`Catalog::construction_order()`]
+    fn construction_positions(&self) -> HashMap<TypeId, usize> {
+        self.construction_order()
+            .into_iter()
+            .enumerate()
+            .map(|(pos, node)| (node.type_id, pos))
+            .collect()
+    }
+
+    /// Groups the component types of this catalog so that every component depends only on the
+    /// components of the preceding groups
+    #[cfg(not(feature = "unsync"))]
+    fn construction_waves(&self) -> Vec<Vec<TypeId>> {
+        fn depth(
+            graph: &DependencyGraph,
+            type_id: TypeId,
+            depths: &mut HashMap<TypeId, usize>,
+        ) -> usize {
+            if let Some(depth) = depths.get(&type_id) {
+                return *depth;
+            }
+
+            // Marking before visiting dependencies guards against cycles
+            depths.insert(type_id, 0);
+
+            let res = graph
+                .edges
+                .iter()
+                .filter(|e| e.from == type_id)
+                .filter_map(|e| e.to)
+                .map(|to| depth(graph, to, depths) + 1)
+                .max()
+                .unwrap_or(0);
+
+            depths.insert(type_id, res);
+            res
+        }
+
+        let graph = self.dependency_graph();
+        let mut depths = HashMap::new();
+        let mut waves: Vec<Vec<TypeId>> = Vec::new();
+        for node in &graph.nodes {
+            let wave = depth(&graph, node.type_id, &mut depths);
+            if waves.len() <= wave {
+                waves.resize_with(wave + 1, Vec::new);
+            }
+            waves[wave].push(node.type_id);
+        }
+        waves
     }
 
     /// A short-hand for `get::<OneOf<T>>()`.
-    pub fn get_one<Iface>(&self) -> Result<Arc<Iface>, InjectionError>
+    pub fn get_one<Iface>(&self) -> Result<Shared<Iface>, InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        self.observed(type_name::<OneOf<Iface>>(), || OneOf::<Iface>::get(self))
+    }
+
+    /// A short-hand for `get::<AllOf<T>>()`, returning all implementations ordered by priority
+    /// (see [`Builder::priority()`]), then by registration.
+    pub fn get_all<Iface>(&self) -> Result<Vec<Shared<Iface>>, InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        self.observed(type_name::<AllOf<Iface>>(), || AllOf::<Iface>::get(self))
+    }
+
+    /// Resolves a dependency, falling back to the value produced by the function when no
+    /// implementations were registered, see [`Maybe`]. Errors that occur while building
+    /// a registered implementation are still propagated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// trait Clock: Send + Sync {
+    ///     fn now(&self) -> u64;
+    /// }
+    ///
+    /// struct SystemClock;
+    /// impl Clock for SystemClock {
+    ///     fn now(&self) -> u64 {
+    ///         42
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().build();
+    ///
+    /// let clock = cat
+    ///     .get_or::<OneOf<dyn Clock>, _>(|| Arc::new(SystemClock))
+    ///     .unwrap();
+    /// assert_eq!(clock.now(), 42);
+    /// ```
+    pub fn get_or<Spec, Fallback>(
+        &self,
+        fallback: Fallback,
+    ) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: DependencySpec + 'static,
+        Fallback: FnOnce() -> Spec::ReturnType,
+        Maybe<Spec>: DependencySpec<ReturnType = Option<Spec::ReturnType>> + 'static,
+    {
+        Ok(self.get::<Maybe<Spec>>()?.unwrap_or_else(fallback))
+    }
+
+    /// A short-hand for `get_or::<OneOf<T>, _>(|| Arc::new(T::default()))`.
+    pub fn get_or_default<Iface>(&self) -> Result<Shared<Iface>, InjectionError>
     where
-        Iface: 'static + ?Sized + Send + Sync,
+        Iface: 'static + Default + MaybeSendSync,
     {
-        OneOf::<Iface>::get(self)
+        self.get_or::<OneOf<Iface>, _>(|| Shared::new(Iface::default()))
     }
 }
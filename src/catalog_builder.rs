@@ -1,6 +1,7 @@
 use std::{
     any::{type_name, TypeId},
-    collections::HashMap,
+    cmp::Reverse,
+    collections::{hash_map::Entry, HashMap, HashSet},
     marker::Unsize,
     sync::Arc,
 };
@@ -12,111 +13,1946 @@ use crate::*;
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
+/// See [`CatalogBuilder::max_resolution_depth()`]
+const DEFAULT_MAX_RESOLUTION_DEPTH: usize = 128;
+
+/// See [`CatalogBuilder::max_async_concurrency()`]
+const DEFAULT_MAX_ASYNC_CONCURRENCY: usize = 16;
+
+/// Handler of the types that have no bindings, see [`CatalogBuilder::on_unresolved()`]
+pub trait UnresolvedFn:
+    Fn(TypeId, &'static str, &Catalog) -> Option<Shared<SharedAny>> + MaybeSendSync
+{
+}
+
+impl<F> UnresolvedFn for F where
+    F: Fn(TypeId, &'static str, &Catalog) -> Option<Shared<SharedAny>> + MaybeSendSync
+{
+}
+
+/// Handling of the repeated registrations, see [`CatalogBuilder::on_duplicate()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Panics naming the type that is registered (or bound to the interface) again
+    Error,
+    /// Replaces the earlier registration, so that the bindings of the implementation to
+    /// the interfaces resolve the new builder
+    Replace,
+    /// Keeps both registrations, e.g. for [`AllOf`] to resolve both instances, with every
+    /// registration bound to its own interfaces. Calls that refer to the implementation by type
+    /// afterwards (e.g. [`CatalogBuilder::bind()`]) apply to the latest registration.
+    Append,
+}
+
+/// Settings of the catalog that are filled in by the [`CatalogBuilder`] and kept by the built
+/// [`Catalog`] as is, so that [`Catalog::builder()`] can carry them over
+#[derive(Clone)]
+pub(crate) struct RegistryConfig {
+    /// Catalog to fall back to for interfaces that have no local bindings
+    pub(crate) parent: Option<Catalog>,
+    pub(crate) memoize_transients: bool,
+    pub(crate) max_resolution_depth: usize,
+    pub(crate) max_async_concurrency: usize,
+    pub(crate) cache_limits: CacheLimits,
+    pub(crate) observers: Vec<Arc<dyn ResolutionObserver>>,
+    /// Implementations that can be replaced on the built catalog, see [`CatalogBuilder::add_swappable()`]
+    pub(crate) swap_slots: HashMap<IfaceTypeId, Arc<SharedAny>>,
+    /// Fallbacks for the types without bindings, see [`CatalogBuilder::on_unresolved()`]
+    pub(crate) unresolved: Vec<Arc<dyn UnresolvedFn>>,
+    /// Configuration keys applied by [`CatalogBuilder::configure_from()`] along with their
+    /// values as JSON, see [`WiringManifest::config`]
+    pub(crate) applied_config: Vec<(String, String)>,
+    /// See [`CatalogBuilder::record_resolutions()`]
+    pub(crate) resolution_log: Option<Arc<ResolutionLog>>,
+}
+
+impl Default for RegistryConfig {
+    fn default() -> Self {
+        Self {
+            parent: None,
+            memoize_transients: false,
+            max_resolution_depth: DEFAULT_MAX_RESOLUTION_DEPTH,
+            max_async_concurrency: DEFAULT_MAX_ASYNC_CONCURRENCY,
+            cache_limits: CacheLimits::default(),
+            observers: Vec::new(),
+            swap_slots: HashMap::new(),
+            unresolved: Vec::new(),
+            applied_config: Vec::new(),
+            resolution_log: None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct CatalogBuilder {
     builders: HashMap<ImplTypeId, Arc<dyn Builder>>,
+    /// Earlier registrations of the types registered again under [`DuplicatePolicy::Append`],
+    /// as `builders` keeps the latest one
+    appended: Vec<Arc<dyn Builder>>,
     bindings: MultiMap<IfaceTypeId, Binding>,
+    decorators: MultiMap<IfaceTypeId, Arc<AnyDecorator>>,
+    /// Supertraits to resolve the implementations of a trait by, see [`CatalogBuilder::bind_upcast()`]
+    upcasts: Vec<Upcast>,
+    eager: HashSet<ImplTypeId>,
+    /// See [`Registration::with_meta()`]
+    metadata: HashMap<ImplTypeId, Metadata>,
+    profiles: Vec<String>,
+    /// Dependencies required by the modules along with their names, see [`Module::requires()`]
+    requirements: Vec<(&'static str, DependencyInfo)>,
+    default_scope: Option<DefaultScope>,
+    /// See [`CatalogBuilder::on_duplicate()`]
+    on_duplicate: Option<DuplicatePolicy>,
+    /// Keys of the configuration documents, see [`CatalogBuilder::configure_from()`]
+    #[cfg(feature = "config")]
+    pub(crate) config_keys: Vec<(String, ConfigApplier)>,
+    pub(crate) registry: RegistryConfig,
+}
+
+#[derive(Clone, Copy)]
+struct Upcast {
+    from: IfaceTypeId,
+    into: IfaceTypeId,
+    cast: fn(&Binding) -> Binding,
+}
+
+#[derive(Clone, Copy)]
+struct DefaultScope {
+    factory: fn() -> Box<dyn Scope>,
+    name: &'static str,
 }
 
 impl CatalogBuilder {
     pub fn new() -> Self {
         Self {
             builders: HashMap::new(),
+            appended: Vec::new(),
             bindings: MultiMap::new(),
+            decorators: MultiMap::new(),
+            upcasts: Vec::new(),
+            eager: HashSet::new(),
+            metadata: HashMap::new(),
+            profiles: Vec::new(),
+            requirements: Vec::new(),
+            default_scope: None,
+            on_duplicate: None,
+            #[cfg(feature = "config")]
+            config_keys: Vec::new(),
+            registry: RegistryConfig::default(),
         }
     }
 
-    pub fn add<Bld: BuilderLike>(&mut self) -> &mut Self {
+    /// Creates a builder of a child catalog that falls back to the `parent` for interfaces
+    /// that have no bindings registered locally, e.g. to provide per-request components
+    /// or to override a few components in tests while inheriting the rest.
+    ///
+    /// Components inherited from the parent are resolved within the parent catalog, so their
+    /// dependencies are never taken from the child and their singletons are shared with the parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// let parent = CatalogBuilder::new()
+    ///     .add_value(8080u16)
+    ///     .add_value("prod".to_owned())
+    ///     .build();
+    ///
+    /// let child = CatalogBuilder::new_chained(&parent)
+    ///     .add_value("test".to_owned())
+    ///     .build();
+    ///
+    /// assert_eq!(*child.get_one::<u16>().unwrap(), 8080);
+    /// assert_eq!(*child.get_one::<String>().unwrap(), "test");
+    /// ```
+    pub fn new_chained(parent: &Catalog) -> Self {
+        Self {
+            registry: RegistryConfig {
+                observers: parent.observers().to_vec(),
+                parent: Some(parent.clone()),
+                ..RegistryConfig::default()
+            },
+            ..Self::new()
+        }
+    }
+
+    /// Creates a builder with existing registrations, see [`Catalog::builder()`]
+    pub(crate) fn new_seeded(
+        builders: HashMap<ImplTypeId, Arc<dyn Builder>>,
+        appended: Vec<Arc<dyn Builder>>,
+        bindings: MultiMap<IfaceTypeId, Binding>,
+        registry: RegistryConfig,
+    ) -> Self {
+        Self {
+            builders,
+            appended,
+            bindings,
+            registry,
+            ..Self::new()
+        }
+    }
+
+    /// Registers a component along with the interfaces specified via `#[interface(...)]`,
+    /// returning a [`Registration`] handle to parametrize its builder in place.
+    pub fn add<Bld>(&mut self) -> Registration<'_, Bld>
+    where
+        Bld: BuilderLike + 'static,
+        Bld::Builder: 'static,
+    {
         Bld::register(self);
+        Registration::new(self)
+    }
+
+    /// Registers a component same as [`CatalogBuilder::add()`], overriding its priority among
+    /// the implementations of the interfaces it's bound to (see [`Builder::priority()`]).
+    ///
+    /// [`OneOf`] resolves the implementation with the highest priority, reporting
+    /// [`InjectionError::Ambiguous`] if several implementations share it, while [`AllOf`]
+    /// and [`Catalog::get_all()`] order the implementations by priority, then by registration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Cache: Send + Sync {
+    ///     fn name(&self) -> &str;
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Cache)]
+    /// struct InMemoryCache;
+    /// impl Cache for InMemoryCache {
+    ///     fn name(&self) -> &str {
+    ///         "memory"
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Cache)]
+    /// struct RedisCache;
+    /// impl Cache for RedisCache {
+    ///     fn name(&self) -> &str {
+    ///         "redis"
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<InMemoryCache>()
+    ///     .add_with_priority::<RedisCache>(10)
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Cache>().unwrap().name(), "redis");
+    /// ```
+    pub fn add_with_priority<Bld>(&mut self, priority: i32) -> &mut Self
+    where
+        Bld: BuilderLike + 'static,
+    {
+        Bld::register(self);
+
+        let impl_type = ImplTypeId(TypeId::of::<Bld>());
+        let builder = match self.builders.get(&impl_type) {
+            Some(builder) => builder.clone(),
+            None => panic!("Type {} is not registered", type_name::<Bld>()),
+        };
+        self.replace_builder(
+            impl_type,
+            Arc::new(PrioritizedBuilder::new(builder, priority)),
+        );
         self
     }
 
-    pub fn add_builder<Bld, Impl>(&mut self, builder: Bld) -> &mut Self
+    /// Registers a component same as [`CatalogBuilder::add()`], letting the application choose
+    /// the scope of a component that doesn't specify one explicitly (e.g. the one defined in
+    /// a library crate). Components with an explicit scope can be overridden via `with_scope()`
+    /// of their generated builder instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// struct Connection;
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add_with_scope::<Connection, Singleton>()
+    ///     .build();
+    ///
+    /// let inst1 = cat.get_one::<Connection>().unwrap();
+    /// let inst2 = cat.get_one::<Connection>().unwrap();
+    /// assert!(Arc::ptr_eq(&inst1, &inst2));
+    /// ```
+    pub fn add_with_scope<Bld, S>(&mut self) -> &mut Self
     where
-        Impl: 'static + Send + Sync,
-        Bld: TypedBuilder<Impl> + 'static,
+        Bld: BuilderLike + 'static,
+        S: Scope + Default + MaybeSendSync + 'static,
     {
-        let key = ImplTypeId(TypeId::of::<Impl>());
-        if self.builders.contains_key(&key) {
+        Bld::register(self);
+
+        let impl_type = ImplTypeId(TypeId::of::<Bld>());
+        let builder = match self.builders.get(&impl_type) {
+            Some(builder) => builder.clone(),
+            None => panic!("Type {} is not registered", type_name::<Bld>()),
+        };
+        if builder.scope_was_explicit() {
             panic!(
-                "Builder for type {} is already registered",
-                type_name::<Impl>()
+                "Type {} has explicit scope {}, use with_scope() of its builder instead",
+                type_name::<Bld>(),
+                builder.scope_name()
             );
         }
+        self.replace_builder(
+            impl_type,
+            Arc::new(ScopedBuilder::new(
+                builder,
+                Box::new(S::default()),
+                type_name::<S>(),
+            )),
+        );
+        self
+    }
+
+    /// Replaces the registered builder of the type along with all bindings referring to it
+    pub(crate) fn replace_builder(&mut self, impl_type: ImplTypeId, builder: Arc<dyn Builder>) {
+        let prev = match self.builders.get(&impl_type) {
+            Some(prev) => prev.clone(),
+            None => panic!("Type {} is not registered", builder.instance_type_name()),
+        };
+
+        for (_, iface_bindings) in self.bindings.iter_all_mut() {
+            for binding in iface_bindings.iter_mut() {
+                if same_builder(&binding.builder, &prev) {
+                    binding.builder = builder.clone();
+                }
+            }
+        }
+        self.builders.insert(impl_type, builder);
+    }
 
-        let builder = Arc::new(builder);
-        self.builders.insert(key, builder.clone());
+    pub(crate) fn insert_metadata<T>(&mut self, impl_type: ImplTypeId, value: T)
+    where
+        T: 'static + MaybeSendSync,
+    {
+        self.metadata.entry(impl_type).or_default().insert(value);
+    }
 
-        self.bindings.insert(
+    /// Registers all components of the module, see [`Module`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {}
+    ///
+    /// #[component]
+    /// struct UserRepo {
+    ///     db: Arc<dyn Db>,
+    /// }
+    ///
+    /// struct UsersModule;
+    ///
+    /// impl Module for UsersModule {
+    ///     fn load(&self, b: &mut CatalogBuilder) {
+    ///         b.add::<UserRepo>();
+    ///     }
+    ///
+    ///     fn requires(&self) -> Vec<DependencyInfo> {
+    ///         vec![DependencyInfo::of::<dyn Db>(DependencyKind::One, None)]
+    ///     }
+    /// }
+    ///
+    /// let err = CatalogBuilder::new()
+    ///     .add_module(UsersModule)
+    ///     .validate()
+    ///     .unwrap_err();
+    ///
+    /// match &err.errors()[0] {
+    ///     InjectionError::Unregistered(e) => assert_eq!(e.required_by(), &[UsersModule.name()]),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn add_module<M: Module>(&mut self, module: M) -> &mut Self {
+        module.load(self);
+        let name = module.name();
+        self.requirements
+            .extend(module.requires().into_iter().map(|dep| (name, dep)));
+        self
+    }
+
+    /// Combines the registrations of another builder (e.g. the one of a subsystem crate) with
+    /// the ones of this builder, failing with the list of conflicts if any interface (including
+    /// the concrete types) is bound by both, rather than letting one registration shadow
+    /// the other. Nothing is merged when there are conflicts.
+    ///
+    /// Registrations that both builders share (e.g. when seeded from the same catalog via
+    /// [`Catalog::builder()`]) are not conflicts. Settings of the merged builder, like its active
+    /// profiles or the default scope, are not carried over, so the merged components follow
+    /// the settings of this builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Storage: Send + Sync {}
+    ///
+    /// #[component]
+    /// #[interface(dyn Storage)]
+    /// struct S3Storage;
+    /// impl Storage for S3Storage {}
+    ///
+    /// #[component]
+    /// #[interface(dyn Storage)]
+    /// struct LocalStorage;
+    /// impl Storage for LocalStorage {}
+    ///
+    /// let mut billing = CatalogBuilder::new();
+    /// billing.add::<S3Storage>();
+    ///
+    /// let mut reports = CatalogBuilder::new();
+    /// reports.add::<LocalStorage>();
+    ///
+    /// let mut b = CatalogBuilder::new();
+    /// b.merge(billing).unwrap();
+    /// let err = b.merge(reports).err().unwrap();
+    ///
+    /// let conflict = &err.conflicts()[0];
+    /// assert_eq!(conflict.iface_name(), std::any::type_name::<dyn Storage>());
+    /// assert_eq!(conflict.merged(), [std::any::type_name::<LocalStorage>()]);
+    /// ```
+    pub fn merge(&mut self, other: CatalogBuilder) -> Result<&mut Self, MergeError> {
+        let conflicts = self.merge_conflicts(&other);
+        if !conflicts.is_empty() {
+            return Err(MergeError::new(conflicts));
+        }
+
+        for (impl_type, builder) in other.builders {
+            self.builders.entry(impl_type).or_insert(builder);
+        }
+        self.appended.extend(other.appended);
+        for (iface_type, bindings) in other.bindings {
+            for binding in bindings {
+                if !self.is_bound(iface_type, &binding) {
+                    self.bindings.insert(iface_type, binding);
+                }
+            }
+        }
+        for (iface_type, decorators) in other.decorators {
+            self.decorators.insert_many(iface_type, decorators);
+        }
+        self.upcasts.extend(other.upcasts);
+        self.eager.extend(other.eager);
+        for (impl_type, metadata) in other.metadata {
+            self.metadata
+                .entry(impl_type)
+                .or_default()
+                .extend(&metadata);
+        }
+        self.requirements.extend(other.requirements);
+        self.registry.observers.extend(other.registry.observers);
+        self.registry.swap_slots.extend(other.registry.swap_slots);
+        self.registry.unresolved.extend(other.registry.unresolved);
+        #[cfg(feature = "config")]
+        self.config_keys.extend(other.config_keys);
+        self.registry
+            .applied_config
+            .extend(other.registry.applied_config);
+        self.registry.resolution_log = self
+            .registry
+            .resolution_log
+            .take()
+            .or(other.registry.resolution_log);
+        Ok(self)
+    }
+
+    /// Whether the builder of the binding is already bound to the interface under the same name
+    fn is_bound(&self, iface_type: IfaceTypeId, binding: &Binding) -> bool {
+        self.bindings
+            .get_vec(&iface_type)
+            .into_iter()
+            .flatten()
+            .any(|b| same_builder(&b.builder, &binding.builder) && b.name == binding.name)
+    }
+
+    fn merge_conflicts(&self, other: &CatalogBuilder) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+
+        for (iface_type, other_bindings) in other.bindings.iter_all() {
+            let bindings = match self.bindings.get_vec(iface_type) {
+                Some(bindings) => bindings,
+                None => continue,
+            };
+
+            let mut names: Vec<_> = other_bindings.iter().map(|b| &b.name).collect();
+            names.sort();
+            names.dedup();
+            for name in names {
+                // Implementations bound under the name that the other builder doesn't share
+                let unshared =
+                    |bindings: &[Binding], other: &CatalogBuilder| -> Vec<&'static str> {
+                        bindings
+                            .iter()
+                            .filter(|b| &b.name == name && !other.is_bound(*iface_type, b))
+                            .map(|b| b.builder.instance_type_name())
+                            .collect()
+                    };
+
+                let existing = unshared(bindings, other);
+                let merged = unshared(other_bindings, self);
+                if !existing.is_empty() && !merged.is_empty() {
+                    conflicts.push(MergeConflict::new(
+                        other_bindings[0].iface_name,
+                        name.clone(),
+                        existing,
+                        merged,
+                    ));
+                }
+            }
+        }
+
+        // Types registered without a binding of their own, see `CatalogBuilder::add_as()`
+        for (impl_type, builder) in &other.builders {
+            match self.builders.get(impl_type) {
+                Some(existing) if !same_builder(existing, builder) => {
+                    let type_name = builder.instance_type_name();
+                    if !conflicts.iter().any(|c| c.iface_name() == type_name) {
+                        conflicts.push(MergeConflict::new(
+                            type_name,
+                            None,
+                            vec![type_name],
+                            vec![type_name],
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        conflicts.sort_by(|a, b| (a.iface_name(), a.name()).cmp(&(b.iface_name(), b.name())));
+        conflicts
+    }
+
+    /// Registers a component under the specified interface only, without binding it to its concrete type,
+    /// so that clients can only resolve it via the interface.
+    pub fn add_as<Iface, Impl>(&mut self) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: BuilderLike + 'static + MaybeSendSync + Unsize<Iface>,
+        Impl::Builder: TypedBuilder<Impl> + 'static,
+    {
+        self.add_builder_as::<Iface, _, Impl>(Impl::builder())
+    }
+
+    pub fn add_builder<Bld, Impl>(&mut self, builder: Bld) -> &mut Self
+    where
+        Impl: 'static + MaybeSendSync,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        let builder = self.insert_builder(builder);
+
+        self.insert_binding(
             IfaceTypeId(TypeId::of::<Impl>()),
             Binding::new(
                 Arc::new(TypeCaster::<Impl> {
                     // SAFETY: `TypeCaster<Iface>` is guaranteed to be invoked only on the `Impl` instances
-                    cast_arc: |v| v.downcast().unwrap(),
+                    cast_arc: Box::new(|v: Shared<SharedAny>| v.downcast::<Impl>().unwrap()),
+                    cast_box: Box::new(|v: Shared<SharedAny>| {
+                        let v: Shared<Impl> = v.downcast().unwrap();
+                        Shared::try_unwrap(v).map(Box::new).map_err(|v| v as _)
+                    }),
                 }),
                 builder,
+                type_name::<Impl>(),
             ),
         );
 
         self
     }
 
+    /// Registers a builder under the specified interface only, see [`CatalogBuilder::add_as()`].
+    pub fn add_builder_as<Iface, Bld, Impl>(&mut self, builder: Bld) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        self.insert_builder(builder);
+        self.bind::<Iface, Impl>()
+    }
+
+    fn insert_builder<Bld, Impl>(&mut self, builder: Bld) -> Arc<dyn Builder>
+    where
+        Impl: 'static + MaybeSendSync,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        let key = ImplTypeId(TypeId::of::<Impl>());
+        let builder: Arc<dyn Builder> = Arc::new(builder);
+
+        match self.builders.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(builder.clone());
+            }
+            Entry::Occupied(mut entry) => match self.on_duplicate.unwrap_or(DuplicatePolicy::Error)
+            {
+                DuplicatePolicy::Error => panic!(
+                    "Builder for type {} is already registered, see CatalogBuilder::on_duplicate()",
+                    type_name::<Impl>()
+                ),
+                DuplicatePolicy::Replace => self.replace_builder(key, builder.clone()),
+                DuplicatePolicy::Append => {
+                    let prev = entry.insert(builder.clone());
+                    self.appended.push(prev);
+                }
+            },
+        }
+        builder
+    }
+
+    /// Adds the binding unless it repeats an existing one, in which case it's handled according
+    /// to the [`DuplicatePolicy`]. Named bindings are never considered duplicates, as their names
+    /// are unique already (see [`CatalogBuilder::bind_named()`]).
+    fn insert_binding(&mut self, iface_type: IfaceTypeId, binding: Binding) {
+        let impl_type = binding.builder.instance_type_id();
+        let is_duplicate = |b: &Binding| {
+            binding.name.is_none() && b.name.is_none() && b.builder.instance_type_id() == impl_type
+        };
+
+        match self.on_duplicate.unwrap_or(DuplicatePolicy::Append) {
+            DuplicatePolicy::Error => {
+                let bindings = self.bindings.get_vec(&iface_type);
+                if bindings.map_or(false, |bindings| bindings.iter().any(is_duplicate)) {
+                    panic!(
+                        "Implementation {} is already bound to {}, see CatalogBuilder::on_duplicate()",
+                        binding.builder.instance_type_name(),
+                        binding.iface_name
+                    );
+                }
+            }
+            DuplicatePolicy::Replace => {
+                if let Some(bindings) = self.bindings.get_vec_mut(&iface_type) {
+                    bindings.retain(|b| !is_duplicate(b));
+                }
+            }
+            DuplicatePolicy::Append => {}
+        }
+
+        self.bindings.insert(iface_type, binding);
+    }
+
     // TODO: Replace with generic add<B: Into<Builder>>?
     pub fn add_factory<Fct, Impl>(&mut self, factory: Fct) -> &mut Self
     where
-        Fct: 'static + Fn() -> Impl + Send + Sync,
-        Impl: 'static + Send + Sync,
+        Fct: 'static + Fn() -> Impl + MaybeSendSync,
+        Impl: 'static + MaybeSendSync,
     {
-        self.add_builder(Factory::new(factory));
+        self.add_builder(FactoryBuilder::new(factory));
         self
     }
 
+    /// Registers a closure that builds a new instance upon every resolution, resolving
+    /// the dependencies of the instance from the catalog it's invoked with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// struct Url(String);
+    ///
+    /// let catalog = CatalogBuilder::new()
+    ///     .add_value(8080u16)
+    ///     .add_builder_fn(|cat| {
+    ///         let port = cat.get_one::<u16>()?;
+    ///         Ok(Url(format!("http://localhost:{}", port)))
+    ///     })
+    ///     .build();
+    ///
+    /// assert_eq!(catalog.get_one::<Url>().unwrap().0, "http://localhost:8080");
+    /// ```
+    pub fn add_builder_fn<Fct, Impl>(&mut self, fun: Fct) -> &mut Self
+    where
+        Fct: 'static + Fn(&Catalog) -> Result<Impl, InjectionError> + MaybeSendSync,
+        Impl: 'static + MaybeSendSync,
+    {
+        self.add_builder(FnBuilder::new(fun));
+        self
+    }
+
+    /// Registers a builder as the implementation of the interface that can be replaced while
+    /// the catalog is in use via [`Catalog::swap_builder()`], e.g. for blue/green upgrades
+    /// of a component or dynamic feature toggles.
+    ///
+    /// The implementation can be resolved only via the interface, and not as [`Owned`]. Every
+    /// implementation is cached according to its own builder's scope, so swapping a
+    /// [`Singleton`] doesn't affect the instances handed out before the swap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Greeter: Send + Sync {
+    ///     fn greet(&self) -> String;
+    /// }
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Blue;
+    /// impl Greeter for Blue {
+    ///     fn greet(&self) -> String {
+    ///         "blue".to_owned()
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Green;
+    /// impl Greeter for Green {
+    ///     fn greet(&self) -> String {
+    ///         "green".to_owned()
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add_swappable::<dyn Greeter, _, Blue>(Blue::builder())
+    ///     .build();
+    ///
+    /// let old = cat.get_one::<dyn Greeter>().unwrap();
+    /// cat.swap_builder::<dyn Greeter, _, Green>(Green::builder()).unwrap();
+    ///
+    /// assert_eq!(old.greet(), "blue");
+    /// assert_eq!(cat.get_one::<dyn Greeter>().unwrap().greet(), "green");
+    /// ```
+    pub fn add_swappable<Iface, Bld, Impl>(&mut self, builder: Bld) -> &mut Self
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        let iface_type = IfaceTypeId(TypeId::of::<Iface>());
+        if self.registry.swap_slots.contains_key(&iface_type) {
+            panic!(
+                "Swappable implementation of {} is already registered",
+                type_name::<Iface>()
+            );
+        }
+
+        let slot = Arc::new(SwapSlot::<Iface>::new(builder));
+        let builder: Arc<dyn Builder> = slot.clone();
+
+        self.builders
+            .insert(SwapSlot::<Iface>::instance_type(), builder.clone());
+        self.bindings.insert(
+            iface_type,
+            Binding::new(
+                Arc::new(SwapSlot::<Iface>::caster()),
+                builder,
+                type_name::<Iface>(),
+            ),
+        );
+        self.registry.swap_slots.insert(iface_type, slot);
+        self
+    }
+
+    /// Registers an existing instance, which will be shared by all clients as a singleton
     // TODO: Replace with generic add<B: Into<Builder>>?
     pub fn add_value<'a, Impl>(&'a mut self, value: Impl) -> &mut Self
     where
-        Impl: 'static + Send + Sync,
+        Impl: 'static + MaybeSendSync,
     {
         self.add_builder(Prebuilt::from_value(value));
         self
     }
 
+    /// Replaces all implementations of the interface with the provided instance, e.g. to substitute
+    /// a mock in tests. The replaced implementations are unregistered entirely, along with their
+    /// bindings to the other interfaces. See [`CatalogBuilder::override_with()`].
+    ///
+    /// Interfaces inherited from the parent catalog (see [`CatalogBuilder::new_chained()`]) are
+    /// shadowed by the replacement, leaving the parent intact.
+    ///
+    /// # Panics
+    ///
+    /// If no implementations of the interface are registered, so that overrides that no longer
+    /// match the actual registrations don't go unnoticed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Mailer: Send + Sync {
+    ///     fn send(&self) -> String;
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Mailer)]
+    /// struct SmtpMailer;
+    /// impl Mailer for SmtpMailer {
+    ///     fn send(&self) -> String {
+    ///         "smtp".to_owned()
+    ///     }
+    /// }
+    ///
+    /// struct FakeMailer;
+    /// impl Mailer for FakeMailer {
+    ///     fn send(&self) -> String {
+    ///         "fake".to_owned()
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<SmtpMailer>()
+    ///     .replace::<dyn Mailer, _>(FakeMailer)
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Mailer>().unwrap().send(), "fake");
+    /// assert!(cat.get_one::<SmtpMailer>().is_err());
+    /// ```
+    pub fn replace<Iface, Impl>(&mut self, value: Impl) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+    {
+        self.override_with::<Iface, _, Impl>(Prebuilt::from_value(value))
+    }
+
+    /// Replaces all implementations of the interface with the provided builder,
+    /// see [`CatalogBuilder::replace()`].
+    ///
+    /// # Panics
+    ///
+    /// If no implementations of the interface are registered.
+    pub fn override_with<Iface, Bld, Impl>(&mut self, builder: Bld) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        self.remove_implementations_of::<Iface>();
+        self.add_builder_as::<Iface, Bld, Impl>(builder)
+    }
+
+    fn remove_implementations_of<Iface>(&mut self)
+    where
+        Iface: 'static + ?Sized,
+    {
+        let iface_type = IfaceTypeId(TypeId::of::<Iface>());
+
+        let replaced: HashSet<_> = self
+            .bindings
+            .get_vec(&iface_type)
+            .into_iter()
+            .flatten()
+            .map(|b| ImplTypeId(b.builder.instance_type_id()))
+            .collect();
+
+        if replaced.is_empty() {
+            let inherited = self
+                .registry
+                .parent
+                .as_ref()
+                .map_or(false, |p| p.bindings_of(iface_type).0.is_some());
+            if !inherited {
+                panic!(
+                    "Cannot replace {}: no implementations are registered",
+                    type_name::<Iface>()
+                );
+            }
+            return;
+        }
+
+        self.builders.retain(|t, _| !replaced.contains(t));
+        self.appended
+            .retain(|b| !replaced.contains(&ImplTypeId(b.instance_type_id())));
+        self.bindings
+            .retain(|_, b| !replaced.contains(&ImplTypeId(b.builder.instance_type_id())));
+        self.eager.retain(|t| !replaced.contains(t));
+        self.metadata.retain(|t, _| !replaced.contains(t));
+    }
+
+    /// Registers a configuration value, which components receive by value (as a clone) or via
+    /// `Arc<T>` when their arguments have the same type, e.g. `port: u16` or `db: DbConfig`.
+    /// Overrides of the builders (e.g. `with_port()`) still take precedence.
+    ///
+    /// Unlike [`CatalogBuilder::add_value()`], registering a config of the same type again replaces
+    /// the previous one, allowing to layer environment-specific values over the defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[derive(Clone)]
+    /// struct DbConfig {
+    ///     url: String,
+    ///     pool_size: usize,
+    /// }
+    ///
+    /// #[component]
+    /// struct Db {
+    ///     config: DbConfig,
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Db>()
+    ///     .add_config(DbConfig { url: "localhost".to_owned(), pool_size: 1 })
+    ///     .add_config(DbConfig { url: "db.prod".to_owned(), pool_size: 10 })
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<Db>().unwrap().config.url, "db.prod");
+    /// ```
+    pub fn add_config<Cfg>(&mut self, config: Cfg) -> &mut Self
+    where
+        Cfg: 'static + MaybeSendSync,
+    {
+        let impl_type = ImplTypeId(TypeId::of::<Cfg>());
+        if self.builders.remove(&impl_type).is_some() {
+            self.appended
+                .retain(|b| ImplTypeId(b.instance_type_id()) != impl_type);
+            self.bindings
+                .retain(|_, b| ImplTypeId(b.builder.instance_type_id()) != impl_type);
+        }
+        self.add_value(config)
+    }
+
     // TODO: WTF is Unsize
     pub fn bind<Iface, Impl>(&mut self) -> &mut Self
     where
         Iface: 'static + ?Sized,
-        Impl: 'static + Send + Sync + Unsize<Iface>,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+    {
+        let binding = Self::new_binding::<Iface, Impl>(&self.builders);
+        self.insert_binding(IfaceTypeId(TypeId::of::<Iface>()), binding);
+        self
+    }
+
+    /// Binds the implementation to the interface in place of the implementations bound to it
+    /// so far, including the ones bound via `#[interface(...)]` upon registration, so that the
+    /// composition root has the final say on which implementation the interface resolves to.
+    /// Named bindings of the interface (see [`CatalogBuilder::bind_named()`]) are replaced too,
+    /// as they participate in the regular resolution. Replaced implementations stay registered
+    /// and resolvable by their own types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {
+    ///     fn name(&self) -> &str;
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Db)]
+    /// struct PgDb;
+    /// impl Db for PgDb {
+    ///     fn name(&self) -> &str {
+    ///         "pg"
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Db)]
+    /// struct SqliteDb;
+    /// impl Db for SqliteDb {
+    ///     fn name(&self) -> &str {
+    ///         "sqlite"
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<PgDb>()
+    ///     .add::<SqliteDb>()
+    ///     .rebind::<dyn Db, SqliteDb>()
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Db>().unwrap().name(), "sqlite");
+    /// assert_eq!(cat.get_all::<dyn Db>().unwrap().len(), 1);
+    /// assert!(cat.get_one::<PgDb>().is_ok());
+    /// ```
+    pub fn rebind<Iface, Impl>(&mut self) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+    {
+        let iface_type = IfaceTypeId(TypeId::of::<Iface>());
+        let binding = Self::new_binding::<Iface, Impl>(&self.builders);
+        self.bindings.remove(&iface_type);
+        self.bindings.insert(iface_type, binding);
+        self
+    }
+
+    /// Binds the implementation to the interface under the specified name, allowing to resolve
+    /// a specific implementation among many via [`Catalog::get_named()`].
+    ///
+    /// Named bindings still participate in the regular resolution, e.g. via [`AllOf`].
+    pub fn bind_named<Iface, Impl>(&mut self, name: impl Into<String>) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+    {
+        self.insert_named_binding::<Iface, Impl>(name.into())
+    }
+
+    fn insert_named_binding<Iface, Impl>(&mut self, name: String) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
     {
         let iface_type = IfaceTypeId(TypeId::of::<Iface>());
+
+        let is_taken = self
+            .bindings
+            .get_vec(&iface_type)
+            .map(|bindings| bindings.iter().any(|b| b.name.as_ref() == Some(&name)))
+            .unwrap_or(false);
+        if is_taken {
+            panic!(
+                "Interface type {} already has a binding named '{}'",
+                type_name::<Iface>(),
+                name
+            );
+        }
+
+        let binding = Self::new_binding::<Iface, Impl>(&self.builders).with_name(name);
+        self.insert_binding(iface_type, binding);
+        self
+    }
+
+    /// Binds the implementation to the interface in the role denoted by the qualifier type,
+    /// allowing to resolve it via `OneOf<Iface, Q>` or `#[dill(qualifier = Q)]`, see [`Qualifier`].
+    ///
+    /// Same as [`CatalogBuilder::bind_named()`] with the name of the qualifier.
+    pub fn bind_qualified<Iface, Impl, Q>(&mut self) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Q: Qualifier,
+    {
+        self.insert_named_binding::<Iface, Impl>(Q::name().to_owned())
+    }
+
+    /// Makes all implementations bound to the `Sub` trait resolvable via its supertrait `Base`,
+    /// e.g. `bind_upcast::<dyn Base, dyn Special>()` for `trait Special: Base`, without binding
+    /// every implementation to both traits.
+    ///
+    /// Upcasting is applied when the catalog is built, so it also covers the implementations
+    /// bound after this call, keeping their binding names. Implementations already bound to `Base`
+    /// directly are not duplicated. Decorators of `Sub` are not applied to the upcast instances,
+    /// while the ones of `Base` are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Base: Send + Sync {
+    ///     fn name(&self) -> String;
+    /// }
+    ///
+    /// trait Special: Base {
+    ///     fn special(&self) -> bool;
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Special)]
+    /// struct SpecialImpl;
+    ///
+    /// impl Base for SpecialImpl {
+    ///     fn name(&self) -> String {
+    ///         "special".to_owned()
+    ///     }
+    /// }
+    ///
+    /// impl Special for SpecialImpl {
+    ///     fn special(&self) -> bool {
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<SpecialImpl>()
+    ///     .bind_upcast::<dyn Base, dyn Special>()
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Base>().unwrap().name(), "special");
+    /// ```
+    pub fn bind_upcast<Base, Sub>(&mut self) -> &mut Self
+    where
+        Base: 'static + ?Sized,
+        Sub: 'static + ?Sized + Unsize<Base>,
+    {
+        self.upcasts.push(Upcast {
+            from: IfaceTypeId(TypeId::of::<Sub>()),
+            into: IfaceTypeId(TypeId::of::<Base>()),
+            cast: Self::upcast_binding::<Base, Sub>,
+        });
+        self
+    }
+
+    fn upcast_binding<Base, Sub>(binding: &Binding) -> Binding
+    where
+        Base: 'static + ?Sized,
+        Sub: 'static + ?Sized + Unsize<Base>,
+    {
+        let caster = binding.caster.clone();
+        let caster_box = binding.caster.clone();
+
+        let upcast = Binding::new(
+            Arc::new(TypeCaster::<Base> {
+                cast_arc: Box::new(move |v: Shared<SharedAny>| {
+                    // SAFETY: upcasts are only applied to the bindings of the `Sub` type
+                    let sub: &TypeCaster<Sub> = unsafe { cast_erased(caster.as_ref()) };
+                    let s: Shared<Sub> = (sub.cast_arc)(v);
+                    let t: Shared<Base> = s;
+                    t
+                }),
+                cast_box: Box::new(move |v: Shared<SharedAny>| {
+                    // SAFETY: upcasts are only applied to the bindings of the `Sub` type
+                    let sub: &TypeCaster<Sub> = unsafe { cast_erased(caster_box.as_ref()) };
+                    let s: Box<Sub> = (sub.cast_box)(v)?;
+                    let t: Box<Base> = s;
+                    Ok(t)
+                }),
+            }),
+            binding.builder.clone(),
+            type_name::<Base>(),
+        );
+
+        match &binding.name {
+            Some(name) => upcast.with_name(name.clone()),
+            None => upcast,
+        }
+    }
+
+    /// Keeps only the binding with the specified name among the bindings of the interface,
+    /// making it the one resolved via [`OneOf`], e.g. to pick an implementation based on config.
+    ///
+    /// # Panics
+    ///
+    /// If the interface has no binding with such name.
+    pub fn select_named<Iface>(&mut self, name: &str) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+    {
+        if !self.try_select_named::<Iface>(name) {
+            panic!(
+                "Interface type {} has no binding named '{}'",
+                type_name::<Iface>(),
+                name
+            );
+        }
+        self
+    }
+
+    pub(crate) fn try_select_named<Iface>(&mut self, name: &str) -> bool
+    where
+        Iface: 'static + ?Sized,
+    {
+        let bindings = match self
+            .bindings
+            .get_vec_mut(&IfaceTypeId(TypeId::of::<Iface>()))
+        {
+            Some(bindings) => bindings,
+            None => return false,
+        };
+        if !bindings.iter().any(|b| b.name.as_deref() == Some(name)) {
+            return false;
+        }
+        bindings.retain(|b| b.name.as_deref() == Some(name));
+        true
+    }
+
+    fn new_binding<Iface, Impl>(builders: &HashMap<ImplTypeId, Arc<dyn Builder>>) -> Binding
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+    {
         let impl_type = ImplTypeId(TypeId::of::<Impl>());
 
-        let builder = self.builders.get(&impl_type);
-        if builder.is_none() {
-            panic!("Interface type {} is not registered", type_name::<Iface>());
+        match builders.get(&impl_type) {
+            Some(builder) => Self::new_binding_of::<Iface, Impl>(builder.clone()),
+            None => panic!("Interface type {} is not registered", type_name::<Iface>()),
         }
+    }
 
-        self.bindings.insert(
-            iface_type,
-            Binding::new(
-                Arc::new(TypeCaster::<Iface> {
-                    cast_arc: |v| {
-                        // SAFETY: `TypeCaster<Iface>` is guaranteed to be invoked only on the `Impl` instances
-                        let s: Arc<Impl> = v.downcast().unwrap();
-                        let t: Arc<Iface> = s;
-                        t
-                    },
+    pub(crate) fn new_binding_of<Iface, Impl>(builder: Arc<dyn Builder>) -> Binding
+    where
+        Iface: 'static + ?Sized,
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+    {
+        Binding::new(
+            Arc::new(TypeCaster::<Iface> {
+                cast_arc: Box::new(|v: Shared<SharedAny>| {
+                    // SAFETY: `TypeCaster<Iface>` is guaranteed to be invoked only on the `Impl` instances
+                    let s: Shared<Impl> = v.downcast().unwrap();
+                    let t: Shared<Iface> = s;
+                    t
                 }),
-                builder.unwrap().clone(),
-            ),
+                cast_box: Box::new(|v: Shared<SharedAny>| {
+                    // SAFETY: `TypeCaster<Iface>` is guaranteed to be invoked only on the `Impl` instances
+                    let s: Shared<Impl> = v.downcast().unwrap();
+                    match Shared::try_unwrap(s) {
+                        Ok(s) => {
+                            let s: Box<Impl> = Box::new(s);
+                            let t: Box<Iface> = s;
+                            Ok(t)
+                        }
+                        Err(s) => Err(s),
+                    }
+                }),
+            }),
+            builder,
+            type_name::<Iface>(),
+        )
+    }
+
+    /// Registers a decorator that wraps every instance resolved via the interface, e.g. to add
+    /// logging or metrics to all implementations of a trait. Multiple decorators are applied
+    /// in the order of registration, so the last one registered becomes the outermost.
+    ///
+    /// The decorator is invoked upon every resolution, while the wrapped instance itself
+    /// is still subject to the scope of its component. Only the bindings registered in this
+    /// builder are decorated, not the ones inherited from the parent catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    /// use std::sync::Arc;
+    ///
+    /// trait Greeter: Send + Sync {
+    ///     fn greet(&self) -> String;
+    /// }
+    ///
+    /// #[component]
+    /// struct Hello;
+    /// impl Greeter for Hello {
+    ///     fn greet(&self) -> String {
+    ///         "hello".to_owned()
+    ///     }
+    /// }
+    ///
+    /// struct Loud(Arc<dyn Greeter>);
+    /// impl Greeter for Loud {
+    ///     fn greet(&self) -> String {
+    ///         self.0.greet().to_uppercase()
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Hello>()
+    ///     .bind::<dyn Greeter, Hello>()
+    ///     .add_decorator::<dyn Greeter, _>(|inner, _| Arc::new(Loud(inner)))
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Greeter>().unwrap().greet(), "HELLO");
+    /// ```
+    pub fn add_decorator<Iface, Fct>(&mut self, decorate: Fct) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+        Fct: 'static + Fn(Shared<Iface>, &Catalog) -> Shared<Iface> + MaybeSendSync,
+    {
+        self.decorators.insert(
+            IfaceTypeId(TypeId::of::<Iface>()),
+            Arc::new(Decorator::<Iface> {
+                decorate: Box::new(decorate),
+            }),
         );
+        self
+    }
+
+    /// Marks a registered component to be instantiated when the catalog is built rather than
+    /// upon first use, same as `#[component(eager)]`, e.g. to fail fast on startup.
+    /// See [`CatalogBuilder::try_build()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Migrations;
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Migrations>()
+    ///     .eager::<Migrations>()
+    ///     .build();
+    /// ```
+    pub fn eager<Impl>(&mut self) -> &mut Self
+    where
+        Impl: 'static,
+    {
+        let impl_type = ImplTypeId(TypeId::of::<Impl>());
+        if !self.builders.contains_key(&impl_type) {
+            panic!("Type {} is not registered", type_name::<Impl>());
+        }
+        self.eager.insert(impl_type);
+        self
+    }
+
+    /// Activates the profile, so that the components registered in it (e.g. via
+    /// `#[component(profiles("test"))]`) are included in the catalog, while the components
+    /// of inactive profiles are excluded along with their bindings. Components without
+    /// profiles are always included. Multiple profiles can be active at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Mailer: Send + Sync {}
+    ///
+    /// #[component(profiles("prod"))]
+    /// #[interface(dyn Mailer)]
+    /// struct SmtpMailer;
+    /// impl Mailer for SmtpMailer {}
+    ///
+    /// #[component(profiles("dev", "test"))]
+    /// #[interface(dyn Mailer)]
+    /// struct FakeMailer;
+    /// impl Mailer for FakeMailer {}
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .with_profile("test")
+    ///     .add::<SmtpMailer>()
+    ///     .add::<FakeMailer>()
+    ///     .build();
+    ///
+    /// assert!(cat.get_one::<dyn Mailer>().is_ok());
+    /// assert!(cat.get_one::<SmtpMailer>().is_err());
+    /// ```
+    pub fn with_profile(&mut self, profile: impl Into<String>) -> &mut Self {
+        self.profiles.push(profile.into());
+        self
+    }
+
+    fn is_active(&self, builder: &dyn Builder) -> bool {
+        let profiles = builder.profiles();
+        profiles.is_empty()
+            || profiles
+                .iter()
+                .any(|p| self.profiles.iter().any(|a| a == p))
+    }
+
+    /// Sets the scope to use for all builders that don't specify a scope explicitly,
+    /// instead of the [`Transient`] scope they default to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// struct A;
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .default_scope::<Singleton>()
+    ///     .add::<A>()
+    ///     .build();
+    ///
+    /// let inst1 = cat.get_one::<A>().unwrap();
+    /// let inst2 = cat.get_one::<A>().unwrap();
+    /// assert!(std::sync::Arc::ptr_eq(&inst1, &inst2));
+    /// ```
+    pub fn default_scope<S>(&mut self) -> &mut Self
+    where
+        S: Scope + Default + MaybeSendSync + 'static,
+    {
+        self.default_scope = Some(DefaultScope {
+            factory: || Box::new(S::default()),
+            name: type_name::<S>(),
+        });
+        self
+    }
+
+    /// Makes every top-level resolution (e.g. a single [`Catalog::get()`] call) construct each
+    /// [`Transient`] component at most once and share the instance among all its dependents,
+    /// so that diamonds in a deep dependency graph are built once per resolution rather than
+    /// once per edge. Separate resolutions still get new instances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// struct Config;
+    ///
+    /// #[component]
+    /// struct Service {
+    ///     a: Arc<Config>,
+    ///     b: Arc<Config>,
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .memoize_transients()
+    ///     .add::<Config>()
+    ///     .add::<Service>()
+    ///     .build();
+    ///
+    /// let service = cat.get_one::<Service>().unwrap();
+    /// assert!(Arc::ptr_eq(&service.a, &service.b));
+    /// ```
+    pub fn memoize_transients(&mut self) -> &mut Self {
+        self.registry.memoize_transients = true;
+        self
+    }
+
+    /// Limits the number of nested types in a single resolution chain, failing the resolution with
+    /// [`InjectionError::MaxDepthExceeded`] instead of overflowing the stack, e.g. when a component
+    /// depends on itself via a [`Lazy`] or a detached catalog, which the cycle detection doesn't see.
+    ///
+    /// Defaults to 128.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// struct A;
+    ///
+    /// #[component]
+    /// struct B {
+    ///     a: Arc<A>,
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .max_resolution_depth(1)
+    ///     .add::<A>()
+    ///     .add::<B>()
+    ///     .build();
+    ///
+    /// assert!(cat.get_one::<A>().is_ok());
+    /// assert!(matches!(
+    ///     cat.get_one::<B>(),
+    ///     Err(InjectionError::MaxDepthExceeded(_))
+    /// ));
+    /// ```
+    pub fn max_resolution_depth(&mut self, depth: usize) -> &mut Self {
+        if depth == 0 {
+            panic!("Maximum resolution depth has to be positive");
+        }
+        self.registry.max_resolution_depth = depth;
+        self
+    }
+
+    /// Limits the number of implementations that [`AllOf`] constructs concurrently when resolved
+    /// asynchronously (see [`Catalog::get_async()`]), e.g. for the plugins that connect to remote
+    /// services in their `async fn new()` not to delay the startup one after another. The instances
+    /// are returned in the same order regardless, while `1` disables the concurrency.
+    ///
+    /// Implementations that depend on the same component with a caching scope (directly or
+    /// transitively) are not constructed concurrently until that component is cached, as
+    /// asynchronous resolution doesn't guarantee that it's constructed only once. Note that the
+    /// constructions run concurrently within the resolving task rather than in parallel threads.
+    ///
+    /// Defaults to 16.
+    pub fn max_async_concurrency(&mut self, limit: usize) -> &mut Self {
+        if limit == 0 {
+            panic!("Maximum async concurrency has to be positive");
+        }
+        self.registry.max_async_concurrency = limit;
+        self
+    }
+
+    /// Limits the number of instances that the [`Cached`], [`Keyed`] and [`Pooled`] scopes keep
+    /// within the catalog, e.g. for the long-running services caching the components per tenant
+    /// not to grow without bound. When the limit is exceeded the least recently used instances
+    /// are evicted, so that the next resolution constructs them anew.
+    ///
+    /// Instances that are referenced outside of the catalog are not evicted, as dropping them
+    /// wouldn't free any memory. Other scopes (like [`Singleton`]) are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// #[derive(Clone, PartialEq, Eq, Hash)]
+    /// struct TenantId(u64);
+    ///
+    /// #[component]
+    /// #[scope(Keyed<TenantId>)]
+    /// struct TenantSettings;
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .max_cached_instances(1)
+    ///     .add::<TenantSettings>()
+    ///     .build();
+    ///
+    /// let s1 = cat.with_context(TenantId(1)).get_one::<TenantSettings>().unwrap();
+    /// let s1 = Arc::downgrade(&s1);
+    /// assert!(s1.upgrade().is_some());
+    ///
+    /// cat.with_context(TenantId(2)).get_one::<TenantSettings>().unwrap();
+    /// assert!(s1.upgrade().is_none());
+    /// ```
+    pub fn max_cached_instances(&mut self, limit: usize) -> &mut Self {
+        if limit == 0 {
+            panic!("Maximum number of cached instances has to be positive");
+        }
+        self.registry.cache_limits.max_instances = Some(limit);
+        self
+    }
+
+    /// Limits the approximate memory (in bytes) taken by the instances that the [`Cached`],
+    /// [`Keyed`] and [`Pooled`] scopes keep within the catalog, evicting the least recently used
+    /// ones the same way as [`CatalogBuilder::max_cached_instances()`].
+    ///
+    /// The size of an instance is the size of its type (see [`std::mem::size_of_val()`]), so the
+    /// memory it owns on the heap (e.g. the contents of a `Vec`) is not accounted for.
+    pub fn cache_memory_budget(&mut self, bytes: usize) -> &mut Self {
+        if bytes == 0 {
+            panic!("Cache memory budget has to be positive");
+        }
+        self.registry.cache_limits.memory_budget = Some(bytes);
+        self
+    }
 
+    /// Registers an observer notified about the resolutions and constructions of the instances,
+    /// see [`ResolutionObserver`]. Child catalogs (see [`CatalogBuilder::new_chained()`]) inherit
+    /// the observers of the parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use dill::*;
+    ///
+    /// #[derive(Default)]
+    /// struct BuildCounter(AtomicUsize);
+    ///
+    /// impl ResolutionObserver for BuildCounter {
+    ///     fn on_build(&self, _builder: &dyn Builder, _elapsed: Duration) {
+    ///         self.0.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// #[scope(Singleton)]
+    /// struct Db;
+    ///
+    /// let counter = Arc::new(BuildCounter::default());
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Db>()
+    ///     .with_observer(counter.clone())
+    ///     .build();
+    ///
+    /// cat.get_one::<Db>().unwrap();
+    /// cat.get_one::<Db>().unwrap();
+    /// assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn with_observer<O>(&mut self, observer: O) -> &mut Self
+    where
+        O: ResolutionObserver + 'static,
+    {
+        self.registry.observers.push(Arc::new(observer));
         self
     }
 
+    /// Controls what happens when the same implementation is registered again, or is bound to the
+    /// same interface again, affecting all registrations that follow. By default a repeated
+    /// implementation is an [`Error`](DuplicatePolicy::Error), while a repeated binding is
+    /// [appended](DuplicatePolicy::Append), making e.g. [`AllOf`] resolve the instance twice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Handler: Send + Sync {}
+    ///
+    /// #[component]
+    /// #[interface(dyn Handler)]
+    /// struct AuditHandler;
+    /// impl Handler for AuditHandler {}
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .on_duplicate(DuplicatePolicy::Replace)
+    ///     .add::<AuditHandler>()
+    ///     .add::<AuditHandler>()
+    ///     .bind::<dyn Handler, AuditHandler>()
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get::<AllOf<dyn Handler>>().unwrap().len(), 1);
+    /// ```
+    pub fn on_duplicate(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self.on_duplicate = Some(policy);
+        self
+    }
+
+    /// Registers a handler consulted when resolving [`OneOf`] of a type that has no bindings,
+    /// before failing with [`InjectionError::Unregistered`], e.g. to load a plugin or to return
+    /// a no-op implementation. The handler receives the id and the name of the type and the
+    /// resolving catalog, returning `None` to pass the type on to the next handler.
+    ///
+    /// The returned instance is expected to be `Arc<Arc<dyn Trait>>` for trait objects, as `dyn Any`
+    /// can't be cast to a trait object, and either `Arc<T>` or `Arc<Arc<T>>` for sized types -
+    /// returning anything else results in [`InjectionError::UnresolvedMismatch`]. Instances are not cached, so the handler is called on every resolution.
+    /// Handlers of the parent catalogs (see [`CatalogBuilder::new_chained()`]) are consulted after
+    /// the local ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::any::TypeId;
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// trait Metrics: Send + Sync {
+    ///     fn name(&self) -> &str;
+    /// }
+    ///
+    /// struct NoopMetrics;
+    /// impl Metrics for NoopMetrics {
+    ///     fn name(&self) -> &str {
+    ///         "noop"
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .on_unresolved(|type_id, _, _| {
+    ///         if type_id == TypeId::of::<dyn Metrics>() {
+    ///             let metrics: Arc<dyn Metrics> = Arc::new(NoopMetrics);
+    ///             Some(Arc::new(metrics))
+    ///         } else if type_id == TypeId::of::<u16>() {
+    ///             Some(Arc::new(8080u16))
+    ///         } else {
+    ///             None
+    ///         }
+    ///     })
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Metrics>().unwrap().name(), "noop");
+    /// assert_eq!(*cat.get_one::<u16>().unwrap(), 8080);
+    /// assert!(cat.get_one::<String>().is_err());
+    /// ```
+    pub fn on_unresolved<F>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(TypeId, &'static str, &Catalog) -> Option<Shared<SharedAny>>
+            + MaybeSendSync
+            + 'static,
+    {
+        self.registry.unresolved.push(Arc::new(handler));
+        self
+    }
+
+    /// Checks that the dependencies declared by all registered builders (see [`Builder::dependencies()`])
+    /// can be resolved, reporting all unregistered and ambiguous dependencies and dependency cycles
+    /// at once, without instantiating anything.
+    ///
+    /// Dependencies of custom builders that don't declare them are not checked.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+        let mut report = |err: InjectionError| {
+            if !errors.contains(&err) {
+                errors.push(err);
+            }
+        };
+
+        // Reported first, as knowing which module expects the dependency helps fixing it the most
+        for (module, dep) in &self.requirements {
+            if self.bindings_for(*dep).next().is_none() {
+                report(
+                    InjectionError::unregistered_type(dep.type_id, dep.type_name)
+                        .with_context(vec![*module], Vec::new()),
+                );
+            }
+        }
+
+        for builder in self.all_builders().filter(|b| self.is_active(b.as_ref())) {
+            for dep in builder.dependencies() {
+                if is_builtin_dependency(dep.type_id) {
+                    continue;
+                }
+
+                // Only the implementations sharing the highest priority are ambiguous
+                let top = self.bindings_for(dep).map(|b| b.builder.priority()).max();
+                let tied: Vec<_> = self
+                    .bindings_for(dep)
+                    .filter(|b| Some(b.builder.priority()) == top)
+                    .map(|b| b.builder.instance_type_name())
+                    .collect();
+                match dep.kind {
+                    DependencyKind::One | DependencyKind::Lazy if tied.is_empty() => report(
+                        InjectionError::unregistered_type(dep.type_id, dep.type_name),
+                    ),
+                    DependencyKind::One | DependencyKind::Lazy | DependencyKind::Maybe
+                        if tied.len() > 1 =>
+                    {
+                        report(InjectionError::ambiguous_type(
+                            dep.type_id,
+                            dep.type_name,
+                            tied,
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut visited = HashSet::new();
+        for builder in self.all_builders().filter(|b| self.is_active(b.as_ref())) {
+            self.find_cycles(builder.as_ref(), &mut Vec::new(), &mut visited, &mut report);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError::new(errors))
+        }
+    }
+
+    /// Validates the dependency graph (see [`CatalogBuilder::validate()`]) and builds the catalog
+    pub fn build_checked(&mut self) -> Result<Catalog, ValidationError> {
+        self.validate()?;
+        Ok(self.build())
+    }
+
+    /// Returns the latest registrations of the types along with the earlier appended ones
+    fn all_builders(&self) -> impl Iterator<Item = &Arc<dyn Builder>> {
+        self.builders.values().chain(&self.appended)
+    }
+
+    fn bindings_for(&self, dep: DependencyInfo) -> impl Iterator<Item = &Binding> {
+        let iface_type = IfaceTypeId(dep.type_id);
+
+        // Bindings of inactive profiles are excluded upon build, so they don't shadow the parent
+        let mut bindings: Vec<_> = self
+            .bindings
+            .get_vec(&iface_type)
+            .into_iter()
+            .flatten()
+            .filter(|b| self.is_active(b.builder.as_ref()))
+            .collect();
+
+        // Implementations of the subtraits are only bound to the interface upon build
+        for upcast in self.upcasts.iter().filter(|u| u.into == iface_type) {
+            let upcast_bindings: Vec<_> = self
+                .bindings
+                .get_vec(&upcast.from)
+                .into_iter()
+                .flatten()
+                .filter(|b| self.is_active(b.builder.as_ref()))
+                .filter(|b| {
+                    !bindings
+                        .iter()
+                        .any(|e| same_builder(&e.builder, &b.builder) && e.name == b.name)
+                })
+                .collect();
+            bindings.extend(upcast_bindings);
+        }
+
+        if bindings.is_empty() {
+            if let Some(parent) = &self.registry.parent {
+                bindings.extend(parent.bindings_of(iface_type).0.into_iter().flatten());
+            }
+        }
+
+        bindings
+            .into_iter()
+            .filter(move |b| dep.name.is_none() || b.name.as_deref() == dep.name)
+    }
+
+    fn find_cycles<'a>(
+        &'a self,
+        builder: &'a dyn Builder,
+        path: &mut Vec<&'a dyn Builder>,
+        visited: &mut HashSet<TypeId>,
+        report: &mut impl FnMut(InjectionError),
+    ) {
+        let type_id = builder.instance_type_id();
+
+        if let Some(pos) = path.iter().position(|b| b.instance_type_id() == type_id) {
+            let mut chain: Vec<_> = path[pos..].iter().map(|b| b.instance_type_name()).collect();
+            chain.push(builder.instance_type_name());
+            report(InjectionError::cycle(chain));
+            return;
+        }
+
+        if !visited.insert(type_id) {
+            return;
+        }
+
+        path.push(builder);
+        for dep in builder.dependencies() {
+            // Lazy dependencies are resolved after construction and thus can't form a cycle
+            if dep.kind == DependencyKind::Lazy {
+                continue;
+            }
+            for binding in self.bindings_for(dep) {
+                self.find_cycles(binding.builder.as_ref(), path, visited, report);
+            }
+        }
+        path.pop();
+    }
+
+    /// Builds the catalog, panicking if any of the eager components fails to instantiate.
+    /// See [`CatalogBuilder::try_build()`].
     pub fn build(&mut self) -> Catalog {
+        match self.try_build() {
+            Ok(cat) => cat,
+            Err(err) => panic!("Failed to instantiate eager component: {}", err),
+        }
+    }
+
+    /// Builds the catalog and instantiates all eager components (e.g. `#[component(eager)]` or the
+    /// ones marked via [`CatalogBuilder::eager()`]), returning the first error encountered.
+    /// Dependencies of eager components are resolved as usual, so the instantiation order
+    /// respects the dependency graph.
+    pub fn try_build(&mut self) -> Result<Catalog, InjectionError> {
         let mut builders = HashMap::new();
+        let mut appended = Vec::new();
         let mut bindings = MultiMap::new();
         std::mem::swap(&mut self.builders, &mut builders);
+        std::mem::swap(&mut self.appended, &mut appended);
         std::mem::swap(&mut self.bindings, &mut bindings);
-        Catalog::new(builders, bindings)
+
+        builders.retain(|_, b| self.is_active(b.as_ref()));
+        appended.retain(|b| self.is_active(b.as_ref()));
+        bindings.retain(|_, b| self.is_active(b.builder.as_ref()));
+
+        for upcast in std::mem::take(&mut self.upcasts) {
+            let upcast_bindings: Vec<_> = bindings
+                .get_vec(&upcast.from)
+                .into_iter()
+                .flatten()
+                .filter(|b| {
+                    !bindings
+                        .get_vec(&upcast.into)
+                        .into_iter()
+                        .flatten()
+                        .any(|existing| {
+                            same_builder(&existing.builder, &b.builder) && existing.name == b.name
+                        })
+                })
+                .map(upcast.cast)
+                .collect();
+
+            for binding in upcast_bindings {
+                bindings.insert(upcast.into, binding);
+            }
+        }
+
+        for (iface_type, decorators) in std::mem::replace(&mut self.decorators, MultiMap::new()) {
+            if let Some(iface_bindings) = bindings.get_vec_mut(&iface_type) {
+                // Bindings seeded from an existing catalog already have decorators applied
+                for binding in iface_bindings.iter_mut() {
+                    binding.decorators.extend(decorators.iter().cloned());
+                }
+            }
+        }
+
+        if let Some(default_scope) = self.default_scope {
+            Self::apply_default_scope(
+                default_scope,
+                builders.values_mut().chain(&mut appended),
+                &mut bindings,
+            );
+        }
+
+        let metadata = std::mem::take(&mut self.metadata);
+        for (_, iface_bindings) in bindings.iter_all_mut() {
+            for binding in iface_bindings.iter_mut() {
+                let impl_type = ImplTypeId(binding.builder.instance_type_id());
+                if let Some(metadata) = metadata.get(&impl_type) {
+                    binding.metadata.extend(metadata);
+                }
+            }
+        }
+
+        // Stable sort keeps the registration order among the implementations of equal priority
+        for (_, iface_bindings) in bindings.iter_all_mut() {
+            iface_bindings.sort_by_key(|b| Reverse(b.builder.priority()));
+        }
+
+        let eager_types = std::mem::take(&mut self.eager);
+        let eager: Vec<_> = builders
+            .values()
+            .chain(&appended)
+            .filter(|b| b.is_eager() || eager_types.contains(&ImplTypeId(b.instance_type_id())))
+            .cloned()
+            .collect();
+
+        let registry = RegistryConfig {
+            swap_slots: std::mem::take(&mut self.registry.swap_slots),
+            ..self.registry.clone()
+        };
+        let cat = Catalog::new(builders, appended, bindings, registry);
+
+        for builder in eager {
+            builder.get(&cat)?;
+        }
+
+        Ok(cat)
     }
+
+    fn apply_default_scope<'a>(
+        default_scope: DefaultScope,
+        builders: impl Iterator<Item = &'a mut Arc<dyn Builder>>,
+        bindings: &mut MultiMap<IfaceTypeId, Binding>,
+    ) {
+        for builder in builders {
+            if builder.scope_was_explicit() {
+                continue;
+            }
+
+            let scoped: Arc<dyn Builder> = Arc::new(ScopedBuilder::new(
+                builder.clone(),
+                (default_scope.factory)(),
+                default_scope.name,
+            ));
+
+            for (_, iface_bindings) in bindings.iter_all_mut() {
+                for binding in iface_bindings.iter_mut() {
+                    if same_builder(&binding.builder, builder) {
+                        binding.builder = scoped.clone();
+                    }
+                }
+            }
+
+            *builder = scoped;
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Registers multiple components at once, a short-hand for a chain of [`CatalogBuilder::add()`] calls.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[component]
+/// struct A;
+///
+/// #[component]
+/// struct B;
+///
+/// let catalog = add_all!(CatalogBuilder::new(), A, B).build();
+///
+/// catalog.get_one::<A>().unwrap();
+/// catalog.get_one::<B>().unwrap();
+/// ```
+#[macro_export]
+macro_rules! add_all {
+    ($cat:expr, $($typ:ty),+ $(,)?) => {
+        $cat $( .add::<$typ>() )+
+    };
 }
@@ -0,0 +1,148 @@
+use std::{any::type_name, sync::Arc};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{CatalogBuilder, MaybeSendSync};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Applies the value of a configuration key to the builder
+pub(crate) type ConfigApplier =
+    Arc<dyn Fn(&mut CatalogBuilder, &str, &Value) -> Result<(), ConfigError> + Send + Sync>;
+
+impl CatalogBuilder {
+    /// Declares a key of the configuration documents that selects one of the named bindings
+    /// of the interface (see [`CatalogBuilder::select_named()`]), e.g. `db = "postgres"`.
+    pub fn select_from_config<Iface>(&mut self, key: impl Into<String>) -> &mut Self
+    where
+        Iface: 'static + ?Sized,
+    {
+        let apply: ConfigApplier = Arc::new(|b, key, value| {
+            let name = value.as_str().ok_or_else(|| ConfigError::InvalidValue {
+                key: key.to_owned(),
+                message: "expected a name of the implementation".to_owned(),
+            })?;
+            if b.try_select_named::<Iface>(name) {
+                Ok(())
+            } else {
+                Err(ConfigError::UnknownImplementation {
+                    key: key.to_owned(),
+                    name: name.to_owned(),
+                    iface: type_name::<Iface>(),
+                })
+            }
+        });
+        self.config_keys.push((key.into(), apply));
+        self
+    }
+
+    /// Declares a key of the configuration documents that holds a value to register via
+    /// [`CatalogBuilder::add_config()`], e.g. `pool_size = 10`.
+    pub fn value_from_config<T>(&mut self, key: impl Into<String>) -> &mut Self
+    where
+        T: 'static + MaybeSendSync + DeserializeOwned,
+    {
+        let apply: ConfigApplier = Arc::new(|b, key, value| {
+            let value: T =
+                serde_json::from_value(value.clone()).map_err(|e| ConfigError::InvalidValue {
+                    key: key.to_owned(),
+                    message: e.to_string(),
+                })?;
+            b.add_config(value);
+            Ok(())
+        });
+        self.config_keys.push((key.into(), apply));
+        self
+    }
+
+    /// Applies the configuration document (e.g. parsed from TOML or JSON) to the keys declared via
+    /// [`CatalogBuilder::select_from_config()`] and [`CatalogBuilder::value_from_config()`],
+    /// allowing to switch implementations without recompiling.
+    ///
+    /// Keys can refer to nested tables using dots, e.g. `db.pool_size`. Keys that are missing
+    /// from the document leave the registrations intact, so documents can be layered
+    /// over the defaults, while other entries of the document are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {
+    ///     fn url(&self) -> String;
+    /// }
+    ///
+    /// #[component]
+    /// struct PostgresDb {
+    ///     pool_size: usize,
+    /// }
+    /// impl Db for PostgresDb {
+    ///     fn url(&self) -> String {
+    ///         format!("postgres://?pool_size={}", self.pool_size)
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// struct SqliteDb;
+    /// impl Db for SqliteDb {
+    ///     fn url(&self) -> String {
+    ///         "sqlite://".to_owned()
+    ///     }
+    /// }
+    ///
+    /// let doc = serde_json::json!({ "db": "postgres", "pool_size": 10 });
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<PostgresDb>()
+    ///     .bind_named::<dyn Db, PostgresDb>("postgres")
+    ///     .add::<SqliteDb>()
+    ///     .bind_named::<dyn Db, SqliteDb>("sqlite")
+    ///     .select_from_config::<dyn Db>("db")
+    ///     .value_from_config::<usize>("pool_size")
+    ///     .configure_from(&doc)
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// assert_eq!(cat.get_one::<dyn Db>().unwrap().url(), "postgres://?pool_size=10");
+    /// ```
+    pub fn configure_from<Doc>(&mut self, doc: &Doc) -> Result<&mut Self, ConfigError>
+    where
+        Doc: Serialize + ?Sized,
+    {
+        let doc =
+            serde_json::to_value(doc).map_err(|e| ConfigError::InvalidDocument(e.to_string()))?;
+        if !doc.is_object() {
+            return Err(ConfigError::InvalidDocument(
+                "expected a table of keys".to_owned(),
+            ));
+        }
+
+        for (key, apply) in self.config_keys.clone() {
+            if let Some(value) = key.split('.').try_fold(&doc, |v, k| v.get(k)) {
+                apply(self, &key, value)?;
+                self.registry.applied_config.retain(|(k, _)| *k != key);
+                self.registry.applied_config.push((key, value.to_string()));
+            }
+        }
+        Ok(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error of applying a configuration document, see [`CatalogBuilder::configure_from()`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    #[error("Invalid configuration document: {0}")]
+    InvalidDocument(String),
+    #[error("Configuration key '{key}' has invalid value: {message}")]
+    InvalidValue { key: String, message: String },
+    #[error("Configuration key '{key}' selects unknown implementation '{name}' of {iface}")]
+    UnknownImplementation {
+        key: String,
+        name: String,
+        iface: &'static str,
+    },
+}
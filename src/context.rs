@@ -0,0 +1,202 @@
+use std::any::TypeId;
+use std::ops::Deref;
+use std::sync::Mutex;
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Value attached to the catalog handle the component is resolved from, see [`Catalog::with()`].
+///
+/// Can be injected as `FromContext<V>` argument of a component, which fails with
+/// [`InjectionError::MissingContext`] when the value is not attached. Note that the value is
+/// captured at construction, so the components caching their instances (e.g. [`Singleton`])
+/// keep the value of the first resolution.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// struct CorrelationId(u64);
+///
+/// #[component]
+/// struct Logger {
+///     correlation_id: FromContext<CorrelationId>,
+/// }
+///
+/// let cat = CatalogBuilder::new().add::<Logger>().build();
+///
+/// let logger = cat.with(CorrelationId(7)).get_one::<Logger>().unwrap();
+/// assert_eq!(logger.correlation_id.0, 7);
+/// ```
+pub struct FromContext<V: ?Sized> {
+    value: Shared<V>,
+}
+
+impl<V> FromContext<V>
+where
+    V: 'static + MaybeSendSync,
+{
+    /// Wraps the value directly, e.g. to override the argument of a builder
+    pub fn new(value: V) -> Self {
+        Self {
+            value: Shared::new(value),
+        }
+    }
+}
+
+impl<V: ?Sized> FromContext<V> {
+    /// Returns the shared pointer to the value
+    pub fn shared(&self) -> Shared<V> {
+        self.value.clone()
+    }
+}
+
+impl<V: ?Sized> Clone for FromContext<V> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<V: ?Sized> Deref for FromContext<V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        self.value.as_ref()
+    }
+}
+
+impl<V: ?Sized + std::fmt::Debug> std::fmt::Debug for FromContext<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FromContext").field(&self.value).finish()
+    }
+}
+
+impl<V> DependencySpec for FromContext<V>
+where
+    V: 'static + MaybeSendSync,
+{
+    type ReturnType = FromContext<V>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        match cat.context_shared::<V>() {
+            Some(value) => Ok(Self { value }),
+            None => Err(InjectionError::missing_context::<V>()),
+        }
+    }
+}
+
+impl<V> AsyncDependencySpec for FromContext<V>
+where
+    V: 'static + MaybeSendSync,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move { Self::get(cat) })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Runtime arguments of a single resolution, see [`Catalog::get_with()`]
+///
+/// Attached to the catalog handle as a context value and taken by the first component
+/// constructed within the resolution that declares `#[dill(arg)]` arguments of these types.
+#[doc(hidden)]
+pub struct RuntimeArgs<A> {
+    args: Mutex<Option<A>>,
+}
+
+impl<A> RuntimeArgs<A>
+where
+    A: 'static + MaybeSendSync,
+{
+    pub(crate) fn new(args: A) -> Self {
+        Self {
+            args: Mutex::new(Some(args)),
+        }
+    }
+
+    /// Takes the arguments passed to the resolution, to be called by the generated builders
+    pub fn take(cat: &Catalog) -> Result<A, InjectionError> {
+        cat.context::<Self>()
+            .and_then(|a| a.args.lock().unwrap().take())
+            .ok_or_else(InjectionError::missing_context::<A>)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Identity of the component being constructed, injectable as a `ComponentContext` argument
+/// of any component, e.g. to label its tracing spans or metrics without hardcoding the names.
+///
+/// Resolving it outside of a component constructor fails with [`InjectionError::MissingContext`].
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[component]
+/// struct Repo {
+///     ctx: ComponentContext,
+/// }
+///
+/// let cat = CatalogBuilder::new().add::<Repo>().build();
+///
+/// let repo = cat.get_one::<Repo>().unwrap();
+/// assert_eq!(repo.ctx.short_type_name(), "Repo");
+/// assert_eq!(repo.ctx.catalog_id(), cat.id());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentContext {
+    type_id: TypeId,
+    type_name: &'static str,
+    catalog_id: usize,
+}
+
+impl ComponentContext {
+    pub(crate) fn new(type_id: TypeId, type_name: &'static str, catalog_id: usize) -> Self {
+        Self {
+            type_id,
+            type_name,
+            catalog_id,
+        }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Full name of the component type, e.g. `my_crate::repo::Repo`
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Name of the component type without the module path and generic arguments, e.g. `Repo`
+    pub fn short_type_name(&self) -> &'static str {
+        short_type_name(self.type_name)
+    }
+
+    /// Id of the catalog that resolves the component, see [`Catalog::id()`]
+    pub fn catalog_id(&self) -> usize {
+        self.catalog_id
+    }
+}
+
+impl DependencySpec for ComponentContext {
+    type ReturnType = ComponentContext;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        cat.component_context()
+            .ok_or_else(InjectionError::missing_context::<ComponentContext>)
+    }
+}
+
+impl AsyncDependencySpec for ComponentContext {
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move { Self::get(cat) })
+    }
+}
@@ -0,0 +1,375 @@
+use std::{any::TypeId, marker::PhantomData, sync::Arc};
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Declares the builder of a component without the `#[component]` attribute, e.g. when the
+/// proc-macro dependencies are undesirable (see the `macros` feature) or the attribute can't
+/// parse an exotic constructor signature.
+///
+/// The component is constructed by calling the specified associated function with the
+/// arguments resolved from the catalog according to their types, same as with `#[component]`:
+///
+/// - `Arc<T>` - resolves [`OneOf<T>`]
+/// - `Vec<Arc<T>>` - resolves [`AllOf<T>`]
+/// - `Option<Arc<T>>` - resolves [`Maybe<OneOf<T>>`]
+/// - `Lazy<Arc<T>>` - resolves [`OneOf<T>`] upon first use
+/// - `Catalog` - receives a handle to the resolving catalog
+/// - `T` - clones the registered value, so unlike with `#[component]` it has to be `Clone`
+///
+/// The scope defaults to [`Transient`] and can be specified via `scope: Singleton`, followed
+/// by the interfaces the component is bound to upon registration via `interfaces: [dyn Iface]`.
+/// The generated builder is a [`DeclaredBuilder`], which has no setters of the arguments.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use dill::*;
+///
+/// trait Repo: Send + Sync {
+///     fn url(&self) -> &str;
+/// }
+///
+/// struct PgRepo {
+///     url: String,
+/// }
+///
+/// impl PgRepo {
+///     fn new(url: String) -> Self {
+///         Self { url }
+///     }
+/// }
+///
+/// impl Repo for PgRepo {
+///     fn url(&self) -> &str {
+///         &self.url
+///     }
+/// }
+///
+/// builder_for!(PgRepo, new(url: String), scope: Singleton, interfaces: [dyn Repo]);
+///
+/// struct Service {
+///     repo: Arc<dyn Repo>,
+/// }
+///
+/// impl Service {
+///     fn new(repo: Arc<dyn Repo>) -> Self {
+///         Self { repo }
+///     }
+/// }
+///
+/// builder_for!(Service, new(repo: Arc<dyn Repo>));
+///
+/// let cat = CatalogBuilder::new()
+///     .add_value("postgres://db".to_owned())
+///     .add::<PgRepo>()
+///     .add::<Service>()
+///     .build();
+///
+/// assert_eq!(cat.get_one::<Service>().unwrap().repo.url(), "postgres://db");
+/// ```
+#[macro_export]
+macro_rules! builder_for {
+    (@scope) => {
+        ($crate::Transient::new(), false)
+    };
+    (@scope $scope:ty) => {
+        (<$scope>::new(), true)
+    };
+    (
+        $impl:ty, $ctor:ident($($arg:ident: $typ:ty),* $(,)?)
+        $(, scope: $scope:ty)?
+        $(, interfaces: [$($iface:ty),* $(,)?])?
+        $(,)?
+    ) => {
+        impl $crate::BuilderLike for $impl {
+            type Builder = $crate::DeclaredBuilder<$impl>;
+
+            fn register(cat: &mut $crate::CatalogBuilder) {
+                cat.add_builder(Self::builder());
+                $($(
+                    cat.bind::<$iface, $impl>();
+                )*)?
+            }
+
+            fn builder() -> Self::Builder {
+                #[allow(unused_imports)]
+                use $crate::{ResolveArg as _, ResolveValue as _};
+
+                let (scope, scope_was_explicit) = $crate::builder_for!(@scope $($scope)?);
+                $crate::DeclaredBuilder::new(
+                    scope,
+                    scope_was_explicit,
+                    || {
+                        let deps: Vec<Option<$crate::DependencyInfo>> =
+                            vec![$( (&&$crate::ArgProbe::<$typ>::default()).dependency() ),*];
+                        deps.into_iter().flatten().collect()
+                    },
+                    |cat| {
+                        $(
+                            let $arg = (&&$crate::ArgProbe::<$typ>::default()).resolve(cat)?;
+                        )*
+                        Ok(<$impl>::$ctor($( $arg ),*))
+                    },
+                )
+            }
+        }
+    };
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Builder of the components declared via [`builder_for!`]
+pub struct DeclaredBuilder<Impl> {
+    // Shared by the clones of the builder, same as with the builders of `#[component]`
+    scope: Arc<dyn Scope>,
+    scope_name: &'static str,
+    scope_was_explicit: bool,
+    dependencies: fn() -> Vec<DependencyInfo>,
+    build: fn(&Catalog) -> Result<Impl, InjectionError>,
+}
+
+impl<Impl> DeclaredBuilder<Impl>
+where
+    Impl: 'static + MaybeSendSync,
+{
+    #[doc(hidden)]
+    pub fn new<S: Scope + 'static>(
+        scope: S,
+        scope_was_explicit: bool,
+        dependencies: fn() -> Vec<DependencyInfo>,
+        build: fn(&Catalog) -> Result<Impl, InjectionError>,
+    ) -> Self {
+        Self {
+            scope: Arc::new(scope),
+            scope_name: std::any::type_name::<S>(),
+            scope_was_explicit,
+            dependencies,
+            build,
+        }
+    }
+
+    /// Overrides the scope of the component, e.g. to let the application decide
+    /// whether a component defined in a library is a singleton
+    #[must_use]
+    pub fn with_scope<S: Scope + 'static>(mut self, scope: S) -> Self {
+        self.scope = Arc::new(scope);
+        self.scope_name = std::any::type_name::<S>();
+        self.scope_was_explicit = true;
+        self
+    }
+
+    /// Builds a new instance regardless of the scope, without caching it in the scope
+    pub fn build_fresh(&self, cat: &Catalog) -> Result<Shared<Impl>, InjectionError> {
+        Ok(Shared::new((self.build)(cat)?))
+    }
+}
+
+// Derived implementation would require `Impl` to be `Clone`
+impl<Impl> Clone for DeclaredBuilder<Impl> {
+    fn clone(&self) -> Self {
+        Self {
+            scope: self.scope.clone(),
+            scope_name: self.scope_name,
+            scope_was_explicit: self.scope_was_explicit,
+            dependencies: self.dependencies,
+            build: self.build,
+        }
+    }
+}
+
+impl<Impl> Builder for DeclaredBuilder<Impl>
+where
+    Impl: 'static + MaybeSendSync,
+{
+    fn instance_type_id(&self) -> TypeId {
+        TypeId::of::<Impl>()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        std::any::type_name::<Impl>()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        self.scope_name
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        self.scope_was_explicit
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.scope.is_catalog_wide()
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        (self.dependencies)()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        Ok(TypedBuilder::get(self, cat)?)
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.scope.get(cat)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        self.scope.invalidate(cat)
+    }
+
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(async move {
+            let inst: Shared<SharedAny> = AsyncTypedBuilder::get_async(self, cat).await?;
+            Ok(inst)
+        })
+    }
+}
+
+impl<Impl> TypedBuilder<Impl> for DeclaredBuilder<Impl>
+where
+    Impl: 'static + MaybeSendSync,
+{
+    fn get(&self, cat: &Catalog) -> Result<Shared<Impl>, InjectionError> {
+        let inst = instrument::get_or_build(self, self.scope.as_ref(), cat, &|| {
+            let inst: Shared<SharedAny> = Shared::new((self.build)(cat)?);
+            Ok(inst)
+        })?;
+
+        Ok(inst.downcast().unwrap())
+    }
+}
+
+impl<Impl> AsyncTypedBuilder<Impl> for DeclaredBuilder<Impl>
+where
+    Impl: 'static + MaybeSendSync,
+{
+    // Constructors of the declared components are synchronous, so are their dependencies
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<Impl>, InjectionError>> {
+        Box::pin(std::future::ready(TypedBuilder::get(self, cat)))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Selects how [`builder_for!`] resolves an argument by its type. Dispatched via auto-ref,
+/// with `(&&ArgProbe::<T>::default()).resolve(cat)` resolving to [`ResolveArg`] for the
+/// supported wrappers first, and to [`ResolveValue`] for the plain values otherwise.
+#[doc(hidden)]
+pub struct ArgProbe<T>(PhantomData<T>);
+
+impl<T> Default for ArgProbe<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ResolveArg {
+    type Arg;
+    fn dependency(&self) -> Option<DependencyInfo>;
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError>;
+}
+
+impl<T> ResolveArg for &ArgProbe<Shared<T>>
+where
+    T: 'static + ?Sized + MaybeSendSync,
+{
+    type Arg = Shared<T>;
+
+    fn dependency(&self) -> Option<DependencyInfo> {
+        Some(DependencyInfo::of::<T>(DependencyKind::One, None))
+    }
+
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError> {
+        cat.get::<OneOf<T>>()
+    }
+}
+
+impl<T> ResolveArg for &ArgProbe<Vec<Shared<T>>>
+where
+    T: 'static + ?Sized + MaybeSendSync,
+{
+    type Arg = Vec<Shared<T>>;
+
+    fn dependency(&self) -> Option<DependencyInfo> {
+        Some(DependencyInfo::of::<T>(DependencyKind::All, None))
+    }
+
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError> {
+        cat.get::<AllOf<T>>()
+    }
+}
+
+impl<T> ResolveArg for &ArgProbe<Option<Shared<T>>>
+where
+    T: 'static + ?Sized + MaybeSendSync,
+{
+    type Arg = Option<Shared<T>>;
+
+    fn dependency(&self) -> Option<DependencyInfo> {
+        Some(DependencyInfo::of::<T>(DependencyKind::Maybe, None))
+    }
+
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError> {
+        cat.get::<Maybe<OneOf<T>>>()
+    }
+}
+
+impl<T> ResolveArg for &ArgProbe<Lazy<Shared<T>>>
+where
+    T: 'static + ?Sized + MaybeSendSync,
+{
+    type Arg = Lazy<Shared<T>>;
+
+    fn dependency(&self) -> Option<DependencyInfo> {
+        Some(DependencyInfo::of::<T>(DependencyKind::Lazy, None))
+    }
+
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError> {
+        Ok(Lazy::<Shared<T>>::one_of(cat))
+    }
+}
+
+impl ResolveArg for &ArgProbe<Catalog> {
+    type Arg = Catalog;
+
+    // Catalog doesn't depend on any registration
+    fn dependency(&self) -> Option<DependencyInfo> {
+        None
+    }
+
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError> {
+        Ok(cat.detached())
+    }
+}
+
+#[doc(hidden)]
+pub trait ResolveValue {
+    type Arg;
+    fn dependency(&self) -> Option<DependencyInfo>;
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError>;
+}
+
+impl<T> ResolveValue for ArgProbe<T>
+where
+    T: 'static + Clone + MaybeSendSync,
+{
+    type Arg = T;
+
+    fn dependency(&self) -> Option<DependencyInfo> {
+        Some(DependencyInfo::of::<T>(DependencyKind::One, None))
+    }
+
+    fn resolve(&self, cat: &Catalog) -> Result<Self::Arg, InjectionError> {
+        cat.get::<OneOf<T>>().map(|v| v.as_ref().clone())
+    }
+}
@@ -1,41 +1,743 @@
-use std::any::{type_name, TypeId};
+use std::{
+    any::{type_name, TypeId},
+    error::Error,
+    sync::Arc,
+    time::Duration,
+};
 
 use thiserror::Error;
 
+/// Error of resolving a dependency from the catalog.
+///
+/// Every variant wraps a dedicated error type describing the failure, e.g. to tell the types
+/// that are not registered (see [`UnregisteredTypeError::required_by()`]) from the constructors
+/// that failed (see [`ConstructorFailedError::error()`]). New variants might be added in the
+/// future, so matches have to handle the unknown ones.
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum InjectionError {
-    #[error("Unregistered type")]
+    #[error(transparent)]
     Unregistered(UnregisteredTypeError),
-    #[error("Ambiguous type")]
+    #[error(transparent)]
+    UnregisteredName(UnregisteredNameError),
+    #[error(transparent)]
     Ambiguous(AmbiguousTypeError),
+    #[error(transparent)]
+    AsyncConstructor(AsyncConstructorError),
+    // Keeps the wrapper in the source chain, so that the error of the constructor is its source
+    #[error("{0}")]
+    ConstructorFailed(#[source] ConstructorFailedError),
+    #[error(transparent)]
+    Cycle(CycleError),
+    #[error(transparent)]
+    MaxDepthExceeded(MaxDepthExceededError),
+    #[error(transparent)]
+    MissingContext(MissingContextError),
+    #[error(transparent)]
+    MissingOverrides(MissingOverridesError),
+    #[error(transparent)]
+    PoolExhausted(PoolExhaustedError),
+    #[error(transparent)]
+    SharedInstance(SharedInstanceError),
+    #[error(transparent)]
+    Timeout(TimeoutError),
+    #[error(transparent)]
+    UnresolvedMismatch(UnresolvedMismatchError),
+    #[error(transparent)]
+    Validation(InvalidValueError),
+    #[error(transparent)]
+    ValueConsumed(ValueConsumedError),
 }
 
 impl InjectionError {
     pub fn unregistered<Iface: 'static + ?Sized>() -> Self {
+        Self::unregistered_type(TypeId::of::<Iface>(), type_name::<Iface>())
+    }
+
+    pub fn unregistered_type_id(type_id: TypeId) -> Self {
+        Self::unregistered_type(type_id, "<unknown>")
+    }
+
+    pub(crate) fn unregistered_type(type_id: TypeId, type_name: &'static str) -> Self {
         Self::Unregistered(UnregisteredTypeError {
+            type_id,
+            type_name,
+            required_by: Vec::new(),
+            suggestions: Vec::new(),
+        })
+    }
+
+    /// Creates an error for the type name that doesn't match any component,
+    /// see [`Catalog::get_by_name()`][crate::Catalog::get_by_name()]
+    pub fn unregistered_name(type_name: impl Into<String>) -> Self {
+        Self::UnregisteredName(UnregisteredNameError {
+            type_name: type_name.into(),
+        })
+    }
+
+    /// Adds the diagnostic context to the [`InjectionError::Unregistered`] error
+    pub(crate) fn with_context(
+        self,
+        required_by: Vec<&'static str>,
+        suggestions: Vec<&'static str>,
+    ) -> Self {
+        match self {
+            Self::Unregistered(err) => Self::Unregistered(UnregisteredTypeError {
+                required_by,
+                suggestions,
+                ..err
+            }),
+            err => err,
+        }
+    }
+
+    /// Creates an error listing the implementations that share the highest priority
+    pub(crate) fn ambiguous_type(
+        type_id: TypeId,
+        type_name: &'static str,
+        candidates: Vec<&'static str>,
+    ) -> Self {
+        Self::Ambiguous(AmbiguousTypeError {
+            type_id,
+            type_name,
+            count: candidates.len(),
+            candidates,
+        })
+    }
+
+    pub fn ambiguous<Iface: 'static + ?Sized>(count: usize) -> Self {
+        Self::Ambiguous(AmbiguousTypeError {
             type_id: TypeId::of::<Iface>(),
             type_name: type_name::<Iface>(),
+            count,
+            candidates: Vec::new(),
         })
     }
 
-    pub fn ambiguous<Iface: 'static + ?Sized>() -> Self {
-        Self::Ambiguous(AmbiguousTypeError {
+    pub fn constructor_failed<Impl: 'static + ?Sized>(
+        error: impl Into<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        Self::ConstructorFailed(ConstructorFailedError {
+            type_id: TypeId::of::<Impl>(),
+            type_name: type_name::<Impl>(),
+            error: Arc::from(error.into()),
+        })
+    }
+
+    /// Creates an error from the chain of types, where the last one repeats an earlier one
+    pub fn cycle(type_names: Vec<&'static str>) -> Self {
+        Self::Cycle(CycleError { type_names })
+    }
+
+    /// Creates an error from the chain of types that exceeded the maximum resolution depth,
+    /// see [`CatalogBuilder::max_resolution_depth()`][crate::CatalogBuilder::max_resolution_depth()]
+    pub fn max_depth_exceeded(max_depth: usize, type_names: Vec<&'static str>) -> Self {
+        Self::MaxDepthExceeded(MaxDepthExceededError {
+            max_depth,
+            type_names,
+        })
+    }
+
+    /// Creates an error for the context value that is not attached to the catalog handle,
+    /// see [`FromContext`][crate::FromContext]
+    pub fn missing_context<V: 'static + ?Sized>() -> Self {
+        Self::MissingContext(MissingContextError {
+            type_id: TypeId::of::<V>(),
+            type_name: type_name::<V>(),
+        })
+    }
+
+    /// Creates an error for the component built without a catalog whose arguments are not
+    /// overridden, see `build_standalone()` of the generated builders
+    pub fn missing_overrides<Impl: 'static + ?Sized>(args: Vec<&'static str>) -> Self {
+        Self::MissingOverrides(MissingOverridesError {
+            type_id: TypeId::of::<Impl>(),
+            type_name: type_name::<Impl>(),
+            args,
+        })
+    }
+
+    /// Creates an error for the pool that has all of its instances checked out,
+    /// see [`Pooled`][crate::Pooled]
+    pub fn pool_exhausted(capacity: usize) -> Self {
+        Self::PoolExhausted(PoolExhaustedError { capacity })
+    }
+
+    /// Creates an error for the instance that can't be moved out of the catalog, see [`Owned`][crate::Owned]
+    pub fn shared_instance(type_name: &'static str, scope_name: &'static str) -> Self {
+        Self::SharedInstance(SharedInstanceError {
+            type_name,
+            scope_name,
+        })
+    }
+
+    /// Creates an error for the resolution that didn't complete in time, see
+    /// [`Catalog::get_async_timeout()`][crate::Catalog::get_async_timeout()]
+    pub fn timeout(
+        type_name: &'static str,
+        timeout: Duration,
+        stuck_at: Vec<&'static str>,
+    ) -> Self {
+        Self::Timeout(TimeoutError {
+            type_name,
+            timeout,
+            stuck_at,
+        })
+    }
+
+    /// Creates an error for the instance of a wrong type returned by the handler of
+    /// [`CatalogBuilder::on_unresolved()`][crate::CatalogBuilder::on_unresolved()]
+    pub fn unresolved_mismatch<Iface: 'static + ?Sized>() -> Self {
+        Self::UnresolvedMismatch(UnresolvedMismatchError {
             type_id: TypeId::of::<Iface>(),
             type_name: type_name::<Iface>(),
         })
     }
+
+    /// Creates an error for the argument rejected by its validation function,
+    /// see `#[dill(validate = "...")]`
+    pub fn invalid_argument(
+        type_name: &'static str,
+        arg: &'static str,
+        reason: Option<String>,
+    ) -> Self {
+        Self::Validation(InvalidValueError {
+            type_name,
+            arg: Some(arg),
+            reason,
+        })
+    }
+
+    /// Creates an error for the instance that failed its [`Validate`][crate::Validate] check
+    pub fn invalid_instance<Impl: 'static + ?Sized>(reason: impl Into<String>) -> Self {
+        Self::Validation(InvalidValueError {
+            type_name: type_name::<Impl>(),
+            arg: None,
+            reason: Some(reason.into()),
+        })
+    }
+
+    /// Creates an error for the argument value that was already moved into an instance built
+    /// earlier, see `with_<arg>_value()` setters of the generated builders
+    pub fn value_consumed(type_name: &'static str, arg: &'static str) -> Self {
+        Self::ValueConsumed(ValueConsumedError { type_name, arg })
+    }
+
+    pub fn async_constructor<Impl: 'static + ?Sized>() -> Self {
+        Self::AsyncConstructor(AsyncConstructorError {
+            type_id: TypeId::of::<Impl>(),
+            type_name: type_name::<Impl>(),
+        })
+    }
 }
 
-#[derive(Error, Debug, Clone, PartialEq, Eq)]
-#[error("Unregistered type: ${type_name}")]
+#[derive(Debug, Clone)]
 pub struct UnregisteredTypeError {
     type_id: TypeId,
     type_name: &'static str,
+    required_by: Vec<&'static str>,
+    suggestions: Vec<&'static str>,
+}
+
+impl UnregisteredTypeError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Types that were being resolved when the error occurred, from the outermost to the one
+    /// that depends on the unregistered type directly
+    pub fn required_by(&self) -> &[&'static str] {
+        &self.required_by
+    }
+
+    /// Registered types that might have been meant to be bound to the unregistered type,
+    /// e.g. implementations that were not bound to the interface
+    pub fn suggestions(&self) -> &[&'static str] {
+        &self.suggestions
+    }
+}
+
+impl std::fmt::Display for UnregisteredTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unregistered type: {}", self.type_name)?;
+        if !self.required_by.is_empty() {
+            write!(f, ", required by: {}", self.required_by.join(" -> "))?;
+        }
+        if !self.suggestions.is_empty() {
+            write!(
+                f,
+                ", similar registered types: {}",
+                self.suggestions.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnregisteredTypeError {}
+
+// Diagnostic context depends on where the resolution started, so it's not part of the error identity
+impl PartialEq for UnregisteredTypeError {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id && self.type_name == other.type_name
+    }
 }
 
+impl Eq for UnregisteredTypeError {}
+
+/// Returned when resolving a component by a type name known only at runtime,
+/// see [`Catalog::get_by_name()`][crate::Catalog::get_by_name()]
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
-#[error("Ambiguous type: ${type_name}")]
+#[error("Unregistered type name: {type_name}")]
+pub struct UnregisteredNameError {
+    type_name: String,
+}
+
+impl UnregisteredNameError {
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct AmbiguousTypeError {
     type_id: TypeId,
     type_name: &'static str,
+    count: usize,
+    candidates: Vec<&'static str>,
+}
+
+impl AmbiguousTypeError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Implementations sharing the highest priority (see [`Builder::priority()`][crate::Builder::priority()]),
+    /// in the order of registration
+    pub fn candidates(&self) -> &[&'static str] {
+        &self.candidates
+    }
+}
+
+impl std::fmt::Display for AmbiguousTypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Ambiguous type: {} has {} implementations",
+            self.type_name, self.count
+        )?;
+        if !self.candidates.is_empty() {
+            write!(f, ": {}", self.candidates.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for AmbiguousTypeError {}
+
+// Candidates are the diagnostic context, so they are not part of the error identity
+impl PartialEq for AmbiguousTypeError {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+            && self.type_name == other.type_name
+            && self.count == other.count
+    }
+}
+
+impl Eq for AmbiguousTypeError {}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Type {type_name} has an async constructor and can only be resolved via Catalog::get_async()"
+)]
+pub struct AsyncConstructorError {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl AsyncConstructorError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+/// Returned when resolving an [`Owned`][crate::Owned] instance that is also referenced by the catalog,
+/// e.g. when the implementation is cached by a [`Singleton`][crate::Singleton] scope
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Instance of {type_name} is shared by its scope ({scope_name}) and can't be moved into a Box, \
+    use Arc<T> or the Transient scope instead"
+)]
+pub struct SharedInstanceError {
+    type_name: &'static str,
+    scope_name: &'static str,
+}
+
+impl SharedInstanceError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn scope_name(&self) -> &'static str {
+        self.scope_name
+    }
+}
+
+/// Returned by [`CatalogBuilder::validate()`][crate::CatalogBuilder::validate()], listing all problems found
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Catalog validation found {} problem(s): {:?}", .errors.len(), .errors)]
+pub struct ValidationError {
+    errors: Vec<InjectionError>,
+}
+
+impl ValidationError {
+    pub(crate) fn new(errors: Vec<InjectionError>) -> Self {
+        Self { errors }
+    }
+
+    pub fn errors(&self) -> &[InjectionError] {
+        &self.errors
+    }
+}
+
+/// Returned by [`CatalogBuilder::merge()`][crate::CatalogBuilder::merge()], listing all
+/// interfaces bound by both builders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeError {
+    conflicts: Vec<MergeConflict>,
+}
+
+impl MergeError {
+    pub(crate) fn new(conflicts: Vec<MergeConflict>) -> Self {
+        Self { conflicts }
+    }
+
+    pub fn conflicts(&self) -> &[MergeConflict] {
+        &self.conflicts
+    }
+}
+
+impl std::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Merged catalog builders conflict on ")?;
+        for (i, conflict) in self.conflicts.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", conflict)?;
+        }
+        Ok(())
+    }
 }
+
+impl Error for MergeError {}
+
+/// Interface (or a named binding of it) bound by both builders combined via
+/// [`CatalogBuilder::merge()`][crate::CatalogBuilder::merge()]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    iface_name: &'static str,
+    name: Option<String>,
+    existing: Vec<&'static str>,
+    merged: Vec<&'static str>,
+}
+
+impl MergeConflict {
+    pub(crate) fn new(
+        iface_name: &'static str,
+        name: Option<String>,
+        existing: Vec<&'static str>,
+        merged: Vec<&'static str>,
+    ) -> Self {
+        Self {
+            iface_name,
+            name,
+            existing,
+            merged,
+        }
+    }
+
+    pub fn iface_name(&self) -> &'static str {
+        self.iface_name
+    }
+
+    /// Name of the binding, see [`CatalogBuilder::bind_named()`][crate::CatalogBuilder::bind_named()]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Implementations bound to the interface in the builder merged into
+    pub fn existing(&self) -> &[&'static str] {
+        &self.existing
+    }
+
+    /// Implementations bound to the interface in the merged builder
+    pub fn merged(&self) -> &[&'static str] {
+        &self.merged
+    }
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.iface_name)?;
+        if let Some(name) = &self.name {
+            write!(f, " named {:?}", name)?;
+        }
+        write!(
+            f,
+            " (bound to {} and {})",
+            self.existing.join(", "),
+            self.merged.join(", ")
+        )
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Dependency cycle: {}", .type_names.join(" -> "))]
+pub struct CycleError {
+    type_names: Vec<&'static str>,
+}
+
+impl CycleError {
+    /// Types forming the cycle in the order of resolution, where the last one repeats an earlier one
+    pub fn type_names(&self) -> &[&'static str] {
+        &self.type_names
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Maximum resolution depth of {max_depth} exceeded: {}",
+    .type_names.join(" -> ")
+)]
+pub struct MaxDepthExceededError {
+    max_depth: usize,
+    type_names: Vec<&'static str>,
+}
+
+impl MaxDepthExceededError {
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Types that were being resolved when the limit was reached, from the outermost one
+    pub fn type_names(&self) -> &[&'static str] {
+        &self.type_names
+    }
+}
+
+/// Returned when resolving a [`FromContext`][crate::FromContext] value that is not attached to
+/// the catalog handle, see [`Catalog::with()`][crate::Catalog::with()], or a
+/// [`ComponentContext`][crate::ComponentContext] outside of a component constructor
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Missing context value: {type_name}")]
+pub struct MissingContextError {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl MissingContextError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+/// Returned when building a component without a catalog (via `build_standalone()` of the
+/// generated builder) while some of its dependencies are not overridden
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Can't build {type_name} without a catalog, missing overrides of: {}",
+    .args.join(", ")
+)]
+pub struct MissingOverridesError {
+    type_id: TypeId,
+    type_name: &'static str,
+    args: Vec<&'static str>,
+}
+
+impl MissingOverridesError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Names of the arguments that have to be overridden
+    pub fn args(&self) -> &[&'static str] {
+        &self.args
+    }
+}
+
+/// Returned when the handler of [`CatalogBuilder::on_unresolved()`][crate::CatalogBuilder::on_unresolved()]
+/// provides an instance that is neither `Arc<T>` nor `Arc<Arc<T>>` of the requested type
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Instance returned by the unresolved handler for {type_name} has a wrong type")]
+pub struct UnresolvedMismatchError {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl UnresolvedMismatchError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+/// Returned when resolving a component of the [`Pooled`][crate::Pooled] scope while all
+/// of the pooled instances are in use
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("All {capacity} pooled instances are checked out")]
+pub struct PoolExhaustedError {
+    capacity: usize,
+}
+
+impl PoolExhaustedError {
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Wraps the error returned by a fallible constructor (`fn new() -> Result<Self, E>`),
+/// which is preserved as the [`Error::source()`].
+#[derive(Debug, Clone)]
+pub struct ConstructorFailedError {
+    type_id: TypeId,
+    type_name: &'static str,
+    error: Arc<dyn Error + Send + Sync>,
+}
+
+impl ConstructorFailedError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.error.as_ref()
+    }
+}
+
+impl std::fmt::Display for ConstructorFailedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to construct {}: {}", self.type_name, self.error)
+    }
+}
+
+impl Error for ConstructorFailedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+// Errors are compared by identity as the user error types are not required to be comparable
+impl PartialEq for ConstructorFailedError {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+            && std::ptr::eq(
+                Arc::as_ptr(&self.error) as *const (),
+                Arc::as_ptr(&other.error) as *const (),
+            )
+    }
+}
+
+impl Eq for ConstructorFailedError {}
+
+/// Returned when a component or one of its arguments fails the validation,
+/// see [`Validate`][crate::Validate] and `#[dill(validate = "...")]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidValueError {
+    type_name: &'static str,
+    arg: Option<&'static str>,
+    reason: Option<String>,
+}
+
+impl InvalidValueError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Name of the rejected argument, or `None` if the constructed instance was rejected
+    pub fn arg(&self) -> Option<&'static str> {
+        self.arg
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+}
+
+impl std::fmt::Display for InvalidValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.arg {
+            Some(arg) => write!(f, "Invalid argument {} of {}", arg, self.type_name)?,
+            None => write!(f, "Invalid instance of {}", self.type_name)?,
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, ": {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for InvalidValueError {}
+
+/// Returned when building a component again after the value of its argument supplied via
+/// the `with_<arg>_value()` setter of the generated builder was moved into an earlier instance
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Value of argument {arg} of {type_name} was consumed by an earlier build, supply it via \
+    with_{arg}() or with_{arg}_fn() to build multiple instances"
+)]
+pub struct ValueConsumedError {
+    type_name: &'static str,
+    arg: &'static str,
+}
+
+impl ValueConsumedError {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn arg(&self) -> &'static str {
+        self.arg
+    }
+}
+
+/// Returned when an asynchronous resolution doesn't complete in time,
+/// see [`Catalog::get_async_timeout()`][crate::Catalog::get_async_timeout()]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeoutError {
+    type_name: &'static str,
+    timeout: Duration,
+    stuck_at: Vec<&'static str>,
+}
+
+impl TimeoutError {
+    /// Type of the dependency that was being resolved, e.g. `dill::OneOf<App>`
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Chain of types whose construction didn't complete, from the outermost one to the
+    /// one that got stuck. Empty if the resolution was stuck outside of the constructors.
+    pub fn stuck_at(&self) -> &[&'static str] {
+        &self.stuck_at
+    }
+}
+
+impl std::fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Resolution of {} timed out after {:?}",
+            self.type_name, self.timeout
+        )?;
+        if !self.stuck_at.is_empty() {
+            write!(f, ", stuck at: {}", self.stuck_at.join(" -> "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for TimeoutError {}
@@ -0,0 +1,18 @@
+use crate::{BoxFuture, MaybeSendSync};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by components reacting to the events of type `E`,
+/// see [`Catalog::publish()`][crate::Catalog::publish()].
+///
+/// Components subscribe by binding to this interface, e.g. via
+/// `#[interface(dyn EventHandler<UserCreated>)]`, so one component can handle multiple events.
+pub trait EventHandler<E>: MaybeSendSync {
+    fn handle(&self, event: &E);
+}
+
+/// Asynchronous counterpart of [`EventHandler`],
+/// see [`Catalog::publish_async()`][crate::Catalog::publish_async()]
+pub trait AsyncEventHandler<E>: MaybeSendSync {
+    fn handle_async<'a>(&'a self, event: &'a E) -> BoxFuture<'a, ()>;
+}
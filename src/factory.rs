@@ -0,0 +1,76 @@
+use std::marker::PhantomData;
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Creates instances of a dependency on demand, e.g. for components that need a fresh
+/// instance per unit of work (like one parser per file) over their whole lifetime.
+///
+/// Every [`Factory::create()`] call resolves `OneOf<T>` from the catalog, so the lifetime
+/// of the instances is still controlled by the scope of the implementation - `Transient`
+/// components are built anew on every call, while the cached ones are returned as is.
+///
+/// Can be injected as `Factory<T>` argument of a component.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+/// use std::sync::Arc;
+///
+/// #[component]
+/// struct Importer {
+///     parsers: Factory<Parser>,
+/// }
+///
+/// #[component]
+/// struct Parser;
+///
+/// let cat = CatalogBuilder::new().add::<Importer>().add::<Parser>().build();
+///
+/// let importer = cat.get_one::<Importer>().unwrap();
+/// let p1 = importer.parsers.create().unwrap();
+/// let p2 = importer.parsers.create().unwrap();
+/// assert!(!Arc::ptr_eq(&p1, &p2));
+/// ```
+pub struct Factory<Iface: ?Sized> {
+    cat: Catalog,
+    _dummy: PhantomData<fn() -> Shared<Iface>>,
+}
+
+impl<Iface> Factory<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    pub fn new(cat: &Catalog) -> Self {
+        Self {
+            cat: cat.detached(),
+            _dummy: PhantomData,
+        }
+    }
+
+    /// Resolves a new instance (or the one cached by the scope) from the catalog
+    pub fn create(&self) -> Result<Shared<Iface>, InjectionError> {
+        self.cat.get_one::<Iface>()
+    }
+
+    /// Asynchronous counterpart of [`Factory::create()`]
+    pub async fn create_async(&self) -> Result<Shared<Iface>, InjectionError> {
+        self.cat.get_one_async::<Iface>().await
+    }
+
+    /// Builds a fresh instance moved into a [`Box`], see [`Owned`]
+    pub fn create_owned(&self) -> Result<Box<Iface>, InjectionError> {
+        self.cat.get::<Owned<Iface>>()
+    }
+}
+
+impl<Iface: ?Sized> Clone for Factory<Iface> {
+    fn clone(&self) -> Self {
+        Self {
+            cat: self.cat.clone(),
+            _dummy: PhantomData,
+        }
+    }
+}
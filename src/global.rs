@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::sync::Once;
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+static INIT: Once = Once::new();
+// Written only once inside of `INIT`, so it can be read without locking once `INIT` completes
+static mut GLOBAL: Option<Catalog> = None;
+
+thread_local! {
+    static OVERRIDE: RefCell<Option<Catalog>> = RefCell::new(None);
+}
+
+/// Sets the catalog returned by [`global()`], e.g. for CLI applications that don't want
+/// to pass the catalog through every function. Should be called once at startup.
+///
+/// # Panics
+///
+/// Panics if the global catalog is already set. Tests should use [`override_global()`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// set_global(CatalogBuilder::new().add_value(8080u16).build());
+///
+/// assert_eq!(*global().get_one::<u16>().unwrap(), 8080);
+/// ```
+pub fn set_global(cat: Catalog) {
+    let mut cat = Some(cat);
+    INIT.call_once(|| {
+        // SAFETY: `Once` guarantees exclusive access, and no reads happen before it completes
+        unsafe { GLOBAL = cat.take() };
+    });
+    if cat.is_some() {
+        panic!("Global catalog is already set");
+    }
+}
+
+/// Returns the global catalog or `None` if it's not set, see [`global()`]
+pub fn try_global() -> Option<Catalog> {
+    if let Some(cat) = OVERRIDE.with(|o| o.borrow().clone()) {
+        return Some(cat);
+    }
+    if !INIT.is_completed() {
+        return None;
+    }
+    // SAFETY: the value is not modified after `INIT` completes
+    unsafe { GLOBAL.clone() }
+}
+
+/// Returns the catalog set via [`set_global()`] or the one overridden for the current thread
+/// via [`override_global()`].
+///
+/// # Panics
+///
+/// Panics if the global catalog is not set, see [`try_global()`] for a non-panicking version.
+pub fn global() -> Catalog {
+    try_global().expect("Global catalog is not set, call dill::set_global() at startup")
+}
+
+/// Replaces the global catalog for the current thread until the returned guard is dropped,
+/// which restores the previous one. Since overrides are per-thread, the tests running
+/// in parallel don't affect each other. Overrides can be nested.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// assert!(try_global().is_none());
+///
+/// {
+///     let _guard = override_global(CatalogBuilder::new().add_value(1u16).build());
+///     assert_eq!(*global().get_one::<u16>().unwrap(), 1);
+///
+///     {
+///         let _guard = override_global(CatalogBuilder::new().add_value(2u16).build());
+///         assert_eq!(*global().get_one::<u16>().unwrap(), 2);
+///     }
+///
+///     assert_eq!(*global().get_one::<u16>().unwrap(), 1);
+/// }
+///
+/// assert!(try_global().is_none());
+/// ```
+pub fn override_global(cat: Catalog) -> GlobalOverride {
+    let prev = OVERRIDE.with(|o| o.borrow_mut().replace(cat));
+    GlobalOverride {
+        prev,
+        _not_send: PhantomData,
+    }
+}
+
+/// Restores the previous global catalog of the thread when dropped, see [`override_global()`]
+#[must_use = "Global catalog is restored as soon as the guard is dropped"]
+pub struct GlobalOverride {
+    prev: Option<Catalog>,
+    // Has to be dropped on the thread that created it
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for GlobalOverride {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+        OVERRIDE.with(|o| *o.borrow_mut() = prev);
+    }
+}
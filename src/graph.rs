@@ -0,0 +1,111 @@
+use std::{any::TypeId, fmt::Write};
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Graph of the components registered in a catalog and their declared dependencies,
+/// see [`Catalog::dependency_graph()`]
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A component registered in the catalog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    pub scope_name: &'static str,
+}
+
+/// A dependency of a component on one of the implementations of an interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: TypeId,
+    /// Implementation the dependency resolves to, or `None` if the interface has no implementations
+    pub to: Option<TypeId>,
+    pub dependency: DependencyInfo,
+}
+
+impl DependencyGraph {
+    /// Renders the graph in the Graphviz DOT format, e.g. to visualize it via `dot -Tsvg`.
+    ///
+    /// Dependencies that have no implementations are highlighted in red, while ambiguous
+    /// ones show up as multiple edges of a [`DependencyKind::One`] dependency.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph dependencies {{").unwrap();
+
+        for node in &self.nodes {
+            writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\\n{}\"];",
+                node.type_name,
+                node.type_name,
+                short_type_name(node.scope_name)
+            )
+            .unwrap();
+        }
+
+        for edge in &self.edges {
+            let mut attrs = Vec::new();
+            match edge.dependency.kind {
+                DependencyKind::One => {}
+                DependencyKind::All => attrs.push("label=\"all\"".to_owned()),
+                DependencyKind::Maybe => attrs.push("style=dashed".to_owned()),
+                DependencyKind::Lazy => attrs.push("style=dotted".to_owned()),
+            }
+            if let Some(name) = edge.dependency.name {
+                attrs.push(format!("taillabel=\"{}\"", name));
+            }
+
+            let to = match edge.to {
+                Some(to) => self.type_name_of(to),
+                None => {
+                    writeln!(
+                        dot,
+                        "    \"{}\" [color=red, fontcolor=red];",
+                        edge.dependency.type_name
+                    )
+                    .unwrap();
+                    attrs.push("color=red".to_owned());
+                    edge.dependency.type_name
+                }
+            };
+
+            let from = self.type_name_of(edge.from);
+            if attrs.is_empty() {
+                writeln!(dot, "    \"{}\" -> \"{}\";", from, to).unwrap();
+            } else {
+                writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\" [{}];",
+                    from,
+                    to,
+                    attrs.join(", ")
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+
+    fn type_name_of(&self, type_id: TypeId) -> &'static str {
+        self.nodes
+            .iter()
+            .find(|n| n.type_id == type_id)
+            .map(|n| n.type_name)
+            .unwrap_or("<unknown>")
+    }
+}
+
+/// Strips the module path and generic arguments, e.g. `dyn app::Repo<app::User>` -> `Repo`
+pub(crate) fn short_type_name(type_name: &str) -> &str {
+    let type_name = type_name.trim_start_matches("dyn ");
+    let type_name = type_name.split(&['<', ' '][..]).next().unwrap_or(type_name);
+    type_name.rsplit("::").next().unwrap_or(type_name)
+}
@@ -0,0 +1,118 @@
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by components that can report whether they function properly (e.g. whether
+/// a connection pool can reach the database), see [`Catalog::check_health()`].
+///
+/// Components opt in by binding to this interface, e.g. via `#[interface(dyn HealthCheck)]`.
+pub trait HealthCheck: MaybeSendSync {
+    fn check_health(&self) -> HealthStatus;
+}
+
+/// Status reported by a [`HealthCheck`], ordered from the best to the worst
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthStatus {
+    Healthy,
+    /// Component functions with limitations, e.g. serves stale data while its source is down
+    Degraded(String),
+    Unhealthy(String),
+}
+
+impl HealthStatus {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+}
+
+/// Status of a single component within a [`HealthReport`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentHealth {
+    type_name: &'static str,
+    status: HealthStatus,
+}
+
+impl ComponentHealth {
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn status(&self) -> &HealthStatus {
+        &self.status
+    }
+}
+
+/// Statuses of all components bound to [`HealthCheck`], see [`Catalog::check_health()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    components: Vec<ComponentHealth>,
+}
+
+impl HealthReport {
+    /// Statuses in the order of resolution, see [`AllOf`]
+    pub fn components(&self) -> &[ComponentHealth] {
+        &self.components
+    }
+
+    /// The worst status among the components, [`HealthStatus::Healthy`] if there are none
+    pub fn status(&self) -> &HealthStatus {
+        self.components
+            .iter()
+            .map(|c| &c.status)
+            .max()
+            .unwrap_or(&HealthStatus::Healthy)
+    }
+
+    /// Whether none of the components is unhealthy, e.g. to drive a readiness endpoint.
+    /// Degraded components are still considered able to serve.
+    pub fn is_ready(&self) -> bool {
+        !matches!(self.status(), HealthStatus::Unhealthy(_))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+impl Catalog {
+    /// Resolves all components bound to the [`HealthCheck`] interface and collects their
+    /// statuses into a report. Components that fail to resolve are reported as unhealthy
+    /// with the resolution error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[component]
+    /// #[interface(dyn HealthCheck)]
+    /// struct Cache;
+    ///
+    /// impl HealthCheck for Cache {
+    ///     fn check_health(&self) -> HealthStatus {
+    ///         HealthStatus::Degraded("Serving stale entries".to_owned())
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add::<Cache>().build();
+    ///
+    /// let report = cat.check_health();
+    /// assert!(report.is_ready());
+    /// assert_eq!(
+    ///     report.status(),
+    ///     &HealthStatus::Degraded("Serving stale entries".to_owned())
+    /// );
+    /// ```
+    pub fn check_health(&self) -> HealthReport {
+        let components = self
+            .builders_for::<dyn HealthCheck>()
+            .map(|builder| ComponentHealth {
+                type_name: builder.instance_type_name(),
+                status: match builder.get(self) {
+                    Ok(inst) => inst.check_health(),
+                    Err(err) => HealthStatus::Unhealthy(err.to_string()),
+                },
+            })
+            .collect();
+
+        HealthReport { components }
+    }
+}
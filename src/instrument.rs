@@ -0,0 +1,135 @@
+//! Instrumentation of the instance construction used by the generated builders.
+//!
+//! Notifies the [`ResolutionObserver`][crate::ResolutionObserver]s of the catalog about
+//! the constructed and the cached instances. With the `tracing` feature enabled every construction
+//! is also wrapped into a `dill::build` span (with `component` and `scope` fields) that reports
+//! the time it took, while the instances returned by the scope without constructing emit
+//! `Cache hit` events.
+
+use std::{cell::Cell, future::Future, time::Instant};
+
+use crate::{Builder, Catalog, InjectionError, Scope, Shared, SharedAny};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Returns the instance cached by the scope or constructs a new one using `build`,
+/// see [`Scope::get_or_init()`]
+pub fn get_or_build<S>(
+    builder: &dyn Builder,
+    scope: &S,
+    cat: &Catalog,
+    build: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+) -> Result<Shared<SharedAny>, InjectionError>
+where
+    S: Scope + ?Sized,
+{
+    get_or_init(builder, scope, cat, &|| {
+        #[cfg(feature = "tracing")]
+        let span = build_span(builder);
+        #[cfg(feature = "tracing")]
+        let _guard = span.enter();
+
+        let started = build_started();
+        let res = build();
+        build_finished(builder, cat, &res, started);
+        res
+    })
+}
+
+/// Returns the instance cached by the scope or initializes it using `init` without
+/// reporting a construction, for builders that delegate it to an already instrumented one
+pub(crate) fn get_or_init<S>(
+    builder: &dyn Builder,
+    scope: &S,
+    cat: &Catalog,
+    init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+) -> Result<Shared<SharedAny>, InjectionError>
+where
+    S: Scope + ?Sized,
+{
+    let built = Cell::new(false);
+
+    let inst = scope.get_or_init(cat, &|| {
+        built.set(true);
+        init()
+    })?;
+
+    // Instance might've been constructed concurrently while waiting for the scope
+    if !built.get() {
+        cache_hit(builder, cat);
+    }
+    Ok(inst)
+}
+
+/// Constructs a new instance asynchronously, see [`Builder::get_async()`]
+pub async fn build_async<T, F>(
+    builder: &dyn Builder,
+    cat: &Catalog,
+    build: F,
+) -> Result<T, InjectionError>
+where
+    F: Future<Output = Result<T, InjectionError>>,
+{
+    let fut = async {
+        let started = build_started();
+        let res = build.await;
+        build_finished(builder, cat, &res, started);
+        res
+    };
+
+    #[cfg(feature = "tracing")]
+    let fut = tracing::Instrument::instrument(fut, build_span(builder));
+
+    fut.await
+}
+
+/// Reports the instance returned by the scope without constructing it
+pub fn cache_hit(builder: &dyn Builder, cat: &Catalog) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        component = builder.instance_type_name(),
+        scope = builder.scope_name(),
+        "Cache hit"
+    );
+
+    for observer in cat.observers() {
+        observer.on_cache_hit(builder);
+    }
+}
+
+fn build_started() -> Instant {
+    #[cfg(feature = "tracing")]
+    tracing::trace!("Cache miss");
+
+    Instant::now()
+}
+
+fn build_finished<T>(
+    builder: &dyn Builder,
+    cat: &Catalog,
+    res: &Result<T, InjectionError>,
+    started: Instant,
+) {
+    let elapsed = started.elapsed();
+
+    #[cfg(feature = "tracing")]
+    match res {
+        Ok(_) => tracing::debug!(?elapsed, "Constructed instance"),
+        Err(error) => tracing::debug!(?elapsed, ?error, "Failed to construct instance"),
+    }
+
+    if res.is_ok() {
+        for observer in cat.observers() {
+            observer.on_build(builder, elapsed);
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn build_span(builder: &dyn Builder) -> tracing::Span {
+    tracing::debug_span!(
+        "dill::build",
+        component = builder.instance_type_name(),
+        scope = builder.scope_name(),
+    )
+}
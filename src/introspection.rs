@@ -0,0 +1,432 @@
+use std::any::TypeId;
+
+use thiserror::Error;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Describes a component registered in the catalog along with its current state,
+/// see [`Catalog::components()`][crate::Catalog::components()]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentInfo {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    /// Type name of the [`Scope`][crate::Scope] controlling the lifetime of instances,
+    /// e.g. `dill::scopes::Singleton`
+    pub scope_name: &'static str,
+    /// Interfaces the component can be resolved by, including its own type unless it was
+    /// registered via [`CatalogBuilder::add_as()`][crate::CatalogBuilder::add_as()]
+    pub bindings: Vec<BindingInfo>,
+    /// Whether the scope currently holds an instance of the component
+    pub is_cached: bool,
+    pub priority: i32,
+    pub tags: Vec<&'static str>,
+}
+
+/// An interface the component is bound to, see [`ComponentInfo`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingInfo {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+    /// Name of the binding, see [`CatalogBuilder::bind_named()`][crate::CatalogBuilder::bind_named()]
+    pub name: Option<String>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Description of the components, their scopes and bindings registered in a catalog that
+/// doesn't depend on its state, see [`Catalog::wiring_snapshot()`][crate::Catalog::wiring_snapshot()].
+///
+/// Converts to and from a line-based text format via [`Display`][std::fmt::Display] and
+/// [`FromStr`][std::str::FromStr], e.g. to be stored as a golden file in tests and compared
+/// via [`snapshot_diff()`]. Note that the type names are the ones of [`std::any::type_name()`],
+/// which can change between compiler versions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WiringSnapshot {
+    /// Components ordered by type name
+    pub components: Vec<ComponentWiring>,
+}
+
+/// Wiring of a single component, see [`WiringSnapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentWiring {
+    pub type_name: String,
+    pub scope_name: String,
+    /// Interfaces the component is bound to, e.g. `dyn app::Db` or `dyn app::Db as "primary"`
+    /// for the named bindings, in sorted order
+    pub bindings: Vec<String>,
+}
+
+impl From<&ComponentInfo> for ComponentWiring {
+    fn from(info: &ComponentInfo) -> Self {
+        Self {
+            type_name: info.type_name.to_owned(),
+            scope_name: info.scope_name.to_owned(),
+            bindings: info
+                .bindings
+                .iter()
+                .map(|b| match &b.name {
+                    None => b.type_name.to_owned(),
+                    Some(name) => format!("{} as {:?}", b.type_name, name),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for WiringSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in &self.components {
+            writeln!(f, "component {}", c.type_name)?;
+            writeln!(f, "  scope {}", c.scope_name)?;
+            for binding in &c.bindings {
+                writeln!(f, "  binds {}", binding)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for WiringSnapshot {
+    type Err = WiringParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components: Vec<ComponentWiring> = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let err = || WiringParseError {
+                line: i + 1,
+                text: line.to_owned(),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(type_name) = line.strip_prefix("component ") {
+                components.push(ComponentWiring {
+                    type_name: type_name.to_owned(),
+                    scope_name: String::new(),
+                    bindings: Vec::new(),
+                });
+                continue;
+            }
+            let component = components.last_mut().ok_or_else(err)?;
+            if let Some(scope_name) = line.strip_prefix("  scope ") {
+                component.scope_name = scope_name.to_owned();
+            } else if let Some(binding) = line.strip_prefix("  binds ") {
+                component.bindings.push(binding.to_owned());
+            } else {
+                return Err(err());
+            }
+        }
+        Ok(Self { components })
+    }
+}
+
+/// Returned when parsing a [`WiringSnapshot`] from text fails
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid wiring snapshot at line {line}: {text}")]
+pub struct WiringParseError {
+    line: usize,
+    text: String,
+}
+
+/// Difference between two [`WiringSnapshot`]s, see [`snapshot_diff()`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WiringDiff {
+    /// Components present only in the second snapshot
+    pub added: Vec<ComponentWiring>,
+    /// Components present only in the first snapshot
+    pub removed: Vec<ComponentWiring>,
+    /// Components present in both snapshots with different scopes or bindings, as in the first
+    /// and in the second snapshot
+    pub changed: Vec<(ComponentWiring, ComponentWiring)>,
+}
+
+impl WiringDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl std::fmt::Display for WiringDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in &self.added {
+            writeln!(f, "+ {} [{}]", c.type_name, c.scope_name)?;
+        }
+        for c in &self.removed {
+            writeln!(f, "- {} [{}]", c.type_name, c.scope_name)?;
+        }
+        for (a, b) in &self.changed {
+            writeln!(f, "~ {}", a.type_name)?;
+            if a.scope_name != b.scope_name {
+                writeln!(f, "  scope {} -> {}", a.scope_name, b.scope_name)?;
+            }
+            for binding in b.bindings.iter().filter(|x| !a.bindings.contains(x)) {
+                writeln!(f, "  + binds {}", binding)?;
+            }
+            for binding in a.bindings.iter().filter(|x| !b.bindings.contains(x)) {
+                writeln!(f, "  - binds {}", binding)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares the wirings of two catalogs, e.g. the golden snapshot of the production wiring
+/// stored in a file and the current one.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[component]
+/// struct A;
+///
+/// #[component]
+/// struct B;
+///
+/// let golden = CatalogBuilder::new().add::<A>().build().wiring_snapshot();
+/// let golden: WiringSnapshot = golden.to_string().parse().unwrap();
+///
+/// let current = CatalogBuilder::new().add::<A>().add::<B>().build().wiring_snapshot();
+///
+/// let diff = snapshot_diff(&golden, &current);
+/// assert_eq!(diff.added.len(), 1);
+/// assert!(diff.removed.is_empty() && diff.changed.is_empty());
+/// ```
+pub fn snapshot_diff(a: &WiringSnapshot, b: &WiringSnapshot) -> WiringDiff {
+    let find = |s: &WiringSnapshot, type_name: &str| {
+        s.components
+            .iter()
+            .find(|c| c.type_name == type_name)
+            .cloned()
+    };
+
+    let mut diff = WiringDiff::default();
+    for ca in &a.components {
+        match find(b, &ca.type_name) {
+            None => diff.removed.push(ca.clone()),
+            Some(cb) if cb != *ca => diff.changed.push((ca.clone(), cb)),
+            Some(_) => {}
+        }
+    }
+    for cb in &b.components {
+        if find(a, &cb.type_name).is_none() {
+            diff.added.push(cb.clone());
+        }
+    }
+    diff
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Effective wiring of a catalog, i.e. its components, the implementations chosen for the
+/// interfaces and the applied configuration, see [`Catalog::wiring_manifest()`][crate::Catalog::wiring_manifest()].
+///
+/// Unlike [`WiringSnapshot`] it records the outcome of the priorities and the configuration,
+/// e.g. to be exported along with a deployment (see `to_json()` of the `config` feature) and
+/// to verify via [`Catalog::assert_wiring()`][crate::Catalog::assert_wiring()] that a release
+/// wires the services the same way as the one it replaces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WiringManifest {
+    /// Components ordered by type name
+    pub components: Vec<ComponentWiring>,
+    /// Interfaces bound to implementations other than themselves, ordered by type name
+    pub interfaces: Vec<InterfaceWiring>,
+    /// Configuration keys applied via [`CatalogBuilder::configure_from()`][crate::CatalogBuilder::configure_from()],
+    /// ordered by key
+    pub config: Vec<ConfigWiring>,
+}
+
+/// Implementation chosen for an interface, see [`WiringManifest`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceWiring {
+    pub type_name: String,
+    /// Type name of the implementation with the highest priority, `None` if several
+    /// implementations share it, i.e. the interface is ambiguous
+    pub implementation: Option<String>,
+}
+
+/// Value of a configuration key, see [`WiringManifest`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigWiring {
+    pub key: String,
+    /// Value as JSON text
+    pub value: String,
+}
+
+impl WiringManifest {
+    /// Describes the differences from another manifest line by line, empty if there are none
+    pub(crate) fn diff(&self, other: &WiringManifest) -> String {
+        let snapshot = |m: &WiringManifest| WiringSnapshot {
+            components: m.components.clone(),
+        };
+        let mut diff = snapshot_diff(&snapshot(self), &snapshot(other)).to_string();
+
+        let describe = |v: Option<&Option<String>>| match v {
+            None => "<unbound>".to_owned(),
+            Some(None) => "<ambiguous>".to_owned(),
+            Some(Some(implementation)) => implementation.clone(),
+        };
+        let mut ifaces: Vec<&str> = self
+            .interfaces
+            .iter()
+            .chain(&other.interfaces)
+            .map(|i| i.type_name.as_str())
+            .collect();
+        ifaces.sort_unstable();
+        ifaces.dedup();
+        for iface in ifaces {
+            let find = |m: &'_ WiringManifest| {
+                m.interfaces
+                    .iter()
+                    .find(|i| i.type_name == iface)
+                    .map(|i| i.implementation.clone())
+            };
+            let (a, b) = (find(self), find(other));
+            if a != b {
+                diff.push_str(&format!(
+                    "~ interface {}: {} -> {}\n",
+                    iface,
+                    describe(a.as_ref()),
+                    describe(b.as_ref())
+                ));
+            }
+        }
+
+        let mut keys: Vec<&str> = self
+            .config
+            .iter()
+            .chain(&other.config)
+            .map(|c| c.key.as_str())
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        for key in keys {
+            let find = |m: &'_ WiringManifest| {
+                m.config
+                    .iter()
+                    .find(|c| c.key == key)
+                    .map(|c| c.value.clone())
+            };
+            let (a, b) = (find(self), find(other));
+            if a != b {
+                diff.push_str(&format!(
+                    "~ config {}: {} -> {}\n",
+                    key,
+                    a.as_deref().unwrap_or("<unset>"),
+                    b.as_deref().unwrap_or("<unset>")
+                ));
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(feature = "config")]
+impl WiringManifest {
+    /// Converts the manifest into a JSON document of the following shape:
+    ///
+    /// ```json
+    /// {
+    ///   "components": [{"type": "app::PgDb", "scope": "dill::Singleton", "bindings": ["dyn app::Db"]}],
+    ///   "interfaces": [{"type": "dyn app::Db", "implementation": "app::PgDb"}],
+    ///   "config": {"db.pool_size": 10}
+    /// }
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::{json, Value};
+
+        let components: Vec<Value> = self
+            .components
+            .iter()
+            .map(|c| json!({"type": c.type_name, "scope": c.scope_name, "bindings": c.bindings}))
+            .collect();
+        let interfaces: Vec<Value> = self
+            .interfaces
+            .iter()
+            .map(|i| json!({"type": i.type_name, "implementation": i.implementation}))
+            .collect();
+        let config: serde_json::Map<String, Value> = self
+            .config
+            .iter()
+            .map(|c| {
+                let value = serde_json::from_str(&c.value).unwrap_or(Value::Null);
+                (c.key.clone(), value)
+            })
+            .collect();
+
+        json!({"components": components, "interfaces": interfaces, "config": config})
+    }
+
+    /// Parses the manifest from a JSON document produced by [`WiringManifest::to_json()`]
+    pub fn from_json(doc: &serde_json::Value) -> Result<Self, WiringManifestError> {
+        use serde_json::Value;
+
+        let err = |path: String, expected: &'static str| WiringManifestError { path, expected };
+        let array = |key: &'static str| match doc.get(key) {
+            None => Ok(&[][..]),
+            Some(v) => v
+                .as_array()
+                .map(|a| a.as_slice())
+                .ok_or_else(|| err(key.to_owned(), "an array")),
+        };
+        let string = |v: &Value, path: String| {
+            v.as_str()
+                .map(|s| s.to_owned())
+                .ok_or_else(|| err(path, "a string"))
+        };
+
+        let mut manifest = WiringManifest::default();
+        for (i, c) in array("components")?.iter().enumerate() {
+            let path = format!("components[{}]", i);
+            let bindings = c
+                .get("bindings")
+                .and_then(|v| v.as_array())
+                .map(|a| a.as_slice())
+                .unwrap_or_default();
+            manifest.components.push(ComponentWiring {
+                type_name: string(&c["type"], format!("{}.type", path))?,
+                scope_name: string(&c["scope"], format!("{}.scope", path))?,
+                bindings: bindings
+                    .iter()
+                    .enumerate()
+                    .map(|(j, b)| string(b, format!("{}.bindings[{}]", path, j)))
+                    .collect::<Result<_, _>>()?,
+            });
+        }
+        for (i, iface) in array("interfaces")?.iter().enumerate() {
+            let path = format!("interfaces[{}]", i);
+            manifest.interfaces.push(InterfaceWiring {
+                type_name: string(&iface["type"], format!("{}.type", path))?,
+                implementation: match &iface["implementation"] {
+                    Value::Null => None,
+                    v => Some(string(v, format!("{}.implementation", path))?),
+                },
+            });
+        }
+        match doc.get("config") {
+            None => {}
+            Some(Value::Object(config)) => {
+                manifest.config = config
+                    .iter()
+                    .map(|(key, value)| ConfigWiring {
+                        key: key.clone(),
+                        value: value.to_string(),
+                    })
+                    .collect();
+                manifest.config.sort_by(|a, b| a.key.cmp(&b.key));
+            }
+            Some(_) => return Err(err("config".to_owned(), "an object")),
+        }
+        Ok(manifest)
+    }
+}
+
+/// Returned when parsing a [`WiringManifest`] from JSON fails
+#[cfg(feature = "config")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid wiring manifest at {path}: expected {expected}")]
+pub struct WiringManifestError {
+    path: String,
+    expected: &'static str,
+}
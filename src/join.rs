@@ -0,0 +1,125 @@
+use std::{
+    any::TypeId,
+    collections::HashSet,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{BoxFuture, Builder, Catalog, InjectionError};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Runs the resolutions of multiple builders concurrently, completing with their results in
+/// the original order or with the first error, see [`CatalogBuilder::max_async_concurrency()`]
+///
+/// [`CatalogBuilder::max_async_concurrency()`]: crate::CatalogBuilder::max_async_concurrency()
+pub(crate) struct JoinAll<'a, T> {
+    cat: &'a Catalog,
+    limit: usize,
+    /// Resolutions that are not started yet, in the order of the results
+    pending: Vec<Task<'a, T>>,
+    running: Vec<Task<'a, T>>,
+    results: Vec<Option<T>>,
+}
+
+struct Task<'a, T> {
+    index: usize,
+    builder: &'a dyn Builder,
+    resolution: BoxFuture<'a, Result<T, InjectionError>>,
+    /// Components that the resolution might construct, see [`Catalog::uncached_dependencies()`]
+    constructs: HashSet<TypeId>,
+}
+
+impl<'a, T> JoinAll<'a, T> {
+    pub(crate) fn new(
+        cat: &'a Catalog,
+        resolutions: impl IntoIterator<
+            Item = (&'a dyn Builder, BoxFuture<'a, Result<T, InjectionError>>),
+        >,
+    ) -> Self {
+        let pending: Vec<_> = resolutions
+            .into_iter()
+            .enumerate()
+            .map(|(index, (builder, resolution))| Task {
+                index,
+                builder,
+                resolution,
+                constructs: HashSet::new(),
+            })
+            .collect();
+
+        Self {
+            cat,
+            limit: cat.max_async_concurrency(),
+            running: Vec::with_capacity(pending.len()),
+            results: pending.iter().map(|_| None).collect(),
+            pending,
+        }
+    }
+
+    /// Starts the pending resolutions (in order) that don't construct the same components
+    /// as the running ones, while there are free slots
+    fn start_pending(&mut self) {
+        let mut i = 0;
+        while i < self.pending.len() && self.running.len() < self.limit {
+            // Without concurrency there are no conflicts to look for
+            let constructs = if self.limit > 1 {
+                self.cat.uncached_dependencies(self.pending[i].builder)
+            } else {
+                HashSet::new()
+            };
+
+            if self
+                .running
+                .iter()
+                .all(|t| t.constructs.is_disjoint(&constructs))
+            {
+                let mut task = self.pending.remove(i);
+                task.constructs = constructs;
+                self.running.push(task);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+// Results are never pinned, as only the boxed resolutions are polled
+impl<'a, T> Unpin for JoinAll<'a, T> {}
+
+impl<'a, T> Future for JoinAll<'a, T> {
+    type Output = Result<Vec<T>, InjectionError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        loop {
+            this.start_pending();
+
+            let mut completed = false;
+            let mut i = 0;
+            while i < this.running.len() {
+                match this.running[i].resolution.as_mut().poll(cx) {
+                    Poll::Ready(Ok(inst)) => {
+                        let task = this.running.swap_remove(i);
+                        this.results[task.index] = Some(inst);
+                        completed = true;
+                    }
+                    // Dropping the other resolutions cancels them
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => i += 1,
+                }
+            }
+
+            if this.running.is_empty() && this.pending.is_empty() {
+                let results = this.results.drain(..).map(Option::unwrap).collect();
+                return Poll::Ready(Ok(results));
+            }
+
+            // Completed resolutions free the slots and cache the components others depend on
+            if !completed {
+                return Poll::Pending;
+            }
+        }
+    }
+}
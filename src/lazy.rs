@@ -0,0 +1,184 @@
+use std::sync::Mutex;
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Defers the resolution of a dependency until it's first used, e.g. to break legitimate
+/// dependency cycles or to avoid constructing heavyweight components that are rarely needed.
+///
+/// Can be injected as `Lazy<Arc<T>>` argument of a component.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+/// use std::sync::Arc;
+///
+/// #[component]
+/// struct A {
+///     b: Lazy<Arc<B>>,
+/// }
+///
+/// #[component]
+/// struct B;
+///
+/// let cat = CatalogBuilder::new().add::<A>().add::<B>().build();
+///
+/// let a = cat.get_one::<A>().unwrap();
+/// let b = a.b.get().unwrap();
+/// ```
+pub struct Lazy<T> {
+    cat: Catalog,
+    resolve: fn(&Catalog) -> Result<T, InjectionError>,
+    value: Mutex<Option<T>>,
+}
+
+impl<T: Clone> Lazy<T> {
+    pub fn new(cat: &Catalog, resolve: fn(&Catalog) -> Result<T, InjectionError>) -> Self {
+        Self {
+            cat: cat.detached(),
+            resolve,
+            value: Mutex::new(None),
+        }
+    }
+
+    /// Resolves the dependency upon first call and returns the cached value afterwards.
+    /// Failed resolution is not cached and will be retried on the next call.
+    pub fn get(&self) -> Result<T, InjectionError> {
+        if let Some(value) = self.lock().as_ref() {
+            return Ok(value.clone());
+        }
+
+        // Lock is not held during resolution so that resolving a cycle doesn't deadlock
+        let value = (self.resolve)(&self.cat)?;
+        Ok(self.lock().get_or_insert(value).clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<T>> {
+        self.value.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<Iface> Lazy<Shared<Iface>>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    /// Creates a lazy dependency that resolves `OneOf<Iface>`
+    pub fn one_of(cat: &Catalog) -> Self {
+        Self::new(cat, |cat| cat.get_one::<Iface>())
+    }
+}
+
+impl<T: Clone> Clone for Lazy<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cat: self.cat.clone(),
+            resolve: self.resolve,
+            value: Mutex::new(self.lock().clone()),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// One of the implementations of an interface that is built upon the first
+/// [`LazyBinding::get()`] call, see [`AllOfLazy`]
+///
+/// Describes the binding (e.g. its name, tags and metadata) upfront, so that
+/// the consumer can tell which implementations to build.
+pub struct LazyBinding<Iface: ?Sized> {
+    cat: Catalog,
+    /// Position of the binding among the bindings of the interface, which don't change
+    /// once the catalog is built
+    index: usize,
+    type_name: &'static str,
+    name: Option<String>,
+    tags: Vec<&'static str>,
+    metadata: Metadata,
+    value: Mutex<Option<Shared<Iface>>>,
+}
+
+impl<Iface> LazyBinding<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    pub(crate) fn new(cat: &Catalog, index: usize, builder: &TypecastBuilder<'_, Iface>) -> Self {
+        Self {
+            cat: cat.detached(),
+            index,
+            type_name: builder.instance_type_name(),
+            name: builder.name().map(str::to_owned),
+            tags: builder.tags().to_vec(),
+            metadata: builder.metadata().clone(),
+            value: Mutex::new(None),
+        }
+    }
+
+    /// Type name of the implementation
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Name of the binding, see [`CatalogBuilder::bind_named()`]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Tags of the implementation, see [`Builder::tags()`]
+    pub fn tags(&self) -> &[&'static str] {
+        &self.tags
+    }
+
+    /// Metadata of the implementation, see [`Registration::with_meta()`]
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Builds the implementation upon first call and returns the cached instance afterwards.
+    /// Failed resolution is not cached and will be retried on the next call.
+    pub fn get(&self) -> Result<Shared<Iface>, InjectionError> {
+        if let Some(inst) = self.lock().as_ref() {
+            return Ok(inst.clone());
+        }
+
+        // Lock is not held during resolution for the same reason as with `Lazy`
+        let inst = self.builder()?.get(&self.cat)?;
+        Ok(self.lock().get_or_insert(inst).clone())
+    }
+
+    /// Asynchronous counterpart of [`LazyBinding::get()`]
+    pub async fn get_async(&self) -> Result<Shared<Iface>, InjectionError> {
+        if let Some(inst) = self.lock().as_ref() {
+            return Ok(inst.clone());
+        }
+
+        let inst = self.builder()?.get_async(&self.cat).await?;
+        Ok(self.lock().get_or_insert(inst).clone())
+    }
+
+    fn builder(&self) -> Result<TypecastBuilder<'_, Iface>, InjectionError> {
+        self.cat
+            .builders_for::<Iface>()
+            .nth(self.index)
+            .ok_or_else(InjectionError::unregistered::<Iface>)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Shared<Iface>>> {
+        self.value.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<Iface: ?Sized> Clone for LazyBinding<Iface> {
+    fn clone(&self) -> Self {
+        Self {
+            cat: self.cat.clone(),
+            index: self.index,
+            type_name: self.type_name,
+            name: self.name.clone(),
+            tags: self.tags.clone(),
+            metadata: self.metadata.clone(),
+            value: Mutex::new(self.value.lock().unwrap_or_else(|e| e.into_inner()).clone()),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 #![feature(unsize)]
-#![feature(min_specialization)]
+// Unlike `min_specialization` allows specializing on the user traits, see `OpenGeneric`
+#![allow(incomplete_features)]
+#![feature(specialization)]
 
 //! Runtime dependency injection.
 //!
@@ -157,26 +159,240 @@
 //! let inst = cat.get::<OneOf<ConnectionPool>>().unwrap();
 //! assert_eq!(inst.url(), "http://foo:8080");
 //! ```
+//!
+//! ## Writing builders by hand
+//!
+//! Builders of the components that can't be declared via `#[component]` (e.g. ones reading
+//! their configuration from a service registry) can implement the [`Builder`] and
+//! [`TypedBuilder`] traits directly. These traits, together with [`AsyncTypedBuilder`],
+//! [`DependencyInfo`], [`Scope`], [`BuilderExt`] and [`ScopedInstance`], are a stable API
+//! that follows semantic versioning. Items hidden from the docs (like `dill::instrument`)
+//! only serve the generated code and might change in any release.
+//!
+//! ```
+//! use dill::*;
+//! use std::any::{type_name, TypeId};
+//!
+//! struct ServiceRegistry;
+//!
+//! impl ServiceRegistry {
+//!     fn endpoint(&self, service: &str) -> String {
+//!         format!("http://{}.internal", service)
+//!     }
+//! }
+//!
+//! struct BillingClient {
+//!     url: String,
+//! }
+//!
+//! struct BillingClientBuilder {
+//!     instance: ScopedInstance<Singleton>,
+//! }
+//!
+//! impl Builder for BillingClientBuilder {
+//!     fn instance_type_id(&self) -> TypeId {
+//!         TypeId::of::<BillingClient>()
+//!     }
+//!
+//!     fn instance_type_name(&self) -> &'static str {
+//!         type_name::<BillingClient>()
+//!     }
+//!
+//!     fn scope_name(&self) -> &'static str {
+//!         self.instance.scope_name()
+//!     }
+//!
+//!     fn scope_was_explicit(&self) -> bool {
+//!         true
+//!     }
+//!
+//!     fn is_catalog_wide(&self) -> bool {
+//!         self.instance.is_catalog_wide()
+//!     }
+//!
+//!     fn dependencies(&self) -> Vec<DependencyInfo> {
+//!         vec![DependencyInfo::of::<ServiceRegistry>(DependencyKind::One, None)]
+//!     }
+//!
+//!     fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+//!         Ok(TypedBuilder::get(self, cat)?)
+//!     }
+//!
+//!     fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+//!         self.instance.cached(cat)
+//!     }
+//!
+//!     fn invalidate(&self, cat: &Catalog) {
+//!         self.instance.invalidate(cat)
+//!     }
+//! }
+//!
+//! impl TypedBuilder<BillingClient> for BillingClientBuilder {
+//!     fn get(&self, cat: &Catalog) -> Result<Shared<BillingClient>, InjectionError> {
+//!         self.instance.get_or_build(self, cat, || {
+//!             let registry = cat.get_one::<ServiceRegistry>()?;
+//!             Ok(BillingClient {
+//!                 url: registry.endpoint("billing"),
+//!             })
+//!         })
+//!     }
+//! }
+//!
+//! let cat = CatalogBuilder::new()
+//!     .add_value(ServiceRegistry)
+//!     .add_builder(BillingClientBuilder {
+//!         instance: ScopedInstance::new(Singleton::new()),
+//!     })
+//!     .build();
+//!
+//! let client = cat.get_one::<BillingClient>().unwrap();
+//! assert_eq!(client.url, "http://billing.internal");
+//! ```
 
+#[cfg(feature = "macros")]
 pub use dill_impl::*;
 
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+pub use inventory;
+
+#[cfg(all(feature = "unsync", any(feature = "dill-axum", feature = "dill-actix")))]
+compile_error!("`unsync` feature cannot be combined with web framework integrations, which share the catalog between threads");
+
+#[cfg(all(feature = "unsync", feature = "global"))]
+compile_error!("`unsync` feature cannot be combined with `global`, which shares the catalog between threads");
+
+#[cfg(feature = "dill-actix")]
+mod actix_integration;
+#[cfg(feature = "dill-actix")]
+pub use actix_integration::*;
+
+#[cfg(feature = "auto-register")]
+mod auto_register;
+#[cfg(feature = "auto-register")]
+pub use auto_register::*;
+
+#[cfg(feature = "dill-axum")]
+mod axum_integration;
+#[cfg(feature = "dill-axum")]
+pub use axum_integration::*;
+
 mod builder;
 pub use builder::*;
 
+mod bundle;
+pub use bundle::*;
+
 mod catalog_builder;
 pub use catalog_builder::*;
 
 mod catalog;
 pub use catalog::*;
 
+#[cfg(feature = "config")]
+mod config;
+#[cfg(feature = "config")]
+pub use config::*;
+
+mod context;
+pub use context::*;
+
+mod declarative;
+pub use declarative::*;
+
 mod errors;
 pub use errors::*;
 
+mod events;
+pub use events::*;
+
+mod factory;
+pub use factory::*;
+
+#[cfg(feature = "global")]
+mod global;
+#[cfg(feature = "global")]
+pub use global::*;
+
+mod graph;
+pub use graph::*;
+
+mod health;
+pub use health::*;
+
+mod introspection;
+pub use introspection::*;
+
+#[doc(hidden)]
+pub mod instrument;
+
+mod join;
+
+mod lazy;
+pub use lazy::*;
+
+mod lifecycle;
+pub use lifecycle::*;
+
+mod metadata;
+pub use metadata::*;
+
+mod module;
+pub use module::*;
+
+mod observer;
+pub use observer::*;
+
+mod open_generic;
+pub use open_generic::*;
+
+mod override_scope;
+pub use override_scope::*;
+
+#[cfg(feature = "plugins")]
+mod plugin;
+#[cfg(feature = "plugins")]
+pub use plugin::*;
+
+mod registration;
+pub use registration::*;
+
+mod resolution_log;
+pub use resolution_log::*;
+
+mod shared;
+pub use shared::*;
+
 mod specs;
 pub use specs::*;
 
 mod scopes;
 pub use scopes::*;
 
+mod swap;
+pub(crate) use swap::*;
+
+mod sync;
+
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "testing")]
+pub use testing::*;
+
+mod thread_bound;
+pub use thread_bound::*;
+
+mod timeout;
+
+#[cfg(feature = "dill-tokio")]
+mod tokio_integration;
+#[cfg(feature = "dill-tokio")]
+pub use tokio_integration::*;
+
+mod type_map;
+
 mod typecast_builder;
 pub use typecast_builder::*;
+
+mod validation;
+pub use validation::*;
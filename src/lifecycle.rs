@@ -0,0 +1,218 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, MutexGuard, Weak,
+    },
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use crate::{BoxFuture, MaybeSendSync};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by components that need to release resources deterministically (e.g. flush
+/// buffers or close connections), see [`Catalog::shutdown()`][crate::Catalog::shutdown()].
+///
+/// Components opt in by binding to this interface, e.g. via `#[interface(dyn Shutdown)]`.
+pub trait Shutdown: MaybeSendSync {
+    fn shutdown(&self);
+}
+
+/// Asynchronous counterpart of [`Shutdown`],
+/// see [`Catalog::shutdown_async()`][crate::Catalog::shutdown_async()]
+pub trait AsyncShutdown: MaybeSendSync {
+    fn shutdown_async(&self) -> BoxFuture<'_, ()>;
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Signals the shutdown of the catalog to the components running background tasks, so that
+/// they don't have to invent their own stop signals. Components receive the token of the
+/// catalog by declaring an argument of this type, while [`Catalog::shutdown()`] cancels it
+/// before shutting down the instances.
+///
+/// Tokens form a hierarchy: cancelling a token cancels all of its children (see
+/// [`ShutdownToken::child()`]), e.g. the tokens of the child catalogs
+/// (see [`CatalogBuilder::new_chained()`]), but not the other way around.
+///
+/// [`Catalog::shutdown()`]: crate::Catalog::shutdown()
+/// [`CatalogBuilder::new_chained()`]: crate::CatalogBuilder::new_chained()
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use dill::*;
+///
+/// #[component]
+/// #[scope(Singleton)]
+/// struct Poller {
+///     worker: std::sync::Mutex<Option<std::thread::JoinHandle<u32>>>,
+/// }
+///
+/// #[component]
+/// impl Poller {
+///     pub fn new(token: ShutdownToken) -> Self {
+///         let worker = std::thread::spawn(move || {
+///             let mut polls = 0;
+///             while !token.wait_timeout(Duration::from_millis(10)) {
+///                 polls += 1;
+///             }
+///             polls
+///         });
+///         Self {
+///             worker: std::sync::Mutex::new(Some(worker)),
+///         }
+///     }
+/// }
+///
+/// let cat = CatalogBuilder::new().add::<Poller>().build();
+/// let poller = cat.get_one::<Poller>().unwrap();
+///
+/// cat.shutdown();
+/// poller.worker.lock().unwrap().take().unwrap().join().unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct ShutdownToken {
+    state: Arc<TokenState>,
+}
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    inner: Mutex<TokenInner>,
+    /// Notifies the threads blocked in [`ShutdownToken::wait_timeout()`]
+    cancelled_cond: Condvar,
+}
+
+#[derive(Default)]
+struct TokenInner {
+    /// Tasks awaiting [`ShutdownToken::cancelled()`]
+    wakers: Vec<Waker>,
+    children: Vec<Weak<TokenState>>,
+}
+
+impl ShutdownToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that is cancelled along with this one, while cancelling the child
+    /// doesn't affect this token, e.g. to stop a single task ahead of the shutdown
+    #[must_use]
+    pub fn child(&self) -> Self {
+        let child = Self::new();
+
+        let mut inner = self.state.lock();
+        if self.is_cancelled() {
+            drop(inner);
+            child.cancel();
+        } else {
+            inner.children.retain(|c| c.strong_count() != 0);
+            inner.children.push(Arc::downgrade(&child.state));
+        }
+        child
+    }
+
+    /// Cancels the token and all of its children, waking up everyone waiting for it.
+    /// Cancelling an already cancelled token does nothing.
+    pub fn cancel(&self) {
+        self.state.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns a future that completes once the token is cancelled
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Blocks the current thread until the token is cancelled or the timeout elapses,
+    /// returning whether the token is cancelled, e.g. to pace the loop of a background thread
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        // Durations too long to be represented are waited for in chunks
+        let deadline = Instant::now().checked_add(timeout);
+
+        let mut inner = self.state.lock();
+        while !self.is_cancelled() {
+            let now = Instant::now();
+            let remaining = match deadline {
+                Some(deadline) if deadline <= now => return false,
+                Some(deadline) => deadline - now,
+                None => timeout,
+            };
+            inner = self
+                .state
+                .cancelled_cond
+                .wait_timeout(inner, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        true
+    }
+}
+
+impl std::fmt::Debug for ShutdownToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShutdownToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl TokenState {
+    // A panic in a waker should not render the token unusable
+    fn lock(&self) -> MutexGuard<'_, TokenInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn cancel(&self) {
+        let mut inner = self.lock();
+        // Flag is only set under the lock, so no waiter is registered after it's taken
+        if self.cancelled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let wakers = std::mem::take(&mut inner.wakers);
+        let children = std::mem::take(&mut inner.children);
+        self.cancelled_cond.notify_all();
+        drop(inner);
+
+        for waker in wakers {
+            waker.wake();
+        }
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+}
+
+/// Future returned by [`ShutdownToken::cancelled()`]
+pub struct Cancelled {
+    state: Arc<TokenState>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.state.cancelled.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        let mut inner = self.state.lock();
+        if self.state.cancelled.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+        if !inner.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            inner.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,134 @@
+use std::any::{type_name, TypeId};
+
+use crate::type_map::TypeIdMap;
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Typed values attached to a registration via [`Registration::with_meta()`], holding at most
+/// one value of each type. Lets the consumers pick the implementations of an interface by their
+/// properties without building them, see [`Catalog::get_where()`].
+#[derive(Clone, Default)]
+pub struct Metadata {
+    values: TypeIdMap<TypeId, Shared<SharedAny>>,
+}
+
+impl Metadata {
+    /// Returns the value of the specified type, if it was attached
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .map(|v| v.downcast_ref().unwrap())
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Attaches the value, replacing the previous value of the same type
+    pub(crate) fn insert<T: 'static + MaybeSendSync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Shared::new(value));
+    }
+
+    pub(crate) fn extend(&mut self, other: &Metadata) {
+        self.values
+            .extend(other.values.iter().map(|(k, v)| (*k, v.clone())));
+    }
+}
+
+impl std::fmt::Debug for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metadata")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+impl Catalog {
+    /// Resolves the implementation of an interface whose metadata (see
+    /// [`Registration::with_meta()`]) matches the predicate, e.g. to dispatch on the content
+    /// type without a separate registry component. Implementations that don't match are not
+    /// built.
+    ///
+    /// Among several matching implementations the one with the highest priority is chosen,
+    /// same as with [`OneOf`], reporting [`InjectionError::Ambiguous`] if several share it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// trait Exporter: Send + Sync {
+    ///     fn export(&self) -> String;
+    /// }
+    ///
+    /// #[derive(PartialEq)]
+    /// struct Format(&'static str);
+    ///
+    /// #[component]
+    /// #[interface(dyn Exporter)]
+    /// struct CsvExporter;
+    /// impl Exporter for CsvExporter {
+    ///     fn export(&self) -> String {
+    ///         "a,b".to_owned()
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Exporter)]
+    /// struct JsonExporter;
+    /// impl Exporter for JsonExporter {
+    ///     fn export(&self) -> String {
+    ///         "[\"a\",\"b\"]".to_owned()
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<CsvExporter>()
+    ///     .with_meta(Format("csv"))
+    ///     .add::<JsonExporter>()
+    ///     .with_meta(Format("json"))
+    ///     .build();
+    ///
+    /// let exporter = cat
+    ///     .get_where::<dyn Exporter>(|m| m.get::<Format>() == Some(&Format("csv")))
+    ///     .unwrap();
+    /// assert_eq!(exporter.export(), "a,b");
+    /// ```
+    pub fn get_where<Iface>(
+        &self,
+        pred: impl Fn(&Metadata) -> bool,
+    ) -> Result<Shared<Iface>, InjectionError>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        let mut matching = self.builders_for::<Iface>().filter(|b| pred(b.metadata()));
+        let first = match matching.next() {
+            Some(first) => first,
+            None => return Err(self.unregistered::<Iface>()),
+        };
+
+        // Bindings are ordered by priority, so the ties follow the first match
+        let tied: Vec<_> = matching
+            .take_while(|b| b.priority() == first.priority())
+            .map(|b| b.instance_type_name())
+            .collect();
+        if !tied.is_empty() {
+            let mut candidates = vec![first.instance_type_name()];
+            candidates.extend(tied);
+            return Err(InjectionError::ambiguous_type(
+                TypeId::of::<Iface>(),
+                type_name::<Iface>(),
+                candidates,
+            ));
+        }
+
+        first.get(self)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{CatalogBuilder, DependencyInfo};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// A reusable bundle of registrations, e.g. shipped by a library crate to wire up its
+/// components, see [`CatalogBuilder::add_module()`][crate::CatalogBuilder::add_module()].
+pub trait Module {
+    /// Registers the components of the module
+    fn load(&self, b: &mut CatalogBuilder);
+
+    /// Dependencies that the module expects to be registered by the application or by other modules,
+    /// which [`CatalogBuilder::validate()`][crate::CatalogBuilder::validate()] reports as required
+    /// by the module when missing
+    fn requires(&self) -> Vec<DependencyInfo> {
+        Vec::new()
+    }
+
+    /// Name of the module used in diagnostics
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
@@ -0,0 +1,55 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{Builder, InjectionError, MaybeSendSync};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Receives notifications about the dependency resolution, e.g. to count instance constructions,
+/// scope cache hits and injection failures in metrics, see [`CatalogBuilder::with_observer()`][crate::CatalogBuilder::with_observer()].
+///
+/// Resolutions are identified by the type name of their [`DependencySpec`][crate::DependencySpec],
+/// e.g. `dill::specs::OneOf<dyn app::Db>`. Callbacks are invoked synchronously on the resolving
+/// thread, so they should be cheap. All callbacks do nothing by default.
+pub trait ResolutionObserver: MaybeSendSync {
+    /// Called before resolving a dependency, including the nested dependencies of the components
+    fn on_resolve_start(&self, _spec: &'static str) {}
+
+    /// Called after resolving a dependency, whether it succeeded or not
+    fn on_resolve_end(&self, _spec: &'static str, _elapsed: Duration) {}
+
+    /// Called after the builder constructed a new instance
+    fn on_build(&self, _builder: &dyn Builder, _elapsed: Duration) {}
+
+    /// Called when the scope of the builder returned the cached instance without constructing it
+    fn on_cache_hit(&self, _builder: &dyn Builder) {}
+
+    /// Called when a top-level resolution fails. Failures of the nested dependencies are only
+    /// reported once, along with the top-level dependency that required them.
+    fn on_error(&self, _spec: &'static str, _error: &InjectionError) {}
+}
+
+/// Allows to keep a handle of the observer, e.g. to read the collected metrics
+impl<T> ResolutionObserver for Arc<T>
+where
+    T: ResolutionObserver + ?Sized,
+{
+    fn on_resolve_start(&self, spec: &'static str) {
+        self.as_ref().on_resolve_start(spec)
+    }
+
+    fn on_resolve_end(&self, spec: &'static str, elapsed: Duration) {
+        self.as_ref().on_resolve_end(spec, elapsed)
+    }
+
+    fn on_build(&self, builder: &dyn Builder, elapsed: Duration) {
+        self.as_ref().on_build(builder, elapsed)
+    }
+
+    fn on_cache_hit(&self, builder: &dyn Builder) {
+        self.as_ref().on_cache_hit(builder)
+    }
+
+    fn on_error(&self, spec: &'static str, error: &InjectionError) {
+        self.as_ref().on_error(spec, error)
+    }
+}
@@ -0,0 +1,82 @@
+use std::marker::Unsize;
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Maps every instantiation of a generic interface to the instantiation of its generic
+/// implementation, so that e.g. `OneOf<dyn Repository<User>>` constructs `PgRepository<User>`
+/// on demand instead of requiring a registration per type argument.
+///
+/// The implementation is instantiated when resolving [`OneOf`] or [`Maybe`] of an interface
+/// that has no bindings, before consulting the handlers of [`CatalogBuilder::on_unresolved()`],
+/// so the implementations bound explicitly (e.g. a `MockRepository<User>`) take precedence.
+/// Its builder is then kept by the catalog, so the scope of the component is respected, e.g.
+/// a [`Singleton`] instantiation is only constructed once.
+///
+/// The catalog doesn't know the instantiations until they are resolved, so they are not listed
+/// by [`Catalog::builders()`] and not resolved by [`AllOf`], while [`CatalogBuilder::validate()`]
+/// reports the components depending on them as having unregistered dependencies.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+/// use std::any::type_name;
+/// use std::marker::PhantomData;
+///
+/// trait Repository<T>: Send + Sync {
+///     fn table(&self) -> &'static str;
+/// }
+///
+/// struct User;
+/// struct Order;
+///
+/// #[component]
+/// #[scope(Singleton)]
+/// struct PgRepository<T: Send + Sync> {
+///     #[dill(skip)]
+///     _entity: PhantomData<T>,
+/// }
+///
+/// impl<T: Send + Sync> Repository<T> for PgRepository<T> {
+///     fn table(&self) -> &'static str {
+///         type_name::<T>()
+///     }
+/// }
+///
+/// impl<T: Send + Sync + 'static> OpenGeneric for dyn Repository<T> {
+///     type Impl = PgRepository<T>;
+/// }
+///
+/// let cat = CatalogBuilder::new().build();
+///
+/// let users = cat.get_one::<dyn Repository<User>>().unwrap();
+/// assert_eq!(users.table(), type_name::<User>());
+/// assert_eq!(cat.get_one::<dyn Repository<Order>>().unwrap().table(), type_name::<Order>());
+/// assert!(std::sync::Arc::ptr_eq(&users, &cat.get_one::<dyn Repository<User>>().unwrap()));
+/// ```
+pub trait OpenGeneric {
+    type Impl: 'static + BuilderLike + MaybeSendSync + Unsize<Self>;
+}
+
+/// Looks the implementation of the interface up via [`OpenGeneric`], if the interface has one
+pub(crate) trait ResolveOpenGeneric {
+    fn open_generic(cat: &Catalog) -> Option<Binding>;
+}
+
+impl<Iface: 'static + ?Sized> ResolveOpenGeneric for Iface {
+    default fn open_generic(_cat: &Catalog) -> Option<Binding> {
+        None
+    }
+}
+
+impl<Iface> ResolveOpenGeneric for Iface
+where
+    Iface: 'static + ?Sized + OpenGeneric,
+    <Iface::Impl as BuilderLike>::Builder: 'static,
+{
+    fn open_generic(cat: &Catalog) -> Option<Binding> {
+        Some(cat.open_generic_binding::<Iface, Iface::Impl>())
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+thread_local! {
+    /// Overrides of the current thread, from the outermost guard to the innermost
+    static OVERRIDES: RefCell<Vec<OverrideEntry>> = RefCell::new(Vec::new());
+}
+
+/// Number of overrides across all threads, sparing the resolutions a thread local lookup
+/// when there are none
+static ACTIVE_OVERRIDES: AtomicUsize = AtomicUsize::new(0);
+
+struct OverrideEntry {
+    guard_id: usize,
+    catalog_id: usize,
+    iface: TypeId,
+    /// Holds `Shared<Iface>`, as `dyn Any` can't be cast to a trait object
+    inst: Shared<SharedAny>,
+}
+
+impl Catalog {
+    /// Starts a set of overrides layered on top of this catalog until the returned guard is
+    /// dropped, e.g. to customize a fixture catalog shared by the tests. Overrides of the nested
+    /// guards shadow the ones of the outer guards.
+    ///
+    /// Overrides apply to the resolutions of [`OneOf`] and [`Maybe<OneOf>`] made on the current
+    /// thread, so the tests running in parallel don't affect each other. Components of the parent
+    /// catalogs (see [`CatalogBuilder::new_chained()`]) keep their dependencies, while the
+    /// instances cached by the scopes (e.g. [`Singleton`]s) are not rebuilt.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    /// use std::sync::Arc;
+    ///
+    /// trait Clock: Send + Sync {
+    ///     fn now(&self) -> u64;
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Clock)]
+    /// struct SystemClock;
+    /// impl Clock for SystemClock {
+    ///     fn now(&self) -> u64 {
+    ///         1_700_000_000
+    ///     }
+    /// }
+    ///
+    /// struct FakeClock(u64);
+    /// impl Clock for FakeClock {
+    ///     fn now(&self) -> u64 {
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add::<SystemClock>().build();
+    ///
+    /// {
+    ///     let _guard = cat.override_scope().bind::<dyn Clock>(Arc::new(FakeClock(42)));
+    ///     assert_eq!(cat.get_one::<dyn Clock>().unwrap().now(), 42);
+    /// }
+    ///
+    /// assert_eq!(cat.get_one::<dyn Clock>().unwrap().now(), 1_700_000_000);
+    /// ```
+    pub fn override_scope(&self) -> OverrideScope {
+        static NEXT_GUARD_ID: AtomicUsize = AtomicUsize::new(0);
+        OverrideScope {
+            guard_id: NEXT_GUARD_ID.fetch_add(1, Ordering::Relaxed),
+            catalog_id: self.id(),
+            count: 0,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the innermost override of the interface on the current thread,
+    /// see [`Catalog::override_scope()`]
+    pub(crate) fn overridden<Iface>(&self) -> Option<Shared<Iface>>
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        if ACTIVE_OVERRIDES.load(Ordering::Acquire) == 0 {
+            return None;
+        }
+
+        let catalog_id = self.id();
+        let iface = TypeId::of::<Iface>();
+        OVERRIDES.with(|overrides| {
+            overrides
+                .borrow()
+                .iter()
+                .rev()
+                .find(|e| e.catalog_id == catalog_id && e.iface == iface)
+                .map(|e| {
+                    let inst: Shared<Shared<Iface>> = e.inst.clone().downcast().unwrap();
+                    inst.as_ref().clone()
+                })
+        })
+    }
+}
+
+/// Removes the overrides layered on top of the catalog when dropped,
+/// see [`Catalog::override_scope()`]
+#[must_use = "Overrides are removed as soon as the guard is dropped"]
+pub struct OverrideScope {
+    guard_id: usize,
+    catalog_id: usize,
+    /// Number of overrides added by this guard
+    count: usize,
+    // Has to be dropped on the thread that created it
+    _not_send: PhantomData<*const ()>,
+}
+
+impl OverrideScope {
+    /// Makes the interface resolve to the instance until the guard is dropped
+    pub fn bind<Iface>(mut self, inst: Shared<Iface>) -> Self
+    where
+        Iface: 'static + ?Sized + MaybeSendSync,
+    {
+        OVERRIDES.with(|overrides| {
+            overrides.borrow_mut().push(OverrideEntry {
+                guard_id: self.guard_id,
+                catalog_id: self.catalog_id,
+                iface: TypeId::of::<Iface>(),
+                inst: Shared::new(inst),
+            })
+        });
+        ACTIVE_OVERRIDES.fetch_add(1, Ordering::AcqRel);
+        self.count += 1;
+        self
+    }
+
+    /// A short-hand for `bind::<T>(Arc::new(value))`
+    pub fn bind_value<T>(self, value: T) -> Self
+    where
+        T: 'static + MaybeSendSync,
+    {
+        self.bind::<T>(Shared::new(value))
+    }
+}
+
+impl Drop for OverrideScope {
+    fn drop(&mut self) {
+        // Guards might be dropped out of order, e.g. when moved into a longer-lived binding
+        let guard_id = self.guard_id;
+        OVERRIDES.with(|overrides| overrides.borrow_mut().retain(|e| e.guard_id != guard_id));
+        ACTIVE_OVERRIDES.fetch_sub(self.count, Ordering::AcqRel);
+    }
+}
@@ -0,0 +1,182 @@
+use std::{ffi::c_void, path::Path};
+
+use libloading::{Library, Symbol};
+use thiserror::Error;
+
+use crate::CatalogBuilder;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Version of the layout of [`PluginDeclaration`], checked before reading the rest of it
+#[doc(hidden)]
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Build of `dill` the plugin is linked with, see [`export_plugin!`]. Includes the features
+/// that change the layout of the types of `dill` (e.g. `0.3.0+sync.config`) and the version of
+/// the compiler, as the registration passes the Rust types across the library boundary.
+#[doc(hidden)]
+pub const PLUGIN_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    "+",
+    env!("DILL_LAYOUT_FEATURES"),
+    " (",
+    env!("DILL_RUSTC_VERSION"),
+    ")"
+);
+
+const DECLARATION_SYMBOL: &[u8] = b"dill_plugin_declaration\0";
+
+/// Describes the plugin to the loader, returned by the `extern "C"` entry point declared via
+/// [`export_plugin!`]. Only consists of C types, so that the loader can reject a plugin built
+/// by another compiler or against another version of `dill` before any Rust types cross
+/// the library boundary.
+#[doc(hidden)]
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// See [`PLUGIN_ABI_VERSION`], has to stay the first field
+    pub abi_version: u32,
+    /// UTF-8 bytes of [`PLUGIN_VERSION`]
+    pub version: *const u8,
+    pub version_len: usize,
+    /// Registers the components in the `CatalogBuilder` passed by pointer, returns `false`
+    /// if the registration panicked
+    pub register: unsafe extern "C" fn(builder: *mut c_void) -> bool,
+}
+
+// Only points to the static data of the plugin
+unsafe impl Sync for PluginDeclaration {}
+
+/// Exports the `extern "C"` entry point of a `cdylib` plugin that is loaded via
+/// [`CatalogBuilder::load_plugin()`], calling the `register(builder: &mut CatalogBuilder)`
+/// function to register the components of the plugin.
+///
+/// # Examples
+///
+/// ```ignore
+/// use dill::*;
+///
+/// #[component]
+/// #[interface(dyn Exporter)]
+/// struct ParquetExporter;
+///
+/// fn register(b: &mut CatalogBuilder) {
+///     b.add::<ParquetExporter>();
+/// }
+///
+/// dill::export_plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    ($register:path) => {
+        #[no_mangle]
+        #[doc(hidden)]
+        pub extern "C" fn dill_plugin_declaration() -> *const $crate::PluginDeclaration {
+            unsafe extern "C" fn __dill_register(builder: *mut ::std::ffi::c_void) -> bool {
+                let builder = &mut *(builder as *mut $crate::CatalogBuilder);
+
+                // Unwinding across the `extern "C"` boundary is undefined behavior
+                ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| $register(builder)))
+                    .is_ok()
+            }
+
+            static DECLARATION: $crate::PluginDeclaration = $crate::PluginDeclaration {
+                abi_version: $crate::PLUGIN_ABI_VERSION,
+                version: $crate::PLUGIN_VERSION.as_ptr(),
+                version_len: $crate::PLUGIN_VERSION.len(),
+                register: __dill_register,
+            };
+            &DECLARATION
+        }
+    };
+}
+
+impl CatalogBuilder {
+    /// Loads a shared library exporting the entry point declared via [`export_plugin!`]
+    /// and lets it register its components in this builder.
+    ///
+    /// Plugins stay loaded until the process exits once their registration starts, even if it
+    /// panics, as the registered builders and the instances they create reference the code of
+    /// the library.
+    ///
+    /// The entry point only exchanges C types, so a plugin built by another compiler or
+    /// against another version of `dill` (or with different `unsync`, `config` or `parking-lot`
+    /// features) is rejected with [`PluginError::IncompatibleVersion`], and a panic during
+    /// the registration with [`PluginError::RegistrationPanicked`] (keeping the components
+    /// registered before the panic).
+    ///
+    /// # Safety
+    ///
+    /// Rust has no stable ABI, so beyond what is checked the plugin has to be built against
+    /// the same versions of the crates whose types cross the boundary (e.g. the interfaces
+    /// the plugin implements) as the application. Loading a library also runs its
+    /// initialization routines.
+    pub unsafe fn load_plugin<P>(&mut self, path: P) -> Result<&mut Self, PluginError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let display = path.display().to_string();
+
+        let lib = Library::new(path).map_err(|e| PluginError::Load {
+            path: display.clone(),
+            message: e.to_string(),
+        })?;
+
+        let missing = |e: libloading::Error| PluginError::MissingEntryPoint {
+            path: display.clone(),
+            message: e.to_string(),
+        };
+
+        let register = {
+            let declare: Symbol<unsafe extern "C" fn() -> *const PluginDeclaration> =
+                lib.get(DECLARATION_SYMBOL).map_err(missing)?;
+            let declaration = &*declare();
+
+            if declaration.abi_version != PLUGIN_ABI_VERSION {
+                return Err(PluginError::IncompatibleVersion {
+                    path: display,
+                    version: format!("ABI v{}", declaration.abi_version),
+                    expected: PLUGIN_VERSION,
+                });
+            }
+
+            let version = std::slice::from_raw_parts(declaration.version, declaration.version_len);
+            if version != PLUGIN_VERSION.as_bytes() {
+                return Err(PluginError::IncompatibleVersion {
+                    path: display,
+                    version: String::from_utf8_lossy(version).into_owned(),
+                    expected: PLUGIN_VERSION,
+                });
+            }
+
+            declaration.register
+        };
+
+        // Builders registered before a panic reference the code of the library as well
+        std::mem::forget(lib);
+
+        if !register(self as *mut CatalogBuilder as *mut c_void) {
+            return Err(PluginError::RegistrationPanicked { path: display });
+        }
+        Ok(self)
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Error of loading a plugin, see [`CatalogBuilder::load_plugin()`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PluginError {
+    #[error("Failed to load plugin {path}: {message}")]
+    Load { path: String, message: String },
+    #[error("Library {path} is not a dill plugin: {message}")]
+    MissingEntryPoint { path: String, message: String },
+    #[error("Plugin {path} is built against dill {version} while {expected} is expected")]
+    IncompatibleVersion {
+        path: String,
+        version: String,
+        expected: &'static str,
+    },
+    #[error("Plugin {path} panicked while registering its components")]
+    RegistrationPanicked { path: String },
+}
@@ -0,0 +1,114 @@
+use std::{
+    any::TypeId,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Handle to a component registered via [`CatalogBuilder::add()`] that allows to parametrize
+/// its builder without an intermediate variable, e.g. via the `with_<arg>` setters that
+/// `#[component]` also implements for the handle (in a `<Type>Registration` trait generated
+/// next to the component).
+///
+/// The handle dereferences into the [`CatalogBuilder`] to continue the chain of registrations.
+/// Modified builder replaces the default one once the handle is used mutably or dropped.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[component]
+/// struct HttpServer {
+///     port: u16,
+/// }
+///
+/// let cat = CatalogBuilder::new()
+///     .add::<HttpServer>()
+///     .with_port(8080)
+///     .build();
+///
+/// assert_eq!(cat.get_one::<HttpServer>().unwrap().port, 8080);
+/// ```
+pub struct Registration<'a, Impl>
+where
+    Impl: BuilderLike + 'static,
+    Impl::Builder: 'static,
+{
+    cat: &'a mut CatalogBuilder,
+    builder: Option<Impl::Builder>,
+}
+
+impl<'a, Impl> Registration<'a, Impl>
+where
+    Impl: BuilderLike + 'static,
+    Impl::Builder: 'static,
+{
+    pub(crate) fn new(cat: &'a mut CatalogBuilder) -> Self {
+        Self { cat, builder: None }
+    }
+
+    /// Parametrizes the builder of the component, starting from the default one
+    #[must_use]
+    pub fn configure(mut self, fun: impl FnOnce(Impl::Builder) -> Impl::Builder) -> Self {
+        let builder = self.builder.take().unwrap_or_else(Impl::builder);
+        self.builder = Some(fun(builder));
+        self
+    }
+
+    /// Attaches a typed value to the component, e.g. the format an exporter produces, that
+    /// the consumers can select the implementations of an interface by without building them,
+    /// see [`Catalog::get_where()`]. Attaching another value of the same type replaces it.
+    #[must_use]
+    pub fn with_meta<T>(self, value: T) -> Self
+    where
+        T: 'static + MaybeSendSync,
+    {
+        self.cat
+            .insert_metadata(ImplTypeId(TypeId::of::<Impl>()), value);
+        self
+    }
+
+    fn commit(&mut self) {
+        if let Some(builder) = self.builder.take() {
+            self.cat
+                .replace_builder(ImplTypeId(TypeId::of::<Impl>()), Arc::new(builder));
+        }
+    }
+}
+
+impl<'a, Impl> Deref for Registration<'a, Impl>
+where
+    Impl: BuilderLike + 'static,
+    Impl::Builder: 'static,
+{
+    type Target = CatalogBuilder;
+
+    fn deref(&self) -> &CatalogBuilder {
+        self.cat
+    }
+}
+
+impl<'a, Impl> DerefMut for Registration<'a, Impl>
+where
+    Impl: BuilderLike + 'static,
+    Impl::Builder: 'static,
+{
+    fn deref_mut(&mut self) -> &mut CatalogBuilder {
+        self.commit();
+        self.cat
+    }
+}
+
+impl<'a, Impl> Drop for Registration<'a, Impl>
+where
+    Impl: BuilderLike + 'static,
+    Impl::Builder: 'static,
+{
+    fn drop(&mut self) {
+        self.commit();
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+use crate::sync::{lock, Mutex};
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Resolution recorded by the catalog, see [`Catalog::recent_resolutions()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolutionRecord {
+    /// Type name of the resolved [`DependencySpec`], e.g. `dill::specs::OneOf<dyn app::Db>`
+    pub spec: &'static str,
+    pub elapsed: Duration,
+    /// `None` if the resolution succeeded
+    pub error: Option<InjectionError>,
+    /// Components whose construction required the dependency, from the outermost to the
+    /// innermost, empty for the top-level resolutions
+    pub required_by: Vec<&'static str>,
+}
+
+impl ResolutionRecord {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Bounded log of the latest resolutions, see [`CatalogBuilder::record_resolutions()`]
+pub(crate) struct ResolutionLog {
+    capacity: usize,
+    records: Mutex<VecDeque<ResolutionRecord>>,
+}
+
+impl ResolutionLog {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub(crate) fn record(&self, record: ResolutionRecord) {
+        let mut records = lock(&self.records);
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+impl CatalogBuilder {
+    /// Keeps the last `capacity` resolutions of the catalog in memory, including the nested
+    /// ones, along with their outcomes, durations and the components that required them
+    /// (see [`Catalog::recent_resolutions()`]), e.g. to attach the resolution history to the
+    /// crash report of a rare injection failure without enabling the tracing globally.
+    ///
+    /// The log is shared by the snapshots of the catalog and by the catalogs derived from it
+    /// via [`Catalog::builder()`].
+    ///
+    /// # Panics
+    ///
+    /// If the capacity is zero.
+    pub fn record_resolutions(&mut self, capacity: usize) -> &mut Self {
+        if capacity == 0 {
+            panic!("Capacity of the resolution log has to be positive");
+        }
+        self.registry.resolution_log = Some(Arc::new(ResolutionLog::new(capacity)));
+        self
+    }
+}
+
+impl Catalog {
+    /// Returns the resolutions recorded since the catalog was built, from the oldest to the
+    /// latest, limited to the capacity set via [`CatalogBuilder::record_resolutions()`].
+    /// Returns nothing if the resolutions are not recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use dill::*;
+    ///
+    /// trait Db: Send + Sync {}
+    ///
+    /// #[component]
+    /// struct Service {
+    ///     db: Arc<dyn Db>,
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new()
+    ///     .add::<Service>()
+    ///     .record_resolutions(16)
+    ///     .build();
+    ///
+    /// assert!(cat.get_one::<Service>().is_err());
+    ///
+    /// let history = cat.recent_resolutions();
+    /// assert_eq!(history.len(), 2);
+    /// assert_eq!(history[0].spec, std::any::type_name::<OneOf<dyn Db>>());
+    /// assert_eq!(history[0].required_by, [std::any::type_name::<Service>()]);
+    /// assert!(history.iter().all(|r| !r.is_ok()));
+    /// ```
+    pub fn recent_resolutions(&self) -> Vec<ResolutionRecord> {
+        match self.resolution_log() {
+            None => Vec::new(),
+            Some(log) => lock(&log.records).iter().cloned().collect(),
+        }
+    }
+}
@@ -1,14 +1,565 @@
 use std::{
-    any::Any,
-    sync::{Arc, Mutex},
+    cell::RefCell,
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
+use arc_swap::{ArcSwap, ArcSwapOption};
+
+use crate::sync::{lock, Mutex};
+use crate::{same_instance, Catalog, InjectionError, MaybeSendSync, Shared, SharedAny, WeakShared};
+
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Controls the lifetime of an instance created by [`Builders`][`crate::Builder`]
-pub trait Scope {
-    fn get(&self) -> Option<Arc<dyn Any + Send + Sync>>;
-    fn set(&self, inst: Arc<dyn Any + Send + Sync>);
+///
+/// Custom scopes (e.g. caching an instance per tenant) are selected via `#[scope(MyScope)]`
+/// the same way as the built-in ones, which requires them to be `Send + Sync` (unless the
+/// `unsync` feature is enabled) and to have a `new()` constructor. Every builder owns a separate
+/// instance of its scope, so the scope only ever holds the instances of a single type.
+///
+/// All methods receive the handle of the catalog that the instance is resolved from, which
+/// allows the scope to tell apart the catalogs (see [`Catalog::snapshot()`]) and the logical
+/// scopes (see [`Catalog::enter_scope()`]), or to resolve the context (like the current tenant)
+/// from it.
+pub trait Scope: MaybeSendSync {
+    /// Returns the cached instance without constructing a new one
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>>;
+
+    /// Caches the newly constructed instance
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>);
+
+    /// Returns the cached instance or creates a new one using `init` function.
+    ///
+    /// The default implementation is a combination of [`Scope::get()`] and [`Scope::set()`],
+    /// so concurrent callers may all end up invoking `init`. Caching scopes should override it
+    /// to guarantee that the instance is constructed exactly once.
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        if let Some(inst) = self.get(cat) {
+            return Ok(inst);
+        }
+
+        let inst = init()?;
+
+        self.set(cat, inst.clone());
+        Ok(inst)
+    }
+
+    /// Caches the instance constructed outside of [`Scope::get_or_init()`] unless another one
+    /// was cached meanwhile, returning the instance that ends up cached.
+    ///
+    /// Used by the asynchronous resolution, which can't hold the construction lock of the scope
+    /// across the `.await` points: concurrent callers may all construct an instance, but they
+    /// all get the one that wins, same as the synchronous ones resolving it meanwhile.
+    fn set_if_absent(
+        &self,
+        cat: &Catalog,
+        inst: Shared<SharedAny>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        self.get_or_init(cat, &|| Ok(inst.clone()))
+    }
+
+    /// Drops the cached instance so that the next resolution constructs a new one,
+    /// see [`Catalog::invalidate()`]. Non-caching scopes do nothing.
+    fn invalidate(&self, _cat: &Catalog) {}
+
+    /// Whether the scope keeps a single instance for the entire [`Catalog`], e.g. [`Singleton`],
+    /// so that the instance can be constructed upfront, see [`Catalog::warm_up_parallel()`].
+    /// Defaults to `false`.
+    fn is_catalog_wide(&self) -> bool {
+        false
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// ScopeCache
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Limits on the instances of [`Cached`], [`Keyed`] and [`Pooled`] scopes that the [`ScopeCache`]
+/// of a catalog keeps, see [`CatalogBuilder::max_cached_instances()`]
+///
+/// [`CatalogBuilder::max_cached_instances()`]: crate::CatalogBuilder::max_cached_instances()
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct CacheLimits {
+    pub max_instances: Option<usize>,
+    /// In bytes, see [`CatalogBuilder::cache_memory_budget()`]
+    ///
+    /// [`CatalogBuilder::cache_memory_budget()`]: crate::CatalogBuilder::cache_memory_budget()
+    pub memory_budget: Option<usize>,
+}
+
+impl CacheLimits {
+    fn is_set(&self) -> bool {
+        self.max_instances.is_some() || self.memory_budget.is_some()
+    }
+
+    fn exceeded(&self, count: usize, size: usize) -> bool {
+        self.max_instances.map_or(false, |max| count > max)
+            || self.memory_budget.map_or(false, |budget| size > budget)
+    }
+}
+
+/// Uniquely identifies an instance of a caching scope.
+///
+/// Besides the counter the key holds the address of the counter itself, as the scopes created
+/// by a plugin (see `CatalogBuilder::load_plugin()`) are numbered by its own copy of `dill`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub(crate) struct ScopeKey {
+    origin: usize,
+    index: usize,
+}
+
+impl ScopeKey {
+    fn new_unique() -> Self {
+        static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+        Self {
+            origin: &NEXT_KEY as *const AtomicUsize as usize,
+            index: NEXT_KEY.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Holds instances cached by scopes. Every [`Catalog`] has its own cache so that catalogs sharing
+/// same builders (see [`Catalog::snapshot()`]) don't share instances.
+///
+/// Every scope key gets its own slot with a separate lock, so that constructing an instance
+/// does not block resolution of the other scopes (including its own dependencies).
+/// Locks are only taken when a slot is created or its instance is being constructed - reading
+/// an already cached instance is lock-free. Adding a slot copies the map of slots, so the slots of
+/// the evicted instances are removed to keep it within the [`CacheLimits`] (plus the slots of
+/// the instances that are never evicted, e.g. the [`Singleton`]s).
+pub(crate) struct ScopeCache {
+    /// Copy-on-write map of slots, replaced entirely when a new slot is added
+    slots: ArcSwap<HashMap<ScopeKey, Arc<ScopeSlot>>>,
+    /// Serializes addition of the new slots
+    slots_lock: Mutex<()>,
+    limits: CacheLimits,
+    /// Sizes of the cached instances that can be evicted when the limits are exceeded
+    evictable: Mutex<HashMap<ScopeKey, usize>>,
+    /// Orders the accesses to the slots, only advanced when the limits are set
+    clock: AtomicUsize,
+}
+
+struct ScopeSlot {
+    instance: ArcSwapOption<CachedInstance>,
+    /// Held while constructing the instance, making concurrent callers wait for the result
+    init_lock: Mutex<()>,
+    /// Tick of the [`ScopeCache`] clock when the instance was last accessed
+    last_used: AtomicUsize,
+}
+
+impl ScopeSlot {
+    fn new() -> Self {
+        Self {
+            instance: ArcSwapOption::empty(),
+            init_lock: Mutex::new(()),
+            last_used: AtomicUsize::new(0),
+        }
+    }
+
+    fn get(&self) -> Option<Shared<SharedAny>> {
+        Option::as_ref(&self.instance.load()).and_then(|c| c.upgrade())
+    }
+}
+
+/// Instance held in a slot of the [`ScopeCache`]
+enum CachedInstance {
+    Strong(Shared<SharedAny>),
+    /// Doesn't keep the instance alive, see [`WeakSingleton`]
+    Weak(WeakShared<SharedAny>),
+    /// Kept until the specified time, see [`Cached`]
+    Expiring(Shared<SharedAny>, Instant),
+    /// Kept until evicted to stay within the [`CacheLimits`], see [`Keyed`] and [`Pooled`]
+    Evictable(Shared<SharedAny>),
+}
+
+impl CachedInstance {
+    fn strong(inst: &Shared<SharedAny>) -> Self {
+        Self::Strong(inst.clone())
+    }
+
+    fn weak(inst: &Shared<SharedAny>) -> Self {
+        Self::Weak(Shared::downgrade(inst))
+    }
+
+    fn evictable(inst: &Shared<SharedAny>) -> Self {
+        Self::Evictable(inst.clone())
+    }
+
+    fn expiring(inst: &Shared<SharedAny>, ttl: Duration) -> Self {
+        // Durations too long to be represented never expire
+        match Instant::now().checked_add(ttl) {
+            Some(expires_at) => Self::Expiring(inst.clone(), expires_at),
+            None => Self::Evictable(inst.clone()),
+        }
+    }
+
+    fn upgrade(&self) -> Option<Shared<SharedAny>> {
+        match self {
+            Self::Strong(inst) | Self::Evictable(inst) => Some(inst.clone()),
+            Self::Weak(inst) => inst.upgrade(),
+            Self::Expiring(inst, expires_at) if Instant::now() < *expires_at => Some(inst.clone()),
+            Self::Expiring(..) => None,
+        }
+    }
+
+    /// Approximate size of the instance if it can be evicted, which doesn't account for
+    /// the memory the instance owns on the heap
+    fn evictable_size(&self) -> Option<usize> {
+        match self {
+            Self::Expiring(inst, _) | Self::Evictable(inst) => Some(std::mem::size_of_val(&**inst)),
+            Self::Strong(_) | Self::Weak(_) => None,
+        }
+    }
+}
+
+impl ScopeCache {
+    pub(crate) fn new() -> Self {
+        Self::with_limits(CacheLimits::default())
+    }
+
+    pub(crate) fn with_limits(limits: CacheLimits) -> Self {
+        Self {
+            slots: ArcSwap::from_pointee(HashMap::new()),
+            slots_lock: Mutex::new(()),
+            limits,
+            evictable: Mutex::new(HashMap::new()),
+            clock: AtomicUsize::new(0),
+        }
+    }
+
+    fn slot(&self, key: ScopeKey) -> Arc<ScopeSlot> {
+        if let Some(slot) = self.slots.load().get(&key) {
+            return slot.clone();
+        }
+
+        let _guard = lock(&self.slots_lock);
+
+        // Slot might've been added while waiting for the lock
+        let slots = self.slots.load_full();
+        if let Some(slot) = slots.get(&key) {
+            return slot.clone();
+        }
+
+        let slot = Arc::new(ScopeSlot::new());
+        let mut slots = slots.as_ref().clone();
+        slots.insert(key, slot.clone());
+        self.slots.store(Arc::new(slots));
+        slot
+    }
+
+    fn get(&self, key: ScopeKey) -> Option<Shared<SharedAny>> {
+        self.slots
+            .load()
+            .get(&key)
+            .and_then(|slot| self.touch(slot).get())
+    }
+
+    /// Whether the key has a slot, i.e. it wasn't evicted since the slot was created
+    fn contains(&self, key: ScopeKey) -> bool {
+        self.slots.load().contains_key(&key)
+    }
+
+    /// Removes the slots of the evicted instances. A concurrent caller that got hold of the slot
+    /// before might still cache a new instance in it, which then is only dropped along with the
+    /// slot, same as if it was evicted right away.
+    fn prune(&self, keys: &[ScopeKey]) {
+        let _guard = lock(&self.slots_lock);
+
+        let mut slots = self.slots.load_full().as_ref().clone();
+        for key in keys {
+            slots.remove(key);
+        }
+        self.slots.store(Arc::new(slots));
+    }
+
+    fn set(&self, key: ScopeKey, inst: CachedInstance) {
+        self.store(key, &self.slot(key), inst);
+    }
+
+    // Instance that is being constructed concurrently will still be cached
+    fn remove(&self, key: ScopeKey) {
+        if let Some(slot) = self.slots.load().get(&key) {
+            slot.instance.store(None);
+        }
+        if self.limits.is_set() {
+            lock(&self.evictable).remove(&key);
+        }
+    }
+
+    /// Drops all cached instances, see [`Catalog::scope()`]
+    pub(crate) fn clear(&self) {
+        for slot in self.slots.load().values() {
+            slot.instance.store(None);
+        }
+        lock(&self.evictable).clear();
+    }
+
+    /// Marks the instance of the slot as the most recently used one
+    fn touch<'a>(&self, slot: &'a ScopeSlot) -> &'a ScopeSlot {
+        if self.limits.is_set() {
+            let tick = self.clock.fetch_add(1, Ordering::Relaxed);
+            slot.last_used.store(tick, Ordering::Relaxed);
+        }
+        slot
+    }
+
+    fn store(&self, key: ScopeKey, slot: &ScopeSlot, inst: CachedInstance) {
+        let size = inst.evictable_size().filter(|_| self.limits.is_set());
+        slot.instance.store(Some(Arc::new(inst)));
+
+        if let Some(size) = size {
+            self.touch(slot);
+            self.evict_over_limits(key, size);
+        }
+    }
+
+    /// Drops the least recently used instances until the evictable ones fit the limits, except
+    /// for the `added` one, and removes their slots. Instances referenced outside of the cache are
+    /// kept, as evicting them wouldn't free any memory.
+    fn evict_over_limits(&self, added: ScopeKey, size: usize) {
+        let slots = self.slots.load();
+        let mut evicted = Vec::new();
+        let mut pruned = Vec::new();
+
+        let mut evictable = lock(&self.evictable);
+        evictable.insert(added, size);
+        while self
+            .limits
+            .exceeded(evictable.len(), evictable.values().sum())
+        {
+            // Keys without a slot were cached in a slot that got removed meanwhile (see `prune()`),
+            // so they go first
+            let lru = evictable
+                .keys()
+                .filter(|key| **key != added)
+                .map(|key| (*key, slots.get(key)))
+                .filter(|(_, slot)| match slot.and_then(|slot| slot.get()) {
+                    // Referenced only by the slot and the clone we've just made
+                    Some(inst) => Shared::strong_count(&inst) == 2,
+                    None => true,
+                })
+                .min_by_key(|(_, slot)| {
+                    slot.map_or(0, |slot| slot.last_used.load(Ordering::Relaxed))
+                })
+                .map(|(key, _)| key);
+
+            match lru {
+                Some(key) => {
+                    evictable.remove(&key);
+                    if let Some(slot) = slots.get(&key) {
+                        evicted.push(slot.instance.swap(None));
+                        pruned.push(key);
+                    }
+                }
+                None => break,
+            }
+        }
+        drop(evictable);
+        drop(slots);
+
+        if !pruned.is_empty() {
+            self.prune(&pruned);
+        }
+
+        // Dropping the instances outside of the lock, as their destructors may use the catalog
+        drop(evicted);
+    }
+
+    fn get_or_init(
+        &self,
+        key: ScopeKey,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+        retain: &dyn Fn(&Shared<SharedAny>) -> CachedInstance,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        if let Some(inst) = self.get(key) {
+            return Ok(inst);
+        }
+
+        let slot = self.slot(key);
+        let _constructing = Constructing::enter(&slot)?;
+        let _guard = lock(&slot.init_lock);
+
+        // Instance might've been constructed while waiting for the lock
+        if let Some(inst) = self.touch(&slot).get() {
+            return Ok(inst);
+        }
+
+        // Failed construction leaves the slot empty so that the next call retries
+        let inst = init()?;
+        self.store(key, &slot, retain(&inst));
+        Ok(inst)
+    }
+
+    /// Returns the instance of the slot if nobody else references it, or constructs a new one
+    /// if the slot is empty and `init` is specified. Checking out under the slot lock makes sure
+    /// that the same instance is not handed out twice, see [`Pooled`].
+    fn check_out(
+        &self,
+        key: ScopeKey,
+        init: Option<&dyn Fn() -> Result<Shared<SharedAny>, InjectionError>>,
+    ) -> Option<Result<Shared<SharedAny>, InjectionError>> {
+        let slot = self.slot(key);
+        let _constructing = match Constructing::enter(&slot) {
+            Ok(constructing) => constructing,
+            Err(err) => return Some(Err(err)),
+        };
+        let _guard = lock(&slot.init_lock);
+
+        match (self.touch(&slot).get(), init) {
+            // Referenced only by the slot and the clone we've just made
+            (Some(inst), _) if Shared::strong_count(&inst) == 2 => Some(Ok(inst)),
+            (Some(_), _) | (None, None) => None,
+            (None, Some(init)) => {
+                let res = init();
+                if let Ok(inst) = &res {
+                    self.store(key, &slot, CachedInstance::evictable(inst));
+                }
+                Some(res)
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// Slots whose instances are being constructed on the current thread
+    static CONSTRUCTING: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+}
+
+/// Marks the slot as being constructed on the current thread until dropped.
+///
+/// Cycle detection of the [`Catalog`] doesn't see the resolutions restarted via a detached
+/// handle (e.g. a constructor calling `get()` on the injected catalog), so a constructor that
+/// ends up resolving its own component would otherwise deadlock on the slot lock.
+struct Constructing(usize);
+
+impl Constructing {
+    fn enter(slot: &Arc<ScopeSlot>) -> Result<Self, InjectionError> {
+        let addr = Arc::as_ptr(slot) as usize;
+        CONSTRUCTING.with(|constructing| {
+            let mut constructing = constructing.borrow_mut();
+            if constructing.contains(&addr) {
+                return Err(InjectionError::cycle(Catalog::thread_resolution_chain()));
+            }
+            constructing.push(addr);
+            Ok(Self(addr))
+        })
+    }
+}
+
+impl Drop for Constructing {
+    fn drop(&mut self) {
+        CONSTRUCTING.with(|constructing| {
+            let mut constructing = constructing.borrow_mut();
+            if let Some(pos) = constructing.iter().rposition(|a| *a == self.0) {
+                constructing.remove(pos);
+            }
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// ScopeKeys
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Assigns a unique [`ScopeKey`] to every value of `K`, for the scopes that cache a separate
+/// instance per value. Like with the [`ScopeCache`], looking up an already assigned key is lock-free.
+///
+/// Keys are never removed, which suits the values that are few and short of retiring, like the
+/// thread ids of a pool. See [`CacheKeys`] for the ones that come and go.
+struct ScopeKeys<K> {
+    keys: ArcSwap<HashMap<K, ScopeKey>>,
+    keys_lock: Mutex<()>,
+}
+
+impl<K> ScopeKeys<K>
+where
+    K: Clone + Eq + std::hash::Hash,
+{
+    fn new() -> Self {
+        Self {
+            keys: ArcSwap::from_pointee(HashMap::new()),
+            keys_lock: Mutex::new(()),
+        }
+    }
+
+    fn get(&self, value: &K) -> ScopeKey {
+        if let Some(key) = self.keys.load().get(value) {
+            return *key;
+        }
+
+        let _guard = lock(&self.keys_lock);
+
+        // Key might've been assigned while waiting for the lock
+        let keys = self.keys.load_full();
+        if let Some(key) = keys.get(value) {
+            return *key;
+        }
+
+        let key = ScopeKey::new_unique();
+        let mut keys = keys.as_ref().clone();
+        keys.insert(value.clone(), key);
+        self.keys.store(Arc::new(keys));
+        key
+    }
+}
+
+/// Assigns a unique [`ScopeKey`] to every value of `K` within a single [`ScopeCache`], see [`Keyed`].
+///
+/// Unlike [`ScopeKeys`], the keys are removed along with their slots when the instances are
+/// evicted (see [`CacheLimits`]), so that the keys of the retired values (e.g. the tenants that
+/// are gone) don't accumulate.
+struct CacheKeys<K> {
+    state: Mutex<CacheKeysState<K>>,
+}
+
+struct CacheKeysState<K> {
+    keys: HashMap<K, ScopeKey>,
+    /// Number of keys after the last removal of the evicted ones
+    swept_len: usize,
+}
+
+impl<K> CacheKeys<K>
+where
+    K: Clone + Eq + std::hash::Hash,
+{
+    /// Number of keys that are never checked for eviction
+    const MIN_SWEPT_LEN: usize = 16;
+
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(CacheKeysState {
+                keys: HashMap::new(),
+                swept_len: 0,
+            }),
+        }
+    }
+
+    fn get(&self, value: &K, cache: &ScopeCache) -> ScopeKey {
+        let mut state = lock(&self.state);
+        if let Some(key) = state.keys.get(value) {
+            return *key;
+        }
+
+        // Checking the keys once their number doubles keeps the insertion amortized O(1)
+        if state.keys.len() >= 2 * state.swept_len.max(Self::MIN_SWEPT_LEN) {
+            state.keys.retain(|_, key| cache.contains(*key));
+            state.swept_len = state.keys.len();
+        }
+
+        let key = ScopeKey::new_unique();
+        state.keys.insert(value.clone(), key);
+        key
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
@@ -16,45 +567,572 @@ pub trait Scope {
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Never caches so that every dependency resolution will result in a new instance.
-pub struct Transient;
+///
+/// Holds no state, so resolving transient components concurrently never contends on a lock.
+/// Unless [`CatalogBuilder::memoize_transients()`][`crate::CatalogBuilder::memoize_transients()`]
+/// is enabled, in which case the instance is shared within a single top-level resolution.
+pub struct Transient {
+    key: ScopeKey,
+}
 
 impl Transient {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            key: ScopeKey::new_unique(),
+        }
+    }
+}
+
+impl Default for Transient {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Scope for Transient {
-    fn get(&self) -> Option<Arc<dyn Any + Send + Sync>> {
-        None
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        cat.resolution_context().and_then(|ctx| ctx.get(self.key))
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        if let Some(ctx) = cat.resolution_context() {
+            ctx.set(self.key, CachedInstance::strong(&inst));
+        }
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        match cat.resolution_context() {
+            Some(ctx) => ctx.get_or_init(self.key, init, &CachedInstance::strong),
+            None => init(),
+        }
     }
 
-    fn set(&self, _inst: Arc<dyn Any + Send + Sync>) {}
+    fn invalidate(&self, cat: &Catalog) {
+        if let Some(ctx) = cat.resolution_context() {
+            ctx.remove(self.key);
+        }
+    }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
 // Singleton
 /////////////////////////////////////////////////////////////////////////////////////////
 
-/// Caches an instance upon first creation for the entire duration of the program.
+/// Caches an instance upon first creation for the entire lifetime of the [`Catalog`].
+///
+/// The instance is guaranteed to be constructed exactly once, even when resolved
+/// concurrently from multiple threads. Once constructed, the instance is resolved
+/// without taking any locks.
+///
+/// Resolving it via [`Catalog::get_async()`] only guarantees that all callers get the same
+/// instance, see [`Scope::set_if_absent()`].
 pub struct Singleton {
-    instance: Mutex<Option<Arc<dyn Any + Send + Sync>>>,
+    key: ScopeKey,
 }
 
 impl Singleton {
     pub fn new() -> Self {
         Self {
-            instance: Mutex::new(None),
+            key: ScopeKey::new_unique(),
         }
     }
 }
 
+impl Default for Singleton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Scope for Singleton {
-    fn get(&self) -> Option<Arc<dyn Any + Send + Sync>> {
-        self.instance.lock().unwrap().clone()
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        cat.scope_cache().get(self.key)
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        cat.scope_cache()
+            .set(self.key, CachedInstance::strong(&inst));
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        cat.scope_cache()
+            .get_or_init(self.key, init, &CachedInstance::strong)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        cat.scope_cache().remove(self.key);
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        true
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// WeakSingleton
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Shares an instance for as long as it's referenced outside of the [`Catalog`], e.g. for large
+/// caches or connection pools that should be released when nobody uses them.
+///
+/// Only a weak reference to the instance is cached, so once all clients drop their references
+/// the instance is released and the next resolution constructs a new one. Like [`Singleton`],
+/// the instance is constructed exactly once while it's alive.
+pub struct WeakSingleton {
+    key: ScopeKey,
+}
+
+impl WeakSingleton {
+    pub fn new() -> Self {
+        Self {
+            key: ScopeKey::new_unique(),
+        }
+    }
+}
+
+impl Default for WeakSingleton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scope for WeakSingleton {
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        cat.scope_cache().get(self.key)
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        cat.scope_cache().set(self.key, CachedInstance::weak(&inst));
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        cat.scope_cache()
+            .get_or_init(self.key, init, &CachedInstance::weak)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        cat.scope_cache().remove(self.key);
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Scoped
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Caches an instance for the duration of a logical scope (e.g. an HTTP request) started via
+/// [`Catalog::enter_scope()`]. Instances are dropped along with the last handle of the scope.
+///
+/// When resolved outside of any scope behaves like [`Transient`].
+pub struct Scoped {
+    key: ScopeKey,
+}
+
+impl Scoped {
+    pub fn new() -> Self {
+        Self {
+            key: ScopeKey::new_unique(),
+        }
+    }
+}
+
+impl Default for Scoped {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scope for Scoped {
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        cat.scope_context().and_then(|ctx| ctx.get(self.key))
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        if let Some(ctx) = cat.scope_context() {
+            ctx.set(self.key, CachedInstance::strong(&inst));
+        }
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        match cat.scope_context() {
+            Some(ctx) => ctx.get_or_init(self.key, init, &CachedInstance::strong),
+            None => init(),
+        }
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        if let Some(ctx) = cat.scope_context() {
+            ctx.remove(self.key);
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Keyed
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Caches an instance per value of the key `K` attached to the catalog handle via
+/// [`Catalog::with_context()`], e.g. to keep a singleton per tenant. Selected via
+/// `#[scope(Keyed<TenantId>)]`.
+///
+/// Every key behaves like a separate [`Singleton`]: the instance is constructed exactly once
+/// and lives as long as the [`Catalog`], unless evicted to stay within the limits set via
+/// [`CatalogBuilder::max_cached_instances()`][crate::CatalogBuilder::max_cached_instances()].
+/// When resolved without the key in the context behaves like [`Transient`].
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// struct TenantId(u64);
+///
+/// #[component]
+/// #[scope(Keyed<TenantId>)]
+/// struct TenantSettings;
+///
+/// let cat = CatalogBuilder::new().add::<TenantSettings>().build();
+///
+/// let s1 = cat.with_context(TenantId(1)).get_one::<TenantSettings>().unwrap();
+/// let s2 = cat.with_context(TenantId(1)).get_one::<TenantSettings>().unwrap();
+/// let s3 = cat.with_context(TenantId(2)).get_one::<TenantSettings>().unwrap();
+/// assert!(std::sync::Arc::ptr_eq(&s1, &s2));
+/// assert!(!std::sync::Arc::ptr_eq(&s1, &s3));
+/// ```
+pub struct Keyed<K> {
+    /// Slot of the [`CacheKeys`] assigned to the values of `K` in every catalog
+    key: ScopeKey,
+    _key_type: PhantomData<K>,
+}
+
+impl<K> Keyed<K>
+where
+    K: 'static + Clone + Eq + std::hash::Hash + MaybeSendSync,
+{
+    pub fn new() -> Self {
+        Self {
+            key: ScopeKey::new_unique(),
+            _key_type: PhantomData,
+        }
+    }
+
+    fn key(&self, cat: &Catalog) -> Option<ScopeKey> {
+        let value = cat.context::<K>()?;
+        let cache = cat.scope_cache();
+
+        let keys = cache
+            .get_or_init(
+                self.key,
+                &|| Ok(Shared::new(CacheKeys::<K>::new())),
+                &CachedInstance::strong,
+            )
+            .ok()?;
+        let keys = match keys.downcast::<CacheKeys<K>>() {
+            Ok(keys) => keys,
+            Err(_) => unreachable!("Slot of the keys holds an instance of another type"),
+        };
+        Some(keys.get(value, cache))
+    }
+}
+
+impl<K> Default for Keyed<K>
+where
+    K: 'static + Clone + Eq + std::hash::Hash + MaybeSendSync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Scope for Keyed<K>
+where
+    K: 'static + Clone + Eq + std::hash::Hash + MaybeSendSync,
+{
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.key(cat).and_then(|key| cat.scope_cache().get(key))
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        if let Some(key) = self.key(cat) {
+            cat.scope_cache().set(key, CachedInstance::evictable(&inst));
+        }
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        match self.key(cat) {
+            Some(key) => cat
+                .scope_cache()
+                .get_or_init(key, init, &CachedInstance::evictable),
+            None => init(),
+        }
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        if let Some(key) = self.key(cat) {
+            cat.scope_cache().remove(key);
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// ThreadLocal
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Caches an instance per OS thread, so that every thread resolving the component gets its own
+/// instance for the entire lifetime of the [`Catalog`], e.g. for the wrappers of C libraries
+/// that can't be used concurrently.
+///
+/// Components still have to be `Send + Sync`, which the types that are only `Send` satisfy
+/// when wrapped into [`ThreadBound`][crate::ThreadBound] instead of a mutex. Instances of
+/// the exited threads are kept until the catalog is dropped, while [`Catalog::invalidate()`]
+/// only drops the instance of the calling thread.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use dill::*;
+///
+/// #[component]
+/// #[scope(ThreadLocal)]
+/// struct Connection;
+///
+/// let cat = CatalogBuilder::new().add::<Connection>().build();
+///
+/// let c1 = cat.get_one::<Connection>().unwrap();
+/// assert!(Arc::ptr_eq(&c1, &cat.get_one::<Connection>().unwrap()));
+///
+/// let cat2 = cat.clone();
+/// let c2 = std::thread::spawn(move || cat2.get_one::<Connection>().unwrap())
+///     .join()
+///     .unwrap();
+/// assert!(!Arc::ptr_eq(&c1, &c2));
+/// ```
+pub struct ThreadLocal {
+    keys: ScopeKeys<std::thread::ThreadId>,
+}
+
+impl ThreadLocal {
+    pub fn new() -> Self {
+        Self {
+            keys: ScopeKeys::new(),
+        }
+    }
+
+    fn key(&self) -> ScopeKey {
+        self.keys.get(&std::thread::current().id())
+    }
+}
+
+impl Default for ThreadLocal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scope for ThreadLocal {
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        cat.scope_cache().get(self.key())
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        cat.scope_cache()
+            .set(self.key(), CachedInstance::strong(&inst));
+    }
+
+    // Instance of a thread is never constructed concurrently, so the slot lock is uncontended
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        cat.scope_cache()
+            .get_or_init(self.key(), init, &CachedInstance::strong)
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        cat.scope_cache().remove(self.key());
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Pooled
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Keeps a bounded pool of instances, e.g. for database connections or large scratch buffers.
+/// Selected via `#[scope(Pooled(4))]`.
+///
+/// Every resolution checks out an instance that is not referenced outside of the pool,
+/// constructing a new one while the pool has fewer instances than its capacity, and fails with
+/// [`InjectionError::PoolExhausted`] otherwise. Dropping all references to the instance checks
+/// it back in. Like with [`Singleton`], the pool is kept for the entire lifetime of the [`Catalog`].
+///
+/// Asynchronous resolution doesn't enforce the capacity - the instances constructed when
+/// the pool is full are not pooled.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use dill::*;
+///
+/// #[component]
+/// #[scope(Pooled(2))]
+/// struct Connection;
+///
+/// let cat = CatalogBuilder::new().add::<Connection>().build();
+///
+/// let c1 = cat.get_one::<Connection>().unwrap();
+/// let c2 = cat.get_one::<Connection>().unwrap();
+/// assert!(!Arc::ptr_eq(&c1, &c2));
+/// assert!(matches!(
+///     cat.get_one::<Connection>(),
+///     Err(InjectionError::PoolExhausted(_))
+/// ));
+///
+/// let c1_ptr = Arc::as_ptr(&c1);
+/// drop(c1);
+/// assert_eq!(Arc::as_ptr(&cat.get_one::<Connection>().unwrap()), c1_ptr);
+/// ```
+pub struct Pooled {
+    keys: Vec<ScopeKey>,
+}
+
+impl Pooled {
+    pub fn new(capacity: usize) -> Self {
+        if capacity == 0 {
+            panic!("Pool capacity has to be positive");
+        }
+        Self {
+            keys: (0..capacity).map(|_| ScopeKey::new_unique()).collect(),
+        }
+    }
+
+    /// Maximum number of instances in the pool
+    pub fn capacity(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+impl Scope for Pooled {
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        self.keys
+            .iter()
+            .find_map(|key| cat.scope_cache().check_out(*key, None))
+            .and_then(|res| res.ok())
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        let init = || Ok(inst.clone());
+        for key in &self.keys {
+            if let Some(Ok(stored)) = cat.scope_cache().check_out(*key, Some(&init)) {
+                // Slot might've held a free instance, which is then replaced
+                if same_instance(&stored, &inst) {
+                    return;
+                }
+                cat.scope_cache()
+                    .set(*key, CachedInstance::evictable(&inst));
+                return;
+            }
+        }
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        // Free instances are preferred over constructing the new ones
+        if let Some(inst) = self.get(cat) {
+            return Ok(inst);
+        }
+        self.keys
+            .iter()
+            .find_map(|key| cat.scope_cache().check_out(*key, Some(init)))
+            .unwrap_or_else(|| Err(InjectionError::pool_exhausted(self.capacity())))
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        for key in &self.keys {
+            cat.scope_cache().remove(*key);
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Cached
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Caches an instance for a limited time, after which the next resolution constructs a new one,
+/// e.g. for refreshing credentials or snapshots of feature flags. Selected via
+/// `#[scope(Cached(Duration::from_secs(300)))]`.
+///
+/// Like [`Singleton`], the instance is cached for the entire [`Catalog`] and is constructed
+/// exactly once until it expires. Clients that hold the expired instance are not affected.
+pub struct Cached {
+    key: ScopeKey,
+    ttl: Duration,
+}
+
+impl Cached {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            key: ScopeKey::new_unique(),
+            ttl,
+        }
+    }
+
+    /// Time for which the instance is cached
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+impl Scope for Cached {
+    fn get(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        cat.scope_cache().get(self.key)
+    }
+
+    fn set(&self, cat: &Catalog, inst: Shared<SharedAny>) {
+        cat.scope_cache()
+            .set(self.key, CachedInstance::expiring(&inst, self.ttl));
+    }
+
+    fn get_or_init(
+        &self,
+        cat: &Catalog,
+        init: &dyn Fn() -> Result<Shared<SharedAny>, InjectionError>,
+    ) -> Result<Shared<SharedAny>, InjectionError> {
+        cat.scope_cache().get_or_init(self.key, init, &|inst| {
+            CachedInstance::expiring(inst, self.ttl)
+        })
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        cat.scope_cache().remove(self.key);
     }
 
-    fn set(&self, inst: Arc<dyn Any + Send + Sync>) {
-        self.instance.lock().unwrap().replace(inst);
+    fn is_catalog_wide(&self) -> bool {
+        true
     }
 }
@@ -0,0 +1,52 @@
+//! Pointers to the instances managed by the catalog and the thread-safety bounds they
+//! require of the components.
+//!
+//! By default instances are shared via [`std::sync::Arc`] and all components, builders and
+//! scopes have to be `Send + Sync`. The `unsync` feature targets single-threaded applications
+//! (e.g. WASM or GUI frontends) - it switches the instances to [`std::rc::Rc`] and drops the
+//! `Send + Sync` bounds, making the [`Catalog`][crate::Catalog] itself `!Send`.
+
+use std::any::Any;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "unsync")]
+pub use std::rc::Rc as Shared;
+/// Shared pointer to the resolved instances: [`std::sync::Arc`], or [`std::rc::Rc`] with
+/// the `unsync` feature
+#[cfg(not(feature = "unsync"))]
+pub use std::sync::Arc as Shared;
+
+#[cfg(feature = "unsync")]
+pub(crate) use std::rc::Weak as WeakShared;
+#[cfg(not(feature = "unsync"))]
+pub(crate) use std::sync::Weak as WeakShared;
+
+/// Type-erased instance as stored by the [`Scope`][crate::Scope]s
+#[cfg(not(feature = "unsync"))]
+pub type SharedAny = dyn Any + Send + Sync;
+#[cfg(feature = "unsync")]
+pub type SharedAny = dyn Any;
+
+/// Whether both pointers refer to the same instance. Compares the data pointers only, as the
+/// vtable addresses of the trait objects are not guaranteed to be unique.
+pub(crate) fn same_instance(a: &Shared<SharedAny>, b: &Shared<SharedAny>) -> bool {
+    std::ptr::eq(
+        Shared::as_ptr(a) as *const (),
+        Shared::as_ptr(b) as *const (),
+    )
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Thread-safety bounds of the components: `Send + Sync`, or none with the `unsync` feature
+#[cfg(not(feature = "unsync"))]
+pub trait MaybeSendSync: Send + Sync {}
+#[cfg(not(feature = "unsync"))]
+impl<T: ?Sized + Send + Sync> MaybeSendSync for T {}
+
+/// Thread-safety bounds of the components: `Send + Sync`, or none with the `unsync` feature
+#[cfg(feature = "unsync")]
+pub trait MaybeSendSync {}
+#[cfg(feature = "unsync")]
+impl<T: ?Sized> MaybeSendSync for T {}
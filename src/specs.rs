@@ -1,6 +1,14 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    any::{type_name, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+};
 
-use crate::{Catalog, InjectionError};
+use crate::join::JoinAll;
+use crate::{
+    Binding, BoxFuture, Builder, Catalog, InjectionError, LazyBinding, MaybeSendSync,
+    ResolveOpenGeneric, Shared, ShutdownToken, TypecastBuilder,
+};
 
 /////////////////////////////////////////////////////////////////////////////////////////
 // DependencySpec
@@ -12,42 +20,386 @@ pub trait DependencySpec {
     fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError>;
 }
 
+/// A [`DependencySpec`] that can be resolved asynchronously, see [`Catalog::get_async()`]
+pub trait AsyncDependencySpec: DependencySpec {
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>>;
+}
+
+/// A [`DependencySpec`] that can also be resolved from a named binding,
+/// see [`Catalog::get_named()`]
+pub trait NamedDependencySpec: DependencySpec {
+    fn get_named(cat: &Catalog, name: &str) -> Result<Self::ReturnType, InjectionError>;
+
+    fn get_named_async<'a>(
+        cat: &'a Catalog,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Self::ReturnType, InjectionError>>;
+}
+
 /////////////////////////////////////////////////////////////////////////////////////////
 // OneOf
 /////////////////////////////////////////////////////////////////////////////////////////
 
 /// Builds a single instance of type implementing specific interface. Will return an error
-/// if no implementations or multiple implementations were found.
-pub struct OneOf<Iface>
+/// if no implementations were found, or if multiple implementations share the highest priority
+/// (see [`Builder::priority()`][crate::Builder::priority()]) - use [`AllOf`] to resolve all
+/// of them instead.
+///
+/// The optional second parameter is a [`Qualifier`] that picks a binding registered via
+/// [`CatalogBuilder::bind_qualified()`][crate::CatalogBuilder::bind_qualified()], e.g.
+/// `OneOf<dyn Db, Primary>`.
+pub struct OneOf<Iface, Q = Unqualified>
 where
-    Iface: 'static + ?Sized + Send + Sync,
+    Iface: 'static + ?Sized + MaybeSendSync,
 {
     _dummy: PhantomData<Iface>,
+    _qualifier: PhantomData<Q>,
 }
 
 impl<Iface> DependencySpec for OneOf<Iface>
 where
-    Iface: 'static + ?Sized + Send + Sync,
+    Iface: 'static + ?Sized + MaybeSendSync,
 {
-    type ReturnType = Arc<Iface>;
+    type ReturnType = Shared<Iface>;
 
     default fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        if let Some(inst) = cat.overridden::<Iface>() {
+            return Ok(inst);
+        }
+        match Self::single_builder(cat) {
+            Ok(builder) => builder.get(cat),
+            Err(err) => match Self::open_generic(cat, err) {
+                // SAFETY: the binding is created for the `Iface` type
+                Ok(binding) => unsafe { binding.typecast(None) }.get(cat),
+                Err(err) => Self::unresolved(cat, err),
+            },
+        }
+    }
+}
+
+impl<Iface> AsyncDependencySpec for OneOf<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    default fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            if let Some(inst) = cat.overridden::<Iface>() {
+                return Ok(inst);
+            }
+            match Self::single_builder(cat) {
+                Ok(builder) => builder.get_async(cat).await,
+                Err(err) => match Self::open_generic(cat, err) {
+                    // SAFETY: the binding is created for the `Iface` type
+                    Ok(binding) => unsafe { binding.typecast(None) }.get_async(cat).await,
+                    Err(err) => Self::unresolved(cat, err),
+                },
+            }
+        })
+    }
+}
+
+impl<Iface> NamedDependencySpec for OneOf<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_named(cat: &Catalog, name: &str) -> Result<Self::ReturnType, InjectionError> {
+        match cat.builder_named::<Iface>(name) {
+            Some(builder) => builder.get(cat),
+            None => Err(cat.unregistered::<Iface>()),
+        }
+    }
+
+    fn get_named_async<'a>(
+        cat: &'a Catalog,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            match cat.builder_named::<Iface>(name) {
+                Some(builder) => builder.get_async(cat).await,
+                None => Err(cat.unregistered::<Iface>()),
+            }
+        })
+    }
+}
+
+impl<Iface> OneOf<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    /// Instantiates the implementation of the unregistered interface, see [`OpenGeneric`][crate::OpenGeneric]
+    fn open_generic(cat: &Catalog, err: InjectionError) -> Result<Binding, InjectionError> {
+        match err {
+            InjectionError::Unregistered(_) => Iface::open_generic(cat).ok_or(err),
+            _ => Err(err),
+        }
+    }
+
+    /// Gives the handlers of [`CatalogBuilder::on_unresolved()`][crate::CatalogBuilder::on_unresolved()]
+    /// a chance to provide the instance
+    fn unresolved(cat: &Catalog, err: InjectionError) -> Result<Shared<Iface>, InjectionError> {
+        match err {
+            InjectionError::Unregistered(_) => {
+                cat.resolve_unresolved::<Iface>().unwrap_or(Err(err))
+            }
+            _ => Err(err),
+        }
+    }
+
+    /// Returns the implementation with the highest priority, which is the first one as the
+    /// bindings are ordered by priority, see [`Builder::priority()`][crate::Builder::priority()]
+    fn single_builder(cat: &Catalog) -> Result<TypecastBuilder<'_, Iface>, InjectionError> {
         let mut builders = cat.builders_for::<Iface>();
         if let Some(first) = builders.next() {
-            if builders.next().is_some() {
-                Err(InjectionError::ambiguous::<Iface>())
+            let tied: Vec<_> = builders
+                .take_while(|b| b.priority() == first.priority())
+                .map(|b| b.instance_type_name())
+                .collect();
+            if !tied.is_empty() {
+                let mut candidates = vec![first.instance_type_name()];
+                candidates.extend(tied);
+                Err(InjectionError::ambiguous_type(
+                    TypeId::of::<Iface>(),
+                    type_name::<Iface>(),
+                    candidates,
+                ))
             } else {
-                first.get(cat)
+                Ok(first)
             }
         } else {
-            Err(InjectionError::unregistered::<Iface>())
+            Err(cat.unregistered::<Iface>())
         }
     }
 }
 
 impl DependencySpec for OneOf<Catalog> {
     fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
-        Ok(Arc::new(cat.clone()))
+        Ok(Shared::new(cat.detached()))
+    }
+}
+
+impl AsyncDependencySpec for OneOf<Catalog> {
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(std::future::ready(Ok(Shared::new(cat.detached()))))
+    }
+}
+
+impl DependencySpec for OneOf<ShutdownToken> {
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        Ok(Shared::new(cat.shutdown_token()))
+    }
+}
+
+impl AsyncDependencySpec for OneOf<ShutdownToken> {
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(std::future::ready(Ok(Shared::new(cat.shutdown_token()))))
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Qualifier
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// A marker type that tells apart the implementations of an interface playing different roles,
+/// e.g. the primary database and its replica. A type-safe alternative to the binding names
+/// (see [`Catalog::get_named()`]), as a misspelled qualifier doesn't compile.
+///
+/// Qualified bindings are named bindings named after the qualifier type (see
+/// [`Qualifier::name()`]), so they are checked by
+/// [`CatalogBuilder::validate()`][crate::CatalogBuilder::validate()] the same way.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// trait Db: Send + Sync {
+///     fn url(&self) -> String;
+/// }
+///
+/// struct Primary;
+/// impl Qualifier for Primary {}
+///
+/// struct Replica;
+/// impl Qualifier for Replica {}
+///
+/// #[component]
+/// struct PgDb {
+///     url: String,
+/// }
+/// impl Db for PgDb {
+///     fn url(&self) -> String {
+///         self.url.clone()
+///     }
+/// }
+///
+/// #[component]
+/// struct Reports {
+///     #[dill(qualifier = Replica)]
+///     db: std::sync::Arc<dyn Db>,
+/// }
+///
+/// let cat = CatalogBuilder::new()
+///     .add_builder(builder_for::<PgDb>().with_url("pg://primary".to_owned()))
+///     .bind_qualified::<dyn Db, PgDb, Primary>()
+///     .add::<Reports>()
+///     .build();
+///
+/// let db = cat.get::<OneOf<dyn Db, Primary>>().unwrap();
+/// assert_eq!(db.url(), "pg://primary");
+///
+/// // No implementation plays the role of a replica
+/// assert!(cat.get_one::<Reports>().is_err());
+/// ```
+pub trait Qualifier: Sized + 'static {
+    /// Name of the bindings qualified by this marker
+    fn name() -> &'static str {
+        type_name::<Self>()
+    }
+}
+
+/// Default parameter of [`OneOf`] that resolves the implementations regardless of their
+/// qualifiers
+pub struct Unqualified;
+
+impl<Iface, Q> DependencySpec for OneOf<Iface, Q>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+    Q: Qualifier,
+{
+    type ReturnType = Shared<Iface>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        OneOf::<Iface>::get_named(cat, Q::name())
+    }
+}
+
+impl<Iface, Q> AsyncDependencySpec for OneOf<Iface, Q>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+    Q: Qualifier,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        OneOf::<Iface>::get_named_async(cat, Q::name())
+    }
+}
+
+impl<Iface, Q> DependencySpec for Maybe<OneOf<Iface, Q>>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+    Q: Qualifier,
+{
+    type ReturnType = Option<Shared<Iface>>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        Maybe::<OneOf<Iface>>::get_named(cat, Q::name())
+    }
+}
+
+impl<Iface, Q> AsyncDependencySpec for Maybe<OneOf<Iface, Q>>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+    Q: Qualifier,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Maybe::<OneOf<Iface>>::get_named_async(cat, Q::name())
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Owned
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Builds a single instance of type implementing specific interface (same as [`OneOf`]),
+/// moving it into a [`Box`] owned by the caller, e.g. `Owned<dyn Iface>` returns `Box<dyn Iface>`.
+///
+/// Only fresh instances can be moved out of the catalog, so the implementation has to be
+/// [`Transient`][crate::Transient] - instances cached by other scopes result in
+/// [`InjectionError::SharedInstance`]. Decorators of the interface are not applied, as they
+/// wrap the shared instances.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// trait Parser: Send + Sync {
+///     fn parse(&mut self, line: &str) -> usize;
+/// }
+///
+/// #[component]
+/// #[interface(dyn Parser)]
+/// struct LineCounter {
+///     lines: usize,
+/// }
+///
+/// impl Parser for LineCounter {
+///     fn parse(&mut self, _line: &str) -> usize {
+///         self.lines += 1;
+///         self.lines
+///     }
+/// }
+///
+/// let cat = CatalogBuilder::new()
+///     .add_builder(builder_for::<LineCounter>().with_lines(0))
+///     .bind::<dyn Parser, LineCounter>()
+///     .build();
+///
+/// let mut parser = cat.get::<Owned<dyn Parser>>().unwrap();
+/// assert_eq!(parser.parse("a"), 1);
+/// assert_eq!(parser.parse("b"), 2);
+/// ```
+pub struct Owned<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    _dummy: PhantomData<Iface>,
+}
+
+impl<Iface> DependencySpec for Owned<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    type ReturnType = Box<Iface>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        OneOf::<Iface>::single_builder(cat)?.get_owned(cat)
+    }
+}
+
+impl<Iface> AsyncDependencySpec for Owned<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            OneOf::<Iface>::single_builder(cat)?
+                .get_owned_async(cat)
+                .await
+        })
+    }
+}
+
+impl<Iface> NamedDependencySpec for Owned<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_named(cat: &Catalog, name: &str) -> Result<Self::ReturnType, InjectionError> {
+        match cat.builder_named::<Iface>(name) {
+            Some(builder) => builder.get_owned(cat),
+            None => Err(cat.unregistered::<Iface>()),
+        }
+    }
+
+    fn get_named_async<'a>(
+        cat: &'a Catalog,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            match cat.builder_named::<Iface>(name) {
+                Some(builder) => builder.get_owned_async(cat).await,
+                None => Err(cat.unregistered::<Iface>()),
+            }
+        })
     }
 }
 
@@ -55,7 +407,8 @@ impl DependencySpec for OneOf<Catalog> {
 // AllOf
 /////////////////////////////////////////////////////////////////////////////////////////
 
-/// Builds all instances that implement a specific interface, returning a [`Vec`].
+/// Builds all instances that implement a specific interface, returning a [`Vec`] ordered by
+/// priority (see [`Builder::priority()`][crate::Builder::priority()]), then by registration.
 pub struct AllOf<Iface>
 where
     Iface: 'static + ?Sized,
@@ -67,9 +420,246 @@ impl<Iface> DependencySpec for AllOf<Iface>
 where
     Iface: 'static + ?Sized,
 {
-    type ReturnType = Vec<Arc<Iface>>;
+    type ReturnType = Vec<Shared<Iface>>;
 
     fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
         cat.builders_for::<Iface>().map(|b| b.get(cat)).collect()
     }
 }
+
+impl<Iface> AsyncDependencySpec for AllOf<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            let builders: Vec<_> = cat.builders_for::<Iface>().collect();
+            let resolutions = builders.iter().map(|b| {
+                let resolution: BoxFuture<'_, _> = Box::pin(b.get_async(cat));
+                (b as &dyn Builder, resolution)
+            });
+            JoinAll::new(cat, resolutions).await
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// AllOfNamed
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Builds all instances bound to a specific interface under a name (see
+/// [`CatalogBuilder::bind_named()`][crate::CatalogBuilder::bind_named()]), returning a [`HashMap`]
+/// keyed by the binding name, e.g. for dispatchers that route by message type or file extension.
+/// Bindings without a name are skipped.
+pub struct AllOfNamed<Iface>
+where
+    Iface: 'static + ?Sized,
+{
+    _dummy: PhantomData<Iface>,
+}
+
+impl<Iface> DependencySpec for AllOfNamed<Iface>
+where
+    Iface: 'static + ?Sized,
+{
+    type ReturnType = HashMap<String, Shared<Iface>>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        cat.builders_for::<Iface>()
+            .filter_map(|b| b.name().map(|name| (name, b)))
+            .map(|(name, b)| Ok((name.to_owned(), b.get(cat)?)))
+            .collect()
+    }
+}
+
+impl<Iface> AsyncDependencySpec for AllOfNamed<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            let builders: Vec<_> = cat
+                .builders_for::<Iface>()
+                .filter_map(|b| b.name().map(|name| (name, b)))
+                .collect();
+            let mut instances = HashMap::with_capacity(builders.len());
+            for (name, builder) in builders {
+                instances.insert(name.to_owned(), builder.get_async(cat).await?);
+            }
+            Ok(instances)
+        })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// AllOfLazy
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Returns a [`LazyBinding`] for every implementation bound to a specific interface without
+/// building any of them, so that the consumer can build only the ones it needs, e.g. picking
+/// one of many exporters by the binding name or the tags.
+///
+/// Can be injected as `Vec<LazyBinding<T>>` argument of a component.
+///
+/// # Examples
+///
+/// ```
+/// use dill::*;
+///
+/// trait Exporter: Send + Sync {
+///     fn format(&self) -> &str;
+/// }
+///
+/// #[component]
+/// struct CsvExporter;
+/// impl Exporter for CsvExporter {
+///     fn format(&self) -> &str {
+///         "csv"
+///     }
+/// }
+///
+/// #[component]
+/// struct JsonExporter;
+/// impl Exporter for JsonExporter {
+///     fn format(&self) -> &str {
+///         "json"
+///     }
+/// }
+///
+/// #[component]
+/// struct Export {
+///     exporters: Vec<LazyBinding<dyn Exporter>>,
+/// }
+///
+/// let cat = CatalogBuilder::new()
+///     .add::<CsvExporter>()
+///     .bind_named::<dyn Exporter, CsvExporter>("csv")
+///     .add::<JsonExporter>()
+///     .bind_named::<dyn Exporter, JsonExporter>("json")
+///     .add::<Export>()
+///     .build();
+///
+/// let export = cat.get_one::<Export>().unwrap();
+/// let json = export
+///     .exporters
+///     .iter()
+///     .find(|e| e.name() == Some("json"))
+///     .unwrap();
+/// assert_eq!(json.get().unwrap().format(), "json");
+/// ```
+pub struct AllOfLazy<Iface>
+where
+    Iface: 'static + ?Sized,
+{
+    _dummy: PhantomData<Iface>,
+}
+
+impl<Iface> DependencySpec for AllOfLazy<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    type ReturnType = Vec<LazyBinding<Iface>>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        Ok(cat
+            .builders_for::<Iface>()
+            .enumerate()
+            .map(|(index, b)| LazyBinding::new(cat, index, &b))
+            .collect())
+    }
+}
+
+impl<Iface> AsyncDependencySpec for AllOfLazy<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move { Self::get(cat) })
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+// Maybe
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Resolves an optional dependency, returning `None` instead of an error when no
+/// implementations were registered, e.g. `Maybe<OneOf<dyn Iface>>` returns `Option<Arc<dyn Iface>>`.
+/// The [`OpenGeneric`][crate::OpenGeneric] implementation and the handlers of
+/// [`CatalogBuilder::on_unresolved()`][crate::CatalogBuilder::on_unresolved()]
+/// are consulted before returning `None`.
+/// Errors that occur while building a registered implementation are still propagated.
+pub struct Maybe<Spec>
+where
+    Spec: DependencySpec,
+{
+    _dummy: PhantomData<Spec>,
+}
+
+impl<Iface> DependencySpec for Maybe<OneOf<Iface>>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    type ReturnType = Option<Shared<Iface>>;
+
+    fn get(cat: &Catalog) -> Result<Self::ReturnType, InjectionError> {
+        if let Some(inst) = cat.overridden::<Iface>() {
+            Ok(Some(inst))
+        } else if cat.builders_for::<Iface>().next().is_none() {
+            match Iface::open_generic(cat) {
+                // SAFETY: the binding is created for the `Iface` type
+                Some(binding) => unsafe { binding.typecast(None) }.get(cat).map(Some),
+                None => cat.resolve_unresolved::<Iface>().transpose(),
+            }
+        } else {
+            OneOf::<Iface>::get(cat).map(Some)
+        }
+    }
+}
+
+impl<Iface> AsyncDependencySpec for Maybe<OneOf<Iface>>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_async(cat: &Catalog) -> BoxFuture<'_, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            if let Some(inst) = cat.overridden::<Iface>() {
+                Ok(Some(inst))
+            } else if cat.builders_for::<Iface>().next().is_none() {
+                match Iface::open_generic(cat) {
+                    // SAFETY: the binding is created for the `Iface` type
+                    Some(binding) => unsafe { binding.typecast(None) }
+                        .get_async(cat)
+                        .await
+                        .map(Some),
+                    None => cat.resolve_unresolved::<Iface>().transpose(),
+                }
+            } else {
+                OneOf::<Iface>::get_async(cat).await.map(Some)
+            }
+        })
+    }
+}
+
+impl<Iface> NamedDependencySpec for Maybe<OneOf<Iface>>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn get_named(cat: &Catalog, name: &str) -> Result<Self::ReturnType, InjectionError> {
+        match cat.builder_named::<Iface>(name) {
+            Some(builder) => builder.get(cat).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn get_named_async<'a>(
+        cat: &'a Catalog,
+        name: &'a str,
+    ) -> BoxFuture<'a, Result<Self::ReturnType, InjectionError>> {
+        Box::pin(async move {
+            match cat.builder_named::<Iface>(name) {
+                Some(builder) => builder.get_async(cat).await.map(Some),
+                None => Ok(None),
+            }
+        })
+    }
+}
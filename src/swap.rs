@@ -0,0 +1,180 @@
+use std::{
+    any::{type_name, TypeId},
+    marker::Unsize,
+    sync::Arc,
+};
+
+use arc_swap::ArcSwap;
+
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implementation currently backing a swappable interface
+struct Backing<Iface: ?Sized> {
+    builder: Arc<dyn Builder>,
+    cast: fn(Shared<SharedAny>) -> Shared<Iface>,
+    /// Copied out of the builder, as the slot can't lend the ones of a backing that may be
+    /// swapped out meanwhile. Leaked, as swaps are rare and the slices are small.
+    tags: &'static [&'static str],
+    profiles: &'static [&'static str],
+}
+
+/// Builder of an interface whose implementation can be replaced on a live catalog,
+/// see [`CatalogBuilder::add_swappable()`].
+///
+/// Instances are handed out as `Shared<Iface>` wrapped into another [`Shared`] pointer,
+/// as the concrete type of the implementation changes with every swap.
+pub(crate) struct SwapSlot<Iface: ?Sized> {
+    current: ArcSwap<Backing<Iface>>,
+}
+
+impl<Iface> SwapSlot<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    pub(crate) fn new<Bld, Impl>(builder: Bld) -> Self
+    where
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        Self {
+            current: ArcSwap::from_pointee(Self::backing(builder)),
+        }
+    }
+
+    /// Replaces the implementation, leaving the instances handed out so far intact
+    pub(crate) fn swap<Bld, Impl>(&self, builder: Bld)
+    where
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        self.current.store(Arc::new(Self::backing(builder)));
+    }
+
+    fn backing<Bld, Impl>(builder: Bld) -> Backing<Iface>
+    where
+        Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        Bld: TypedBuilder<Impl> + 'static,
+    {
+        fn cast<Iface, Impl>(v: Shared<SharedAny>) -> Shared<Iface>
+        where
+            Iface: ?Sized,
+            Impl: 'static + MaybeSendSync + Unsize<Iface>,
+        {
+            // SAFETY: the caster is stored along with the builder of `Impl` instances
+            let s: Shared<Impl> = v.downcast().unwrap();
+            s
+        }
+
+        fn leak(values: &[&'static str]) -> &'static [&'static str] {
+            if values.is_empty() {
+                &[]
+            } else {
+                Box::leak(values.to_vec().into_boxed_slice())
+            }
+        }
+
+        Backing {
+            tags: leak(builder.tags()),
+            profiles: leak(builder.profiles()),
+            builder: Arc::new(builder),
+            cast: cast::<Iface, Impl>,
+        }
+    }
+
+    /// Type of the instances produced by the slot, which it's registered under
+    pub(crate) fn instance_type() -> ImplTypeId {
+        ImplTypeId(TypeId::of::<Shared<Iface>>())
+    }
+
+    /// Caster of the interface binding unwrapping the instances produced by the slot
+    pub(crate) fn caster() -> TypeCaster<Iface> {
+        TypeCaster {
+            cast_arc: Box::new(|v: Shared<SharedAny>| {
+                // SAFETY: the caster is only used in the bindings of the slot
+                let s: Shared<Shared<Iface>> = v.downcast().unwrap();
+                s.as_ref().clone()
+            }),
+            // The instance is always referenced by the wrapper
+            cast_box: Box::new(Err),
+        }
+    }
+
+    fn wrap(&self, backing: &Backing<Iface>, inst: Shared<SharedAny>) -> Shared<SharedAny> {
+        Shared::new((backing.cast)(inst))
+    }
+}
+
+impl<Iface> Builder for SwapSlot<Iface>
+where
+    Iface: 'static + ?Sized + MaybeSendSync,
+{
+    fn instance_type_id(&self) -> TypeId {
+        TypeId::of::<Shared<Iface>>()
+    }
+
+    fn instance_type_name(&self) -> &'static str {
+        type_name::<Shared<Iface>>()
+    }
+
+    fn scope_name(&self) -> &'static str {
+        self.current.load().builder.scope_name()
+    }
+
+    // The scope is owned by the current implementation, so it's never overridden by the catalog
+    fn scope_was_explicit(&self) -> bool {
+        true
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.current.load().builder.is_catalog_wide()
+    }
+
+    fn tags(&self) -> &[&'static str] {
+        self.current.load().tags
+    }
+
+    fn profiles(&self) -> &[&'static str] {
+        self.current.load().profiles
+    }
+
+    fn is_eager(&self) -> bool {
+        self.current.load().builder.is_eager()
+    }
+
+    fn priority(&self) -> i32 {
+        self.current.load().builder.priority()
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        self.current.load().builder.dependencies()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        let backing = self.current.load_full();
+        let inst = backing.builder.get(cat)?;
+        Ok(self.wrap(&backing, inst))
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        let backing = self.current.load_full();
+        let inst = backing.builder.cached(cat)?;
+        Some(self.wrap(&backing, inst))
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        self.current.load().builder.invalidate(cat)
+    }
+
+    fn get_async<'a>(
+        &'a self,
+        cat: &'a Catalog,
+    ) -> BoxFuture<'a, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(async move {
+            let backing = self.current.load_full();
+            let inst = backing.builder.get_async(cat).await?;
+            Ok(self.wrap(&backing, inst))
+        })
+    }
+}
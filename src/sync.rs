@@ -0,0 +1,25 @@
+//! Synchronization primitives guarding the initialization and the writes of the scope caches.
+//!
+//! Lookups of the cached instances are lock-free regardless of the backend (see `arc-swap`),
+//! while the mutexes are provided by `std` by default or by `parking_lot` when the `parking-lot`
+//! feature is enabled, which spins briefly before parking and thus contends less on many cores.
+
+#[cfg(feature = "parking-lot")]
+pub(crate) use parking_lot::{Mutex, MutexGuard};
+#[cfg(not(feature = "parking-lot"))]
+pub(crate) use std::sync::{Mutex, MutexGuard};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Acquires the mutex, ignoring the poisoning of `std` mutexes, as a panic in a constructor
+/// should not render the scope unusable
+#[cfg(not(feature = "parking-lot"))]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Acquires the mutex, which is never poisoned with `parking_lot`
+#[cfg(feature = "parking-lot")]
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock()
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::sync::{lock, Mutex};
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implemented for the interfaces declared via `#[interface]` on a trait by the generated
+/// proxy that records the calls before delegating them to the real implementation,
+/// see [`CatalogBuilder::spy()`].
+///
+/// Traits whose methods can't be delegated through a shared reference (e.g. the ones taking
+/// `&mut self` or `self`) don't get a proxy.
+pub trait Spyable {
+    fn spy(inner: Shared<Self>, recorder: CallRecorder) -> Shared<Self>;
+}
+
+/// Calls recorded by the proxies of an interface, see [`CatalogBuilder::spy()`].
+///
+/// Clones share the recorded calls.
+#[derive(Debug, Clone, Default)]
+pub struct CallRecorder {
+    calls: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl CallRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc(hidden)]
+    pub fn record(&self, method: &'static str) {
+        lock(&self.calls).push(method);
+    }
+
+    /// Names of the called methods in the order of the calls
+    pub fn calls(&self) -> Vec<&'static str> {
+        lock(&self.calls).clone()
+    }
+
+    /// How many times the method was called
+    pub fn call_count(&self, method: &str) -> usize {
+        lock(&self.calls).iter().filter(|m| **m == method).count()
+    }
+
+    pub fn was_called(&self, method: &str) -> bool {
+        self.call_count(method) != 0
+    }
+
+    pub fn clear(&self) {
+        lock(&self.calls).clear()
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+impl CatalogBuilder {
+    /// Decorates the implementations of the interface (see [`CatalogBuilder::add_decorator()`])
+    /// with the proxy generated by `#[interface]`, which records the calls of the trait methods
+    /// and delegates them to the real component, e.g. to verify the interactions in tests
+    /// without re-declaring the trait in a mocking framework.
+    ///
+    /// Returns the recorder shared by all instances resolved from the catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// #[interface]
+    /// trait Mailer: Send + Sync {
+    ///     fn send(&self, to: &str) -> bool;
+    /// }
+    ///
+    /// #[component]
+    /// #[interface(dyn Mailer)]
+    /// struct SmtpMailer;
+    /// impl Mailer for SmtpMailer {
+    ///     fn send(&self, _to: &str) -> bool {
+    ///         true
+    ///     }
+    /// }
+    ///
+    /// let mut b = CatalogBuilder::new();
+    /// b.add::<SmtpMailer>();
+    /// let mailer_calls = b.spy::<dyn Mailer>();
+    /// let cat = b.build();
+    ///
+    /// assert!(cat.get_mailer().unwrap().send("alice@example.com"));
+    /// assert_eq!(mailer_calls.call_count("send"), 1);
+    /// ```
+    pub fn spy<Iface>(&mut self) -> CallRecorder
+    where
+        Iface: 'static + ?Sized + Spyable,
+    {
+        let recorder = CallRecorder::new();
+        let proxy_recorder = recorder.clone();
+        self.add_decorator::<Iface, _>(move |inner, _| Iface::spy(inner, proxy_recorder.clone()));
+        recorder
+    }
+}
@@ -0,0 +1,96 @@
+use std::thread::ThreadId;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Makes a value that is `Send` but not `Sync` (e.g. a wrapper of a C library handle) shareable
+/// by only allowing to access it from the thread that created it, which is what the
+/// [`ThreadLocal`][crate::ThreadLocal] scope guarantees to its clients without any locking.
+///
+/// Accessing the value from another thread panics, see [`ThreadBound::try_get()`].
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use dill::*;
+///
+/// // Stands for a type that is `Send` but not `Sync`
+/// struct Handle {
+///     calls: Cell<u32>,
+/// }
+///
+/// #[component]
+/// #[scope(ThreadLocal)]
+/// fn handle() -> ThreadBound<Handle> {
+///     ThreadBound::new(Handle { calls: Cell::new(0) })
+/// }
+///
+/// let cat = CatalogBuilder::new().add_builder(HandleBuilder::new()).build();
+///
+/// let handle = cat.get_one::<ThreadBound<Handle>>().unwrap();
+/// handle.calls.set(handle.calls.get() + 1);
+/// assert_eq!(cat.get_one::<ThreadBound<Handle>>().unwrap().calls.get(), 1);
+///
+/// let other = std::thread::spawn(move || handle.try_get().is_none()).join().unwrap();
+/// assert!(other);
+/// ```
+pub struct ThreadBound<T> {
+    value: T,
+    owner: ThreadId,
+}
+
+impl<T> ThreadBound<T> {
+    /// Binds the value to the current thread
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            owner: std::thread::current().id(),
+        }
+    }
+
+    /// Returns the value if called from the thread that created it
+    pub fn try_get(&self) -> Option<&T> {
+        if self.is_owned() {
+            Some(&self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value, panicking if called from a thread other than the one that created it
+    pub fn get(&self) -> &T {
+        match self.try_get() {
+            Some(value) => value,
+            None => panic!(
+                "Value of type {} is bound to another thread",
+                std::any::type_name::<T>()
+            ),
+        }
+    }
+
+    /// Exclusive access doesn't race with the owner thread, so it's allowed from any thread
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Whether the value can be accessed from the current thread
+    pub fn is_owned(&self) -> bool {
+        std::thread::current().id() == self.owner
+    }
+}
+
+impl<T> std::ops::Deref for ThreadBound<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+// SAFETY: shared references to the value are only handed out on the owner thread, so it's never
+// accessed from multiple threads, while `T: Send` allows it to be moved and dropped on any thread
+unsafe impl<T: Send> Sync for ThreadBound<T> {}
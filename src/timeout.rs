@@ -0,0 +1,108 @@
+use std::{
+    any::type_name,
+    future::Future,
+    pin::Pin,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+use crate::sync::{lock, Mutex};
+use crate::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+impl Catalog {
+    /// Resolves a dependency asynchronously (see [`Catalog::get_async()`]), failing with
+    /// [`InjectionError::Timeout`] if the resolution doesn't complete within the specified
+    /// duration, e.g. when a constructor hangs on a slow connection or waits for a lock held
+    /// by another resolution. The error names the chain of types whose construction got stuck.
+    ///
+    /// The deadline is tracked by a watchdog thread, so it works with any executor. Note that
+    /// the resolution is dropped on timeout, so the hung constructors are cancelled at their
+    /// next suspension point, while the ones blocking the thread are not interrupted.
+    pub async fn get_async_timeout<Spec>(
+        &self,
+        timeout: Duration,
+    ) -> Result<Spec::ReturnType, InjectionError>
+    where
+        Spec: AsyncDependencySpec + 'static,
+    {
+        let watch = Arc::new(ResolutionWatch::default());
+        let cat = self.watched(watch.clone());
+
+        // Keeps the resolution alive until the frames it got stuck at are collected
+        let mut resolution = Deadline::new(Box::pin(cat.get_async::<Spec>()), timeout);
+        match (&mut resolution).await {
+            Some(res) => res,
+            None => Err(InjectionError::timeout(
+                type_name::<Spec>(),
+                timeout,
+                watch.stuck_chain(),
+            )),
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+struct Alarm {
+    expired: bool,
+    waker: Option<Waker>,
+}
+
+/// Completes with `None` if the future doesn't complete before the watchdog thread fires
+struct Deadline<F> {
+    fut: F,
+    alarm: Arc<Mutex<Alarm>>,
+    // Disconnecting the channel stops the watchdog as soon as the future completes
+    _stop: mpsc::Sender<()>,
+}
+
+impl<F: Future + Unpin> Deadline<F> {
+    fn new(fut: F, timeout: Duration) -> Self {
+        let alarm: Arc<Mutex<Alarm>> = Arc::default();
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let watchdog_alarm = alarm.clone();
+        std::thread::Builder::new()
+            .name("dill-watchdog".to_owned())
+            .spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(timeout) {
+                    let mut alarm = lock(&watchdog_alarm);
+                    alarm.expired = true;
+                    if let Some(waker) = alarm.waker.take() {
+                        waker.wake();
+                    }
+                }
+            })
+            .expect("Failed to spawn the resolution watchdog thread");
+
+        Self {
+            fut,
+            alarm,
+            _stop: stop,
+        }
+    }
+}
+
+impl<F: Future + Unpin> Future for Deadline<F> {
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(res) = Pin::new(&mut self.fut).poll(cx) {
+            return Poll::Ready(Some(res));
+        }
+
+        let mut alarm = lock(&self.alarm);
+        if alarm.expired {
+            return Poll::Ready(None);
+        }
+        alarm.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
@@ -0,0 +1,77 @@
+use std::future::Future;
+
+use crate::Catalog;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+tokio::task_local! {
+    static CURRENT: Catalog;
+}
+
+impl Catalog {
+    /// Runs the future with this catalog handle stored in a tokio task-local, so that the code
+    /// it calls can retrieve the catalog via [`current_catalog()`] instead of receiving it
+    /// through every function signature, e.g. to run an HTTP request handler with the handle
+    /// returned by [`Catalog::enter_scope()`].
+    ///
+    /// Scopes can be nested, in which case the innermost catalog is the current one until its
+    /// future completes. Tasks spawned by the future don't inherit the catalog, see
+    /// [`spawn_scoped()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dill::*;
+    ///
+    /// async fn handle_request() -> u16 {
+    ///     *current_catalog().get_one::<u16>().unwrap()
+    /// }
+    ///
+    /// let cat = CatalogBuilder::new().add_value(8080u16).build();
+    ///
+    /// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// assert_eq!(rt.block_on(cat.run_scoped(handle_request())), 8080);
+    /// ```
+    pub fn run_scoped<F>(&self, fut: F) -> impl Future<Output = F::Output>
+    where
+        F: Future,
+    {
+        CURRENT.scope(self.clone(), fut)
+    }
+}
+
+/// Returns the catalog of the enclosing [`Catalog::run_scoped()`] or `None` if called outside
+/// of it, see [`current_catalog()`]
+pub fn try_current_catalog() -> Option<Catalog> {
+    CURRENT.try_with(|cat| cat.clone()).ok()
+}
+
+/// Returns the catalog of the enclosing [`Catalog::run_scoped()`] of the current task,
+/// including from the synchronous functions it calls.
+///
+/// # Panics
+///
+/// Panics if called outside of [`Catalog::run_scoped()`], see [`try_current_catalog()`] for
+/// a non-panicking version.
+pub fn current_catalog() -> Catalog {
+    try_current_catalog()
+        .expect("No current catalog, the task has to be run via Catalog::run_scoped()")
+}
+
+/// Spawns a tokio task that runs with the current catalog (see [`current_catalog()`]) if there
+/// is one, as task-locals don't propagate to the spawned tasks on their own.
+///
+/// # Panics
+///
+/// Same as [`tokio::spawn()`][tokio::task::spawn()], if called outside of a tokio runtime.
+#[cfg(not(feature = "unsync"))]
+pub fn spawn_scoped<F>(fut: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match try_current_catalog() {
+        Some(cat) => tokio::task::spawn(cat.run_scoped(fut)),
+        None => tokio::task::spawn(fut),
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Map keyed by a [`TypeId`](std::any::TypeId) wrapper, used for the lookups on the resolution
+/// hot path. The registry is built once and never mutated, so reads require no locking.
+pub(crate) type TypeIdMap<K, V> = HashMap<K, V, BuildHasherDefault<TypeIdHasher>>;
+
+/// Passes the [`TypeId`](std::any::TypeId) through as is, as it is already a hash of the type
+/// and hashing it again with the default SipHash only slows the lookups down
+#[derive(Default)]
+pub(crate) struct TypeIdHasher(u64);
+
+impl Hasher for TypeIdHasher {
+    fn write_u64(&mut self, n: u64) {
+        self.0 = n;
+    }
+
+    // `TypeId` is hashed as a single integer, but other keys are still hashed correctly
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*b);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -1,8 +1,4 @@
-use std::{
-    any::{Any, TypeId},
-    marker::PhantomData,
-    sync::Arc,
-};
+use std::{any::TypeId, marker::PhantomData, sync::Arc};
 
 use crate::*;
 
@@ -12,11 +8,56 @@ use crate::*;
 pub(crate) struct Binding {
     pub caster: Arc<AnyTypeCaster>,
     pub builder: Arc<dyn Builder>,
+    /// Type name of the interface, see [`Catalog::components()`]
+    pub iface_name: &'static str,
+    pub name: Option<String>,
+    /// Decorators of the interface, see [`CatalogBuilder::add_decorator()`]
+    pub decorators: Vec<Arc<AnyDecorator>>,
+    /// Metadata of the implementation, see [`Registration::with_meta()`]
+    pub metadata: Metadata,
 }
 
 impl Binding {
-    pub(crate) fn new(caster: Arc<AnyTypeCaster>, builder: Arc<dyn Builder>) -> Self {
-        Self { caster, builder }
+    pub(crate) fn new(
+        caster: Arc<AnyTypeCaster>,
+        builder: Arc<dyn Builder>,
+        iface_name: &'static str,
+    ) -> Self {
+        Self {
+            caster,
+            builder,
+            iface_name,
+            name: None,
+            decorators: Vec::new(),
+            metadata: Metadata::default(),
+        }
+    }
+
+    pub(crate) fn with_name(self, name: String) -> Self {
+        Self {
+            name: Some(name),
+            ..self
+        }
+    }
+
+    /// Returns the builder casting the instances to the interface of the binding
+    ///
+    /// # Safety
+    ///
+    /// The binding has to be created for the `Iface` type
+    pub(crate) unsafe fn typecast<'a, Iface: 'static + ?Sized>(
+        &'a self,
+        owner: Option<&'a Catalog>,
+    ) -> TypecastBuilder<'a, Iface> {
+        let caster: &TypeCaster<Iface> = cast_erased(self.caster.as_ref());
+        TypecastBuilder::new(
+            self.builder.as_ref(),
+            caster,
+            self.name.as_deref(),
+            &self.decorators,
+            &self.metadata,
+            owner,
+        )
     }
 }
 
@@ -29,6 +70,11 @@ where
 {
     builder: &'a dyn Builder,
     caster: &'a TypeCaster<Iface>,
+    name: Option<&'a str>,
+    decorators: &'a [Arc<AnyDecorator>],
+    metadata: &'a Metadata,
+    /// Parent catalog that the builder belongs to, which it should resolve dependencies from
+    owner: Option<&'a Catalog>,
 }
 
 impl<'a, Iface> Builder for TypecastBuilder<'a, Iface>
@@ -43,8 +89,65 @@ where
         self.builder.instance_type_name()
     }
 
-    fn get(&self, cat: &Catalog) -> Result<Arc<dyn Any + Send + Sync>, InjectionError> {
-        self.builder.get(cat)
+    fn scope_name(&self) -> &'static str {
+        self.builder.scope_name()
+    }
+
+    fn scope_was_explicit(&self) -> bool {
+        self.builder.scope_was_explicit()
+    }
+
+    fn is_catalog_wide(&self) -> bool {
+        self.builder.is_catalog_wide()
+    }
+
+    fn tags(&self) -> &[&'static str] {
+        self.builder.tags()
+    }
+
+    fn profiles(&self) -> &[&'static str] {
+        self.builder.profiles()
+    }
+
+    fn is_eager(&self) -> bool {
+        self.builder.is_eager()
+    }
+
+    fn priority(&self) -> i32 {
+        self.builder.priority()
+    }
+
+    fn dependencies(&self) -> Vec<DependencyInfo> {
+        self.builder.dependencies()
+    }
+
+    fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+        let _guard = cat.guard_depth(self.builder)?;
+        self.builder.get(&self.enter(cat)?)
+    }
+
+    fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+        match self.owner {
+            Some(owner) => self.builder.cached(&cat.rebased(owner)),
+            None => self.builder.cached(cat),
+        }
+    }
+
+    fn invalidate(&self, cat: &Catalog) {
+        match self.owner {
+            Some(owner) => self.builder.invalidate(&cat.rebased(owner)),
+            None => self.builder.invalidate(cat),
+        }
+    }
+
+    fn get_async<'b>(
+        &'b self,
+        cat: &'b Catalog,
+    ) -> BoxFuture<'b, Result<Shared<SharedAny>, InjectionError>> {
+        Box::pin(async move {
+            let cat = self.enter(cat)?;
+            self.builder.get_async(&cat).await
+        })
     }
 }
 
@@ -52,36 +155,138 @@ impl<'a, Iface> TypecastBuilder<'a, Iface>
 where
     Iface: 'static + ?Sized,
 {
-    fn new(builder: &'a dyn Builder, caster: &'a TypeCaster<Iface>) -> Self {
-        Self { builder, caster }
+    fn new(
+        builder: &'a dyn Builder,
+        caster: &'a TypeCaster<Iface>,
+        name: Option<&'a str>,
+        decorators: &'a [Arc<AnyDecorator>],
+        metadata: &'a Metadata,
+        owner: Option<&'a Catalog>,
+    ) -> Self {
+        Self {
+            builder,
+            caster,
+            name,
+            decorators,
+            metadata,
+            owner,
+        }
+    }
+
+    /// Returns the catalog handle to resolve the dependencies of the instance with
+    fn enter(&self, cat: &Catalog) -> Result<Catalog, InjectionError> {
+        match self.owner {
+            Some(owner) => cat.rebased(owner).enter(self.builder),
+            None => cat.enter(self.builder),
+        }
+    }
+
+    /// Name of the binding, if it was registered via [`CatalogBuilder::bind_named()`]
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    /// Metadata attached to the implementation via [`Registration::with_meta()`]
+    pub fn metadata(&self) -> &'a Metadata {
+        self.metadata
+    }
+
+    pub fn get(&self, cat: &Catalog) -> Result<Shared<Iface>, InjectionError> {
+        let inst = Builder::get(self, cat)?;
+        Ok(self.decorate((self.caster.cast_arc)(inst), cat))
+    }
+
+    /// Returns the instance cached by the scope, if any, without applying the decorators
+    pub fn cached(&self, cat: &Catalog) -> Option<Shared<Iface>> {
+        Builder::cached(self, cat).map(|inst| (self.caster.cast_arc)(inst))
+    }
+
+    pub async fn get_async(&self, cat: &Catalog) -> Result<Shared<Iface>, InjectionError> {
+        let inst = Builder::get_async(self, cat).await?;
+        Ok(self.decorate((self.caster.cast_arc)(inst), cat))
+    }
+
+    /// Builds an instance owned by the caller, without applying the decorators, see [`Owned`]
+    pub fn get_owned(&self, cat: &Catalog) -> Result<Box<Iface>, InjectionError> {
+        let inst = Builder::get(self, cat)?;
+        self.take_ownership(inst)
+    }
+
+    /// Asynchronous counterpart of [`TypecastBuilder::get_owned()`]
+    pub async fn get_owned_async(&self, cat: &Catalog) -> Result<Box<Iface>, InjectionError> {
+        let inst = Builder::get_async(self, cat).await?;
+        self.take_ownership(inst)
+    }
+
+    fn take_ownership(&self, inst: Shared<SharedAny>) -> Result<Box<Iface>, InjectionError> {
+        (self.caster.cast_box)(inst).map_err(|_| {
+            InjectionError::shared_instance(self.instance_type_name(), self.scope_name())
+        })
     }
 
-    pub fn get(&self, cat: &Catalog) -> Result<Arc<Iface>, InjectionError> {
-        let inst = self.builder.get(cat)?;
-        Ok((self.caster.cast_arc)(inst))
+    /// Wraps the instance into all decorators of the interface, in the order of registration
+    fn decorate(&self, inst: Shared<Iface>, cat: &Catalog) -> Shared<Iface> {
+        self.decorators.iter().fold(inst, |inst, d| {
+            // SAFETY: decorators are stored in bindings of the matching `Iface` type only
+            let decorator: &Decorator<Iface> = unsafe { cast_erased(d.as_ref()) };
+            (decorator.decorate)(inst, cat)
+        })
     }
 }
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
 pub(crate) struct TypeCaster<Into: ?Sized> {
-    pub cast_arc: fn(Arc<dyn Any + Send + Sync>) -> Arc<Into>,
+    pub cast_arc: Box<dyn CastFn<Shared<Into>>>,
+    /// Moves the instance into a box, returning it back if it's referenced elsewhere
+    pub cast_box: Box<dyn CastFn<Result<Box<Into>, Shared<SharedAny>>>>,
 }
 
-pub(crate) type AnyTypeCaster = dyn Any + Send + Sync;
+/// Casters are closures rather than plain functions so that they can be composed,
+/// see [`CatalogBuilder::bind_upcast()`]
+pub(crate) trait CastFn<T>: Fn(Shared<SharedAny>) -> T + MaybeSendSync {}
+
+impl<T, F> CastFn<T> for F where F: Fn(Shared<SharedAny>) -> T + MaybeSendSync {}
+
+pub(crate) type AnyTypeCaster = SharedAny;
+
+/// Casts a type-erased value of `dill` itself (e.g. a [`TypeCaster`]) to the type guaranteed
+/// by its key. Unlike `downcast_ref()` doesn't compare the ids of the types, as they differ
+/// between the copies of `dill` linked into the application and into the plugins that register
+/// the values, see `CatalogBuilder::load_plugin()`.
+///
+/// # Safety
+///
+/// The value has to be of type `T`
+pub(crate) unsafe fn cast_erased<T>(value: &SharedAny) -> &T {
+    &*(value as *const SharedAny as *const T)
+}
+
+pub(crate) struct Decorator<Iface: ?Sized> {
+    pub decorate: Box<DecorateFn<Iface>>,
+}
+
+#[cfg(not(feature = "unsync"))]
+pub(crate) type DecorateFn<Iface> = dyn Fn(Shared<Iface>, &Catalog) -> Shared<Iface> + Send + Sync;
+#[cfg(feature = "unsync")]
+pub(crate) type DecorateFn<Iface> = dyn Fn(Shared<Iface>, &Catalog) -> Shared<Iface>;
+
+pub(crate) type AnyDecorator = SharedAny;
 
 /////////////////////////////////////////////////////////////////////////////////////////
 
 pub(crate) struct TypecastBuilderIterator<'a, Iface: 'static + ?Sized> {
     bindings: Option<&'a Vec<Binding>>,
+    owner: Option<&'a Catalog>,
     pos: usize,
     _dummy: PhantomData<Iface>,
 }
 
 impl<'a, Iface: 'static + ?Sized> TypecastBuilderIterator<'a, Iface> {
-    pub(crate) fn new(bindings: Option<&'a Vec<Binding>>) -> Self {
+    pub(crate) fn new(bindings: Option<&'a Vec<Binding>>, owner: Option<&'a Catalog>) -> Self {
         Self {
             bindings,
+            owner,
             pos: 0,
             _dummy: PhantomData,
         }
@@ -96,8 +301,7 @@ impl<'a, Iface: 'static + ?Sized> Iterator for TypecastBuilderIterator<'a, Iface
             self.pos += 1;
             bindings.get(prev_pos).map(|b| {
                 // SAFETY: the TypeID key of the `bindings` map is guaranteed to match the `Iface` type
-                let caster: &TypeCaster<Iface> = b.caster.downcast_ref().unwrap();
-                TypecastBuilder::new(b.builder.as_ref(), caster)
+                unsafe { b.typecast(self.owner) }
             })
         } else {
             None
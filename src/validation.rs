@@ -0,0 +1,34 @@
+use std::fmt::Display;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+/// Implemented by components that check their invariants once constructed, e.g. that the
+/// configured limits are consistent. The check is opted into via `#[component(validate)]`
+/// and fails the resolution with [`InjectionError::Validation`][crate::InjectionError::Validation].
+pub trait Validate {
+    /// Returns the reason why the instance is not valid
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Outcome of a function validating a component argument, see `#[dill(validate = "...")]`.
+/// Implemented for `bool` and for `Result<(), E>` with a displayable error.
+pub trait ValidationOutcome {
+    /// Converts the outcome into a result with an optional reason of the failure
+    fn into_result(self) -> Result<(), Option<String>>;
+}
+
+impl ValidationOutcome for bool {
+    fn into_result(self) -> Result<(), Option<String>> {
+        if self {
+            Ok(())
+        } else {
+            Err(None)
+        }
+    }
+}
+
+impl<E: Display> ValidationOutcome for Result<(), E> {
+    fn into_result(self) -> Result<(), Option<String>> {
+        self.map_err(|e| Some(e.to_string()))
+    }
+}
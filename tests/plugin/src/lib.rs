@@ -0,0 +1,30 @@
+use dill::*;
+
+fn register(b: &mut CatalogBuilder) {
+    b.add_value("plugin".to_owned());
+    if cfg!(feature = "panic") {
+        panic!("Failed to register the plugin");
+    }
+    b.add_value(42u32);
+}
+
+#[cfg(not(feature = "mismatch"))]
+dill::export_plugin!(register);
+
+#[cfg(feature = "mismatch")]
+#[no_mangle]
+pub extern "C" fn dill_plugin_declaration() -> *const PluginDeclaration {
+    const VERSION: &str = "0.0.0+sync (rustc 1.0.0)";
+
+    unsafe extern "C" fn never_called(_builder: *mut std::ffi::c_void) -> bool {
+        unreachable!()
+    }
+
+    static DECLARATION: PluginDeclaration = PluginDeclaration {
+        abi_version: PLUGIN_ABI_VERSION,
+        version: VERSION.as_ptr(),
+        version_len: VERSION.len(),
+        register: never_called,
+    };
+    &DECLARATION
+}
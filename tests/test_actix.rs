@@ -0,0 +1,124 @@
+#![cfg(feature = "dill-actix")]
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use actix_web::body::to_bytes;
+use actix_web::dev::{fn_service, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use actix_web::web::Data;
+use actix_web::{FromRequest, HttpResponse};
+use dill::*;
+
+/// Drives the futures on a tokio runtime, as they might suspend
+fn block_on<F: Future>(fut: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(fut)
+}
+
+trait Greeter: Send + Sync {
+    fn greet(&self, user: &User) -> String;
+}
+
+struct User {
+    name: String,
+}
+
+#[component]
+struct HelloGreeter;
+
+impl Greeter for HelloGreeter {
+    fn greet(&self, user: &User) -> String {
+        format!("hello {}", user.name)
+    }
+}
+
+#[test]
+fn test_injected_extractor() {
+    let cat = CatalogBuilder::new()
+        .add::<HelloGreeter>()
+        .bind::<dyn Greeter, HelloGreeter>()
+        .build();
+
+    let (req, mut payload) = TestRequest::default().to_http_parts();
+    let res = block_on(Injected::<dyn Greeter>::from_request(&req, &mut payload));
+    assert!(res.is_err());
+
+    // Falls back to the catalog registered as app data
+    let (req, mut payload) = TestRequest::default()
+        .app_data(Data::new(cat))
+        .to_http_parts();
+
+    let greeter = block_on(Injected::<dyn Greeter>::from_request(&req, &mut payload)).unwrap();
+    let user = User {
+        name: "world".to_owned(),
+    };
+    assert_eq!(greeter.greet(&user), "hello world");
+
+    let res = block_on(Injected::<User>::from_request(&req, &mut payload));
+    let err = res.err().unwrap();
+    assert!(err.to_string().contains(std::any::type_name::<User>()));
+
+    // Clients don't get the details naming the internal types
+    let response = err.error_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = block_on(to_bytes(response.into_body())).unwrap();
+    assert_eq!(body, "Internal Server Error");
+}
+
+#[test]
+fn test_request_scope_middleware() {
+    #[component]
+    #[scope(Scoped)]
+    struct RequestState {}
+
+    let cat = CatalogBuilder::new()
+        .add::<HelloGreeter>()
+        .bind::<dyn Greeter, HelloGreeter>()
+        .add::<RequestState>()
+        .build();
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let handler = {
+        let seen = seen.clone();
+        fn_service(move |req: ServiceRequest| {
+            let seen = seen.clone();
+            async move {
+                let (req, mut payload) = req.into_parts();
+                let greeter = Injected::<dyn Greeter>::from_request(&req, &mut payload).await?;
+                let user = Injected::<User>::from_request(&req, &mut payload).await?;
+                let s1 = Injected::<RequestState>::from_request(&req, &mut payload).await?;
+                let s2 = Injected::<RequestState>::from_request(&req, &mut payload).await?;
+                seen.lock()
+                    .unwrap()
+                    .push((greeter.greet(&user), s1.0, s2.0));
+                Ok::<_, actix_web::Error>(ServiceResponse::new(req, HttpResponse::Ok().finish()))
+            }
+        })
+    };
+
+    let middleware = RequestScope::new(cat).with_request_components(|b, req| {
+        let name = req.headers()["x-user"].to_str().unwrap();
+        b.add_value(User {
+            name: name.to_owned(),
+        });
+    });
+    let svc = block_on(middleware.new_transform(handler)).unwrap();
+
+    for user in ["alice", "bob"] {
+        let req = TestRequest::default()
+            .insert_header(("x-user", user))
+            .to_srv_request();
+        block_on(svc.call(req)).unwrap();
+    }
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0].0, "hello alice");
+    assert_eq!(seen[1].0, "hello bob");
+    assert!(Arc::ptr_eq(&seen[0].1, &seen[0].2));
+    assert!(!Arc::ptr_eq(&seen[0].1, &seen[1].1));
+}
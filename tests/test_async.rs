@@ -0,0 +1,585 @@
+#![cfg(not(feature = "unsync"))]
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use dill::*;
+
+fn noop_waker() -> Waker {
+    fn noop_raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+
+    unsafe { Waker::from_raw(noop_raw_waker()) }
+}
+
+/// Minimal executor sufficient for futures that never actually suspend
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = Box::pin(fut);
+    loop {
+        if let Poll::Ready(res) = fut.as_mut().poll(&mut cx) {
+            return res;
+        }
+    }
+}
+
+#[test]
+fn test_async_new() {
+    #[component]
+    struct Config {
+        url: String,
+    }
+
+    struct Pool {
+        url: String,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    impl Pool {
+        pub async fn new(config: Arc<Config>) -> Self {
+            Self {
+                url: config.url.clone(),
+            }
+        }
+    }
+
+    // Synchronous component that depends on an async one
+    #[component]
+    struct Repo {
+        pool: Arc<Pool>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value("postgres://".to_owned())
+        .add::<Config>()
+        .add::<Pool>()
+        .add::<Repo>()
+        .build();
+
+    assert_eq!(
+        cat.get_one::<Repo>().err(),
+        Some(InjectionError::async_constructor::<Pool>())
+    );
+
+    let repo = block_on(cat.get_async::<OneOf<Repo>>()).unwrap();
+    assert_eq!(repo.pool.url, "postgres://");
+
+    let pool = block_on(cat.get_one_async::<Pool>()).unwrap();
+    assert!(Arc::ptr_eq(&repo.pool, &pool));
+
+    // Once cached by the scope the instance is available synchronously too
+    let pool_sync = cat.get_one::<Pool>().unwrap();
+    assert!(Arc::ptr_eq(&pool, &pool_sync));
+}
+
+#[test]
+fn test_inject_async_fn() {
+    struct Pool {
+        url: String,
+    }
+
+    #[component]
+    impl Pool {
+        pub async fn new(url: String) -> Self {
+            Self { url }
+        }
+    }
+
+    #[dill::inject]
+    async fn migrate(#[dill] pool: Arc<Pool>, version: u32) -> String {
+        format!("{}@{}", pool.url, version)
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value("postgres://".to_owned())
+        .add::<Pool>()
+        .build();
+
+    assert_eq!(
+        block_on(migrate_injected(&cat, 3)).unwrap(),
+        "postgres://@3"
+    );
+}
+
+#[test]
+fn test_async_all_of() {
+    trait Plugin: Send + Sync {}
+
+    struct PluginA;
+    impl Plugin for PluginA {}
+
+    #[component]
+    impl PluginA {
+        pub async fn new() -> Self {
+            Self
+        }
+    }
+
+    #[component]
+    struct PluginB;
+    impl Plugin for PluginB {}
+
+    let cat = CatalogBuilder::new()
+        .add::<PluginA>()
+        .bind::<dyn Plugin, PluginA>()
+        .add::<PluginB>()
+        .bind::<dyn Plugin, PluginB>()
+        .build();
+
+    assert!(cat.get::<AllOf<dyn Plugin>>().is_err());
+    assert_eq!(
+        block_on(cat.get_async::<AllOf<dyn Plugin>>())
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_async_all_of_concurrent() {
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+    static MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+    static POOLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Suspends the specified number of times, e.g. as if waiting for a connection
+    struct Suspend(usize);
+    impl Future for Suspend {
+        type Output = ();
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 == 0 {
+                return Poll::Ready(());
+            }
+            self.0 -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    async fn connect() {
+        let in_flight = IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+        MAX_IN_FLIGHT.fetch_max(in_flight, Ordering::SeqCst);
+        Suspend(3).await;
+        IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    trait Analyzer: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    struct Pool;
+
+    #[component]
+    #[scope(Singleton)]
+    impl Pool {
+        pub async fn new() -> Self {
+            POOLS.fetch_add(1, Ordering::SeqCst);
+            Suspend(3).await;
+            Self
+        }
+    }
+
+    macro_rules! analyzer {
+        ($name:ident $(, $dep:ident: $dep_type:ty)?) => {
+            struct $name;
+            impl Analyzer for $name {
+                fn name(&self) -> &str {
+                    stringify!($name)
+                }
+            }
+
+            #[component]
+            impl $name {
+                pub async fn new($($dep: $dep_type)?) -> Self {
+                    connect().await;
+                    Self
+                }
+            }
+        };
+    }
+
+    analyzer!(Lint);
+    analyzer!(Format);
+    analyzer!(Audit);
+    analyzer!(Index, _pool: Arc<Pool>);
+    analyzer!(Search, _pool: Arc<Pool>);
+
+    let build = |limit| {
+        CatalogBuilder::new()
+            .max_async_concurrency(limit)
+            .add::<Pool>()
+            .add::<Lint>()
+            .bind::<dyn Analyzer, Lint>()
+            .add::<Index>()
+            .bind::<dyn Analyzer, Index>()
+            .add::<Format>()
+            .bind::<dyn Analyzer, Format>()
+            .add::<Search>()
+            .bind::<dyn Analyzer, Search>()
+            .add::<Audit>()
+            .bind::<dyn Analyzer, Audit>()
+            .build()
+    };
+
+    let cat = build(3);
+    let names: Vec<_> = block_on(cat.get_async::<AllOf<dyn Analyzer>>())
+        .unwrap()
+        .iter()
+        .map(|a| a.name().to_owned())
+        .collect();
+    assert_eq!(names, ["Lint", "Index", "Format", "Search", "Audit"]);
+    assert!((2..=3).contains(&MAX_IN_FLIGHT.load(Ordering::SeqCst)));
+    // Analyzers sharing the pool are not constructed concurrently until it's cached
+    assert_eq!(POOLS.load(Ordering::SeqCst), 1);
+
+    MAX_IN_FLIGHT.store(0, Ordering::SeqCst);
+    let cat = build(1);
+    assert_eq!(
+        block_on(cat.get_async::<AllOf<dyn Analyzer>>())
+            .unwrap()
+            .len(),
+        5
+    );
+    assert_eq!(MAX_IN_FLIGHT.load(Ordering::SeqCst), 1);
+    assert_eq!(POOLS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_get_async_concurrent_singleton() {
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static POOLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Suspends once, letting the other resolution start meanwhile
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                Poll::Pending
+            }
+        }
+    }
+
+    struct Pool;
+
+    #[component]
+    #[scope(Singleton)]
+    impl Pool {
+        pub async fn new() -> Self {
+            POOLS.fetch_add(1, Ordering::SeqCst);
+            YieldOnce(false).await;
+            Self
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<Pool>().build();
+
+    let waker = noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    let mut first = Box::pin(cat.get_one_async::<Pool>());
+    let mut second = Box::pin(cat.get_one_async::<Pool>());
+    assert!(first.as_mut().poll(&mut cx).is_pending());
+    assert!(second.as_mut().poll(&mut cx).is_pending());
+
+    let first = match first.as_mut().poll(&mut cx) {
+        Poll::Ready(res) => res.unwrap(),
+        Poll::Pending => unreachable!(),
+    };
+    let second = match second.as_mut().poll(&mut cx) {
+        Poll::Ready(res) => res.unwrap(),
+        Poll::Pending => unreachable!(),
+    };
+
+    // Both were constructed, but the one cached first is shared by all callers
+    assert_eq!(POOLS.load(Ordering::SeqCst), 2);
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(Arc::ptr_eq(&first, &cat.get_one::<Pool>().unwrap()));
+}
+
+#[test]
+fn test_shutdown_async() {
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<&'static str>>);
+
+    #[component]
+    #[interface(dyn AsyncShutdown)]
+    #[scope(Singleton)]
+    struct Pool {
+        log: Arc<Log>,
+    }
+
+    impl AsyncShutdown for Pool {
+        fn shutdown_async(&self) -> BoxFuture<'_, ()> {
+            Box::pin(async move { self.log.0.lock().unwrap().push("pool") })
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Singleton)]
+    struct Repo {
+        pool: Arc<Pool>,
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Repo {
+        fn shutdown(&self) {
+            self.log.0.lock().unwrap().push("repo");
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(Log::default())
+        .add::<Pool>()
+        .add::<Repo>()
+        .build();
+
+    let repo = cat.get_one::<Repo>().unwrap();
+
+    // Synchronous shutdown skips the async components
+    cat.shutdown();
+    assert_eq!(*repo.log.0.lock().unwrap(), ["repo"]);
+
+    repo.log.0.lock().unwrap().clear();
+    block_on(cat.shutdown_async());
+    assert_eq!(*repo.log.0.lock().unwrap(), ["repo", "pool"]);
+}
+
+#[test]
+fn test_publish_events() {
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<String>>);
+
+    struct UserCreated(&'static str);
+    struct UserDeleted(&'static str);
+
+    #[component]
+    #[interface(dyn EventHandler<UserCreated>)]
+    #[interface(dyn EventHandler<UserDeleted>)]
+    struct AuditLog {
+        log: Arc<Log>,
+    }
+
+    impl EventHandler<UserCreated> for AuditLog {
+        fn handle(&self, event: &UserCreated) {
+            self.log
+                .0
+                .lock()
+                .unwrap()
+                .push(format!("audit created {}", event.0));
+        }
+    }
+
+    impl EventHandler<UserDeleted> for AuditLog {
+        fn handle(&self, event: &UserDeleted) {
+            self.log
+                .0
+                .lock()
+                .unwrap()
+                .push(format!("audit deleted {}", event.0));
+        }
+    }
+
+    #[component]
+    #[interface(dyn AsyncEventHandler<UserCreated>)]
+    struct WelcomeMailer {
+        log: Arc<Log>,
+    }
+
+    impl AsyncEventHandler<UserCreated> for WelcomeMailer {
+        fn handle_async<'a>(&'a self, event: &'a UserCreated) -> BoxFuture<'a, ()> {
+            Box::pin(async move {
+                self.log
+                    .0
+                    .lock()
+                    .unwrap()
+                    .push(format!("welcome {}", event.0));
+            })
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(Log::default())
+        .add::<AuditLog>()
+        .add::<WelcomeMailer>()
+        .build();
+    let log = cat.get_one::<Log>().unwrap();
+
+    // Synchronous publishing skips the async handlers
+    cat.publish(&UserCreated("alice")).unwrap();
+    cat.publish(&UserDeleted("bob")).unwrap();
+    assert_eq!(
+        *log.0.lock().unwrap(),
+        ["audit created alice", "audit deleted bob"]
+    );
+
+    log.0.lock().unwrap().clear();
+    block_on(cat.publish_async(&UserCreated("carol"))).unwrap();
+    assert_eq!(
+        *log.0.lock().unwrap(),
+        ["audit created carol", "welcome carol"]
+    );
+
+    // Events without handlers are dropped
+    struct Unhandled;
+    cat.publish(&Unhandled).unwrap();
+}
+
+#[test]
+fn test_scope_async() {
+    use std::pin::Pin;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<&'static str>>);
+
+    #[component]
+    #[interface(dyn AsyncShutdown)]
+    #[scope(Scoped)]
+    struct Session {
+        log: Arc<Log>,
+    }
+
+    impl AsyncShutdown for Session {
+        fn shutdown_async(&self) -> BoxFuture<'_, ()> {
+            Box::pin(async move { self.log.0.lock().unwrap().push("session async") })
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Scoped)]
+    struct Transaction {
+        session: Arc<Session>,
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Transaction {
+        fn shutdown(&self) {
+            self.log.0.lock().unwrap().push("tx");
+        }
+    }
+
+    /// Suspends once, giving a chance to cancel the surrounding future
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                Poll::Pending
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(Log::default())
+        .add::<Session>()
+        .add::<Transaction>()
+        .build();
+    let log = cat.get_one::<Log>().unwrap();
+
+    let tx = block_on(cat.scope_async(|cat| async move {
+        YieldOnce(false).await;
+        cat.get_one::<Transaction>().unwrap()
+    }));
+    assert_eq!(*log.0.lock().unwrap(), ["tx", "session async"]);
+    assert_eq!(Arc::strong_count(&tx), 1);
+
+    // Cancelled scope only runs the synchronous hooks
+    log.0.lock().unwrap().clear();
+    let mut fut = Box::pin(cat.scope_async(|cat| async move {
+        cat.get_one::<Transaction>().unwrap();
+        YieldOnce(false).await;
+        unreachable!()
+    }));
+
+    let waker = noop_waker();
+    assert!(fut
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker))
+        .is_pending());
+    drop(fut);
+    assert_eq!(*log.0.lock().unwrap(), ["tx"]);
+}
+
+#[test]
+fn test_get_async_timeout() {
+    struct Connection;
+
+    #[component]
+    impl Connection {
+        pub async fn new() -> Self {
+            // Server that never responds
+            std::future::pending::<()>().await;
+            Self
+        }
+    }
+
+    #[component]
+    struct Repo {
+        _conn: Arc<Connection>,
+    }
+
+    #[component]
+    struct Config;
+
+    let cat = CatalogBuilder::new()
+        .add::<Connection>()
+        .add::<Repo>()
+        .add::<Config>()
+        .build();
+
+    let timeout = std::time::Duration::from_millis(50);
+    assert!(block_on(cat.get_async_timeout::<OneOf<Config>>(timeout)).is_ok());
+
+    let err = block_on(cat.get_async_timeout::<OneOf<Repo>>(timeout))
+        .err()
+        .unwrap();
+    assert_eq!(
+        err,
+        InjectionError::timeout(
+            std::any::type_name::<OneOf<Repo>>(),
+            timeout,
+            vec![
+                std::any::type_name::<Repo>(),
+                std::any::type_name::<Connection>(),
+            ],
+        )
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Resolution of {} timed out after 50ms, stuck at: {} -> {}",
+            std::any::type_name::<OneOf<Repo>>(),
+            std::any::type_name::<Repo>(),
+            std::any::type_name::<Connection>(),
+        )
+    );
+}
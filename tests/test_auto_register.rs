@@ -0,0 +1,39 @@
+#![cfg(all(feature = "auto-register", not(feature = "unsync")))]
+
+use std::sync::Arc;
+
+use dill::*;
+
+trait Greeter: Send + Sync {
+    fn greet(&self) -> String;
+}
+
+#[component(register)]
+#[interface(dyn Greeter)]
+#[scope(Singleton)]
+struct GreeterImpl {
+    name: Arc<Name>,
+}
+
+impl Greeter for GreeterImpl {
+    fn greet(&self) -> String {
+        format!("hello {}", self.name.0)
+    }
+}
+
+struct Name(String);
+
+#[component(register)]
+impl Name {
+    pub fn new() -> Self {
+        Self("world".to_owned())
+    }
+}
+
+#[test]
+fn test_auto_register() {
+    let cat = CatalogBuilder::new().auto_register().build();
+
+    let greeter = cat.get_one::<dyn Greeter>().unwrap();
+    assert_eq!(greeter.greet(), "hello world");
+}
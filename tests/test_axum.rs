@@ -0,0 +1,144 @@
+#![cfg(feature = "dill-axum")]
+
+use std::convert::Infallible;
+use std::future::{ready, Future, Ready};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::body::HttpBody;
+use axum::extract::FromRequestParts;
+use axum::http::{Request, StatusCode};
+use axum::response::IntoResponse;
+use dill::*;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Drives the futures on a tokio runtime, as they might suspend
+fn block_on<F: Future>(fut: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(fut)
+}
+
+trait Greeter: Send + Sync {
+    fn greet(&self, user: &User) -> String;
+}
+
+struct User(String);
+
+#[component]
+struct HelloGreeter;
+
+impl Greeter for HelloGreeter {
+    fn greet(&self, user: &User) -> String {
+        format!("hello {}", user.0)
+    }
+}
+
+/// Handler that resolves the greeter from the request catalog
+struct GreetHandler;
+
+impl Service<Request<()>> for GreetHandler {
+    type Response = String;
+    type Error = Infallible;
+    type Future = Ready<Result<String, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<()>) -> Self::Future {
+        let (mut parts, _) = req.into_parts();
+        let Inject(greeter) =
+            block_on(Inject::<dyn Greeter>::from_request_parts(&mut parts, &())).unwrap();
+        let Inject(user) = block_on(Inject::<User>::from_request_parts(&mut parts, &())).unwrap();
+        ready(Ok(greeter.greet(&user)))
+    }
+}
+
+#[test]
+fn test_inject_extractor() {
+    let cat = CatalogBuilder::new()
+        .add::<HelloGreeter>()
+        .bind::<dyn Greeter, HelloGreeter>()
+        .build();
+
+    let (mut parts, _) = Request::builder().body(()).unwrap().into_parts();
+
+    let res = block_on(Inject::<dyn Greeter>::from_request_parts(&mut parts, &()));
+    assert!(matches!(res, Err(InjectRejection::MissingCatalog)));
+
+    parts.extensions.insert(cat);
+    let greeter = block_on(Inject::<dyn Greeter>::from_request_parts(&mut parts, &())).unwrap();
+    assert_eq!(greeter.greet(&User("world".to_owned())), "hello world");
+
+    let res = block_on(Inject::<User>::from_request_parts(&mut parts, &()));
+    let rejection = res.err().unwrap();
+    assert!(matches!(rejection, InjectRejection::Injection(_)));
+
+    // Clients don't get the details naming the internal types
+    let mut response = rejection.into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = block_on(response.body_mut().data()).unwrap().unwrap();
+    assert_eq!(body, "Internal Server Error");
+}
+
+#[test]
+fn test_catalog_layer_per_request_components() {
+    let cat = CatalogBuilder::new()
+        .add::<HelloGreeter>()
+        .bind::<dyn Greeter, HelloGreeter>()
+        .build();
+
+    let mut svc = CatalogLayer::new(cat)
+        .with_request_components(|b, parts| {
+            let user = parts.headers["x-user"].to_str().unwrap();
+            b.add_value(User(user.to_owned()));
+        })
+        .layer(GreetHandler);
+
+    let mut greet = |user: &str| {
+        let req = Request::builder().header("x-user", user).body(()).unwrap();
+        block_on(svc.call(req)).unwrap()
+    };
+
+    assert_eq!(greet("alice"), "hello alice");
+    assert_eq!(greet("bob"), "hello bob");
+}
+
+#[test]
+fn test_catalog_layer_enters_scope() {
+    #[component]
+    #[scope(Scoped)]
+    struct RequestState {}
+
+    struct StateHandler;
+
+    impl Service<Request<()>> for StateHandler {
+        type Response = (Arc<RequestState>, Arc<RequestState>);
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: Request<()>) -> Self::Future {
+            let cat = req.extensions().get::<Catalog>().unwrap();
+            ready(Ok((
+                cat.get_one::<RequestState>().unwrap(),
+                cat.get_one::<RequestState>().unwrap(),
+            )))
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<RequestState>().build();
+    let mut svc = CatalogLayer::new(cat).layer(StateHandler);
+
+    let (a1, a2) = block_on(svc.call(Request::new(()))).unwrap();
+    let (b1, _) = block_on(svc.call(Request::new(()))).unwrap();
+
+    assert!(Arc::ptr_eq(&a1, &a2));
+    assert!(!Arc::ptr_eq(&a1, &b1));
+}
@@ -1,3 +1,5 @@
+#![cfg(not(feature = "unsync"))]
+
 use std::{any::TypeId, sync::Arc};
 
 use dill::*;
@@ -254,3 +256,2328 @@ fn test_new_ctor_by_ref() {
     let inst = cat.get::<OneOf<dyn A>>().unwrap();
     assert_eq!(inst.test(), "aimpl::foo");
 }
+
+#[test]
+fn test_new_ctor_boxed() {
+    trait A: Send + Sync {
+        fn test(&self) -> String;
+    }
+
+    struct AImpl {
+        b: Box<B>,
+    }
+
+    #[component]
+    impl AImpl {
+        pub fn new(bee: Box<B>) -> Self {
+            Self { b: bee }
+        }
+    }
+
+    impl A for AImpl {
+        fn test(&self) -> String {
+            format!("aimpl::{}", self.b.0)
+        }
+    }
+
+    #[derive(Clone)]
+    struct B(String);
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .bind::<dyn A, AImpl>()
+        .add_value(B("foo".to_owned()))
+        .build();
+
+    let inst = cat.get::<OneOf<dyn A>>().unwrap();
+    assert_eq!(inst.test(), "aimpl::foo");
+
+    let cat = CatalogBuilder::new()
+        .add_builder(builder_for::<AImpl>().with_bee(Box::new(B("bar".to_owned()))))
+        .bind::<dyn A, AImpl>()
+        .build();
+
+    let inst = cat.get::<OneOf<dyn A>>().unwrap();
+    assert_eq!(inst.test(), "aimpl::bar");
+}
+
+#[test]
+fn test_new_ctor_boxed_trait_object() {
+    trait Parser: Send + Sync {
+        fn parse(&mut self, line: &str) -> usize;
+    }
+
+    #[component]
+    #[interface(dyn Parser)]
+    struct LineParser {
+        #[dill::default]
+        parsed: usize,
+    }
+
+    impl Parser for LineParser {
+        fn parse(&mut self, line: &str) -> usize {
+            self.parsed += line.len();
+            self.parsed
+        }
+    }
+
+    struct Importer {
+        parser: Box<dyn Parser>,
+    }
+
+    #[component]
+    impl Importer {
+        pub fn new(parser: Box<dyn Parser>) -> Self {
+            Self { parser }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<LineParser>()
+        .add::<Importer>()
+        .build();
+
+    // Every injection receives a fresh instance
+    let mut owned = cat.get::<Owned<dyn Parser>>().unwrap();
+    assert_eq!(owned.parse("abc"), 3);
+    assert_eq!(owned.parse("de"), 5);
+
+    let importer = cat.get_one::<Importer>().unwrap();
+    let mut parser = match Arc::try_unwrap(importer) {
+        Ok(importer) => importer.parser,
+        Err(_) => unreachable!(),
+    };
+    assert_eq!(parser.parse("x"), 1);
+
+    // Instances cached by the scope can't be moved out of the catalog
+    let cat = CatalogBuilder::new()
+        .default_scope::<Singleton>()
+        .add::<LineParser>()
+        .add::<Importer>()
+        .build();
+
+    assert!(matches!(
+        cat.get_one::<Importer>(),
+        Err(InjectionError::SharedInstance(_))
+    ));
+}
+
+#[test]
+fn test_scope_name() {
+    #[component]
+    struct ATransient;
+
+    #[component]
+    #[scope(Singleton)]
+    struct ASingleton;
+
+    struct CustomScope;
+
+    impl CustomScope {
+        fn new() -> Self {
+            Self
+        }
+    }
+
+    impl Scope for CustomScope {
+        fn get(&self, _cat: &Catalog) -> Option<Arc<dyn std::any::Any + Send + Sync>> {
+            None
+        }
+
+        fn set(&self, _cat: &Catalog, _inst: Arc<dyn std::any::Any + Send + Sync>) {}
+    }
+
+    #[component]
+    #[scope(CustomScope)]
+    struct ACustom;
+
+    let cat = CatalogBuilder::new()
+        .add::<ATransient>()
+        .add::<ASingleton>()
+        .add::<ACustom>()
+        .add_value(String::new())
+        .build();
+
+    let scope_name = |type_id| {
+        cat.builders()
+            .find(|b| b.instance_type_id() == type_id)
+            .unwrap()
+            .scope_name()
+    };
+
+    assert_eq!(
+        scope_name(TypeId::of::<ATransient>()),
+        "dill::scopes::Transient"
+    );
+    assert_eq!(
+        scope_name(TypeId::of::<ASingleton>()),
+        "dill::scopes::Singleton"
+    );
+    assert_eq!(
+        scope_name(TypeId::of::<ACustom>()),
+        "test_builder::test_scope_name::CustomScope"
+    );
+    assert_eq!(
+        scope_name(TypeId::of::<String>()),
+        "dill::scopes::Singleton"
+    );
+}
+
+#[test]
+fn test_scope_override() {
+    trait Conn: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Conn)]
+    struct ConnImpl;
+    impl Conn for ConnImpl {}
+
+    #[component]
+    #[scope(Singleton)]
+    struct Cache;
+
+    let cat = CatalogBuilder::new()
+        .add_with_scope::<ConnImpl, Singleton>()
+        .add_builder(Cache::builder().with_scope(Transient::new()))
+        .build();
+
+    // Interface bindings share the overridden scope
+    let conn1 = cat.get_one::<dyn Conn>().unwrap();
+    let conn2 = cat.get_one::<dyn Conn>().unwrap();
+    assert!(Arc::ptr_eq(&conn1, &conn2));
+
+    let cache1 = cat.get_one::<Cache>().unwrap();
+    let cache2 = cat.get_one::<Cache>().unwrap();
+    assert!(!Arc::ptr_eq(&cache1, &cache2));
+
+    let builder = cat
+        .builders()
+        .find(|b| b.instance_type_id() == TypeId::of::<Cache>())
+        .unwrap();
+    assert_eq!(builder.scope_name(), "dill::scopes::Transient");
+    assert!(builder.scope_was_explicit());
+}
+
+#[test]
+fn test_with_args_from() {
+    #[derive(Clone)]
+    struct Config {
+        url: String,
+    }
+
+    struct TestConfig {
+        host: String,
+    }
+
+    impl From<Arc<TestConfig>> for Config {
+        fn from(c: Arc<TestConfig>) -> Self {
+            Self {
+                url: format!("http://{}", c.host),
+            }
+        }
+    }
+
+    #[component]
+    struct AImpl {
+        config: Config,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(builder_for::<AImpl>().with_config_from::<TestConfig>(Into::into))
+        .add_value(TestConfig {
+            host: "test".to_owned(),
+        })
+        .build();
+
+    let inst = cat.get_one::<AImpl>().unwrap();
+    assert_eq!(inst.config.url, "http://test");
+}
+
+#[test]
+fn test_derive_builder() {
+    #[derive(Builder)]
+    #[builder(scope = Singleton)]
+    struct AImpl {
+        name: String,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let inst1 = cat.get_one::<AImpl>().unwrap();
+    let inst2 = cat.get_one::<AImpl>().unwrap();
+    assert_eq!(inst1.name, "foo");
+    assert_eq!(
+        inst1.as_ref() as *const AImpl,
+        inst2.as_ref() as *const AImpl
+    );
+}
+
+#[test]
+fn test_new_ctor_by_trait_object_ref() {
+    trait Repo: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component]
+    struct RepoImpl;
+
+    impl Repo for RepoImpl {
+        fn name(&self) -> String {
+            "repo".to_owned()
+        }
+    }
+
+    struct AImpl {
+        repo_name: String,
+        suffix: String,
+    }
+
+    #[component]
+    impl AImpl {
+        pub fn new(repo: &dyn Repo, suffix: String) -> Self {
+            Self {
+                repo_name: repo.name(),
+                suffix,
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<RepoImpl>()
+        .bind::<dyn Repo, RepoImpl>()
+        .add_builder(builder_for::<AImpl>().with_suffix("foo".to_owned()))
+        .build();
+
+    let inst = cat.get_one::<AImpl>().unwrap();
+    assert_eq!(inst.repo_name, "repo");
+    assert_eq!(inst.suffix, "foo");
+}
+
+#[test]
+fn test_new_ctor_by_ref_overrides() {
+    trait Repo: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    struct RepoImpl(String);
+
+    impl Repo for RepoImpl {
+        fn name(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Counter;
+
+    struct AImpl {
+        repo_name: String,
+        counter: *const Counter,
+    }
+
+    // Raw pointer is only used for identity comparison
+    unsafe impl Send for AImpl {}
+    unsafe impl Sync for AImpl {}
+
+    #[component]
+    impl AImpl {
+        pub fn new(repo: &dyn Repo, counter: &Counter) -> Self {
+            Self {
+                repo_name: repo.name(),
+                counter: counter as *const Counter,
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Counter>()
+        .add_builder(builder_for::<AImpl>().with_repo(Arc::new(RepoImpl("foo".to_owned()))))
+        .build();
+
+    let inst1 = cat.get_one::<AImpl>().unwrap();
+    let inst2 = cat.get_one::<AImpl>().unwrap();
+    assert_eq!(inst1.repo_name, "foo");
+
+    // Referenced singleton is shared between constructions
+    assert_eq!(inst1.counter, inst2.counter);
+    assert_eq!(
+        inst1.counter,
+        cat.get_one::<Counter>().unwrap().as_ref() as *const Counter
+    );
+
+    let cat = CatalogBuilder::new()
+        .add::<Counter>()
+        .add_value("bar".to_owned())
+        .add_builder(builder_for::<AImpl>().with_repo_fn(|cat| {
+            let name = cat.get_one::<String>()?;
+            Ok(Arc::new(RepoImpl(name.as_ref().clone())) as Arc<dyn Repo>)
+        }))
+        .build();
+
+    assert_eq!(cat.get_one::<AImpl>().unwrap().repo_name, "bar");
+
+    // Override satisfies the dependency during validation
+    assert!(CatalogBuilder::new()
+        .add::<Counter>()
+        .add_builder(builder_for::<AImpl>().with_repo(Arc::new(RepoImpl("foo".to_owned()))))
+        .validate()
+        .is_ok());
+}
+
+#[test]
+fn test_zero_dependency_components() {
+    #[component]
+    struct AUnit;
+
+    #[component]
+    struct ABraced {}
+
+    struct ANew {
+        name: String,
+    }
+
+    #[component]
+    impl ANew {
+        pub fn new() -> Self {
+            Self {
+                name: "anew".to_owned(),
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<AUnit>()
+        .add::<ABraced>()
+        .add::<ANew>()
+        .build();
+
+    cat.get_one::<AUnit>().unwrap();
+    cat.get_one::<ABraced>().unwrap();
+    assert_eq!(cat.get_one::<ANew>().unwrap().name, "anew");
+
+    let inst = builder_for::<ANew>().build_fresh(&cat).unwrap();
+    assert_eq!(inst.name, "anew");
+}
+
+#[test]
+fn test_inject_with_conversion() {
+    struct Config {
+        url: String,
+    }
+
+    // Deliberately not `Clone`
+    struct Endpoint {
+        url: String,
+    }
+
+    fn endpoint_from_config(config: Arc<Config>) -> Endpoint {
+        Endpoint {
+            url: config.url.clone(),
+        }
+    }
+
+    #[component]
+    struct AStruct {
+        #[inject(with = endpoint_from_config)]
+        endpoint: Endpoint,
+    }
+
+    struct ANew {
+        url: String,
+    }
+
+    #[component]
+    impl ANew {
+        pub fn new(#[inject(with = endpoint_from_config)] endpoint: Endpoint) -> Self {
+            Self { url: endpoint.url }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(Config {
+            url: "http://foo".to_owned(),
+        })
+        .add::<AStruct>()
+        .add::<ANew>()
+        .build();
+
+    assert_eq!(cat.get_one::<AStruct>().unwrap().endpoint.url, "http://foo");
+    assert_eq!(cat.get_one::<ANew>().unwrap().url, "http://foo");
+}
+
+#[test]
+fn test_all_of_args() {
+    trait Handler: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component]
+    struct HandlerA;
+    impl Handler for HandlerA {
+        fn name(&self) -> String {
+            "a".to_owned()
+        }
+    }
+
+    #[component]
+    struct HandlerB;
+    impl Handler for HandlerB {
+        fn name(&self) -> String {
+            "b".to_owned()
+        }
+    }
+
+    #[component]
+    struct Dispatcher {
+        handlers: Vec<Arc<dyn Handler>>,
+    }
+
+    struct DispatcherNew {
+        handlers: Vec<Arc<dyn Handler>>,
+    }
+
+    #[component]
+    impl DispatcherNew {
+        pub fn new(handlers: Vec<Arc<dyn Handler>>) -> Self {
+            Self { handlers }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<HandlerA>()
+        .bind::<dyn Handler, HandlerA>()
+        .add::<HandlerB>()
+        .bind::<dyn Handler, HandlerB>()
+        .add::<Dispatcher>()
+        .add::<DispatcherNew>()
+        .build();
+
+    let mut names: Vec<_> = cat
+        .get_one::<Dispatcher>()
+        .unwrap()
+        .handlers
+        .iter()
+        .map(|h| h.name())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["a", "b"]);
+
+    assert_eq!(cat.get_one::<DispatcherNew>().unwrap().handlers.len(), 2);
+
+    // No implementations result in an empty list
+    let cat = CatalogBuilder::new().add::<Dispatcher>().build();
+    assert!(cat.get_one::<Dispatcher>().unwrap().handlers.is_empty());
+}
+
+#[test]
+fn test_all_of_lazy_args() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static BUILT: AtomicUsize = AtomicUsize::new(0);
+
+    trait Exporter: Send + Sync {
+        fn format(&self) -> String;
+    }
+
+    struct CsvExporter;
+    #[component]
+    impl CsvExporter {
+        pub fn new() -> Self {
+            BUILT.fetch_add(1, Ordering::Relaxed);
+            Self
+        }
+    }
+    impl Exporter for CsvExporter {
+        fn format(&self) -> String {
+            "csv".to_owned()
+        }
+    }
+
+    struct JsonExporter;
+    #[component]
+    impl JsonExporter {
+        pub fn new() -> Self {
+            BUILT.fetch_add(1, Ordering::Relaxed);
+            Self
+        }
+    }
+    impl Exporter for JsonExporter {
+        fn format(&self) -> String {
+            "json".to_owned()
+        }
+    }
+
+    #[component]
+    struct Report {
+        exporters: Vec<LazyBinding<dyn Exporter>>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<CsvExporter>()
+        .bind_named::<dyn Exporter, CsvExporter>("csv")
+        .add::<JsonExporter>()
+        .bind_named::<dyn Exporter, JsonExporter>("json")
+        .add::<Report>()
+        .build();
+
+    let report = cat.get_one::<Report>().unwrap();
+    assert_eq!(report.exporters.len(), 2);
+    assert_eq!(BUILT.load(Ordering::Relaxed), 0);
+
+    let csv = report
+        .exporters
+        .iter()
+        .find(|e| e.name() == Some("csv"))
+        .unwrap();
+    assert!(csv.type_name().ends_with("CsvExporter"));
+    assert_eq!(csv.get().unwrap().format(), "csv");
+    assert_eq!(BUILT.load(Ordering::Relaxed), 1);
+
+    // The instance is cached by the binding
+    assert_eq!(csv.get().unwrap().format(), "csv");
+    assert_eq!(BUILT.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_keyed_map_args() {
+    use std::collections::HashMap;
+
+    trait Handler: Send + Sync {
+        fn handle(&self) -> String;
+    }
+
+    #[component]
+    struct JsonHandler;
+    impl Handler for JsonHandler {
+        fn handle(&self) -> String {
+            "json".to_owned()
+        }
+    }
+
+    #[component]
+    struct CsvHandler;
+    impl Handler for CsvHandler {
+        fn handle(&self) -> String {
+            "csv".to_owned()
+        }
+    }
+
+    #[component]
+    struct FallbackHandler;
+    impl Handler for FallbackHandler {
+        fn handle(&self) -> String {
+            "fallback".to_owned()
+        }
+    }
+
+    #[component]
+    struct Dispatcher {
+        handlers: HashMap<String, Arc<dyn Handler>>,
+    }
+
+    impl Dispatcher {
+        fn dispatch(&self, ext: &str) -> Option<String> {
+            self.handlers.get(ext).map(|h| h.handle())
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<JsonHandler>()
+        .bind_named::<dyn Handler, JsonHandler>("json")
+        .add::<CsvHandler>()
+        .bind_named::<dyn Handler, CsvHandler>("csv")
+        .add::<FallbackHandler>()
+        .bind::<dyn Handler, FallbackHandler>()
+        .add::<Dispatcher>()
+        .build();
+
+    // Bindings without a name are skipped
+    let dispatcher = cat.get_one::<Dispatcher>().unwrap();
+    assert_eq!(dispatcher.handlers.len(), 2);
+    assert_eq!(dispatcher.dispatch("json"), Some("json".to_owned()));
+    assert_eq!(dispatcher.dispatch("csv"), Some("csv".to_owned()));
+    assert_eq!(dispatcher.dispatch("xml"), None);
+
+    assert_eq!(
+        builder_for::<Dispatcher>().dependencies()[0].kind,
+        DependencyKind::All
+    );
+}
+
+#[test]
+fn test_optional_args() {
+    trait Metrics: Send + Sync {}
+
+    #[component]
+    struct MetricsImpl;
+    impl Metrics for MetricsImpl {}
+
+    #[component]
+    struct Service {
+        metrics: Option<Arc<dyn Metrics>>,
+    }
+
+    let cat = CatalogBuilder::new().add::<Service>().build();
+    assert!(cat.get_one::<Service>().unwrap().metrics.is_none());
+
+    let cat = CatalogBuilder::new()
+        .add::<MetricsImpl>()
+        .bind::<dyn Metrics, MetricsImpl>()
+        .add::<Service>()
+        .build();
+    assert!(cat.get_one::<Service>().unwrap().metrics.is_some());
+}
+
+#[test]
+fn test_new_ctor_fallible() {
+    #[derive(Debug)]
+    struct InvalidPort(u16);
+
+    impl std::fmt::Display for InvalidPort {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid port {}", self.0)
+        }
+    }
+
+    impl std::error::Error for InvalidPort {}
+
+    struct Server {
+        port: u16,
+    }
+
+    #[component]
+    impl Server {
+        pub fn new(port: u16) -> Result<Self, InvalidPort> {
+            if port == 0 {
+                Err(InvalidPort(port))
+            } else {
+                Ok(Self { port })
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(8080u16)
+        .add::<Server>()
+        .build();
+    assert_eq!(cat.get_one::<Server>().unwrap().port, 8080);
+
+    let cat = CatalogBuilder::new()
+        .add_value(0u16)
+        .add::<Server>()
+        .build();
+
+    let err = cat.get_one::<Server>().err().unwrap();
+    let source = match &err {
+        InjectionError::ConstructorFailed(e) => {
+            assert!(e.to_string().contains("invalid port 0"));
+            e.error()
+        }
+        _ => panic!("Unexpected error: {:?}", err),
+    };
+    assert!(source.downcast_ref::<InvalidPort>().is_some());
+
+    // Error chain is preserved
+    use std::error::Error;
+    let root = err.source().and_then(|e| e.source()).unwrap();
+    assert_eq!(root.to_string(), "invalid port 0");
+}
+
+#[test]
+fn test_new_ctor_shared() {
+    use std::sync::Weak;
+
+    // Keeps a weak reference to itself, which requires building the shared instance
+    struct Node {
+        me: Weak<Node>,
+        name: String,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    impl Node {
+        pub fn new(name: String) -> Arc<Self> {
+            Arc::new_cyclic(|me| Self {
+                me: me.clone(),
+                name,
+            })
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value("root".to_owned())
+        .add::<Node>()
+        .build();
+
+    let node = cat.get_one::<Node>().unwrap();
+    assert_eq!(node.name, "root");
+    assert!(Arc::ptr_eq(&node.me.upgrade().unwrap(), &node));
+    assert!(Arc::ptr_eq(&cat.get_one::<Node>().unwrap(), &node));
+
+    let node = NodeBuilder::new()
+        .with_name("standalone")
+        .build_standalone()
+        .unwrap();
+    assert!(Arc::ptr_eq(&node.me.upgrade().unwrap(), &node));
+}
+
+#[test]
+fn test_new_ctor_shared_fallible() {
+    struct Server {
+        port: u16,
+    }
+
+    #[component]
+    impl Server {
+        pub fn new(port: u16) -> Result<Arc<Self>, std::num::TryFromIntError> {
+            Ok(Arc::new(Self {
+                port: u8::try_from(port)?.into(),
+            }))
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(80u16)
+        .add::<Server>()
+        .build();
+    assert_eq!(cat.get_one::<Server>().unwrap().port, 80);
+
+    let cat = CatalogBuilder::new()
+        .add_value(8080u16)
+        .add::<Server>()
+        .build();
+    assert!(matches!(
+        cat.get_one::<Server>(),
+        Err(InjectionError::ConstructorFailed(_))
+    ));
+}
+
+#[test]
+fn test_component_fn() {
+    // Stands for the types defined in another crate
+    mod external {
+        pub struct Client {
+            pub url: String,
+            pub timeout: u64,
+        }
+
+        pub struct Port(pub u16);
+    }
+
+    trait Transport: Send + Sync {
+        fn url(&self) -> String;
+    }
+
+    impl Transport for external::Client {
+        fn url(&self) -> String {
+            self.url.clone()
+        }
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    fn http_client(url: Arc<String>, timeout: u64) -> external::Client {
+        external::Client {
+            url: url.as_ref().clone(),
+            timeout,
+        }
+    }
+
+    #[component]
+    fn parse_port(raw: &String) -> Result<external::Port, std::num::ParseIntError> {
+        Ok(external::Port(raw.parse()?))
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(HttpClientBuilder::new())
+        .bind::<dyn Transport, external::Client>()
+        .add_value("http://localhost".to_owned())
+        .add_value(10u64)
+        .build();
+
+    let client = cat.get_one::<external::Client>().unwrap();
+    assert_eq!(client.url, "http://localhost");
+    assert_eq!(client.timeout, 10);
+    assert_eq!(
+        cat.get_one::<dyn Transport>().unwrap().url(),
+        "http://localhost"
+    );
+    assert!(Arc::ptr_eq(
+        &client,
+        &cat.get_one::<external::Client>().unwrap()
+    ));
+
+    let builder = HttpClientBuilder::new().with_timeout(5);
+    assert_eq!(builder.dependencies().len(), 1);
+
+    let cat = CatalogBuilder::new()
+        .add_builder(builder)
+        .add_builder(ParsePortBuilder::new())
+        .add_value("foo".to_owned())
+        .build();
+
+    assert_eq!(cat.get_one::<external::Client>().unwrap().timeout, 5);
+    assert!(matches!(
+        cat.get_one::<external::Port>().err(),
+        Some(InjectionError::ConstructorFailed(_))
+    ));
+}
+
+#[test]
+fn test_generic_components() {
+    trait Entity: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component]
+    struct User;
+    impl Entity for User {
+        fn name(&self) -> String {
+            "user".to_owned()
+        }
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Repo<T: Entity> {
+        entities: Vec<Arc<T>>,
+    }
+
+    struct Service<T>
+    where
+        T: Entity,
+    {
+        repo: Arc<Repo<T>>,
+    }
+
+    #[component]
+    impl<T> Service<T>
+    where
+        T: Entity,
+    {
+        pub fn new(repo: Arc<Repo<T>>) -> Self {
+            Self { repo }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<User>()
+        .add::<Repo<User>>()
+        .add::<Service<User>>()
+        .build();
+
+    let svc = cat.get_one::<Service<User>>().unwrap();
+    assert_eq!(svc.repo.entities.len(), 1);
+    assert_eq!(svc.repo.entities[0].name(), "user");
+    assert_eq!(
+        svc.repo.as_ref() as *const Repo<User>,
+        cat.get_one::<Repo<User>>().unwrap().as_ref() as *const Repo<User>
+    );
+
+    let repo = builder_for::<Repo<User>>()
+        .with_entities_fn(|_| Ok(Vec::new()))
+        .build_fresh(&cat)
+        .unwrap();
+    assert!(repo.entities.is_empty());
+}
+
+#[test]
+fn test_default_args() {
+    #[derive(Default)]
+    struct Config {
+        retries: u32,
+    }
+
+    struct Client {
+        host: String,
+        port: u16,
+        config: Arc<Config>,
+    }
+
+    #[component]
+    impl Client {
+        pub fn new(
+            #[dill::default = "localhost".to_owned()] host: String,
+            #[dill::default = 8080] port: u16,
+            #[dill::default] config: Arc<Config>,
+        ) -> Self {
+            Self { host, port, config }
+        }
+    }
+
+    let mut cat = CatalogBuilder::new();
+    cat.add::<Client>();
+    cat.validate().unwrap();
+
+    let client = cat.build().get_one::<Client>().unwrap();
+    assert_eq!(client.host, "localhost");
+    assert_eq!(client.port, 8080);
+    assert_eq!(client.config.retries, 0);
+
+    // Registered dependencies take precedence over the defaults
+    let client = CatalogBuilder::new()
+        .add::<Client>()
+        .add_value("example.com".to_owned())
+        .add_value(Config { retries: 3 })
+        .build()
+        .get_one::<Client>()
+        .unwrap();
+    assert_eq!(client.host, "example.com");
+    assert_eq!(client.port, 8080);
+    assert_eq!(client.config.retries, 3);
+}
+
+#[test]
+fn test_after_build_hook() {
+    use std::sync::Mutex;
+
+    struct EventBus {
+        subscribers: Mutex<Vec<String>>,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    impl EventBus {
+        pub fn new() -> Self {
+            Self {
+                subscribers: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[component(after_build = "subscribe")]
+    struct Mailer {
+        bus: Arc<EventBus>,
+        #[dill::default]
+        subscribed: bool,
+    }
+
+    impl Mailer {
+        fn subscribe(&mut self, _cat: &Catalog) -> Result<(), InjectionError> {
+            self.bus
+                .subscribers
+                .lock()
+                .unwrap()
+                .push("mailer".to_owned());
+            self.subscribed = true;
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct NotReady;
+
+    impl std::fmt::Display for NotReady {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "not ready")
+        }
+    }
+
+    impl std::error::Error for NotReady {}
+
+    struct Indexer;
+
+    #[component(after_build = "check")]
+    impl Indexer {
+        pub fn new() -> Self {
+            Self
+        }
+
+        fn check(&self, _cat: &Catalog) -> Result<(), InjectionError> {
+            Err(InjectionError::constructor_failed::<Self>(NotReady))
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<EventBus>()
+        .add::<Mailer>()
+        .add::<Indexer>()
+        .build();
+
+    let mailer = cat.get_one::<Mailer>().unwrap();
+    assert!(mailer.subscribed);
+    assert_eq!(
+        *cat.get_one::<EventBus>()
+            .unwrap()
+            .subscribers
+            .lock()
+            .unwrap(),
+        vec!["mailer".to_owned()]
+    );
+
+    // Failed hooks fail the resolution
+    assert!(matches!(
+        cat.get_one::<Indexer>(),
+        Err(InjectionError::ConstructorFailed(_))
+    ));
+}
+
+#[test]
+fn test_weak_args() {
+    use std::sync::Weak;
+
+    trait Parent: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[component]
+    #[interface(dyn Parent)]
+    #[scope(Singleton)]
+    struct Window;
+    impl Parent for Window {
+        fn name(&self) -> &str {
+            "window"
+        }
+    }
+
+    #[component]
+    struct Button {
+        parent: Weak<dyn Parent>,
+        window: Weak<Window>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Window>()
+        .add::<Button>()
+        .build();
+
+    let button = cat.get_one::<Button>().unwrap();
+    assert_eq!(button.parent.upgrade().unwrap().name(), "window");
+    assert!(Arc::ptr_eq(
+        &button.window.upgrade().unwrap(),
+        &cat.get_one::<Window>().unwrap()
+    ));
+
+    // Weak references don't keep the instance alive
+    cat.invalidate::<Window>();
+    assert!(button.window.upgrade().is_none());
+}
+
+#[test]
+fn test_dill_arg_attrs() {
+    use std::sync::Mutex;
+
+    trait Plugin: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[component]
+    struct PluginA;
+    impl Plugin for PluginA {
+        fn name(&self) -> &str {
+            "a"
+        }
+    }
+
+    #[component]
+    struct PluginB;
+    impl Plugin for PluginB {
+        fn name(&self) -> &str {
+            "b"
+        }
+    }
+
+    #[component]
+    struct Host {
+        #[dill(named = "main")]
+        main: Arc<dyn Plugin>,
+        #[dill(lazy)]
+        plugins: Lazy<Vec<Arc<dyn Plugin>>>,
+        #[dill(skip)]
+        calls: Mutex<u32>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<PluginA>()
+        .bind::<dyn Plugin, PluginA>()
+        .add::<PluginB>()
+        .bind_named::<dyn Plugin, PluginB>("main")
+        .add::<Host>()
+        .build();
+
+    let host = cat.get_one::<Host>().unwrap();
+    assert_eq!(host.main.name(), "b");
+    assert_eq!(*host.calls.lock().unwrap(), 0);
+    assert_eq!(
+        host.plugins
+            .get()
+            .unwrap()
+            .iter()
+            .map(|p| p.name().to_owned())
+            .collect::<Vec<_>>(),
+        vec!["a".to_owned(), "b".to_owned()]
+    );
+
+    // Skipped arguments are not dependencies, while lazy ones are reported as such
+    let deps = builder_for::<Host>().dependencies();
+    assert_eq!(deps.len(), 2);
+    assert_eq!(deps[0].name, Some("main"));
+    assert_eq!(deps[1].kind, DependencyKind::Lazy);
+}
+
+#[test]
+fn test_ctor_selection() {
+    #[component]
+    struct Config {
+        port: u16,
+    }
+
+    struct Server {
+        port: u16,
+        verbose: bool,
+    }
+
+    // Only the selected constructor has its arguments injected
+    #[component(ctor = "with_config")]
+    impl Server {
+        #[allow(dead_code)]
+        pub fn with_port(port: u16) -> Self {
+            Self {
+                port,
+                verbose: false,
+            }
+        }
+
+        pub fn with_config(config: Arc<Config>) -> Self {
+            Self {
+                port: config.port,
+                verbose: true,
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(builder_for::<Config>().with_port(8080))
+        .add::<Server>()
+        .build();
+
+    let server = cat.get_one::<Server>().unwrap();
+    assert_eq!(server.port, 8080);
+    assert!(server.verbose);
+}
+
+#[test]
+fn test_registration_setters() {
+    trait Tls: Send + Sync {
+        fn cert(&self) -> &str;
+    }
+
+    #[component]
+    #[interface(dyn Tls)]
+    struct FileTls;
+    impl Tls for FileTls {
+        fn cert(&self) -> &str {
+            "file"
+        }
+    }
+
+    #[component]
+    struct OverrideTls;
+    impl Tls for OverrideTls {
+        fn cert(&self) -> &str {
+            "override"
+        }
+    }
+
+    #[component]
+    #[interface(dyn Server)]
+    #[scope(Singleton)]
+    struct HttpServer {
+        port: u16,
+        tls: Arc<dyn Tls>,
+    }
+
+    trait Server: Send + Sync {
+        fn port(&self) -> u16;
+    }
+    impl Server for HttpServer {
+        fn port(&self) -> u16 {
+            self.port
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<FileTls>()
+        .add::<HttpServer>()
+        .with_port(8080)
+        .with_tls_fn(|_| Ok(Arc::new(OverrideTls)))
+        .add::<OverrideTls>()
+        .build();
+
+    let server = cat.get_one::<HttpServer>().unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.tls.cert(), "override");
+
+    // Interface bindings refer to the parametrized builder
+    let server_dyn = cat.get_one::<dyn Server>().unwrap();
+    assert_eq!(server_dyn.port(), 8080);
+    assert!(Arc::ptr_eq(&server, &cat.get_one::<HttpServer>().unwrap()));
+
+    // Handles dropped at the end of the statement register the builder as well
+    let mut b = CatalogBuilder::new();
+    b.add::<FileTls>();
+    b.add::<HttpServer>().with_port(9090);
+    let cat = b.build();
+    assert_eq!(cat.get_one::<dyn Server>().unwrap().port(), 9090);
+    assert_eq!(cat.get_one::<HttpServer>().unwrap().tls.cert(), "file");
+}
+
+#[test]
+fn test_context_args() {
+    #[derive(Debug, PartialEq, Eq)]
+    struct Principal(String);
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct CorrelationId(u64);
+
+    #[component]
+    struct Audit {
+        principal: FromContext<Principal>,
+        correlation_id: FromContext<CorrelationId>,
+    }
+
+    #[component]
+    struct Handler {
+        audit: Arc<Audit>,
+        lazy_audit: Lazy<Arc<Audit>>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Audit>()
+        .add::<Handler>()
+        .build();
+
+    let req = cat
+        .with(Principal("alice".to_owned()))
+        .with(CorrelationId(1));
+
+    // Values are visible to the nested and the deferred resolutions
+    let handler = req.get::<OneOf<Handler>>().unwrap();
+    assert_eq!(*handler.audit.principal, Principal("alice".to_owned()));
+    assert_eq!(*handler.audit.correlation_id, CorrelationId(1));
+    assert_eq!(
+        *handler.lazy_audit.get().unwrap().correlation_id,
+        CorrelationId(1)
+    );
+
+    // Most recently attached value shadows the previous one
+    let audit = req.with(CorrelationId(2)).get_one::<Audit>().unwrap();
+    assert_eq!(*audit.correlation_id, CorrelationId(2));
+    assert_eq!(*audit.principal, Principal("alice".to_owned()));
+
+    let err = cat.with(CorrelationId(3)).get_one::<Audit>().err().unwrap();
+    assert_eq!(err, InjectionError::missing_context::<Principal>());
+
+    // Context values don't depend on the registrations
+    assert!(builder_for::<Audit>().dependencies().is_empty());
+    assert!(CatalogBuilder::new().add::<Audit>().validate().is_ok());
+
+    let cat = CatalogBuilder::new()
+        .add_builder(
+            builder_for::<Audit>()
+                .with_principal(FromContext::new(Principal("bob".to_owned())))
+                .with_correlation_id(FromContext::new(CorrelationId(0))),
+        )
+        .build();
+    assert_eq!(
+        *cat.get_one::<Audit>().unwrap().principal,
+        Principal("bob".to_owned())
+    );
+}
+
+#[test]
+fn test_builder_default_and_clone() {
+    #[component]
+    #[scope(Singleton)]
+    struct Db {
+        host: String,
+        port: u16,
+    }
+
+    let default: DbBuilder = Default::default();
+    let cat = CatalogBuilder::new()
+        .add_builder(default.with_host("localhost".to_owned()))
+        .add_value(5432u16)
+        .build();
+    assert_eq!(cat.get_one::<Db>().unwrap().port, 5432);
+
+    // Template builder registered with variations in multiple catalogs
+    let template = Db::builder().with_host("db.example.com".to_owned());
+
+    let cat1 = CatalogBuilder::new()
+        .add_builder(template.clone().with_port(1))
+        .build();
+    let cat2 = CatalogBuilder::new()
+        .add_builder(template.clone().with_port(2))
+        .build();
+
+    let db1 = cat1.get_one::<Db>().unwrap();
+    let db2 = cat2.get_one::<Db>().unwrap();
+    assert_eq!((db1.host.as_str(), db1.port), ("db.example.com", 1));
+    assert_eq!((db2.host.as_str(), db2.port), ("db.example.com", 2));
+
+    // Instances are not shared by the catalogs despite sharing the scope
+    assert!(Arc::ptr_eq(&db1, &cat1.get_one::<Db>().unwrap()));
+    assert!(!Arc::ptr_eq(
+        &db1,
+        &cat1.snapshot().get_one::<Db>().unwrap()
+    ));
+
+    // Clone keeps the scope override
+    let transient = template.with_scope(Transient::new()).with_port(3);
+    let cat = CatalogBuilder::new().add_builder(transient.clone()).build();
+    assert!(!Arc::ptr_eq(
+        &cat.get_one::<Db>().unwrap(),
+        &cat.get_one::<Db>().unwrap()
+    ));
+    assert_eq!(transient.scope_name(), std::any::type_name::<Transient>());
+}
+
+#[test]
+fn test_array_tuple_and_const_generic_args() {
+    #[derive(Clone)]
+    struct Buffer<const N: usize> {
+        data: Vec<u8>,
+    }
+
+    #[component]
+    impl<const N: usize> Buffer<N> {
+        pub fn new() -> Self {
+            Self { data: vec![0; N] }
+        }
+    }
+
+    struct Packet {
+        header: [u8; 4],
+        endpoint: (String, u16),
+        buffer: std::sync::Arc<Buffer<1024>>,
+        copy: Buffer<16>,
+    }
+
+    #[component]
+    impl Packet {
+        pub fn new(
+            header: [u8; 4],
+            endpoint: (String, u16),
+            buffer: std::sync::Arc<Buffer<1024>>,
+            copy: Buffer<16>,
+        ) -> Self {
+            Self {
+                header,
+                endpoint,
+                buffer,
+                copy,
+            }
+        }
+    }
+
+    // Types substituted by `macro_rules!` are wrapped into invisible groups
+    macro_rules! holder {
+        ($name:ident, $t:ty) => {
+            #[component]
+            struct $name {
+                inner: $t,
+            }
+        };
+    }
+
+    holder!(SmallBufferHolder, Arc<Buffer<16>>);
+
+    let cat = CatalogBuilder::new()
+        .add::<Buffer<1024>>()
+        .add::<Buffer<16>>()
+        .add::<Packet>()
+        .add::<SmallBufferHolder>()
+        .add_value([1u8, 2, 3, 4])
+        .add_value(("localhost".to_owned(), 8080u16))
+        .build();
+
+    let packet = cat.get_one::<Packet>().unwrap();
+    assert_eq!(packet.header, [1, 2, 3, 4]);
+    assert_eq!(packet.endpoint, ("localhost".to_owned(), 8080));
+    assert_eq!(packet.buffer.data.len(), 1024);
+    assert_eq!(packet.copy.data.len(), 16);
+
+    let holder = cat.get_one::<SmallBufferHolder>().unwrap();
+    assert_eq!(holder.inner.data.len(), 16);
+
+    // Unknown shapes are dependencies with override setters like any other value
+    let deps: Vec<_> = builder_for::<Packet>()
+        .dependencies()
+        .into_iter()
+        .map(|d| d.type_id)
+        .collect();
+    assert!(deps.contains(&TypeId::of::<[u8; 4]>()));
+    assert!(deps.contains(&TypeId::of::<(String, u16)>()));
+    assert!(deps.contains(&TypeId::of::<Buffer<16>>()));
+
+    let packet = builder_for::<Packet>()
+        .with_header([0; 4])
+        .with_endpoint(("example.com".to_owned(), 443))
+        .build_fresh(&cat)
+        .unwrap();
+    assert_eq!(packet.header, [0; 4]);
+    assert_eq!(packet.endpoint.1, 443);
+}
+
+#[test]
+fn test_generic_ctor_args() {
+    trait Db: Send + Sync {
+        fn url(&self) -> String;
+    }
+
+    #[component]
+    struct PgDb;
+    impl Db for PgDb {
+        fn url(&self) -> String {
+            "postgres://".to_owned()
+        }
+    }
+
+    struct Repo {
+        url: String,
+    }
+
+    #[component]
+    impl Repo {
+        pub fn new(#[dill(concrete = "Arc<PgDb>")] db: Arc<impl Db>) -> Self {
+            Self { url: db.url() }
+        }
+    }
+
+    struct Service {
+        urls: Vec<String>,
+    }
+
+    #[component]
+    impl Service {
+        pub fn new<D: Db, const N: usize>(
+            #[dill(concrete = "Arc<PgDb>")] db: Arc<D>,
+            #[dill(concrete = "[u8; 2]")] replicas: [u8; N],
+        ) -> Self {
+            Self {
+                urls: replicas
+                    .iter()
+                    .map(|r| format!("{}{}", db.url(), r))
+                    .collect(),
+            }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<PgDb>()
+        .add::<Repo>()
+        .add::<Service>()
+        .add_value([1u8, 2])
+        .build();
+
+    assert_eq!(cat.get_one::<Repo>().unwrap().url, "postgres://");
+    assert_eq!(
+        cat.get_one::<Service>().unwrap().urls,
+        vec!["postgres://1", "postgres://2"]
+    );
+
+    // Concrete type is the dependency of the component
+    assert_eq!(
+        builder_for::<Repo>().dependencies()[0].type_id,
+        TypeId::of::<PgDb>()
+    );
+
+    let repo = builder_for::<Repo>()
+        .with_db(Arc::new(PgDb))
+        .build_fresh(&cat)
+        .unwrap();
+    assert_eq!(repo.url, "postgres://");
+}
+
+#[test]
+fn test_interface_on_trait() {
+    #[interface]
+    trait UserRepo: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[interface]
+    trait DBPool: Send + Sync {}
+
+    #[component]
+    #[interface(dyn UserRepo)]
+    struct PgUserRepo;
+    impl UserRepo for PgUserRepo {
+        fn name(&self) -> &str {
+            "pg"
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<PgUserRepo>().build();
+
+    let repo: UserRepoRef = cat.get_user_repo().unwrap();
+    assert_eq!(repo.name(), "pg");
+
+    assert!(matches!(
+        cat.get_db_pool(),
+        Err(e) if e == InjectionError::unregistered::<dyn DBPool>()
+    ));
+}
+
+#[test]
+fn test_enum_components() {
+    #[component]
+    struct Config {
+        #[dill(skip)]
+        dir: String,
+    }
+
+    #[component]
+    struct S3Client;
+
+    // Variant picked by the value registered in the catalog
+    #[component]
+    enum Storage {
+        Local { config: Arc<Config> },
+        S3(Arc<S3Client>, #[dill(skip)] u32),
+        InMemory,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Storage>()
+        .add::<S3Client>()
+        .add_value(StorageVariant::S3)
+        .build();
+    assert!(matches!(
+        cat.get_one::<Storage>().unwrap().as_ref(),
+        Storage::S3(_, 0)
+    ));
+
+    // Dependencies of other variants don't have to be registered
+    let mut b = CatalogBuilder::new();
+    b.add::<Storage>();
+    b.add_value(StorageVariant::InMemory);
+    b.validate().unwrap();
+    let cat = b.build();
+    assert!(matches!(
+        cat.get_one::<Storage>().unwrap().as_ref(),
+        Storage::InMemory
+    ));
+
+    let cat = CatalogBuilder::new()
+        .add::<Storage>()
+        .add_value(StorageVariant::Local)
+        .build();
+    assert!(matches!(
+        cat.get_one::<Storage>().err(),
+        Some(e) if e == InjectionError::unregistered::<Config>()
+    ));
+
+    let cat = CatalogBuilder::new()
+        .add::<Storage>()
+        .add::<Config>()
+        .build();
+    assert!(matches!(
+        cat.get_one::<Storage>().err(),
+        Some(e) if e == InjectionError::unregistered::<StorageVariant>()
+    ));
+
+    // Variant picked by the selector function, with the setters prefixed by the variant name
+    fn select_cache(cat: &Catalog) -> Result<CacheVariant, InjectionError> {
+        Ok(match cat.get_one::<Config>()?.dir.as_str() {
+            "" => CacheVariant::Disabled,
+            _ => CacheVariant::Disk,
+        })
+    }
+
+    #[component]
+    #[dill(variant_selector = "select_cache")]
+    #[scope(Singleton)]
+    enum Cache {
+        Disk { dir: String },
+        Disabled,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Config>()
+        .add_builder(CacheBuilder::new().with_disk_dir("/tmp".to_owned()))
+        .build();
+    assert!(matches!(
+        cat.get_one::<Cache>().unwrap().as_ref(),
+        Cache::Disabled
+    ));
+
+    let cat = CatalogBuilder::new()
+        .add_value(Config {
+            dir: "/var".to_owned(),
+        })
+        .add_builder(CacheBuilder::new().with_disk_dir("/tmp".to_owned()))
+        .build();
+    assert!(
+        matches!(cat.get_one::<Cache>().unwrap().as_ref(), Cache::Disk { dir } if dir == "/tmp")
+    );
+}
+
+#[test]
+fn test_from_args() {
+    #[derive(Clone)]
+    struct AppConfig {
+        db_url: String,
+        port: i32,
+    }
+
+    #[derive(Clone)]
+    struct DbConfig {
+        url: String,
+    }
+
+    impl From<AppConfig> for DbConfig {
+        fn from(config: AppConfig) -> Self {
+            Self { url: config.db_url }
+        }
+    }
+
+    #[component]
+    struct Db {
+        #[dill(from = "AppConfig")]
+        config: DbConfig,
+    }
+
+    #[component]
+    struct Server {
+        #[dill(from = "AppConfig")]
+        port: u16,
+    }
+
+    impl TryFrom<AppConfig> for u16 {
+        type Error = std::num::TryFromIntError;
+
+        fn try_from(config: AppConfig) -> Result<Self, Self::Error> {
+            u16::try_from(config.port)
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Db>()
+        .add::<Server>()
+        .add_value(AppConfig {
+            db_url: "postgres://".to_owned(),
+            port: 8080,
+        })
+        .build();
+
+    assert_eq!(cat.get_one::<Db>().unwrap().config.url, "postgres://");
+    assert_eq!(cat.get_one::<Server>().unwrap().port, 8080);
+    assert_eq!(
+        DbBuilder::new().dependencies(),
+        vec![DependencyInfo::of::<AppConfig>(DependencyKind::One, None)]
+    );
+
+    let cat = CatalogBuilder::new()
+        .add::<Server>()
+        .add_value(AppConfig {
+            db_url: "postgres://".to_owned(),
+            port: -1,
+        })
+        .build();
+
+    let err = cat.get_one::<Server>().err().unwrap();
+    assert!(matches!(err, InjectionError::ConstructorFailed(_)));
+    assert_eq!(
+        err.to_string(),
+        "Failed to construct u16: out of range integral type conversion attempted"
+    );
+}
+
+#[test]
+fn test_builder_overrides() {
+    #[component]
+    struct Server {
+        host: String,
+        port: u16,
+        workers: usize,
+    }
+
+    struct Settings {
+        port: Option<u16>,
+        workers: Option<usize>,
+    }
+
+    let settings = Settings {
+        port: Some(8080),
+        workers: None,
+    };
+
+    let cat =
+        CatalogBuilder::new()
+            .add_builder(ServerBuilder::new().with_workers(4).with_overrides(
+                ServerBuilderOverrides {
+                    host: Some("localhost".to_owned()),
+                    port: settings.port,
+                    workers: settings.workers,
+                },
+            ))
+            .build();
+
+    let server = cat.get_one::<Server>().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, 4);
+
+    // Arguments that are not overridden are resolved from the catalog
+    let overrides = ServerBuilderOverrides {
+        port: Some(9090),
+        ..Default::default()
+    };
+    let cat = CatalogBuilder::new()
+        .add_builder(ServerBuilder::new().with_overrides(overrides.clone()))
+        .add_value("0.0.0.0".to_owned())
+        .add_value(1usize)
+        .build();
+
+    let server = cat.get_one::<Server>().unwrap();
+    assert_eq!(server.host, "0.0.0.0");
+    assert_eq!(server.port, 9090);
+    assert_eq!(server.workers, 1);
+    assert_eq!(overrides.port, Some(9090));
+}
+
+#[test]
+fn test_trait_object_bounds() {
+    // No `Send + Sync` supertraits, so the bounds are spelled out in the trait objects
+    trait Store {
+        fn get(&self) -> u32;
+    }
+
+    #[component]
+    #[interface(dyn Store + Send + Sync)]
+    struct MemStore;
+    impl Store for MemStore {
+        fn get(&self) -> u32 {
+            42
+        }
+    }
+
+    #[component]
+    struct Service {
+        store: Arc<dyn Store + Send + Sync + 'static>,
+        maybe_store: Option<Arc<dyn Store + Sync + Send>>,
+        all_stores: Vec<std::sync::Arc<dyn Store + Send + Sync>>,
+    }
+
+    struct RefService {
+        value: u32,
+    }
+
+    #[component]
+    impl RefService {
+        pub fn new(store: &(dyn Store + Send + Sync)) -> Self {
+            Self { value: store.get() }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<MemStore>()
+        .add::<Service>()
+        .add::<RefService>()
+        .build();
+
+    let service = cat.get_one::<Service>().unwrap();
+    assert_eq!(service.store.get(), 42);
+    assert_eq!(service.maybe_store.as_ref().unwrap().get(), 42);
+    assert_eq!(service.all_stores.len(), 1);
+    assert_eq!(cat.get_one::<RefService>().unwrap().value, 42);
+
+    assert_eq!(
+        ServiceBuilder::new().dependencies()[0],
+        DependencyInfo::of::<dyn Store + Send + Sync>(DependencyKind::One, None)
+    );
+}
+
+#[test]
+fn test_setters_accept_into() {
+    #[derive(Clone, Debug, PartialEq)]
+    struct Port(u16);
+
+    impl From<u16> for Port {
+        fn from(port: u16) -> Self {
+            Self(port)
+        }
+    }
+
+    #[component]
+    struct Server {
+        host: String,
+        port: Port,
+        workers: u8,
+        tags: Vec<String>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(
+            ServerBuilder::new()
+                .with_host("localhost")
+                .with_port(8080)
+                .with_workers(4)
+                .with_tags(vec!["a".to_owned()]),
+        )
+        .build();
+
+    let server = cat.get_one::<Server>().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, Port(8080));
+    assert_eq!(server.workers, 4);
+    assert_eq!(server.tags, vec!["a".to_owned()]);
+
+    // Same applies to the setters of `Registration`
+    let mut b = CatalogBuilder::new();
+    b.add::<Server>()
+        .with_host("0.0.0.0")
+        .with_port(Port(9090))
+        .with_workers(1)
+        .with_tags(Vec::new());
+    let server = b.build().get_one::<Server>().unwrap();
+    assert_eq!(server.host, "0.0.0.0");
+    assert_eq!(server.port, Port(9090));
+}
+
+#[test]
+fn test_build_standalone() {
+    trait Db: Send + Sync {
+        fn url(&self) -> String;
+    }
+
+    struct MockDb;
+    impl Db for MockDb {
+        fn url(&self) -> String {
+            "mock://".to_owned()
+        }
+    }
+
+    trait Metrics: Send + Sync {}
+
+    #[component]
+    struct Service {
+        db: Arc<dyn Db>,
+        name: String,
+        metrics: Option<Arc<dyn Metrics>>,
+        ctx: ComponentContext,
+    }
+
+    // Arguments without an override are listed in the error
+    let err = ServiceBuilder::new().build_standalone().err().unwrap();
+    assert!(matches!(&err, InjectionError::MissingOverrides(e) if e.args() == ["db", "name"]));
+    assert!(err.to_string().ends_with("missing overrides of: db, name"));
+
+    let service = ServiceBuilder::new()
+        .with_db(Arc::new(MockDb))
+        .with_name("test")
+        .build_standalone()
+        .unwrap();
+    assert_eq!(service.db.url(), "mock://");
+    assert_eq!(service.name, "test");
+
+    // Arguments that don't require a registration are resolved as if by an empty catalog
+    assert!(service.metrics.is_none());
+    assert_eq!(service.ctx.short_type_name(), "Service");
+}
+
+#[test]
+fn test_validation() {
+    fn is_valid_port(port: &u16) -> bool {
+        *port != 0
+    }
+
+    fn is_valid_host(host: &String) -> Result<(), String> {
+        if host.is_empty() {
+            Err("host is empty".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[component]
+    impl Server {
+        pub fn new(
+            #[dill(validate = "is_valid_host")] host: String,
+            #[dill(validate = "is_valid_port")] port: u16,
+        ) -> Self {
+            Self { host, port }
+        }
+    }
+
+    #[derive(Debug)]
+    #[component(validate)]
+    struct Limits {
+        min: usize,
+        max: usize,
+    }
+
+    impl Validate for Limits {
+        fn validate(&self) -> Result<(), String> {
+            if self.min > self.max {
+                return Err(format!("min {} exceeds max {}", self.min, self.max));
+            }
+            Ok(())
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value("localhost".to_owned())
+        .add_value(8080u16)
+        .add::<Server>()
+        .build();
+    let server = cat.get_one::<Server>().unwrap();
+    assert_eq!((server.host.as_str(), server.port), ("localhost", 8080));
+
+    // Overridden values are validated as well
+    let cat = CatalogBuilder::new()
+        .add_value("localhost".to_owned())
+        .add_builder(ServerBuilder::new().with_port(0u16))
+        .build();
+    let err = cat.get_one::<Server>().unwrap_err();
+    assert_eq!(
+        err,
+        InjectionError::invalid_argument(std::any::type_name::<Server>(), "port", None)
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Invalid argument port of {}",
+            std::any::type_name::<Server>()
+        )
+    );
+
+    let cat = CatalogBuilder::new()
+        .add_value(String::new())
+        .add_value(8080u16)
+        .add::<Server>()
+        .build();
+    assert_eq!(
+        cat.get_one::<Server>().unwrap_err().to_string(),
+        format!(
+            "Invalid argument host of {}: host is empty",
+            std::any::type_name::<Server>()
+        )
+    );
+
+    let cat = CatalogBuilder::new()
+        .add_value(10usize)
+        .add::<Limits>()
+        .build();
+    assert!(cat.get_one::<Limits>().is_ok());
+
+    let cat = CatalogBuilder::new()
+        .add_builder(LimitsBuilder::new().with_min(5usize).with_max(1usize))
+        .build();
+    assert_eq!(
+        cat.get_one::<Limits>().unwrap_err(),
+        InjectionError::invalid_instance::<Limits>("min 5 exceeds max 1")
+    );
+}
+
+#[test]
+fn test_value_setters() {
+    // Neither Clone nor registered in the catalog
+    struct Credentials {
+        token: String,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Client {
+        credentials: Credentials,
+    }
+
+    #[component]
+    struct Session {
+        credentials: Credentials,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(ClientBuilder::new().with_credentials_value(Credentials {
+            token: "secret".to_owned(),
+        }))
+        .build();
+    assert_eq!(cat.get_one::<Client>().unwrap().credentials.token, "secret");
+    // Singleton is built only once
+    assert_eq!(cat.get_one::<Client>().unwrap().credentials.token, "secret");
+
+    let mut b = CatalogBuilder::new();
+    b.add::<Session>().with_credentials_value(Credentials {
+        token: "secret".to_owned(),
+    });
+    let cat = b.build();
+    assert_eq!(
+        cat.get_one::<Session>().unwrap().credentials.token,
+        "secret"
+    );
+
+    let err = cat.get_one::<Session>().err().unwrap();
+    assert_eq!(
+        err,
+        InjectionError::value_consumed(std::any::type_name::<Session>(), "credentials")
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Value of argument credentials of {} was consumed by an earlier build, supply it via \
+            with_credentials() or with_credentials_fn() to build multiple instances",
+            std::any::type_name::<Session>()
+        )
+    );
+
+    // Setters of the values that are not Clone hand them to the first build as well
+    let cat = CatalogBuilder::new()
+        .add_builder(SessionBuilder::new().with_credentials(Credentials {
+            token: "secret".to_owned(),
+        }))
+        .build();
+    assert!(cat.get_one::<Session>().is_ok());
+    assert!(matches!(
+        cat.get_one::<Session>().err(),
+        Some(InjectionError::ValueConsumed(_))
+    ));
+
+    // Values that are not Clone are moved out of fresh instances
+    #[component]
+    struct Connection;
+
+    #[component]
+    struct Pool {
+        _conn: Connection,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Connection>()
+        .add::<Pool>()
+        .build();
+    assert!(cat.get_one::<Pool>().is_ok());
+}
+
+#[test]
+fn test_hand_written_builder() {
+    struct Registry;
+
+    struct Client {
+        url: String,
+    }
+
+    struct ClientBuilder {
+        instance: ScopedInstance<Singleton>,
+    }
+
+    impl Builder for ClientBuilder {
+        fn instance_type_id(&self) -> TypeId {
+            TypeId::of::<Client>()
+        }
+
+        fn instance_type_name(&self) -> &'static str {
+            std::any::type_name::<Client>()
+        }
+
+        fn scope_name(&self) -> &'static str {
+            self.instance.scope_name()
+        }
+
+        fn scope_was_explicit(&self) -> bool {
+            true
+        }
+
+        fn is_catalog_wide(&self) -> bool {
+            self.instance.is_catalog_wide()
+        }
+
+        fn dependencies(&self) -> Vec<DependencyInfo> {
+            vec![DependencyInfo::of::<Registry>(DependencyKind::One, None)]
+        }
+
+        fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+            Ok(TypedBuilder::get(self, cat)?)
+        }
+
+        fn cached(&self, cat: &Catalog) -> Option<Shared<SharedAny>> {
+            self.instance.cached(cat)
+        }
+
+        fn invalidate(&self, cat: &Catalog) {
+            self.instance.invalidate(cat)
+        }
+    }
+
+    impl TypedBuilder<Client> for ClientBuilder {
+        fn get(&self, cat: &Catalog) -> Result<Shared<Client>, InjectionError> {
+            self.instance.get_or_build(self, cat, || {
+                cat.get_one::<Registry>()?;
+                Ok(Client {
+                    url: "http://billing".to_owned(),
+                })
+            })
+        }
+    }
+
+    let new_builder = || ClientBuilder {
+        instance: ScopedInstance::new(Singleton::new()),
+    };
+
+    let mut b = CatalogBuilder::new();
+    b.add_builder(new_builder());
+    assert!(b.validate().is_err());
+
+    let cat = CatalogBuilder::new()
+        .add_value(Registry)
+        .add_builder(new_builder())
+        .build();
+
+    let builder = cat.builders().find(|b| b.builds::<Client>()).unwrap();
+    assert_eq!(builder.scope_name(), std::any::type_name::<Singleton>());
+    assert!(builder.is_catalog_wide());
+    assert!(builder.cached(&cat).is_none());
+
+    let inst1 = builder.get_instance::<Client>(&cat).unwrap();
+    let inst2 = cat.get_one::<Client>().unwrap();
+    assert_eq!(inst1.url, "http://billing");
+    assert!(Arc::ptr_eq(&inst1, &inst2));
+    assert!(builder.cached(&cat).is_some());
+
+    cat.invalidate::<Client>();
+    assert!(builder.cached(&cat).is_none());
+    assert!(!Arc::ptr_eq(&inst1, &cat.get_one::<Client>().unwrap()));
+}
+
+#[test]
+fn test_hand_written_builder_defaults() {
+    struct Clock {
+        _tick: u64,
+    }
+
+    struct ClockBuilder;
+
+    impl Builder for ClockBuilder {
+        fn instance_type_id(&self) -> TypeId {
+            TypeId::of::<Clock>()
+        }
+
+        fn instance_type_name(&self) -> &'static str {
+            std::any::type_name::<Clock>()
+        }
+
+        fn get(&self, cat: &Catalog) -> Result<Shared<SharedAny>, InjectionError> {
+            Ok(TypedBuilder::get(self, cat)?)
+        }
+    }
+
+    impl TypedBuilder<Clock> for ClockBuilder {
+        fn get(&self, _cat: &Catalog) -> Result<Shared<Clock>, InjectionError> {
+            Ok(Shared::new(Clock { _tick: 0 }))
+        }
+    }
+
+    assert_eq!(
+        ClockBuilder.scope_name(),
+        std::any::type_name::<Transient>()
+    );
+
+    let cat = CatalogBuilder::new().add_builder(ClockBuilder).build();
+    let inst1 = cat.get_one::<Clock>().unwrap();
+    let inst2 = cat.get_one::<Clock>().unwrap();
+    assert!(!Arc::ptr_eq(&inst1, &inst2));
+
+    // Scope can be overridden catalog-wide, as it wasn't chosen explicitly
+    assert!(!ClockBuilder.scope_was_explicit());
+    let cat = CatalogBuilder::new()
+        .default_scope::<Singleton>()
+        .add_builder(ClockBuilder)
+        .build();
+    let inst1 = cat.get_one::<Clock>().unwrap();
+    let inst2 = cat.get_one::<Clock>().unwrap();
+    assert!(Arc::ptr_eq(&inst1, &inst2));
+}
+
+#[test]
+fn test_builder_combinators() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Hello {
+        name: String,
+    }
+
+    impl Greeter for Hello {
+        fn greet(&self) -> String {
+            format!("hello {}", self.name)
+        }
+    }
+
+    struct Traced<T> {
+        inner: Arc<T>,
+    }
+
+    impl<T: Greeter> Greeter for Traced<T> {
+        fn greet(&self) -> String {
+            format!("[traced] {}", self.inner.greet())
+        }
+    }
+
+    static CHECKS: AtomicUsize = AtomicUsize::new(0);
+    let ready = Arc::new(Mutex::new(false));
+    let ready_check = ready.clone();
+
+    let cat = CatalogBuilder::new()
+        .add_value("world".to_owned())
+        .add_builder(
+            Hello::builder()
+                .map(|inner| Arc::new(Traced { inner }))
+                .before_build(move |_| {
+                    CHECKS.fetch_add(1, Ordering::SeqCst);
+                    if *ready_check.lock().unwrap() {
+                        Ok(())
+                    } else {
+                        Err(InjectionError::unregistered::<Hello>())
+                    }
+                }),
+        )
+        .bind::<dyn Greeter, Traced<Hello>>()
+        .build();
+
+    assert!(cat.get_one::<Hello>().is_err());
+
+    let builder = cat
+        .builders()
+        .find(|b| b.builds::<Traced<Hello>>())
+        .unwrap();
+    assert_eq!(builder.scope_name(), std::any::type_name::<Singleton>());
+    assert_eq!(
+        builder.dependencies(),
+        vec![DependencyInfo::of::<String>(DependencyKind::One, None)]
+    );
+
+    // Failed check doesn't construct the instance
+    assert!(cat.get_one::<dyn Greeter>().is_err());
+    assert!(builder.cached(&cat).is_none());
+
+    *ready.lock().unwrap() = true;
+    let inst1 = cat.get_one::<Traced<Hello>>().unwrap();
+    let inst2 = cat.get_one::<Traced<Hello>>().unwrap();
+    assert_eq!(
+        cat.get_one::<dyn Greeter>().unwrap().greet(),
+        "[traced] hello world"
+    );
+    assert_eq!(CHECKS.load(Ordering::SeqCst), 4);
+
+    // Wrapper is created upon every resolution, while the inner instance is still a singleton
+    assert!(!Arc::ptr_eq(&inst1, &inst2));
+    assert!(Arc::ptr_eq(&inst1.inner, &inst2.inner));
+}
@@ -1,5 +1,7 @@
 #![feature(assert_matches)]
+#![cfg(not(feature = "unsync"))]
 
+use std::any::TypeId;
 use std::assert_matches::assert_matches;
 use std::sync::Arc;
 
@@ -212,6 +214,62 @@ fn test_add_value() {
     assert_eq!(val.as_ref(), "foo");
 }
 
+#[test]
+fn test_add_config() {
+    #[derive(Clone)]
+    struct ServerConfig {
+        host: String,
+    }
+
+    struct Server {
+        host: String,
+        port: u16,
+        workers: Arc<usize>,
+    }
+
+    #[component]
+    impl Server {
+        pub fn new(config: ServerConfig, port: u16, workers: Arc<usize>) -> Self {
+            Self {
+                host: config.host,
+                port,
+                workers,
+            }
+        }
+    }
+
+    let mut b = CatalogBuilder::new();
+    b.add::<Server>()
+        .add_config(ServerConfig {
+            host: "localhost".to_owned(),
+        })
+        .add_config(8080u16)
+        .add_config(4usize);
+
+    // Configs registered later replace the earlier ones
+    b.add_config(ServerConfig {
+        host: "0.0.0.0".to_owned(),
+    });
+
+    let cat = b.build();
+    let server = cat.get_one::<Server>().unwrap();
+    assert_eq!(server.host, "0.0.0.0");
+    assert_eq!(server.port, 8080);
+    assert_eq!(*server.workers, 4);
+    assert_eq!(cat.get::<AllOf<ServerConfig>>().unwrap().len(), 1);
+
+    // Builder overrides take precedence
+    let cat = CatalogBuilder::new()
+        .add_builder(builder_for::<Server>().with_port(9090))
+        .add_config(ServerConfig {
+            host: "localhost".to_owned(),
+        })
+        .add_config(8080u16)
+        .add_config(4usize)
+        .build();
+    assert_eq!(cat.get_one::<Server>().unwrap().port, 9090);
+}
+
 #[test]
 fn test_add_factory() {
     let mut cat = CatalogBuilder::new();
@@ -222,6 +280,51 @@ fn test_add_factory() {
     assert_eq!(val.as_ref(), "foo");
 }
 
+#[test]
+fn test_add_builder_fn() {
+    trait A: Send + Sync {
+        fn test(&self) -> String;
+    }
+
+    struct AImpl {
+        name: Arc<String>,
+    }
+
+    impl A for AImpl {
+        fn test(&self) -> String {
+            format!("aimpl::{}", self.name)
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value("foo".to_owned())
+        .add_builder_fn(|cat| {
+            Ok(AImpl {
+                name: cat.get_one()?,
+            })
+        })
+        .bind::<dyn A, AImpl>()
+        .build();
+
+    let inst = cat.get::<OneOf<dyn A>>().unwrap();
+    assert_eq!(inst.test(), "aimpl::foo");
+    assert_eq!(cat.get::<AllOf<dyn A>>().unwrap().len(), 1);
+
+    // Errors of the closure are propagated
+    let cat = CatalogBuilder::new()
+        .add_builder_fn(|cat| {
+            Ok(AImpl {
+                name: cat.get_one()?,
+            })
+        })
+        .build();
+
+    assert_eq!(
+        cat.get_one::<AImpl>().err(),
+        Some(InjectionError::unregistered::<String>())
+    );
+}
+
 #[test]
 fn test_self_injection() {
     trait A: Send + Sync {
@@ -290,3 +393,3428 @@ fn test_self_injection() {
     let inst = cat.get::<OneOf<dyn A>>().unwrap();
     assert_eq!(inst.test(), "aimpl::bimpl::c");
 }
+
+#[test]
+fn test_self_injection_as_service_locator() {
+    trait Plugin: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component]
+    struct PluginA;
+    impl Plugin for PluginA {
+        fn name(&self) -> String {
+            "a".to_owned()
+        }
+    }
+
+    #[component]
+    struct PluginB;
+    impl Plugin for PluginB {
+        fn name(&self) -> String {
+            "b".to_owned()
+        }
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Dispatcher {
+        catalog: Arc<Catalog>,
+    }
+
+    impl Dispatcher {
+        fn dispatch(&self) -> Vec<String> {
+            // Resolving the dispatcher itself is not reported as a cycle
+            let this = self.catalog.get_one::<Dispatcher>().unwrap();
+            assert!(std::ptr::eq(this.as_ref(), self));
+
+            let mut names: Vec<_> = self
+                .catalog
+                .get::<AllOf<dyn Plugin>>()
+                .unwrap()
+                .iter()
+                .map(|p| p.name())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    struct Handler {
+        catalog: Catalog,
+    }
+
+    #[component]
+    impl Handler {
+        pub fn new(catalog: Catalog) -> Self {
+            Self { catalog }
+        }
+    }
+
+    let mut b = CatalogBuilder::new();
+    b.add::<PluginA>()
+        .bind::<dyn Plugin, PluginA>()
+        .add::<PluginB>()
+        .bind::<dyn Plugin, PluginB>()
+        .add::<Dispatcher>()
+        .add::<Handler>();
+
+    // Catalog is not a dependency that has to be registered
+    assert!(b.validate().is_ok());
+    assert_eq!(builder_for::<Handler>().dependencies().len(), 0);
+
+    let cat = b.build();
+
+    let dispatcher = cat.get_one::<Dispatcher>().unwrap();
+    assert_eq!(dispatcher.dispatch(), vec!["a".to_owned(), "b".to_owned()]);
+
+    let handler = cat.get_one::<Handler>().unwrap();
+    assert!(Arc::ptr_eq(
+        &handler.catalog.get_one::<Dispatcher>().unwrap(),
+        &dispatcher
+    ));
+}
+
+#[test]
+fn test_get_by_type_id() {
+    #[component]
+    struct AImpl;
+
+    impl AImpl {
+        fn test(&self) -> String {
+            "aimpl".to_owned()
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<AImpl>().build();
+
+    let inst = cat.get_by_type_id(TypeId::of::<AImpl>()).unwrap();
+    let inst: Arc<AImpl> = inst.downcast().unwrap();
+    assert_eq!(inst.test(), "aimpl");
+
+    let res = cat.get_by_type_id(TypeId::of::<String>());
+    assert_matches!(res.err(), Some(e) if e == InjectionError::unregistered_type_id(TypeId::of::<String>()));
+}
+
+#[test]
+fn test_get_by_name() {
+    #[component]
+    struct AImpl;
+
+    impl AImpl {
+        fn test(&self) -> String {
+            "aimpl".to_owned()
+        }
+    }
+
+    #[component]
+    struct BImpl;
+
+    let parent = CatalogBuilder::new().add::<AImpl>().build();
+    let cat = parent.builder_chained().add::<BImpl>().build();
+
+    let inst = cat.get_by_name(std::any::type_name::<AImpl>()).unwrap();
+    let inst: Arc<AImpl> = inst.downcast().unwrap();
+    assert_eq!(inst.test(), "aimpl");
+
+    let inst = cat.get_by_name(std::any::type_name::<BImpl>()).unwrap();
+    assert!(inst.downcast::<BImpl>().is_ok());
+
+    let res = cat.get_by_name("AImpl");
+    assert_matches!(res.err(), Some(e) if e == InjectionError::unregistered_name("AImpl"));
+}
+
+#[test]
+fn test_get_tagged() {
+    trait Task: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component(tags("startup"))]
+    struct Migrate;
+
+    impl Task for Migrate {
+        fn name(&self) -> String {
+            "migrate".to_owned()
+        }
+    }
+
+    #[component(tags("startup", "health"))]
+    struct Ping;
+
+    impl Task for Ping {
+        fn name(&self) -> String {
+            "ping".to_owned()
+        }
+    }
+
+    #[component]
+    struct Cleanup;
+
+    impl Task for Cleanup {
+        fn name(&self) -> String {
+            "cleanup".to_owned()
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Migrate>()
+        .bind::<dyn Task, Migrate>()
+        .add::<Ping>()
+        .bind::<dyn Task, Ping>()
+        .add::<Cleanup>()
+        .bind::<dyn Task, Cleanup>()
+        .build();
+
+    let names = |tasks: Vec<Arc<dyn Task>>| {
+        let mut names: Vec<_> = tasks.iter().map(|t| t.name()).collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        names(cat.get_tagged::<dyn Task>("startup").unwrap()),
+        vec!["migrate", "ping"]
+    );
+    assert_eq!(
+        names(cat.get_tagged::<dyn Task>("health").unwrap()),
+        vec!["ping"]
+    );
+    assert!(cat.get_tagged::<dyn Task>("unknown").unwrap().is_empty());
+    assert_eq!(
+        names(cat.get::<AllOf<dyn Task>>().unwrap()),
+        vec!["cleanup", "migrate", "ping"]
+    );
+}
+
+#[test]
+fn test_add_all() {
+    #[component]
+    struct A;
+
+    impl A {
+        fn test(&self) -> String {
+            "a".to_owned()
+        }
+    }
+
+    struct B {
+        a: Arc<A>,
+    }
+
+    #[component]
+    impl B {
+        fn new(a: Arc<A>) -> Self {
+            Self { a }
+        }
+    }
+
+    impl B {
+        fn test(&self) -> String {
+            format!("b::{}", self.a.test())
+        }
+    }
+
+    let mut cat = CatalogBuilder::new();
+    add_all!(cat, A, B);
+    let cat = cat.build();
+
+    let b = cat.get_one::<B>().unwrap();
+    assert_eq!(b.test(), "b::a");
+}
+
+#[test]
+fn test_open_generic() {
+    trait Repository<T>: Send + Sync {
+        fn table(&self) -> &'static str;
+    }
+
+    #[component]
+    struct User;
+
+    #[component]
+    struct Order;
+
+    struct Invoice;
+
+    // Requires the entity to be registered
+    #[component]
+    #[scope(Singleton)]
+    struct PgRepository<T: Send + Sync> {
+        _entity: Arc<T>,
+    }
+
+    impl<T: Send + Sync> Repository<T> for PgRepository<T> {
+        fn table(&self) -> &'static str {
+            std::any::type_name::<T>()
+        }
+    }
+
+    impl<T: Send + Sync + 'static> OpenGeneric for dyn Repository<T> {
+        type Impl = PgRepository<T>;
+    }
+
+    #[component]
+    #[interface(dyn Repository<Order>)]
+    struct MockOrderRepository;
+
+    impl Repository<Order> for MockOrderRepository {
+        fn table(&self) -> &'static str {
+            "mock"
+        }
+    }
+
+    let mut cat = CatalogBuilder::new();
+    add_all!(cat, User, Order);
+    let cat = cat.build();
+
+    assert_eq!(
+        cat.get_one::<dyn Repository<User>>().unwrap().table(),
+        std::any::type_name::<User>()
+    );
+    assert_eq!(
+        cat.get::<Maybe<OneOf<dyn Repository<Order>>>>()
+            .unwrap()
+            .unwrap()
+            .table(),
+        std::any::type_name::<Order>()
+    );
+
+    // Every instantiation is a separate singleton
+    let users = cat.get_one::<dyn Repository<User>>().unwrap();
+    assert!(Arc::ptr_eq(
+        &users,
+        &cat.get_one::<dyn Repository<User>>().unwrap()
+    ));
+    let orders = cat.get_one::<dyn Repository<Order>>().unwrap();
+    assert_ne!(
+        Arc::as_ptr(&users) as *const (),
+        Arc::as_ptr(&orders) as *const ()
+    );
+
+    // Instantiations are not registered as components
+    assert_matches!(
+        cat.get_one::<PgRepository<User>>(),
+        Err(InjectionError::Unregistered(_))
+    );
+
+    // Errors of the instantiations are propagated
+    assert_matches!(
+        cat.get_one::<dyn Repository<Invoice>>(),
+        Err(InjectionError::Unregistered(e)) if e.type_name() == std::any::type_name::<Invoice>()
+    );
+    assert_matches!(
+        cat.get::<Maybe<OneOf<dyn Repository<Invoice>>>>(),
+        Err(InjectionError::Unregistered(_))
+    );
+
+    // Explicit bindings take precedence
+    let cat = CatalogBuilder::new()
+        .add::<Order>()
+        .add::<MockOrderRepository>()
+        .build();
+    assert_eq!(
+        cat.get_one::<dyn Repository<Order>>().unwrap().table(),
+        "mock"
+    );
+}
+
+#[test]
+fn test_one_of_ambiguous() {
+    trait A: Send + Sync {}
+
+    #[component]
+    struct AImpl1;
+    impl A for AImpl1 {}
+
+    #[component]
+    struct AImpl2;
+    impl A for AImpl2 {}
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl1>()
+        .bind::<dyn A, AImpl1>()
+        .add::<AImpl2>()
+        .bind::<dyn A, AImpl2>()
+        .build();
+
+    let res = cat.get::<OneOf<dyn A>>();
+    assert_matches!(res.err(), Some(e) if e == InjectionError::ambiguous::<dyn A>(2));
+
+    // Top-level message carries the details of the variant
+    let err = cat.get::<OneOf<dyn A>>().err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Ambiguous type: {} has 2 implementations: {}, {}",
+            std::any::type_name::<dyn A>(),
+            std::any::type_name::<AImpl1>(),
+            std::any::type_name::<AImpl2>()
+        )
+    );
+
+    let res = cat.get::<AllOf<dyn A>>();
+    assert_eq!(res.unwrap().len(), 2);
+}
+
+#[test]
+fn test_one_of_priority() {
+    trait A: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[component]
+    #[interface(dyn A)]
+    struct AImpl1;
+    impl A for AImpl1 {
+        fn name(&self) -> &str {
+            "a1"
+        }
+    }
+
+    #[component(priority = 10)]
+    #[interface(dyn A)]
+    struct AImpl2;
+    impl A for AImpl2 {
+        fn name(&self) -> &str {
+            "a2"
+        }
+    }
+
+    #[component]
+    #[interface(dyn A)]
+    struct AImpl3;
+    impl A for AImpl3 {
+        fn name(&self) -> &str {
+            "a3"
+        }
+    }
+
+    let names = |v: Vec<Arc<dyn A>>| v.iter().map(|a| a.name().to_owned()).collect::<Vec<_>>();
+
+    // Highest priority wins regardless of the registration order
+    let cat = CatalogBuilder::new()
+        .add::<AImpl1>()
+        .add::<AImpl2>()
+        .add::<AImpl3>()
+        .build();
+
+    assert_eq!(cat.get_one::<dyn A>().unwrap().name(), "a2");
+    assert_eq!(names(cat.get_all::<dyn A>().unwrap()), ["a2", "a1", "a3"]);
+    assert_eq!(cat.get::<AllOf<dyn A>>().unwrap().len(), 3);
+
+    // Priority can be overridden upon registration
+    let cat = CatalogBuilder::new()
+        .add::<AImpl1>()
+        .add::<AImpl2>()
+        .add_with_priority::<AImpl3>(20)
+        .build();
+
+    assert_eq!(cat.get_one::<dyn A>().unwrap().name(), "a3");
+    assert_eq!(names(cat.get_all::<dyn A>().unwrap()), ["a3", "a2", "a1"]);
+    assert!(cat.builder().validate().is_ok());
+
+    // Tie between the highest priorities is ambiguous
+    let cat = CatalogBuilder::new()
+        .add::<AImpl1>()
+        .add_with_priority::<AImpl2>(5)
+        .add_with_priority::<AImpl3>(5)
+        .build();
+
+    let err = cat.get_one::<dyn A>().err().unwrap();
+    assert_eq!(err, InjectionError::ambiguous::<dyn A>(2));
+    match err {
+        InjectionError::Ambiguous(e) => assert_eq!(
+            e.candidates(),
+            [
+                std::any::type_name::<AImpl2>(),
+                std::any::type_name::<AImpl3>()
+            ]
+        ),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_get_all_where() {
+    trait Route: Send + Sync {
+        fn method(&self) -> &str;
+        fn path(&self) -> &str;
+    }
+
+    #[component]
+    struct GetUsers;
+    impl Route for GetUsers {
+        fn method(&self) -> &str {
+            "GET"
+        }
+        fn path(&self) -> &str {
+            "/users"
+        }
+    }
+
+    #[component]
+    struct PostUser;
+    impl Route for PostUser {
+        fn method(&self) -> &str {
+            "POST"
+        }
+        fn path(&self) -> &str {
+            "/users"
+        }
+    }
+
+    #[component]
+    struct GetOrders;
+    impl Route for GetOrders {
+        fn method(&self) -> &str {
+            "GET"
+        }
+        fn path(&self) -> &str {
+            "/orders"
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<GetUsers>()
+        .bind::<dyn Route, GetUsers>()
+        .add::<PostUser>()
+        .bind::<dyn Route, PostUser>()
+        .add::<GetOrders>()
+        .bind::<dyn Route, GetOrders>()
+        .build();
+
+    let routes = cat
+        .get_all_where::<dyn Route>(|r| r.method() == "GET")
+        .unwrap();
+    let paths: Vec<_> = routes.iter().map(|r| r.path()).collect();
+    assert_eq!(paths, vec!["/users", "/orders"]);
+}
+
+#[test]
+fn test_get_where_metadata() {
+    trait Exporter: Send + Sync {
+        fn export(&self) -> String;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Format(&'static str);
+
+    #[component]
+    #[interface(dyn Exporter)]
+    struct CsvExporter;
+    impl Exporter for CsvExporter {
+        fn export(&self) -> String {
+            "csv".to_owned()
+        }
+    }
+
+    #[component]
+    #[interface(dyn Exporter)]
+    struct JsonExporter;
+    impl Exporter for JsonExporter {
+        fn export(&self) -> String {
+            "json".to_owned()
+        }
+    }
+
+    #[component]
+    #[interface(dyn Exporter)]
+    struct PrettyJsonExporter;
+    impl Exporter for PrettyJsonExporter {
+        fn export(&self) -> String {
+            "pretty-json".to_owned()
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<CsvExporter>()
+        .with_meta(Format("csv"))
+        .add::<JsonExporter>()
+        .with_meta(Format("json"))
+        .add::<PrettyJsonExporter>()
+        .with_meta(Format("json"))
+        .with_meta(true)
+        .build();
+
+    let by_format =
+        |format: &'static str| move |m: &Metadata| m.get::<Format>() == Some(&Format(format));
+
+    assert_eq!(
+        cat.get_where::<dyn Exporter>(by_format("csv"))
+            .unwrap()
+            .export(),
+        "csv"
+    );
+    assert_eq!(
+        cat.get_where::<dyn Exporter>(|m| m.contains::<bool>())
+            .unwrap()
+            .export(),
+        "pretty-json"
+    );
+    assert_matches!(
+        cat.get_where::<dyn Exporter>(by_format("json")),
+        Err(InjectionError::Ambiguous(_))
+    );
+    assert_matches!(
+        cat.get_where::<dyn Exporter>(by_format("xml")),
+        Err(InjectionError::Unregistered(_))
+    );
+}
+
+#[test]
+fn test_generic_instantiations_are_distinct() {
+    trait Repo<E>: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    struct Repository<E> {
+        name: String,
+        _entity: std::marker::PhantomData<E>,
+    }
+
+    impl<E> Repository<E> {
+        fn new(name: &str) -> Self {
+            Self {
+                name: name.to_owned(),
+                _entity: std::marker::PhantomData,
+            }
+        }
+    }
+
+    impl<E: Send + Sync> Repo<E> for Repository<E> {
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+    }
+
+    struct User;
+    struct Order;
+
+    let cat = CatalogBuilder::new()
+        .add_value(Repository::<User>::new("users"))
+        .bind::<dyn Repo<User>, Repository<User>>()
+        .add_factory(|| Repository::<Order>::new("orders"))
+        .bind::<dyn Repo<Order>, Repository<Order>>()
+        .build();
+
+    assert_eq!(cat.get_one::<Repository<User>>().unwrap().name, "users");
+    assert_eq!(cat.get_one::<Repository<Order>>().unwrap().name, "orders");
+    assert_eq!(cat.get_one::<dyn Repo<User>>().unwrap().name(), "users");
+    assert_eq!(cat.get_one::<dyn Repo<Order>>().unwrap().name(), "orders");
+    assert_eq!(cat.get::<AllOf<dyn Repo<User>>>().unwrap().len(), 1);
+}
+
+#[test]
+fn test_add_as_interface_only() {
+    trait A: Send + Sync {
+        fn test(&self) -> String;
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct AImpl {
+        name: String,
+    }
+
+    impl A for AImpl {
+        fn test(&self) -> String {
+            format!("aimpl::{}", self.name)
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_as::<dyn A, AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let inst1 = cat.get_one::<dyn A>().unwrap();
+    let inst2 = cat.get_one::<dyn A>().unwrap();
+    assert_eq!(inst1.test(), "aimpl::foo");
+    assert_eq!(
+        inst1.as_ref() as *const dyn A as *const u8,
+        inst2.as_ref() as *const dyn A as *const u8
+    );
+
+    let res = cat.get_one::<AImpl>();
+    assert_matches!(res.err(), Some(e) if e == InjectionError::unregistered::<AImpl>());
+}
+
+#[test]
+fn test_maybe() {
+    trait A: Send + Sync {}
+
+    #[component]
+    struct AImpl;
+    impl A for AImpl {}
+
+    #[component]
+    struct BImpl {
+        _dep: Arc<AImpl>,
+    }
+    impl A for BImpl {}
+
+    let cat = CatalogBuilder::new().build();
+    assert!(cat.get::<Maybe<OneOf<dyn A>>>().unwrap().is_none());
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .bind::<dyn A, AImpl>()
+        .build();
+    assert!(cat.get::<Maybe<OneOf<dyn A>>>().unwrap().is_some());
+
+    // Errors of a registered implementation are not masked
+    let cat = CatalogBuilder::new()
+        .add::<BImpl>()
+        .bind::<dyn A, BImpl>()
+        .build();
+    assert_eq!(
+        cat.get::<Maybe<OneOf<dyn A>>>().err(),
+        Some(InjectionError::unregistered::<AImpl>())
+    );
+}
+
+#[test]
+fn test_get_or_fallback() {
+    trait A: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    #[derive(Default)]
+    struct Fallback;
+    impl A for Fallback {
+        fn name(&self) -> &str {
+            "fallback"
+        }
+    }
+
+    #[component]
+    #[interface(dyn A)]
+    struct AImpl;
+    impl A for AImpl {
+        fn name(&self) -> &str {
+            "registered"
+        }
+    }
+
+    #[component]
+    #[interface(dyn A)]
+    struct BImpl {
+        _dep: Arc<AImpl>,
+    }
+    impl A for BImpl {
+        fn name(&self) -> &str {
+            "broken"
+        }
+    }
+
+    let cat = CatalogBuilder::new().build();
+    let inst = cat
+        .get_or::<OneOf<dyn A>, _>(|| Arc::new(Fallback))
+        .unwrap();
+    assert_eq!(inst.name(), "fallback");
+    assert!(cat.get_or_default::<Fallback>().is_ok());
+
+    let cat = CatalogBuilder::new().add::<AImpl>().build();
+    let inst = cat
+        .get_or::<OneOf<dyn A>, _>(|| Arc::new(Fallback))
+        .unwrap();
+    assert_eq!(inst.name(), "registered");
+
+    // Errors of a registered implementation are not masked
+    let cat = CatalogBuilder::new().add::<BImpl>().build();
+    assert_eq!(
+        cat.get_or::<OneOf<dyn A>, _>(|| Arc::new(Fallback)).err(),
+        Some(InjectionError::unregistered::<AImpl>())
+    );
+}
+
+#[test]
+fn test_named_bindings() {
+    trait Db: Send + Sync {
+        fn kind(&self) -> &str;
+    }
+
+    #[component]
+    struct PostgresDb;
+    impl Db for PostgresDb {
+        fn kind(&self) -> &str {
+            "postgres"
+        }
+    }
+
+    #[component]
+    struct SqliteDb;
+    impl Db for SqliteDb {
+        fn kind(&self) -> &str {
+            "sqlite"
+        }
+    }
+
+    #[component]
+    struct Repo {
+        #[dill::named("sqlite")]
+        db: Arc<dyn Db>,
+        #[named("mysql")]
+        fallback: Option<Arc<dyn Db>>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<PostgresDb>()
+        .bind_named::<dyn Db, PostgresDb>("postgres")
+        .add::<SqliteDb>()
+        .bind_named::<dyn Db, SqliteDb>("sqlite")
+        .add::<Repo>()
+        .build();
+
+    assert_eq!(
+        cat.get_named::<OneOf<dyn Db>>("postgres").unwrap().kind(),
+        "postgres"
+    );
+    assert_eq!(
+        cat.get_one_named::<dyn Db>("sqlite").unwrap().kind(),
+        "sqlite"
+    );
+    assert_eq!(
+        cat.get_named::<OneOf<dyn Db>>("mysql").err(),
+        Some(InjectionError::unregistered::<dyn Db>())
+    );
+    assert!(cat
+        .get_named::<Maybe<OneOf<dyn Db>>>("mysql")
+        .unwrap()
+        .is_none());
+
+    // Named bindings remain visible to the regular resolution
+    assert_eq!(cat.get::<AllOf<dyn Db>>().unwrap().len(), 2);
+
+    let repo = cat.get_one::<Repo>().unwrap();
+    assert_eq!(repo.db.kind(), "sqlite");
+    assert!(repo.fallback.is_none());
+}
+
+#[test]
+fn test_qualified_bindings() {
+    trait Db: Send + Sync {
+        fn kind(&self) -> &str;
+    }
+
+    struct Primary;
+    impl Qualifier for Primary {}
+
+    struct Replica;
+    impl Qualifier for Replica {}
+
+    struct Archive;
+    impl Qualifier for Archive {}
+
+    #[component]
+    struct PostgresDb;
+    impl Db for PostgresDb {
+        fn kind(&self) -> &str {
+            "postgres"
+        }
+    }
+
+    #[component]
+    struct SqliteDb;
+    impl Db for SqliteDb {
+        fn kind(&self) -> &str {
+            "sqlite"
+        }
+    }
+
+    #[component]
+    struct Repo {
+        #[dill(qualifier = Replica)]
+        db: Arc<dyn Db>,
+        #[dill(qualifier = Archive)]
+        archive: Option<Arc<dyn Db>>,
+    }
+
+    let mut b = CatalogBuilder::new();
+    b.add::<PostgresDb>()
+        .bind_qualified::<dyn Db, PostgresDb, Primary>()
+        .add::<SqliteDb>()
+        .bind_qualified::<dyn Db, SqliteDb, Replica>()
+        .add::<Repo>();
+    b.validate().unwrap();
+    let cat = b.build();
+
+    assert_eq!(
+        cat.get::<OneOf<dyn Db, Primary>>().unwrap().kind(),
+        "postgres"
+    );
+    assert_eq!(
+        cat.get::<OneOf<dyn Db, Archive>>().err(),
+        Some(InjectionError::unregistered::<dyn Db>())
+    );
+    assert!(cat
+        .get::<Maybe<OneOf<dyn Db, Archive>>>()
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        cat.get_one_named::<dyn Db>(Replica::name()).unwrap().kind(),
+        "sqlite"
+    );
+
+    let repo = cat.get_one::<Repo>().unwrap();
+    assert_eq!(repo.db.kind(), "sqlite");
+    assert!(repo.archive.is_none());
+
+    // Qualified dependencies are checked upon validation
+    let mut b = CatalogBuilder::new();
+    b.add::<SqliteDb>()
+        .bind_qualified::<dyn Db, SqliteDb, Primary>()
+        .add::<Repo>();
+    assert_eq!(
+        b.validate().err().unwrap().errors(),
+        [InjectionError::unregistered::<dyn Db>()]
+    );
+}
+
+#[test]
+fn test_select_named() {
+    trait Db: Send + Sync {
+        fn kind(&self) -> &str;
+    }
+
+    #[component]
+    struct PostgresDb;
+    impl Db for PostgresDb {
+        fn kind(&self) -> &str {
+            "postgres"
+        }
+    }
+
+    #[component]
+    struct SqliteDb;
+    impl Db for SqliteDb {
+        fn kind(&self) -> &str {
+            "sqlite"
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<PostgresDb>()
+        .bind_named::<dyn Db, PostgresDb>("postgres")
+        .add::<SqliteDb>()
+        .bind_named::<dyn Db, SqliteDb>("sqlite")
+        .select_named::<dyn Db>("sqlite")
+        .build();
+
+    assert_eq!(cat.get_one::<dyn Db>().unwrap().kind(), "sqlite");
+    assert_eq!(cat.get::<AllOf<dyn Db>>().unwrap().len(), 1);
+    // Unselected implementations are still registered under their own type
+    assert!(cat.get_one::<PostgresDb>().is_ok());
+}
+
+#[test]
+fn test_rebind() {
+    trait Db: Send + Sync {
+        fn kind(&self) -> &str;
+    }
+
+    #[component]
+    #[interface(dyn Db)]
+    struct PostgresDb;
+    impl Db for PostgresDb {
+        fn kind(&self) -> &str {
+            "postgres"
+        }
+    }
+
+    #[component]
+    #[interface(dyn Db)]
+    struct SqliteDb;
+    impl Db for SqliteDb {
+        fn kind(&self) -> &str {
+            "sqlite"
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<PostgresDb>()
+        .add::<SqliteDb>()
+        .bind_named::<dyn Db, PostgresDb>("primary")
+        .rebind::<dyn Db, SqliteDb>()
+        .build();
+
+    // Bindings declared by the components are replaced by the one of the composition root
+    assert_eq!(cat.get_one::<dyn Db>().unwrap().kind(), "sqlite");
+    assert_eq!(cat.get::<AllOf<dyn Db>>().unwrap().len(), 1);
+    assert!(cat.get_named::<OneOf<dyn Db>>("primary").is_err());
+    assert!(cat.get_one::<PostgresDb>().is_ok());
+
+    // Later bindings add to the rebound one
+    let cat = CatalogBuilder::new()
+        .add::<PostgresDb>()
+        .add::<SqliteDb>()
+        .rebind::<dyn Db, PostgresDb>()
+        .bind_named::<dyn Db, SqliteDb>("replica")
+        .build();
+
+    assert_eq!(cat.get::<AllOf<dyn Db>>().unwrap().len(), 2);
+    assert_eq!(
+        cat.get_named::<OneOf<dyn Db>>("replica").unwrap().kind(),
+        "sqlite"
+    );
+}
+
+#[test]
+fn test_bind_upcast() {
+    trait Handler: Send + Sync {
+        fn name(&self) -> &str;
+    }
+
+    trait AdminHandler: Handler {
+        fn is_admin(&self) -> bool;
+    }
+
+    #[component]
+    #[interface(dyn Handler)]
+    struct PublicHandler;
+    impl Handler for PublicHandler {
+        fn name(&self) -> &str {
+            "public"
+        }
+    }
+
+    #[component]
+    struct UsersHandler;
+    impl Handler for UsersHandler {
+        fn name(&self) -> &str {
+            "users"
+        }
+    }
+    impl AdminHandler for UsersHandler {
+        fn is_admin(&self) -> bool {
+            true
+        }
+    }
+
+    #[component]
+    #[interface(dyn AdminHandler)]
+    #[interface(dyn Handler)]
+    struct AuditHandler;
+    impl Handler for AuditHandler {
+        fn name(&self) -> &str {
+            "audit"
+        }
+    }
+    impl AdminHandler for AuditHandler {
+        fn is_admin(&self) -> bool {
+            true
+        }
+    }
+
+    #[component]
+    struct Router {
+        handlers: Vec<Arc<dyn Handler>>,
+    }
+
+    let mut b = CatalogBuilder::new();
+    b.add::<PublicHandler>()
+        .add::<AuditHandler>()
+        .bind_upcast::<dyn Handler, dyn AdminHandler>()
+        .add::<Router>();
+
+    // Implementations bound after the upcast are covered too
+    b.add::<UsersHandler>()
+        .bind_named::<dyn AdminHandler, UsersHandler>("users");
+
+    b.validate().unwrap();
+    let cat = b.build();
+
+    let admin = cat.get_all::<dyn AdminHandler>().unwrap();
+    assert_eq!(admin.len(), 2);
+    assert!(admin.iter().all(|h| h.is_admin()));
+
+    // Directly bound implementations are not duplicated
+    let names: Vec<_> = cat
+        .get_all::<dyn Handler>()
+        .unwrap()
+        .iter()
+        .map(|h| h.name().to_owned())
+        .collect();
+    assert_eq!(names, ["public", "audit", "users"]);
+
+    // Binding names are kept
+    assert_eq!(
+        cat.get_one_named::<dyn Handler>("users").unwrap().name(),
+        "users"
+    );
+
+    assert_eq!(cat.get_one::<Router>().unwrap().handlers.len(), 3);
+}
+
+#[test]
+fn test_swap_builder() {
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    trait Marker: Send + Sync {}
+
+    #[component(tags("color"), profiles("dev"), eager)]
+    #[scope(Singleton)]
+    struct Blue;
+    impl Greeter for Blue {
+        fn greet(&self) -> String {
+            "blue".to_owned()
+        }
+    }
+    impl Marker for Blue {}
+
+    #[component]
+    struct Green {
+        suffix: String,
+    }
+    impl Greeter for Green {
+        fn greet(&self) -> String {
+            format!("green-{}", self.suffix)
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .with_profile("dev")
+        .add_value("v2".to_owned())
+        .add_swappable::<dyn Greeter, _, Blue>(Blue::builder())
+        .build();
+    let child = cat.builder_chained().build();
+
+    let old = cat.get_one::<dyn Greeter>().unwrap();
+    assert_eq!(old.greet(), "blue");
+    assert!(Arc::ptr_eq(&old, &cat.get_one::<dyn Greeter>().unwrap()));
+
+    // Metadata follows the current implementation
+    let slot = cat.builders_for::<dyn Greeter>().next().unwrap();
+    assert_eq!(slot.tags(), ["color"]);
+    assert_eq!(slot.profiles(), ["dev"]);
+    assert!(slot.is_eager());
+
+    // Swapping via the child affects the parent, new implementation resolves its dependencies
+    child
+        .swap_builder::<dyn Greeter, _, Green>(Green::builder())
+        .unwrap();
+    assert_eq!(old.greet(), "blue");
+    assert_eq!(cat.get_one::<dyn Greeter>().unwrap().greet(), "green-v2");
+    assert_eq!(
+        cat.builders_for::<dyn Greeter>()
+            .next()
+            .unwrap()
+            .scope_name(),
+        std::any::type_name::<Transient>()
+    );
+    let slot = cat.builders_for::<dyn Greeter>().next().unwrap();
+    assert!(slot.tags().is_empty() && slot.profiles().is_empty() && !slot.is_eager());
+
+    // Swappable implementations are not moved out of the catalog
+    assert_matches!(
+        cat.get::<Owned<dyn Greeter>>().err(),
+        Some(InjectionError::SharedInstance(_))
+    );
+
+    assert_matches!(
+        cat.swap_builder::<dyn Marker, _, Blue>(Blue::builder()),
+        Err(e) if e == InjectionError::unregistered::<dyn Marker>()
+    );
+}
+
+#[test]
+fn test_cycle_detection() {
+    #[component]
+    #[scope(Singleton)]
+    struct A {
+        _b: Arc<B>,
+    }
+
+    #[component]
+    struct B {
+        _c: Arc<C>,
+    }
+
+    #[component]
+    struct C {
+        _a: Arc<A>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<A>()
+        .add::<B>()
+        .add::<C>()
+        .build();
+
+    let err = cat.get_one::<B>().err().unwrap();
+    let cycle = match &err {
+        InjectionError::Cycle(cycle) => cycle,
+        _ => panic!("Unexpected error: {:?}", err),
+    };
+
+    let short_names: Vec<_> = cycle
+        .type_names()
+        .iter()
+        .map(|n| n.rsplit("::").next().unwrap())
+        .collect();
+    assert_eq!(short_names, vec!["B", "C", "A", "B"]);
+    assert!(cycle.to_string().contains(" -> "));
+
+    // Same type used by multiple dependents is not a cycle
+    #[component]
+    struct D {
+        _e1: Arc<E>,
+        _e2: Arc<E>,
+    }
+
+    #[component]
+    struct E;
+
+    let cat = CatalogBuilder::new().add::<D>().add::<E>().build();
+    cat.get_one::<D>().unwrap();
+}
+
+#[test]
+fn test_cycle_detection_through_injected_catalog() {
+    struct A {
+        b: Result<Arc<B>, InjectionError>,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    impl A {
+        pub fn new(cat: Catalog) -> Self {
+            // Resolution restarted from the injected catalog re-enters the singleton under construction
+            Self {
+                b: cat.get_one::<B>(),
+            }
+        }
+    }
+
+    #[component]
+    struct B {
+        _a: Arc<A>,
+    }
+
+    let cat = CatalogBuilder::new().add::<A>().add::<B>().build();
+
+    let a = cat.get_one::<A>().unwrap();
+    let err = a.b.as_ref().err().unwrap();
+    let cycle = match err {
+        InjectionError::Cycle(cycle) => cycle,
+        _ => panic!("Unexpected error: {:?}", err),
+    };
+
+    let short_names: Vec<_> = cycle
+        .type_names()
+        .iter()
+        .map(|n| n.rsplit("::").next().unwrap())
+        .collect();
+    assert_eq!(short_names, vec!["A", "B", "A"]);
+
+    // Singleton is usable once constructed
+    assert!(Arc::ptr_eq(&a, &cat.get_one::<A>().unwrap()));
+}
+
+#[test]
+fn test_lazy_breaks_cycle() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static B_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    #[component]
+    #[scope(Singleton)]
+    struct A {
+        b: Lazy<Arc<B>>,
+    }
+
+    struct B {
+        a: Arc<A>,
+    }
+
+    #[component]
+    impl B {
+        pub fn new(a: Arc<A>) -> Self {
+            B_CREATED.fetch_add(1, Ordering::SeqCst);
+            Self { a }
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<A>().add::<B>().build();
+
+    let a = cat.get_one::<A>().unwrap();
+    assert_eq!(B_CREATED.load(Ordering::SeqCst), 0);
+
+    let b = a.b.get().unwrap();
+    assert_eq!(B_CREATED.load(Ordering::SeqCst), 1);
+    assert!(Arc::ptr_eq(&b.a, &a));
+
+    // Value is cached after first use
+    let b2 = a.b.get().unwrap();
+    assert!(Arc::ptr_eq(&b, &b2));
+    assert_eq!(B_CREATED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_max_resolution_depth() {
+    #[component]
+    struct A;
+
+    #[component]
+    struct B {
+        _a: Arc<A>,
+    }
+
+    #[component]
+    struct C {
+        _b: Arc<B>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .max_resolution_depth(2)
+        .add::<A>()
+        .add::<B>()
+        .add::<C>()
+        .build();
+
+    assert!(cat.get_one::<B>().is_ok());
+
+    let err = cat.get_one::<C>().err().unwrap();
+    let exceeded = match &err {
+        InjectionError::MaxDepthExceeded(e) => e,
+        _ => panic!("Unexpected error: {:?}", err),
+    };
+    let short_names: Vec<_> = exceeded
+        .type_names()
+        .iter()
+        .map(|n| n.rsplit("::").next().unwrap())
+        .collect();
+    assert_eq!(exceeded.max_depth(), 2);
+    assert_eq!(short_names, vec!["C", "B", "A"]);
+}
+
+#[test]
+fn test_max_resolution_depth_lazy_recursion() {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static ERROR: RefCell<Option<InjectionError>> = RefCell::new(None);
+    }
+
+    // Recursion via a lazy handle is invisible to the cycle detection
+    struct Node {
+        _next: Option<Arc<Node>>,
+    }
+
+    #[component]
+    impl Node {
+        pub fn new(next: Lazy<Arc<Node>>) -> Self {
+            let next = next
+                .get()
+                .map_err(|e| ERROR.with(|err| err.borrow_mut().get_or_insert(e).clone()))
+                .ok();
+            Self { _next: next }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .max_resolution_depth(8)
+        .add::<Node>()
+        .build();
+
+    cat.get_one::<Node>().unwrap();
+
+    let err = ERROR.with(|err| err.borrow_mut().take()).unwrap();
+    let exceeded = match &err {
+        InjectionError::MaxDepthExceeded(e) => e,
+        _ => panic!("Unexpected error: {:?}", err),
+    };
+    assert_eq!(exceeded.type_names().len(), 9);
+    assert!(exceeded.to_string().contains("depth of 8"));
+}
+
+#[test]
+fn test_factory_honors_scope() {
+    trait Parser: Send + Sync {
+        fn parse(&mut self, line: &str) -> usize;
+    }
+
+    #[component]
+    #[interface(dyn Parser)]
+    struct LineParser {
+        #[dill::default]
+        parsed: usize,
+    }
+
+    impl Parser for LineParser {
+        fn parse(&mut self, line: &str) -> usize {
+            self.parsed += line.len();
+            self.parsed
+        }
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Config;
+
+    #[component]
+    #[scope(Singleton)]
+    struct Importer {
+        parsers: Factory<dyn Parser>,
+        configs: Factory<Config>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<LineParser>()
+        .add::<Config>()
+        .add::<Importer>()
+        .build();
+
+    let importer = cat.get_one::<Importer>().unwrap();
+
+    // Transient implementation is built anew on every call
+    let p1 = importer.parsers.create().unwrap();
+    let p2 = importer.parsers.create().unwrap();
+    assert!(!Arc::ptr_eq(&p1, &p2));
+
+    let mut p3 = importer.parsers.create_owned().unwrap();
+    assert_eq!(p3.parse("abc"), 3);
+    assert_eq!(p3.parse("de"), 5);
+
+    // Cached instances are shared
+    let c1 = importer.configs.create().unwrap();
+    let c2 = importer.configs.create().unwrap();
+    assert!(Arc::ptr_eq(&c1, &c2));
+
+    assert_eq!(
+        builder_for::<Importer>().dependencies(),
+        vec![
+            DependencyInfo::of::<dyn Parser>(DependencyKind::Lazy, None),
+            DependencyInfo::of::<Config>(DependencyKind::Lazy, None),
+        ]
+    );
+
+    // Factory doesn't require the dependency to be registered until it's used
+    let cat = CatalogBuilder::new().add::<Importer>().build();
+    let importer = cat.get_one::<Importer>().unwrap();
+    assert_matches!(
+        importer.configs.create(),
+        Err(InjectionError::Unregistered(_))
+    );
+}
+
+#[test]
+fn test_validate() {
+    trait Db: Send + Sync {}
+
+    #[component]
+    struct DbImpl1;
+    impl Db for DbImpl1 {}
+
+    #[component]
+    struct DbImpl2;
+    impl Db for DbImpl2 {}
+
+    #[component]
+    struct Repo {
+        _db: Arc<dyn Db>,
+        config: Arc<String>,
+        _cat: Catalog,
+    }
+
+    #[component]
+    struct Optional {
+        _db: Option<Arc<dyn Db>>,
+        _all: Vec<Arc<dyn Db>>,
+    }
+
+    let mut b = CatalogBuilder::new();
+    b.add::<DbImpl1>()
+        .bind::<dyn Db, DbImpl1>()
+        .add::<DbImpl2>()
+        .bind::<dyn Db, DbImpl2>()
+        .add::<Repo>()
+        .add::<Optional>();
+
+    let errors = b.validate().err().unwrap().errors().to_vec();
+    assert_eq!(errors.len(), 2);
+    assert!(errors.contains(&InjectionError::unregistered::<String>()));
+    assert!(errors.contains(&InjectionError::ambiguous::<dyn Db>(2)));
+
+    // Overridden dependencies are not validated
+    let mut b = CatalogBuilder::new();
+    b.add::<DbImpl1>()
+        .bind::<dyn Db, DbImpl1>()
+        .add_builder(builder_for::<Repo>().with_config(Arc::new("foo".to_owned())));
+    b.validate().unwrap();
+
+    let cat = b.build_checked().unwrap();
+    assert_eq!(*cat.get_one::<Repo>().unwrap().config, "foo");
+}
+
+#[test]
+fn test_validate_cycles() {
+    #[component]
+    struct A {
+        _b: Arc<B>,
+    }
+
+    #[component]
+    struct B {
+        _a: Arc<A>,
+    }
+
+    #[component]
+    struct C {
+        _a: Lazy<Arc<A>>,
+    }
+
+    let err = CatalogBuilder::new()
+        .add::<A>()
+        .add::<B>()
+        .add::<C>()
+        .validate()
+        .err()
+        .unwrap();
+
+    assert_eq!(err.errors().len(), 1);
+    assert_matches!(err.errors()[0], InjectionError::Cycle(_));
+}
+
+#[test]
+fn test_modules() {
+    trait Db: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Db)]
+    struct DbImpl;
+    impl Db for DbImpl {}
+
+    #[component]
+    struct UserRepo {
+        _db: Arc<dyn Db>,
+    }
+
+    #[component]
+    struct UserService {
+        _repo: Arc<UserRepo>,
+    }
+
+    struct DbModule;
+
+    impl Module for DbModule {
+        fn load(&self, b: &mut CatalogBuilder) {
+            b.add::<DbImpl>();
+        }
+    }
+
+    struct UsersModule;
+
+    impl Module for UsersModule {
+        fn load(&self, b: &mut CatalogBuilder) {
+            b.add::<UserRepo>().add::<UserService>();
+        }
+
+        fn requires(&self) -> Vec<DependencyInfo> {
+            vec![DependencyInfo::of::<dyn Db>(DependencyKind::One, None)]
+        }
+
+        fn name(&self) -> &'static str {
+            "users"
+        }
+    }
+
+    let mut b = CatalogBuilder::new();
+    b.add_module(UsersModule);
+
+    let errors = b.validate().err().unwrap().errors().to_vec();
+    assert_eq!(errors.len(), 1);
+    assert_matches!(
+        &errors[0],
+        InjectionError::Unregistered(e)
+            if e.type_name() == std::any::type_name::<dyn Db>() && e.required_by() == ["users"]
+    );
+
+    let cat = CatalogBuilder::new()
+        .add_module(UsersModule)
+        .add_module(DbModule)
+        .build_checked()
+        .unwrap();
+
+    cat.get_one::<UserService>().unwrap();
+}
+
+#[test]
+fn test_merge() {
+    use std::any::type_name;
+
+    trait Storage: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Storage)]
+    struct S3Storage;
+    impl Storage for S3Storage {}
+
+    #[component]
+    #[interface(dyn Storage)]
+    struct LocalStorage;
+    impl Storage for LocalStorage {}
+
+    #[component]
+    struct Invoices {
+        _storage: Arc<dyn Storage>,
+    }
+
+    #[component]
+    struct Reports;
+
+    let base = CatalogBuilder::new().add_value(8080u16).build();
+
+    let billing = || {
+        let mut b = base.builder();
+        b.add::<S3Storage>()
+            .add::<Invoices>()
+            .bind_named::<dyn Storage, S3Storage>("archive");
+        b
+    };
+
+    let mut reports = base.builder();
+    reports
+        .add::<LocalStorage>()
+        .add::<Reports>()
+        .bind_named::<dyn Storage, LocalStorage>("archive");
+
+    let err = base
+        .builder()
+        .merge(billing())
+        .unwrap()
+        .merge(reports.clone())
+        .err()
+        .unwrap();
+
+    // Registrations shared via the seed catalog are not conflicts
+    assert_eq!(err.conflicts().len(), 2);
+    assert_eq!(err.conflicts()[0].iface_name(), type_name::<dyn Storage>());
+    assert_eq!(err.conflicts()[0].name(), None);
+    assert_eq!(err.conflicts()[0].existing(), [type_name::<S3Storage>()]);
+    assert_eq!(err.conflicts()[0].merged(), [type_name::<LocalStorage>()]);
+    assert_eq!(err.conflicts()[1].name(), Some("archive"));
+    assert!(err.to_string().contains("LocalStorage"));
+
+    // Nothing is merged on conflicts
+    let mut b = base.builder();
+    b.merge(billing()).unwrap();
+    assert!(b.merge(reports).is_err());
+    let cat = b.build();
+    assert!(cat.get_one::<Reports>().is_err());
+
+    cat.get_one::<Invoices>().unwrap();
+    assert_eq!(cat.get::<AllOf<dyn Storage>>().unwrap().len(), 1);
+    assert_eq!(cat.get::<AllOf<u16>>().unwrap().len(), 1);
+
+    let mut reports = CatalogBuilder::new();
+    reports.add::<Reports>();
+    let cat = CatalogBuilder::new()
+        .merge(billing())
+        .unwrap()
+        .merge(reports)
+        .unwrap()
+        .build();
+    cat.get_one::<Invoices>().unwrap();
+    cat.get_one::<Reports>().unwrap();
+}
+
+#[test]
+fn test_replace() {
+    trait Reader: Send + Sync {
+        fn read(&self) -> String;
+    }
+
+    trait Writer: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Reader)]
+    #[interface(dyn Writer)]
+    struct Storage;
+    impl Writer for Storage {}
+    impl Reader for Storage {
+        fn read(&self) -> String {
+            "storage".to_owned()
+        }
+    }
+
+    struct FakeReader(String);
+    impl Reader for FakeReader {
+        fn read(&self) -> String {
+            self.0.clone()
+        }
+    }
+
+    #[component]
+    struct Service {
+        reader: Arc<dyn Reader>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Storage>()
+        .add::<Service>()
+        .replace::<dyn Reader, _>(FakeReader("fake".to_owned()))
+        .build();
+
+    assert_eq!(cat.get_one::<Service>().unwrap().reader.read(), "fake");
+
+    // Replaced implementation is unregistered along with its other bindings
+    assert!(cat.get_one::<Storage>().is_err());
+    assert!(cat.get_one::<dyn Writer>().is_err());
+    assert_eq!(cat.get::<AllOf<dyn Reader>>().unwrap().len(), 1);
+
+    // Builders can be used as replacements too
+    let cat = CatalogBuilder::new()
+        .add::<Storage>()
+        .add_value("built".to_owned())
+        .override_with::<dyn Reader, _, _>(FnBuilder::new(|cat: &Catalog| {
+            Ok(FakeReader(cat.get_one::<String>()?.as_ref().clone()))
+        }))
+        .build();
+
+    assert_eq!(cat.get_one::<dyn Reader>().unwrap().read(), "built");
+
+    // Interfaces of the parent catalog are shadowed
+    let parent = CatalogBuilder::new().add::<Storage>().build();
+    let child = CatalogBuilder::new_chained(&parent)
+        .replace::<dyn Reader, _>(FakeReader("child".to_owned()))
+        .build();
+
+    assert_eq!(child.get_one::<dyn Reader>().unwrap().read(), "child");
+    assert_eq!(parent.get_one::<dyn Reader>().unwrap().read(), "storage");
+}
+
+#[test]
+#[should_panic(expected = "no implementations are registered")]
+fn test_replace_unregistered() {
+    trait Reader: Send + Sync {}
+
+    struct FakeReader;
+    impl Reader for FakeReader {}
+
+    CatalogBuilder::new().replace::<dyn Reader, _>(FakeReader);
+}
+
+#[test]
+fn test_chained_catalog() {
+    #[component]
+    struct Config {
+        name: String,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Service {
+        config: Arc<Config>,
+    }
+
+    #[component]
+    struct Handler {
+        service: Arc<Service>,
+        config: Arc<Config>,
+    }
+
+    let parent = CatalogBuilder::new()
+        .add_value(Config {
+            name: "parent".to_owned(),
+        })
+        .add::<Service>()
+        .build();
+
+    let child = parent
+        .builder_chained()
+        .add_value(Config {
+            name: "child".to_owned(),
+        })
+        .add::<Handler>()
+        .build();
+
+    let handler = child.get_one::<Handler>().unwrap();
+    assert_eq!(handler.config.name, "child");
+
+    // Inherited components are resolved within the parent
+    assert_eq!(handler.service.config.name, "parent");
+    assert!(Arc::ptr_eq(
+        &handler.service,
+        &parent.get_one::<Service>().unwrap()
+    ));
+
+    // Parent is not affected by the child
+    assert_eq!(parent.get_one::<Config>().unwrap().name, "parent");
+    assert!(parent.get_one::<Handler>().is_err());
+}
+
+#[test]
+fn test_builder_from_catalog() {
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<Catalog>();
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    #[component]
+    #[interface(dyn Greeter)]
+    #[scope(Singleton)]
+    struct GreeterImpl {
+        name: Arc<String>,
+    }
+
+    impl Greeter for GreeterImpl {
+        fn greet(&self) -> String {
+            format!("hello {}", self.name)
+        }
+    }
+
+    struct Loud(Arc<dyn Greeter>);
+
+    impl Greeter for Loud {
+        fn greet(&self) -> String {
+            self.0.greet().to_uppercase()
+        }
+    }
+
+    struct Excited(Arc<dyn Greeter>);
+
+    impl Greeter for Excited {
+        fn greet(&self) -> String {
+            format!("{}!", self.0.greet())
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<GreeterImpl>()
+        .add_value("world".to_owned())
+        .add_decorator::<dyn Greeter, _>(|inner, _| Arc::new(Loud(inner)))
+        .build();
+
+    #[component]
+    struct Service {
+        greeter: Arc<dyn Greeter>,
+    }
+
+    let updated = cat
+        .builder()
+        .add::<Service>()
+        .add_decorator::<dyn Greeter, _>(|inner, _| Arc::new(Excited(inner)))
+        .build();
+
+    // Existing registrations and decorators are kept
+    assert_eq!(cat.get_one::<dyn Greeter>().unwrap().greet(), "HELLO WORLD");
+    assert_eq!(
+        updated.get_one::<Service>().unwrap().greeter.greet(),
+        "HELLO WORLD!"
+    );
+    assert!(cat.get_one::<Service>().is_err());
+
+    // Instances are not shared with the original catalog
+    assert!(!Arc::ptr_eq(
+        &cat.get_one::<GreeterImpl>().unwrap(),
+        &updated.get_one::<GreeterImpl>().unwrap()
+    ));
+}
+
+#[test]
+fn test_interface_attributes() {
+    trait Reader: Send + Sync {
+        fn read(&self) -> String;
+    }
+
+    trait Writer: Send + Sync {
+        fn write(&self) -> String;
+    }
+
+    #[component]
+    #[interface(dyn Reader)]
+    #[interface(dyn Writer)]
+    #[scope(Singleton)]
+    struct Storage;
+
+    impl Reader for Storage {
+        fn read(&self) -> String {
+            "read".to_owned()
+        }
+    }
+
+    impl Writer for Storage {
+        fn write(&self) -> String {
+            "write".to_owned()
+        }
+    }
+
+    struct Service;
+
+    #[component]
+    #[interface(dyn Reader)]
+    impl Service {
+        pub fn new() -> Self {
+            Self
+        }
+    }
+
+    impl Reader for Service {
+        fn read(&self) -> String {
+            "service".to_owned()
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<Storage>().build();
+
+    let reader = cat.get_one::<dyn Reader>().unwrap();
+    let writer = cat.get_one::<dyn Writer>().unwrap();
+    assert_eq!(reader.read(), "read");
+    assert_eq!(writer.write(), "write");
+
+    // Both interfaces resolve to the same instance
+    let inst = cat.get_one::<Storage>().unwrap();
+    assert_eq!(
+        inst.as_ref() as *const Storage as *const u8,
+        reader.as_ref() as *const dyn Reader as *const u8
+    );
+    assert_eq!(
+        inst.as_ref() as *const Storage as *const u8,
+        writer.as_ref() as *const dyn Writer as *const u8
+    );
+
+    let cat = CatalogBuilder::new()
+        .add::<Storage>()
+        .add::<Service>()
+        .build();
+
+    let mut names: Vec<_> = cat
+        .get::<AllOf<dyn Reader>>()
+        .unwrap()
+        .iter()
+        .map(|r| r.read())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["read", "service"]);
+}
+
+#[test]
+fn test_decorators() {
+    trait Repository: Send + Sync {
+        fn get(&self) -> String;
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct RepositoryImpl;
+
+    impl Repository for RepositoryImpl {
+        fn get(&self) -> String {
+            "repo".to_owned()
+        }
+    }
+
+    struct LoggingRepository {
+        inner: Arc<dyn Repository>,
+        prefix: Arc<String>,
+    }
+
+    impl Repository for LoggingRepository {
+        fn get(&self) -> String {
+            format!("{}({})", self.prefix, self.inner.get())
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<RepositoryImpl>()
+        .bind::<dyn Repository, RepositoryImpl>()
+        .add_value("log".to_owned())
+        .add_decorator::<dyn Repository, _>(|inner, cat| {
+            Arc::new(LoggingRepository {
+                inner,
+                prefix: cat.get_one().unwrap(),
+            })
+        })
+        .add_decorator::<dyn Repository, _>(|inner, _| {
+            Arc::new(LoggingRepository {
+                inner,
+                prefix: Arc::new("outer".to_owned()),
+            })
+        })
+        .build();
+
+    let repo = cat.get_one::<dyn Repository>().unwrap();
+    assert_eq!(repo.get(), "outer(log(repo))");
+
+    let all = cat.get::<AllOf<dyn Repository>>().unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].get(), "outer(log(repo))");
+
+    // Resolving the concrete type bypasses the decorators of the interface
+    assert_eq!(cat.get_one::<RepositoryImpl>().unwrap().get(), "repo");
+}
+
+#[test]
+fn test_dependency_graph() {
+    trait B: Send + Sync {}
+
+    #[component]
+    #[scope(Singleton)]
+    struct BImpl;
+    impl B for BImpl {}
+
+    #[component]
+    struct C;
+
+    #[allow(dead_code)]
+    #[component]
+    struct A {
+        b: Arc<dyn B>,
+        c: Option<Arc<C>>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<A>()
+        .add::<BImpl>()
+        .bind::<dyn B, BImpl>()
+        .build();
+
+    let graph = cat.dependency_graph();
+    assert_eq!(graph.nodes.len(), 2);
+    assert_eq!(
+        graph.edges,
+        vec![
+            GraphEdge {
+                from: TypeId::of::<A>(),
+                to: Some(TypeId::of::<BImpl>()),
+                dependency: DependencyInfo::of::<dyn B>(DependencyKind::One, None),
+            },
+            GraphEdge {
+                from: TypeId::of::<A>(),
+                to: None,
+                dependency: DependencyInfo::of::<C>(DependencyKind::Maybe, None),
+            },
+        ]
+    );
+
+    let dot = graph.to_dot();
+    let a = std::any::type_name::<A>();
+    let b = std::any::type_name::<BImpl>();
+    let c = std::any::type_name::<C>();
+    assert!(dot.starts_with("digraph dependencies {\n"));
+    assert!(dot.contains(&format!("\"{}\" [label=\"{}\\nSingleton\"];", b, b)));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\";", a, b)));
+    assert!(dot.contains(&format!("\"{}\" [color=red, fontcolor=red];", c)));
+    assert!(dot.contains(&format!(
+        "\"{}\" -> \"{}\" [style=dashed, color=red];",
+        a, c
+    )));
+}
+
+#[test]
+fn test_construction_order() {
+    #[component]
+    struct Clock;
+
+    #[component]
+    #[scope(Singleton)]
+    struct Pool;
+
+    #[allow(dead_code)]
+    #[component]
+    #[scope(Singleton)]
+    struct Cache {
+        clock: Arc<Clock>,
+    }
+
+    #[allow(dead_code)]
+    #[component]
+    struct Service {
+        cache: Arc<Cache>,
+        pool: Arc<Pool>,
+    }
+
+    let order = |cat: &Catalog| -> Vec<TypeId> {
+        cat.construction_order()
+            .into_iter()
+            .map(|node| node.type_id)
+            .collect()
+    };
+
+    let cat = CatalogBuilder::new()
+        .add::<Service>()
+        .add::<Cache>()
+        .add::<Clock>()
+        .add::<Pool>()
+        .build();
+
+    // Singletons go first unless they depend on other components
+    let expected = vec![
+        TypeId::of::<Pool>(),
+        TypeId::of::<Clock>(),
+        TypeId::of::<Cache>(),
+        TypeId::of::<Service>(),
+    ];
+    assert_eq!(order(&cat), expected);
+
+    let cat = CatalogBuilder::new()
+        .add::<Pool>()
+        .add::<Clock>()
+        .add::<Cache>()
+        .add::<Service>()
+        .build();
+    assert_eq!(order(&cat), expected);
+}
+
+#[test]
+fn test_components() {
+    trait Db: Send + Sync {}
+
+    #[component]
+    #[scope(Singleton)]
+    struct PgDb;
+    impl Db for PgDb {}
+
+    #[component(tags("api"))]
+    struct Handler {
+        _db: Arc<dyn Db>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<PgDb>()
+        .bind::<dyn Db, PgDb>()
+        .bind_named::<dyn Db, PgDb>("primary")
+        .add_with_priority::<Handler>(5)
+        .build();
+
+    let child = cat.builder_chained().add_value(8080u16).build();
+
+    let info = |cat: &Catalog, type_name: &str| {
+        cat.components()
+            .into_iter()
+            .find(|c| c.type_name == type_name)
+            .unwrap()
+    };
+
+    let db = info(&cat, std::any::type_name::<PgDb>());
+    assert_eq!(db.type_id, TypeId::of::<PgDb>());
+    assert_eq!(db.scope_name, std::any::type_name::<Singleton>());
+    assert!(!db.is_cached);
+
+    let mut bindings: Vec<_> = db
+        .bindings
+        .iter()
+        .map(|b| (b.type_name, b.name.as_deref()))
+        .collect();
+    bindings.sort();
+    let mut expected = vec![
+        (std::any::type_name::<PgDb>(), None),
+        (std::any::type_name::<dyn Db>(), None),
+        (std::any::type_name::<dyn Db>(), Some("primary")),
+    ];
+    expected.sort();
+    assert_eq!(bindings, expected);
+
+    let handler = info(&cat, std::any::type_name::<Handler>());
+    assert_eq!(handler.scope_name, std::any::type_name::<Transient>());
+    assert_eq!(handler.priority, 5);
+    assert_eq!(handler.tags, ["api"]);
+
+    cat.get_one::<Handler>().unwrap();
+    assert!(info(&cat, std::any::type_name::<PgDb>()).is_cached);
+    assert!(!info(&cat, std::any::type_name::<Handler>()).is_cached);
+
+    // Components of the parent catalog are not included
+    assert_eq!(child.components().len(), 1);
+    assert!(info(&child, std::any::type_name::<u16>()).is_cached);
+}
+
+#[test]
+fn test_wiring_snapshot() {
+    trait Db: Send + Sync {}
+
+    #[component]
+    #[scope(Singleton)]
+    struct PgDb;
+    impl Db for PgDb {}
+
+    #[component]
+    struct Handler {
+        _db: Arc<dyn Db>,
+    }
+
+    #[component]
+    struct Metrics;
+
+    let golden = CatalogBuilder::new()
+        .add::<PgDb>()
+        .bind::<dyn Db, PgDb>()
+        .bind_named::<dyn Db, PgDb>("primary")
+        .add::<Handler>()
+        .build()
+        .wiring_snapshot();
+
+    // Snapshot doesn't depend on the state of the catalog
+    let cat = CatalogBuilder::new()
+        .add::<PgDb>()
+        .bind::<dyn Db, PgDb>()
+        .bind_named::<dyn Db, PgDb>("primary")
+        .add::<Handler>()
+        .build();
+    cat.get_one::<Handler>().unwrap();
+    assert_eq!(cat.wiring_snapshot(), golden);
+    assert!(snapshot_diff(&golden, &cat.wiring_snapshot()).is_empty());
+
+    // Text format round-trips
+    let text = golden.to_string();
+    assert!(text.contains(&format!(
+        "binds {} as \"primary\"",
+        std::any::type_name::<dyn Db>()
+    )));
+    assert_eq!(text.parse::<WiringSnapshot>().unwrap(), golden);
+    assert!("  scope dill::Singleton".parse::<WiringSnapshot>().is_err());
+
+    let current = CatalogBuilder::new()
+        .add_with_scope::<PgDb, Transient>()
+        .bind::<dyn Db, PgDb>()
+        .add::<Metrics>()
+        .build()
+        .wiring_snapshot();
+
+    let diff = snapshot_diff(&golden, &current);
+    assert!(!diff.is_empty());
+
+    let names =
+        |c: &[ComponentWiring]| -> Vec<String> { c.iter().map(|c| c.type_name.clone()).collect() };
+    assert_eq!(names(&diff.added), [std::any::type_name::<Metrics>()]);
+    assert_eq!(names(&diff.removed), [std::any::type_name::<Handler>()]);
+    assert_eq!(diff.changed.len(), 1);
+
+    let (before, after) = &diff.changed[0];
+    assert_eq!(before.scope_name, std::any::type_name::<Singleton>());
+    assert_eq!(after.scope_name, std::any::type_name::<Transient>());
+
+    let report = diff.to_string();
+    assert!(report.contains(&format!("+ {}", std::any::type_name::<Metrics>())));
+    assert!(report.contains(&format!("- {}", std::any::type_name::<Handler>())));
+    assert!(report.contains("- binds"));
+}
+
+#[test]
+fn test_wiring_manifest() {
+    trait Db: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Db)]
+    struct PgDb;
+    impl Db for PgDb {}
+
+    #[component]
+    #[interface(dyn Db)]
+    struct SqliteDb;
+    impl Db for SqliteDb {}
+
+    let expected = CatalogBuilder::new()
+        .add_with_priority::<PgDb>(10)
+        .add::<SqliteDb>()
+        .build()
+        .wiring_manifest();
+
+    assert_eq!(expected.components.len(), 2);
+    assert_eq!(
+        expected.interfaces,
+        [InterfaceWiring {
+            type_name: std::any::type_name::<dyn Db>().to_owned(),
+            implementation: Some(std::any::type_name::<PgDb>().to_owned()),
+        }]
+    );
+    assert!(expected.config.is_empty());
+
+    CatalogBuilder::new()
+        .add::<SqliteDb>()
+        .add_with_priority::<PgDb>(10)
+        .build()
+        .assert_wiring(&expected);
+
+    // Same components with a different outcome of the priorities
+    let cat = CatalogBuilder::new()
+        .add::<PgDb>()
+        .add::<SqliteDb>()
+        .build();
+    assert_eq!(cat.wiring_manifest().interfaces[0].implementation, None);
+
+    let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cat.assert_wiring(&expected)
+    }))
+    .err()
+    .unwrap();
+    let message = err.downcast_ref::<String>().unwrap();
+    assert!(message.contains(&format!(
+        "~ interface {}: {} -> <ambiguous>",
+        std::any::type_name::<dyn Db>(),
+        std::any::type_name::<PgDb>()
+    )));
+}
+
+#[test]
+fn test_unregistered_diagnostics() {
+    trait Repository: Send + Sync {}
+
+    #[component]
+    struct RepositoryImpl;
+    impl Repository for RepositoryImpl {}
+
+    #[allow(dead_code)]
+    #[component]
+    struct Service {
+        repo: Arc<dyn Repository>,
+    }
+
+    #[allow(dead_code)]
+    #[component]
+    struct App {
+        service: Arc<Service>,
+    }
+
+    // Implementation is registered, but not bound to the interface
+    let cat = CatalogBuilder::new()
+        .add::<App>()
+        .add::<Service>()
+        .add::<RepositoryImpl>()
+        .build();
+
+    let err = cat.get_one::<App>().err().unwrap();
+    assert_eq!(err, InjectionError::unregistered::<dyn Repository>());
+
+    match &err {
+        InjectionError::Unregistered(e) => {
+            assert_eq!(e.type_name(), std::any::type_name::<dyn Repository>());
+            assert_eq!(
+                e.required_by(),
+                [
+                    std::any::type_name::<App>(),
+                    std::any::type_name::<Service>()
+                ]
+            );
+            assert_eq!(e.suggestions(), [std::any::type_name::<RepositoryImpl>()]);
+        }
+        _ => panic!("Unexpected error: {:?}", err),
+    }
+
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Unregistered type: {}, required by: {} -> {}, similar registered types: {}",
+            std::any::type_name::<dyn Repository>(),
+            std::any::type_name::<App>(),
+            std::any::type_name::<Service>(),
+            std::any::type_name::<RepositoryImpl>(),
+        )
+    );
+}
+
+#[test]
+fn test_warm_up_parallel() {
+    use std::sync::Mutex;
+    use std::thread::ThreadId;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<(&'static str, ThreadId)>>);
+
+    impl Log {
+        fn push(&self, entry: &'static str) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((entry, std::thread::current().id()));
+        }
+    }
+
+    struct Db;
+
+    #[component]
+    #[scope(Singleton)]
+    impl Db {
+        pub fn new(log: Arc<Log>) -> Self {
+            log.push("db");
+            Self
+        }
+    }
+
+    struct Search;
+
+    #[component]
+    #[scope(Singleton)]
+    impl Search {
+        pub fn new(log: Arc<Log>) -> Self {
+            log.push("search");
+            Self
+        }
+    }
+
+    struct Service;
+
+    #[component]
+    #[scope(Singleton)]
+    impl Service {
+        pub fn new(_db: Arc<Db>, _search: Arc<Search>, log: Arc<Log>) -> Self {
+            log.push("service");
+            Self
+        }
+    }
+
+    #[component]
+    struct Request;
+
+    let cat = CatalogBuilder::new()
+        .add_value(Log::default())
+        .add::<Db>()
+        .add::<Search>()
+        .add::<Service>()
+        .add::<Request>()
+        .build();
+
+    cat.warm_up_parallel().unwrap();
+
+    let log = cat.get_one::<Log>().unwrap();
+    let entries = log.0.lock().unwrap().clone();
+    assert_eq!(entries.len(), 3);
+
+    // Independent components are constructed concurrently, before their dependents
+    let thread_of = |name| entries.iter().find(|(e, _)| *e == name).unwrap().1;
+    if std::thread::available_parallelism().unwrap().get() > 1 {
+        assert_ne!(thread_of("db"), thread_of("search"));
+    }
+    assert_ne!(thread_of("db"), std::thread::current().id());
+    assert_eq!(entries[2].0, "service");
+
+    // Cached instances are not constructed again
+    cat.warm_up_parallel().unwrap();
+    assert_eq!(log.0.lock().unwrap().len(), 3);
+}
+
+#[test]
+fn test_shutdown() {
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<&'static str>>);
+
+    impl Log {
+        fn push(&self, entry: &'static str) {
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Singleton)]
+    struct Db {
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Db {
+        fn shutdown(&self) {
+            self.log.push("db");
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Singleton)]
+    struct Service {
+        db: Arc<Db>,
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Service {
+        fn shutdown(&self) {
+            self.log.push("service");
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    struct Request {
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Request {
+        fn shutdown(&self) {
+            self.log.push("request");
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Singleton)]
+    struct Unused {
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Unused {
+        fn shutdown(&self) {
+            self.log.push("unused");
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(Log::default())
+        .add::<Db>()
+        .add::<Service>()
+        .add::<Request>()
+        .add::<Unused>()
+        .build();
+
+    let service = cat.get_one::<Service>().unwrap();
+    cat.get_one::<Request>().unwrap();
+    cat.shutdown();
+
+    // Dependents go first, while instances that are not cached are not affected
+    assert_eq!(*service.log.0.lock().unwrap(), ["service", "db"]);
+    assert!(Arc::ptr_eq(&service.db, &cat.get_one::<Db>().unwrap()));
+}
+
+#[test]
+fn test_shutdown_token() {
+    use std::sync::Mutex;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    struct Poller {
+        token: ShutdownToken,
+        worker: Mutex<Option<JoinHandle<()>>>,
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Singleton)]
+    impl Poller {
+        pub fn new(token: ShutdownToken) -> Self {
+            let worker_token = token.child();
+            let worker =
+                std::thread::spawn(
+                    move || {
+                        while !worker_token.wait_timeout(Duration::from_millis(10)) {}
+                    },
+                );
+            Self {
+                token,
+                worker: Mutex::new(Some(worker)),
+            }
+        }
+    }
+
+    impl Shutdown for Poller {
+        fn shutdown(&self) {
+            // Token is cancelled before the components are shut down
+            assert!(self.token.is_cancelled());
+            self.worker.lock().unwrap().take().unwrap().join().unwrap();
+        }
+    }
+
+    // Token is injectable without a registration
+    let cat = CatalogBuilder::new()
+        .add::<Poller>()
+        .build_checked()
+        .unwrap();
+    let child = CatalogBuilder::new_chained(&cat).build();
+
+    let poller = cat.get_one::<Poller>().unwrap();
+    assert!(!poller.token.is_cancelled());
+    assert!(!child.shutdown_token().is_cancelled());
+
+    // Shutting down the child doesn't affect the parent
+    child.shutdown();
+    assert!(child.shutdown_token().is_cancelled());
+    assert!(!cat.shutdown_token().is_cancelled());
+
+    let snapshot = cat.snapshot();
+    cat.shutdown();
+    assert!(poller.worker.lock().unwrap().is_none());
+    assert!(!snapshot.shutdown_token().is_cancelled());
+
+    // Tokens derived after the shutdown are cancelled right away
+    assert!(cat.shutdown_token().child().is_cancelled());
+}
+
+#[test]
+fn test_get_with_runtime_args() {
+    #[component]
+    struct Pricing;
+
+    #[component]
+    struct OrderHandler {
+        pricing: Arc<Pricing>,
+        #[dill(arg)]
+        order_id: u64,
+        #[dill(arg)]
+        user: String,
+    }
+
+    struct Audit {
+        handler: Arc<OrderHandler>,
+        note: &'static str,
+    }
+
+    #[component]
+    impl Audit {
+        fn new(handler: Arc<OrderHandler>, #[dill(arg)] note: &'static str) -> Self {
+            Self { handler, note }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Pricing>()
+        .add::<OrderHandler>()
+        .add::<Audit>()
+        .build();
+
+    let handler = cat
+        .get_with::<OneOf<OrderHandler>>((42u64, "alice".to_owned()))
+        .unwrap();
+    assert_eq!(handler.order_id, 42);
+    assert_eq!(handler.user, "alice");
+
+    // Runtime arguments are not dependencies of the component
+    let deps: Vec<_> = OrderHandler::builder()
+        .dependencies()
+        .into_iter()
+        .map(|d| d.type_id)
+        .collect();
+    assert_eq!(deps, vec![TypeId::of::<Pricing>()]);
+
+    // Resolution without the arguments or with arguments of other types fails
+    assert_matches!(
+        cat.get_one::<OrderHandler>(),
+        Err(e) if e == InjectionError::missing_context::<(u64, String)>()
+    );
+    assert_matches!(
+        cat.get_with::<OneOf<OrderHandler>>((42u64,)),
+        Err(e) if e == InjectionError::missing_context::<(u64, String)>()
+    );
+
+    // Arguments are consumed by the requested component rather than its dependencies
+    assert_matches!(
+        cat.get_with::<OneOf<Audit>>(("checked",)),
+        Err(e) if e == InjectionError::missing_context::<(u64, String)>()
+    );
+}
+
+#[test]
+fn test_profiles() {
+    trait Mailer: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component(profiles("prod"))]
+    #[interface(dyn Mailer)]
+    struct SmtpMailer;
+    impl Mailer for SmtpMailer {
+        fn name(&self) -> String {
+            "smtp".to_owned()
+        }
+    }
+
+    #[component(profiles("dev", "test"))]
+    #[interface(dyn Mailer)]
+    struct FakeMailer;
+    impl Mailer for FakeMailer {
+        fn name(&self) -> String {
+            "fake".to_owned()
+        }
+    }
+
+    #[component]
+    struct Notifier {
+        mailer: Arc<dyn Mailer>,
+    }
+
+    let build = |profile: Option<&str>| {
+        let mut cat = CatalogBuilder::new();
+        if let Some(profile) = profile {
+            cat.with_profile(profile);
+        }
+        cat.add::<SmtpMailer>()
+            .add::<FakeMailer>()
+            .add::<Notifier>();
+        cat
+    };
+
+    let cat = build(Some("prod")).build();
+    assert_eq!(cat.get_one::<Notifier>().unwrap().mailer.name(), "smtp");
+    assert_eq!(
+        cat.get_one::<FakeMailer>().err(),
+        Some(InjectionError::unregistered::<FakeMailer>())
+    );
+
+    let cat = build(Some("test")).build();
+    assert_eq!(cat.get_one::<Notifier>().unwrap().mailer.name(), "fake");
+    assert_eq!(cat.get::<AllOf<dyn Mailer>>().unwrap().len(), 1);
+
+    // Components of all profiles are excluded when none is active
+    let mut cat = build(None);
+    assert_eq!(
+        cat.validate().err().unwrap().errors(),
+        [InjectionError::unregistered::<dyn Mailer>()]
+    );
+    assert_eq!(cat.build().builders().count(), 1);
+}
+
+#[test]
+fn test_resolution_observer() {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Recorder {
+        fn push(&self, entry: String) {
+            self.0.lock().unwrap().push(entry);
+        }
+
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    // Strips the paths, e.g. `dill::specs::OneOf<alloc::string::String>` -> `OneOf<String>`
+    fn short(type_name: &str) -> String {
+        type_name
+            .split_inclusive(|c: char| c == '<' || c == '>')
+            .map(|part| part.rsplit("::").next().unwrap())
+            .collect()
+    }
+
+    impl ResolutionObserver for Recorder {
+        fn on_resolve_start(&self, spec: &'static str) {
+            self.push(format!("start {}", short(spec)));
+        }
+
+        fn on_resolve_end(&self, spec: &'static str, _elapsed: Duration) {
+            self.push(format!("end {}", short(spec)));
+        }
+
+        fn on_build(&self, builder: &dyn Builder, _elapsed: Duration) {
+            self.push(format!("build {}", short(builder.instance_type_name())));
+        }
+
+        fn on_cache_hit(&self, builder: &dyn Builder) {
+            self.push(format!("hit {}", short(builder.instance_type_name())));
+        }
+
+        fn on_error(&self, spec: &'static str, _error: &InjectionError) {
+            self.push(format!("error {}", short(spec)));
+        }
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Config;
+
+    #[component]
+    struct Service {
+        _config: Arc<Config>,
+    }
+
+    #[component]
+    struct Broken {
+        _name: Arc<String>,
+    }
+
+    let recorder = Arc::new(Recorder::default());
+    let cat = CatalogBuilder::new()
+        .add::<Config>()
+        .add::<Service>()
+        .add::<Broken>()
+        .with_observer(recorder.clone())
+        .build();
+
+    cat.get_one::<Service>().unwrap();
+    assert_eq!(
+        recorder.take(),
+        [
+            "start OneOf<Service>",
+            "start OneOf<Config>",
+            "build Config",
+            "end OneOf<Config>",
+            "build Service",
+            "end OneOf<Service>",
+        ]
+    );
+
+    cat.get_one::<Service>().unwrap();
+    assert_eq!(
+        recorder.take(),
+        [
+            "start OneOf<Service>",
+            "start OneOf<Config>",
+            "hit Config",
+            "end OneOf<Config>",
+            "build Service",
+            "end OneOf<Service>",
+        ]
+    );
+
+    // Errors are reported once for the top-level resolution
+    assert!(cat.get_one::<Broken>().is_err());
+    assert_eq!(
+        recorder.take(),
+        [
+            "start OneOf<Broken>",
+            "start OneOf<String>",
+            "end OneOf<String>",
+            "end OneOf<Broken>",
+            "error OneOf<Broken>",
+        ]
+    );
+
+    // Child catalogs inherit the observers
+    let child = cat.builder_chained().build();
+    child.get_one::<Config>().unwrap();
+    assert_eq!(
+        recorder.take(),
+        ["start OneOf<Config>", "hit Config", "end OneOf<Config>"]
+    );
+
+    // As well as hits in the catalog-wide default scope
+    let recorder = Arc::new(Recorder::default());
+    let cat = CatalogBuilder::new()
+        .add::<Config>()
+        .add::<Service>()
+        .default_scope::<Singleton>()
+        .with_observer(recorder.clone())
+        .build();
+
+    cat.get_one::<Service>().unwrap();
+    recorder.take();
+
+    cat.get_one::<Service>().unwrap();
+    assert_eq!(
+        recorder.take(),
+        ["start OneOf<Service>", "hit Service", "end OneOf<Service>"]
+    );
+}
+
+#[test]
+fn test_recent_resolutions() {
+    #[component]
+    struct Config;
+
+    #[component]
+    struct Service {
+        _config: Arc<Config>,
+    }
+
+    #[component]
+    struct Broken {
+        _name: Arc<String>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Config>()
+        .add::<Service>()
+        .add::<Broken>()
+        .record_resolutions(3)
+        .build();
+
+    cat.get_one::<Service>().unwrap();
+    let history = cat.recent_resolutions();
+    let specs: Vec<_> = history.iter().map(|r| r.spec).collect();
+    assert_eq!(
+        specs,
+        [
+            std::any::type_name::<OneOf<Config>>(),
+            std::any::type_name::<OneOf<Service>>(),
+        ]
+    );
+    assert_eq!(history[0].required_by, [std::any::type_name::<Service>()]);
+    assert!(history[1].required_by.is_empty());
+    assert!(history.iter().all(|r| r.is_ok()));
+
+    // Only the latest resolutions are kept
+    cat.get_one::<Broken>().err().unwrap();
+    let history = cat.recent_resolutions();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[0].spec, std::any::type_name::<OneOf<Service>>());
+    assert_eq!(history[1].spec, std::any::type_name::<OneOf<String>>());
+    assert_eq!(history[1].required_by, [std::any::type_name::<Broken>()]);
+    assert_matches!(history[2].error, Some(InjectionError::Unregistered(_)));
+
+    // Recording is opt-in
+    let cat = CatalogBuilder::new().add::<Config>().build();
+    cat.get_one::<Config>().unwrap();
+    assert!(cat.recent_resolutions().is_empty());
+}
+
+#[test]
+fn test_catalog_macro() {
+    trait Db: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    trait Health: Send + Sync {
+        fn ok(&self) -> bool;
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct PgDb;
+
+    impl Db for PgDb {
+        fn name(&self) -> String {
+            "pg".to_owned()
+        }
+    }
+
+    impl Health for PgDb {
+        fn ok(&self) -> bool {
+            true
+        }
+    }
+
+    #[component]
+    struct Config {
+        #[dill::default = 10]
+        pool_size: usize,
+    }
+
+    #[component]
+    struct MyService {
+        db: Arc<dyn Db>,
+        config: Arc<Config>,
+        health: Option<Arc<dyn Health>>,
+        plugins: Vec<Arc<dyn Plugin>>,
+    }
+
+    trait Plugin: Send + Sync {}
+
+    impl MyService {
+        fn describe(&self) -> String {
+            format!(
+                "{}:{}:{}:{}",
+                self.db.name(),
+                self.config.pool_size,
+                self.health.as_ref().map(|h| h.ok()).unwrap_or(false),
+                self.plugins.len(),
+            )
+        }
+    }
+
+    let cat = catalog! {
+        MyService,
+        Config,
+        PgDb as dyn Db,
+        PgDb as dyn Health,
+    }
+    .build();
+
+    assert_eq!(
+        cat.get_one::<MyService>().unwrap().describe(),
+        "pg:10:true:0"
+    );
+
+    // Implementation is shared between the interfaces it's bound to
+    let db = cat.get_one::<dyn Db>().unwrap();
+    let health = cat.get_one::<dyn Health>().unwrap();
+    assert_eq!(
+        Arc::as_ptr(&db) as *const (),
+        Arc::as_ptr(&health) as *const ()
+    );
+}
+
+#[test]
+fn test_on_unresolved() {
+    trait Metrics: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    struct NoopMetrics;
+    impl Metrics for NoopMetrics {
+        fn name(&self) -> String {
+            "noop".to_owned()
+        }
+    }
+
+    #[component]
+    struct Service {
+        metrics: Arc<dyn Metrics>,
+        port: Arc<u16>,
+    }
+
+    let parent = CatalogBuilder::new()
+        .on_unresolved(|type_id, _, _| {
+            if type_id == TypeId::of::<u16>() {
+                Some(Arc::new(8080u16))
+            } else {
+                None
+            }
+        })
+        .build();
+
+    let cat = parent
+        .builder_chained()
+        .add::<Service>()
+        .on_unresolved(|type_id, type_name, _| {
+            if type_id == TypeId::of::<dyn Metrics>() {
+                assert!(type_name.contains("Metrics"));
+                let metrics: Arc<dyn Metrics> = Arc::new(NoopMetrics);
+                Some(Arc::new(metrics))
+            } else {
+                None
+            }
+        })
+        .build();
+
+    // Fallbacks are used for the arguments, parent handlers are consulted after the local ones
+    let service = cat.get_one::<Service>().unwrap();
+    assert_eq!(service.metrics.name(), "noop");
+    assert_eq!(*service.port, 8080);
+
+    // Types declined by all handlers are still unregistered
+    assert_matches!(
+        cat.get_one::<String>(),
+        Err(InjectionError::Unregistered(_))
+    );
+
+    // Handlers of the child don't affect the parent
+    assert_matches!(
+        parent.get_one::<dyn Metrics>(),
+        Err(InjectionError::Unregistered(_))
+    );
+
+    // Optional dependencies are provided by the handlers too
+    assert_eq!(
+        cat.get::<Maybe<OneOf<dyn Metrics>>>()
+            .unwrap()
+            .map(|m| m.name()),
+        Some("noop".to_owned())
+    );
+    assert_eq!(
+        cat.get::<Maybe<OneOf<u16>>>().unwrap().as_deref(),
+        Some(&8080)
+    );
+    assert!(cat.get::<Maybe<OneOf<String>>>().unwrap().is_none());
+}
+
+#[test]
+fn test_on_unresolved_shapes() {
+    // Sized types can be returned the same way as the trait objects
+    let cat = CatalogBuilder::new()
+        .on_unresolved(|type_id, _, _| {
+            if type_id == TypeId::of::<u16>() {
+                Some(Arc::new(Arc::new(8080u16)))
+            } else if type_id == TypeId::of::<i32>() {
+                Some(Arc::new(1u32))
+            } else {
+                None
+            }
+        })
+        .build();
+
+    assert_eq!(*cat.get_one::<u16>().unwrap(), 8080);
+
+    let err = cat.get_one::<i32>().err().unwrap();
+    assert_matches!(&err, InjectionError::UnresolvedMismatch(e) if e.type_name() == "i32");
+    assert_eq!(
+        err.to_string(),
+        "Instance returned by the unresolved handler for i32 has a wrong type"
+    );
+    assert_matches!(
+        cat.get::<Maybe<OneOf<i32>>>(),
+        Err(InjectionError::UnresolvedMismatch(_))
+    );
+}
+
+#[test]
+fn test_component_context() {
+    #[component]
+    struct Db {
+        ctx: ComponentContext,
+    }
+
+    struct Repo {
+        db: Arc<Db>,
+        ctx: ComponentContext,
+    }
+
+    #[component]
+    impl Repo {
+        fn new(db: Arc<Db>, ctx: ComponentContext) -> Self {
+            Self { db, ctx }
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<Db>().add::<Repo>().build();
+
+    // Every component receives its own identity
+    let repo = cat.get_one::<Repo>().unwrap();
+    assert_eq!(repo.ctx.short_type_name(), "Repo");
+    assert_eq!(repo.ctx.type_id(), TypeId::of::<Repo>());
+    assert!(repo.ctx.type_name().ends_with("::Repo"));
+    assert_eq!(repo.db.ctx.short_type_name(), "Db");
+
+    // Handles share the id of the catalog, while snapshots get their own
+    assert_eq!(repo.ctx.catalog_id(), cat.id());
+    assert_eq!(cat.enter_scope().id(), cat.id());
+
+    let snapshot = cat.snapshot();
+    assert_ne!(snapshot.id(), cat.id());
+    assert_eq!(
+        snapshot.get_one::<Repo>().unwrap().ctx.catalog_id(),
+        snapshot.id()
+    );
+
+    // There is no component outside of a constructor
+    assert_matches!(
+        cat.get::<ComponentContext>(),
+        Err(InjectionError::MissingContext(_))
+    );
+}
+
+#[test]
+fn test_bundles() {
+    trait Auth: Send + Sync {
+        fn user(&self) -> String;
+    }
+
+    trait Audit: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Auth)]
+    struct AuthImpl;
+    impl Auth for AuthImpl {
+        fn user(&self) -> String {
+            "alice".to_owned()
+        }
+    }
+
+    #[component]
+    #[interface(dyn Audit)]
+    struct AuditImpl;
+    impl Audit for AuditImpl {}
+
+    #[derive(Inject)]
+    struct Handlers {
+        auth: Arc<dyn Auth>,
+        audits: Vec<Arc<dyn Audit>>,
+        region: String,
+        db: Option<Arc<dyn Db>>,
+        #[dill(skip)]
+        requests: usize,
+    }
+
+    trait Db: Send + Sync {}
+
+    let cat = CatalogBuilder::new()
+        .add::<AuthImpl>()
+        .add::<AuditImpl>()
+        .add_value("eu".to_owned())
+        .build();
+
+    let handlers = cat.get_bundle::<Handlers>().unwrap();
+    assert_eq!(handlers.auth.user(), "alice");
+    assert_eq!(handlers.audits.len(), 1);
+    assert_eq!(handlers.region, "eu");
+    assert!(handlers.db.is_none());
+    assert_eq!(handlers.requests, 0);
+
+    // Resolution fails on the first missing dependency
+    let cat = CatalogBuilder::new().add::<AuditImpl>().build();
+    assert_matches!(
+        cat.get_bundle::<Handlers>().err().unwrap(),
+        InjectionError::Unregistered(e) if e.type_name().contains("Auth")
+    );
+}
+
+#[test]
+fn test_inject_fn() {
+    trait Greeter: Send + Sync {
+        fn greet(&self, name: &str) -> String;
+    }
+
+    #[component]
+    #[interface(dyn Greeter)]
+    struct GreeterImpl;
+    impl Greeter for GreeterImpl {
+        fn greet(&self, name: &str) -> String {
+            format!("Hello, {}", name)
+        }
+    }
+
+    struct Config {
+        punctuation: char,
+    }
+
+    #[dill::inject]
+    fn greet(
+        name: &str,
+        #[dill] greeter: Arc<dyn Greeter>,
+        #[dill] config: &Config,
+        #[dill] audit: Option<Arc<dyn Audit>>,
+        excited: bool,
+    ) -> String {
+        assert!(audit.is_none());
+        let mut greeting = greeter.greet(name);
+        if excited {
+            greeting.push(config.punctuation);
+        }
+        greeting
+    }
+
+    trait Audit: Send + Sync {}
+
+    let cat = CatalogBuilder::new()
+        .add::<GreeterImpl>()
+        .add_value(Config { punctuation: '!' })
+        .build();
+
+    assert_eq!(
+        greet_injected(&cat, "alice", true).unwrap(),
+        "Hello, alice!"
+    );
+    assert_eq!(
+        greet(
+            "bob",
+            Arc::new(GreeterImpl),
+            &Config { punctuation: '?' },
+            None,
+            true
+        ),
+        "Hello, bob?"
+    );
+
+    let cat = CatalogBuilder::new().add::<GreeterImpl>().build();
+    assert_eq!(
+        greet_injected(&cat, "alice", false).err(),
+        Some(InjectionError::unregistered::<Config>())
+    );
+}
+
+#[test]
+fn test_on_duplicate() {
+    trait Handler: Send + Sync {
+        fn name(&self) -> String;
+    }
+
+    #[component]
+    #[interface(dyn Handler)]
+    struct Audit {
+        name: String,
+    }
+    impl Handler for Audit {
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+    }
+
+    // Bindings are appended by default
+    let cat = CatalogBuilder::new()
+        .add_value("audit".to_owned())
+        .add::<Audit>()
+        .bind::<dyn Handler, Audit>()
+        .build();
+    assert_eq!(cat.get::<AllOf<dyn Handler>>().unwrap().len(), 2);
+
+    // Replacing the implementation rebinds the interfaces to the new builder
+    let cat = CatalogBuilder::new()
+        .on_duplicate(DuplicatePolicy::Replace)
+        .add_builder(AuditBuilder::new().with_name("first"))
+        .bind::<dyn Handler, Audit>()
+        .add_builder(AuditBuilder::new().with_name("second"))
+        .bind::<dyn Handler, Audit>()
+        .build();
+    let handlers = cat.get::<AllOf<dyn Handler>>().unwrap();
+    assert_eq!(handlers.len(), 1);
+    assert_eq!(handlers[0].name(), "second");
+    assert_eq!(cat.get_one::<Audit>().unwrap().name, "second");
+
+    // Appended implementations are resolved side by side
+    let cat = CatalogBuilder::new()
+        .on_duplicate(DuplicatePolicy::Append)
+        .add_builder(AuditBuilder::new().with_name("first"))
+        .add_builder(AuditBuilder::new().with_name("second"))
+        .build();
+    let names: Vec<_> = cat
+        .get::<AllOf<Audit>>()
+        .unwrap()
+        .iter()
+        .map(|a| a.name.clone())
+        .collect();
+    assert_eq!(names, ["first", "second"]);
+
+    // Every registration is bound to its own interfaces and is visible to the validation
+    let mut b = CatalogBuilder::new();
+    b.on_duplicate(DuplicatePolicy::Append)
+        .add_builder(AuditBuilder::new())
+        .bind::<dyn Handler, Audit>()
+        .add_value(Audit {
+            name: "second".to_owned(),
+        })
+        .bind::<dyn Handler, Audit>();
+    assert_eq!(
+        b.validate().err().unwrap().errors(),
+        [InjectionError::unregistered::<String>()]
+    );
+
+    let cat = b.add_value("first".to_owned()).build();
+    let names: Vec<_> = cat
+        .get::<AllOf<dyn Handler>>()
+        .unwrap()
+        .iter()
+        .map(|h| h.name())
+        .collect();
+    assert_eq!(names, ["first", "second"]);
+    assert_eq!(
+        cat.components()
+            .iter()
+            .filter(|c| c.type_name == std::any::type_name::<Audit>())
+            .count(),
+        2
+    );
+}
+
+#[test]
+#[should_panic(expected = "is already bound to")]
+fn test_on_duplicate_error() {
+    trait Handler: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Handler)]
+    struct Audit;
+    impl Handler for Audit {}
+
+    CatalogBuilder::new()
+        .on_duplicate(DuplicatePolicy::Error)
+        .add::<Audit>()
+        .bind::<dyn Handler, Audit>();
+}
+
+#[test]
+fn test_override_scope() {
+    trait Clock: Send + Sync {
+        fn now(&self) -> u64;
+    }
+
+    #[component]
+    #[interface(dyn Clock)]
+    struct SystemClock;
+    impl Clock for SystemClock {
+        fn now(&self) -> u64 {
+            0
+        }
+    }
+
+    struct FakeClock(u64);
+    impl Clock for FakeClock {
+        fn now(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[component]
+    struct Scheduler {
+        clock: Arc<dyn Clock>,
+        name: Option<Arc<String>>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<SystemClock>()
+        .add::<Scheduler>()
+        .build();
+    let other = cat.snapshot();
+
+    {
+        let _guard = cat
+            .override_scope()
+            .bind::<dyn Clock>(Arc::new(FakeClock(1)))
+            .bind_value("test".to_owned());
+
+        // Overrides are injected into the components
+        let scheduler = cat.get_one::<Scheduler>().unwrap();
+        assert_eq!(scheduler.clock.now(), 1);
+        assert_eq!(scheduler.name.as_deref().unwrap(), "test");
+
+        // Nested guards shadow the outer ones
+        {
+            let _guard = cat
+                .override_scope()
+                .bind::<dyn Clock>(Arc::new(FakeClock(2)));
+            assert_eq!(cat.get_one::<dyn Clock>().unwrap().now(), 2);
+        }
+        assert_eq!(cat.get_one::<dyn Clock>().unwrap().now(), 1);
+
+        // Other catalogs and threads are not affected
+        assert_eq!(other.get_one::<dyn Clock>().unwrap().now(), 0);
+        let thread_cat = cat.clone();
+        let now = std::thread::spawn(move || thread_cat.get_one::<dyn Clock>().unwrap().now());
+        assert_eq!(now.join().unwrap(), 0);
+    }
+
+    let scheduler = cat.get_one::<Scheduler>().unwrap();
+    assert_eq!(scheduler.clock.now(), 0);
+    assert!(scheduler.name.is_none());
+}
+
+#[test]
+fn test_check_health() {
+    #[component]
+    #[interface(dyn HealthCheck)]
+    struct Cache;
+    impl HealthCheck for Cache {
+        fn check_health(&self) -> HealthStatus {
+            HealthStatus::Degraded("stale".to_owned())
+        }
+    }
+
+    #[component]
+    #[interface(dyn HealthCheck)]
+    struct Queue;
+    impl HealthCheck for Queue {
+        fn check_health(&self) -> HealthStatus {
+            HealthStatus::Healthy
+        }
+    }
+
+    #[component]
+    #[interface(dyn HealthCheck)]
+    struct Db {
+        _url: Arc<String>,
+    }
+    impl HealthCheck for Db {
+        fn check_health(&self) -> HealthStatus {
+            HealthStatus::Healthy
+        }
+    }
+
+    let cat = CatalogBuilder::new().build();
+    let report = cat.check_health();
+    assert!(report.components().is_empty());
+    assert_eq!(report.status(), &HealthStatus::Healthy);
+
+    let cat = CatalogBuilder::new().add::<Cache>().add::<Queue>().build();
+    let report = cat.check_health();
+    assert_eq!(
+        report
+            .components()
+            .iter()
+            .map(|c| (c.type_name(), c.status().clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                std::any::type_name::<Cache>(),
+                HealthStatus::Degraded("stale".to_owned())
+            ),
+            (std::any::type_name::<Queue>(), HealthStatus::Healthy),
+        ]
+    );
+    assert_eq!(report.status(), &HealthStatus::Degraded("stale".to_owned()));
+    assert!(report.is_ready());
+
+    // Components that fail to resolve are unhealthy
+    let cat = CatalogBuilder::new().add::<Queue>().add::<Db>().build();
+    let report = cat.check_health();
+    assert_eq!(
+        report.components()[1].status(),
+        &HealthStatus::Unhealthy(cat.get_one::<Db>().err().unwrap().to_string())
+    );
+    assert_matches!(report.status(), HealthStatus::Unhealthy(_));
+    assert!(!report.is_ready());
+}
@@ -0,0 +1,7 @@
+/// Misuse of the macros has to be reported as a compile error pointing at the offending code
+/// rather than a panic of the macro. Run with `TRYBUILD=overwrite` to update the snapshots.
+#[test]
+fn test_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,147 @@
+#![feature(assert_matches)]
+#![cfg(feature = "config")]
+
+use std::assert_matches::assert_matches;
+
+use dill::*;
+
+trait Storage: Send + Sync {
+    fn describe(&self) -> String;
+}
+
+#[component]
+struct LocalStorage {
+    root: String,
+}
+
+impl Storage for LocalStorage {
+    fn describe(&self) -> String {
+        format!("local:{}", self.root)
+    }
+}
+
+#[component]
+struct S3Storage {
+    bucket: String,
+}
+
+impl Storage for S3Storage {
+    fn describe(&self) -> String {
+        format!("s3:{}", self.bucket)
+    }
+}
+
+fn storage_catalog() -> CatalogBuilder {
+    let mut b = CatalogBuilder::new();
+    b.add::<LocalStorage>()
+        .bind_named::<dyn Storage, LocalStorage>("local")
+        .add::<S3Storage>()
+        .bind_named::<dyn Storage, S3Storage>("s3")
+        .add_config("/tmp".to_owned())
+        .select_from_config::<dyn Storage>("storage.kind")
+        .value_from_config::<String>("storage.location");
+    b
+}
+
+#[test]
+fn test_configure_from() {
+    let doc = serde_json::json!({
+        "storage": {
+            "kind": "s3",
+            "location": "my-bucket",
+        },
+        "unrelated": true,
+    });
+
+    let cat = storage_catalog().configure_from(&doc).unwrap().build();
+
+    assert_eq!(
+        cat.get_one::<dyn Storage>().unwrap().describe(),
+        "s3:my-bucket"
+    );
+    assert_eq!(cat.get::<AllOf<dyn Storage>>().unwrap().len(), 1);
+}
+
+#[test]
+fn test_configure_from_missing_keys() {
+    let doc = serde_json::json!({ "storage": { "kind": "local" } });
+
+    let cat = storage_catalog().configure_from(&doc).unwrap().build();
+
+    // Value registered by default is kept
+    assert_eq!(
+        cat.get_one::<dyn Storage>().unwrap().describe(),
+        "local:/tmp"
+    );
+}
+
+#[test]
+fn test_configure_from_errors() {
+    let res = storage_catalog()
+        .configure_from(&serde_json::json!({ "storage": { "kind": "gcs" } }))
+        .map(|_| ());
+    assert_matches!(
+        res,
+        Err(ConfigError::UnknownImplementation { key, name, .. }) if key == "storage.kind" && name == "gcs"
+    );
+
+    let res = storage_catalog()
+        .configure_from(&serde_json::json!({ "storage": { "location": 10 } }))
+        .map(|_| ());
+    assert_matches!(
+        res,
+        Err(ConfigError::InvalidValue { key, .. }) if key == "storage.location"
+    );
+
+    let res = storage_catalog()
+        .configure_from(&serde_json::json!(["s3"]))
+        .map(|_| ());
+    assert_matches!(res, Err(ConfigError::InvalidDocument(_)));
+}
+
+#[test]
+fn test_wiring_manifest_json() {
+    let doc = serde_json::json!({ "storage": { "kind": "s3", "location": "my-bucket" } });
+
+    let cat = storage_catalog().configure_from(&doc).unwrap().build();
+    let manifest = cat.wiring_manifest();
+
+    assert_eq!(
+        manifest.config,
+        [
+            ConfigWiring {
+                key: "storage.kind".to_owned(),
+                value: "\"s3\"".to_owned(),
+            },
+            ConfigWiring {
+                key: "storage.location".to_owned(),
+                value: "\"my-bucket\"".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(
+        manifest.interfaces[0].implementation.as_deref(),
+        Some(std::any::type_name::<S3Storage>())
+    );
+
+    // Manifest exported along with a deployment round-trips
+    let json = manifest.to_json();
+    assert_eq!(json["config"]["storage.kind"], "s3");
+    assert_eq!(WiringManifest::from_json(&json).unwrap(), manifest);
+    assert!(WiringManifest::from_json(&serde_json::json!({ "components": {} })).is_err());
+
+    storage_catalog()
+        .configure_from(&doc)
+        .unwrap()
+        .build()
+        .assert_wiring(&manifest);
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        storage_catalog()
+            .configure_from(&serde_json::json!({ "storage": { "kind": "local" } }))
+            .unwrap()
+            .build()
+            .assert_wiring(&manifest)
+    }));
+    assert!(res.is_err());
+}
@@ -0,0 +1,143 @@
+#![cfg(not(feature = "unsync"))]
+
+use std::sync::Arc;
+
+use dill::*;
+
+trait Repo: Send + Sync {
+    fn url(&self) -> &str;
+}
+
+struct PgRepo {
+    url: String,
+}
+
+impl PgRepo {
+    fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+impl Repo for PgRepo {
+    fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+builder_for!(
+    PgRepo,
+    new(url: String),
+    scope: Singleton,
+    interfaces: [dyn Repo]
+);
+
+struct Audit;
+
+impl Audit {
+    fn create() -> Self {
+        Self
+    }
+}
+
+builder_for!(Audit, create());
+
+struct Service {
+    repo: Arc<dyn Repo>,
+    repos: Vec<Arc<dyn Repo>>,
+    audit: Option<Arc<Audit>>,
+    lazy_repo: Lazy<Arc<dyn Repo>>,
+    port: u16,
+}
+
+impl Service {
+    fn new(
+        repo: Arc<dyn Repo>,
+        repos: Vec<Arc<dyn Repo>>,
+        audit: Option<Arc<Audit>>,
+        lazy_repo: Lazy<Arc<dyn Repo>>,
+        _cat: Catalog,
+        port: u16,
+    ) -> Self {
+        Self {
+            repo,
+            repos,
+            audit,
+            lazy_repo,
+            port,
+        }
+    }
+}
+
+builder_for!(
+    Service,
+    new(
+        repo: Arc<dyn Repo>,
+        repos: Vec<Arc<dyn Repo>>,
+        audit: Option<Arc<Audit>>,
+        lazy_repo: Lazy<Arc<dyn Repo>>,
+        cat: Catalog,
+        port: u16,
+    ),
+);
+
+#[test]
+fn test_builder_for() {
+    let cat = CatalogBuilder::new()
+        .add_value("postgres://db".to_owned())
+        .add_value(5432u16)
+        .add::<PgRepo>()
+        .add::<Service>()
+        .build();
+
+    let service = cat.get_one::<Service>().unwrap();
+    assert_eq!(service.repo.url(), "postgres://db");
+    assert_eq!(service.repos.len(), 1);
+    assert!(service.audit.is_none());
+    assert_eq!(service.lazy_repo.get().unwrap().url(), "postgres://db");
+    assert_eq!(service.port, 5432);
+
+    // Scope and the interfaces are taken from the declaration
+    assert!(Arc::ptr_eq(
+        &service.repo,
+        &cat.get_one::<dyn Repo>().unwrap()
+    ));
+    assert!(!Arc::ptr_eq(&service, &cat.get_one::<Service>().unwrap()));
+
+    let cat = CatalogBuilder::new()
+        .add_value("postgres://db".to_owned())
+        .add_value(5432u16)
+        .add::<PgRepo>()
+        .add::<Audit>()
+        .add::<Service>()
+        .build();
+    assert!(cat.get_one::<Service>().unwrap().audit.is_some());
+}
+
+#[test]
+fn test_builder_for_dependencies() {
+    let deps: Vec<_> = Service::builder()
+        .dependencies()
+        .into_iter()
+        .map(|d| (d.type_name, d.kind))
+        .collect();
+    assert_eq!(
+        deps,
+        vec![
+            ("dyn test_declarative::Repo", DependencyKind::One),
+            ("dyn test_declarative::Repo", DependencyKind::All),
+            ("test_declarative::Audit", DependencyKind::Maybe),
+            ("dyn test_declarative::Repo", DependencyKind::Lazy),
+            ("u16", DependencyKind::One),
+        ]
+    );
+
+    let err = CatalogBuilder::new()
+        .add::<Service>()
+        .validate()
+        .err()
+        .unwrap();
+    assert_eq!(err.errors().len(), 2);
+
+    let builder = PgRepo::builder().with_scope(Transient::new());
+    assert_eq!(builder.scope_name(), "dill::scopes::Transient");
+}
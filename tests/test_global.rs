@@ -0,0 +1,32 @@
+#![cfg(all(feature = "global", not(feature = "unsync")))]
+
+use dill::*;
+
+// Global catalog can be set only once per process, so the scenarios share a single test
+#[test]
+fn test_global() {
+    assert!(try_global().is_none());
+
+    let res = std::panic::catch_unwind(global);
+    assert!(res.is_err());
+
+    set_global(CatalogBuilder::new().add_value("global".to_owned()).build());
+    assert_eq!(*global().get_one::<String>().unwrap(), "global");
+
+    let res = std::panic::catch_unwind(|| set_global(CatalogBuilder::new().build()));
+    assert!(res.is_err());
+    assert_eq!(*global().get_one::<String>().unwrap(), "global");
+
+    {
+        let _guard = override_global(CatalogBuilder::new().add_value("test".to_owned()).build());
+        assert_eq!(*global().get_one::<String>().unwrap(), "test");
+
+        // Overrides are only visible to the thread that made them
+        let other = std::thread::spawn(|| global().get_one::<String>().unwrap().to_string())
+            .join()
+            .unwrap();
+        assert_eq!(other, "global");
+    }
+
+    assert_eq!(*global().get_one::<String>().unwrap(), "global");
+}
@@ -0,0 +1,106 @@
+#![feature(assert_matches)]
+#![cfg(feature = "plugins")]
+
+use std::assert_matches::assert_matches;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use dill::*;
+
+/// Builds the plugin of `tests/plugin` with the features of this test run that are a part of
+/// [`PLUGIN_VERSION`] and the `extra` ones, returning the path of the library
+fn build_plugin(extra: &[&str]) -> PathBuf {
+    let mut features: Vec<_> = [
+        (cfg!(feature = "config"), "config"),
+        (cfg!(feature = "parking-lot"), "parking-lot"),
+        (cfg!(feature = "unsync"), "unsync"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, feature)| enabled.then(|| feature))
+    .collect();
+    features.extend(extra);
+
+    // Every variant gets its own directory, as they share the name of the library
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("plugin")
+        .join(extra.join("-"));
+
+    let status = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+        .arg("build")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/plugin/Cargo.toml"
+        ))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .arg("--features")
+        .arg(features.join(","))
+        .status()
+        .unwrap();
+    assert!(status.success(), "Failed to build the test plugin");
+
+    target_dir.join("debug").join(format!(
+        "{}dill_test_plugin{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+#[test]
+fn test_load_plugin() {
+    let path = build_plugin(&[]);
+
+    let mut b = CatalogBuilder::new();
+    unsafe { b.load_plugin(&path) }.map(|_| ()).unwrap();
+    let cat = b.build();
+
+    assert_eq!(*cat.get_one::<String>().unwrap(), "plugin");
+    assert_eq!(*cat.get_one::<u32>().unwrap(), 42);
+}
+
+#[test]
+fn test_load_plugin_incompatible_version() {
+    let path = build_plugin(&["mismatch"]);
+
+    let mut b = CatalogBuilder::new();
+    let res = unsafe { b.load_plugin(&path) }.map(|_| ());
+    assert_matches!(
+        res,
+        Err(PluginError::IncompatibleVersion { version, expected, .. })
+            if version == "0.0.0+sync (rustc 1.0.0)" && expected == PLUGIN_VERSION
+    );
+    assert_eq!(b.build().builders().count(), 0);
+}
+
+#[test]
+fn test_load_plugin_registration_panicked() {
+    let path = build_plugin(&["panic"]);
+
+    let mut b = CatalogBuilder::new();
+    let res = unsafe { b.load_plugin(&path) }.map(|_| ());
+    assert_matches!(res, Err(PluginError::RegistrationPanicked { .. }));
+
+    // Components registered before the panic are kept, as the library stays loaded
+    let cat = b.build();
+    assert_eq!(*cat.get_one::<String>().unwrap(), "plugin");
+    assert_matches!(cat.get_one::<u32>(), Err(InjectionError::Unregistered(_)));
+}
+
+#[test]
+fn test_load_plugin_missing_library() {
+    let mut b = CatalogBuilder::new();
+
+    let res = unsafe { b.load_plugin("/nonexistent/libplugin.so") }.map(|_| ());
+    assert_matches!(res, Err(PluginError::Load { path, .. }) if path == "/nonexistent/libplugin.so");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_load_plugin_not_a_plugin() {
+    let mut b = CatalogBuilder::new();
+
+    let res = unsafe { b.load_plugin("libc.so.6") }.map(|_| ());
+    assert_matches!(res, Err(PluginError::MissingEntryPoint { .. }));
+}
@@ -1,3 +1,5 @@
+#![cfg(not(feature = "unsync"))]
+
 use dill::*;
 
 #[test]
@@ -73,3 +75,855 @@ fn test_singleton() {
     assert_eq!(inst1.test(), "aimpl::foo");
     assert_eq!(inst2.test(), "aimpl::foo");
 }
+
+#[test]
+fn test_singleton_snapshot() {
+    #[component]
+    #[scope(Singleton)]
+    struct AImpl {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    let base = CatalogBuilder::new()
+        .add::<AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let snap1 = base.snapshot();
+    let snap2 = base.snapshot();
+
+    let inst_base = base.get_one::<AImpl>().unwrap();
+    let inst1 = snap1.get_one::<AImpl>().unwrap();
+    let inst2 = snap2.get_one::<AImpl>().unwrap();
+
+    assert_ne!(
+        inst_base.as_ref() as *const AImpl,
+        inst1.as_ref() as *const AImpl
+    );
+    assert_ne!(
+        inst1.as_ref() as *const AImpl,
+        inst2.as_ref() as *const AImpl
+    );
+
+    assert_eq!(
+        inst1.as_ref() as *const AImpl,
+        snap1.get_one::<AImpl>().unwrap().as_ref() as *const AImpl
+    );
+    assert_eq!(
+        inst_base.as_ref() as *const AImpl,
+        base.clone().get_one::<AImpl>().unwrap().as_ref() as *const AImpl
+    );
+    assert_eq!(inst1.name, "foo");
+}
+
+#[test]
+fn test_default_scope() {
+    trait A: Send + Sync {}
+
+    #[component]
+    struct ADefault;
+    impl A for ADefault {}
+
+    #[component]
+    #[scope(Transient)]
+    struct ATransient;
+
+    let cat = CatalogBuilder::new()
+        .default_scope::<Singleton>()
+        .add::<ADefault>()
+        .bind::<dyn A, ADefault>()
+        .add::<ATransient>()
+        .build();
+
+    let inst1 = cat.get_one::<ADefault>().unwrap();
+    let inst2 = cat.get_one::<ADefault>().unwrap();
+    assert_eq!(
+        inst1.as_ref() as *const ADefault,
+        inst2.as_ref() as *const ADefault
+    );
+
+    // Same instance is shared via interface binding
+    let inst3 = cat.get_one::<dyn A>().unwrap();
+    assert_eq!(
+        inst1.as_ref() as *const ADefault as *const u8,
+        inst3.as_ref() as *const dyn A as *const u8
+    );
+
+    let inst1 = cat.get_one::<ATransient>().unwrap();
+    let inst2 = cat.get_one::<ATransient>().unwrap();
+    assert_ne!(
+        inst1.as_ref() as *const ATransient,
+        inst2.as_ref() as *const ATransient
+    );
+}
+
+#[test]
+fn test_build_fresh() {
+    #[component]
+    #[scope(Singleton)]
+    struct AImpl {
+        name: String,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let builder = builder_for::<AImpl>();
+    let fresh1 = builder.build_fresh(&cat).unwrap();
+    let fresh2 = builder.build_fresh(&cat).unwrap();
+    assert_eq!(fresh1.name, "foo");
+    assert_ne!(
+        fresh1.as_ref() as *const AImpl,
+        fresh2.as_ref() as *const AImpl
+    );
+
+    // Fresh builds are not cached in the scope
+    let inst1 = TypedBuilder::get(&builder, &cat).unwrap();
+    let inst2 = TypedBuilder::get(&builder, &cat).unwrap();
+    assert_eq!(
+        inst1.as_ref() as *const AImpl,
+        inst2.as_ref() as *const AImpl
+    );
+    assert_ne!(
+        inst1.as_ref() as *const AImpl,
+        fresh1.as_ref() as *const AImpl
+    );
+    assert_ne!(
+        inst1.as_ref() as *const AImpl,
+        fresh2.as_ref() as *const AImpl
+    );
+}
+
+#[test]
+fn test_eager() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    #[component]
+    struct Config {}
+
+    struct Service {
+        _config: std::sync::Arc<Config>,
+    }
+
+    #[component(eager)]
+    #[scope(Singleton)]
+    impl Service {
+        pub fn new(config: std::sync::Arc<Config>) -> Self {
+            CREATED.fetch_add(1, Ordering::SeqCst);
+            Self { _config: config }
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Config>()
+        .add::<Service>()
+        .build();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+
+    cat.get_one::<Service>().unwrap();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+
+    // Missing dependency fails the build instead of the first use
+    let res = CatalogBuilder::new().add::<Service>().try_build();
+    assert_eq!(res.err(), Some(InjectionError::unregistered::<Config>()));
+}
+
+#[test]
+fn test_scoped() {
+    #[component]
+    #[scope(Scoped)]
+    struct AImpl {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    // Outside of a scope behaves like a transient
+    let inst1 = cat.get_one::<AImpl>().unwrap();
+    let inst2 = cat.get_one::<AImpl>().unwrap();
+    assert_ne!(
+        inst1.as_ref() as *const AImpl,
+        inst2.as_ref() as *const AImpl
+    );
+
+    let scope1 = cat.enter_scope();
+    let scope2 = cat.enter_scope();
+
+    let inst1 = scope1.get_one::<AImpl>().unwrap();
+    let inst2 = scope2.get_one::<AImpl>().unwrap();
+    assert_eq!(
+        inst1.as_ref() as *const AImpl,
+        scope1.get_one::<AImpl>().unwrap().as_ref() as *const AImpl
+    );
+    assert_ne!(
+        inst1.as_ref() as *const AImpl,
+        inst2.as_ref() as *const AImpl
+    );
+    assert_eq!(inst1.name, "foo");
+
+    // Instance is released together with the scope
+    let weak = std::sync::Arc::downgrade(&inst1);
+    drop(inst1);
+    assert!(weak.upgrade().is_some());
+    drop(scope1);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn test_weak_singleton() {
+    #[component]
+    #[scope(WeakSingleton)]
+    struct AImpl {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let inst1 = cat.get_one::<AImpl>().unwrap();
+    let inst2 = cat.get_one::<AImpl>().unwrap();
+    assert_eq!(
+        inst1.as_ref() as *const AImpl,
+        inst2.as_ref() as *const AImpl
+    );
+    assert_eq!(inst1.name, "foo");
+
+    // Catalog doesn't keep the instance alive
+    let weak = std::sync::Arc::downgrade(&inst1);
+    drop(inst1);
+    drop(inst2);
+    assert!(weak.upgrade().is_none());
+
+    let inst3 = cat.get_one::<AImpl>().unwrap();
+    assert_eq!(inst3.name, "foo");
+    assert_eq!(
+        inst3.as_ref() as *const AImpl,
+        cat.get_one::<AImpl>().unwrap().as_ref() as *const AImpl
+    );
+}
+
+#[test]
+fn test_eager_via_builder() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Worker;
+
+    #[component]
+    #[scope(Singleton)]
+    impl Worker {
+        pub fn new() -> Self {
+            CREATED.fetch_add(1, Ordering::SeqCst);
+            Self
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<Worker>().build();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 0);
+
+    let cat_eager = CatalogBuilder::new()
+        .add::<Worker>()
+        .eager::<Worker>()
+        .build();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+
+    cat_eager.get_one::<Worker>().unwrap();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+
+    cat.get_one::<Worker>().unwrap();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_transient_memoization() {
+    use std::sync::Arc;
+
+    #[component]
+    struct D;
+
+    #[component]
+    struct B {
+        d: Arc<D>,
+    }
+
+    #[component]
+    struct C {
+        d: Arc<D>,
+    }
+
+    #[component]
+    struct A {
+        b: Arc<B>,
+        c: Arc<C>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<A>()
+        .add::<B>()
+        .add::<C>()
+        .add::<D>()
+        .build();
+
+    // Every edge gets its own instance by default
+    let inst = cat.get_one::<A>().unwrap();
+    assert!(!Arc::ptr_eq(&inst.b.d, &inst.c.d));
+
+    let cat = CatalogBuilder::new()
+        .memoize_transients()
+        .add::<A>()
+        .add::<B>()
+        .add::<C>()
+        .add::<D>()
+        .build();
+
+    // Diamond is built once per resolution
+    let inst1 = cat.get_one::<A>().unwrap();
+    assert!(Arc::ptr_eq(&inst1.b.d, &inst1.c.d));
+
+    // Separate resolutions still get new instances
+    let inst2 = cat.get_one::<A>().unwrap();
+    assert!(!Arc::ptr_eq(&inst1.b.d, &inst2.b.d));
+    assert!(!Arc::ptr_eq(
+        &cat.get_one::<D>().unwrap(),
+        &cat.get_one::<D>().unwrap()
+    ));
+}
+
+#[test]
+fn test_custom_scope() {
+    use std::any::Any;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    struct Tenant(String);
+
+    // Caches an instance per tenant resolved from the catalog
+    struct PerTenant {
+        instances: Mutex<HashMap<String, Arc<dyn Any + Send + Sync>>>,
+    }
+
+    impl PerTenant {
+        pub fn new() -> Self {
+            Self {
+                instances: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    impl Scope for PerTenant {
+        fn get(&self, cat: &Catalog) -> Option<Arc<dyn Any + Send + Sync>> {
+            let tenant = cat.get_one::<Tenant>().ok()?;
+            self.instances.lock().unwrap().get(&tenant.0).cloned()
+        }
+
+        fn set(&self, cat: &Catalog, inst: Arc<dyn Any + Send + Sync>) {
+            if let Ok(tenant) = cat.get_one::<Tenant>() {
+                self.instances
+                    .lock()
+                    .unwrap()
+                    .insert(tenant.0.clone(), inst);
+            }
+        }
+
+        fn invalidate(&self, cat: &Catalog) {
+            if let Ok(tenant) = cat.get_one::<Tenant>() {
+                self.instances.lock().unwrap().remove(&tenant.0);
+            }
+        }
+    }
+
+    #[component]
+    #[scope(PerTenant)]
+    struct Settings {
+        tenant: Arc<Tenant>,
+    }
+
+    let base = CatalogBuilder::new().add::<Settings>().build();
+    assert_eq!(
+        builder_for::<Settings>().scope_name(),
+        std::any::type_name::<PerTenant>()
+    );
+
+    // Catalogs seeded from the same one share the builder along with its scope
+    let cat_a = base.builder().add_value(Tenant("a".to_owned())).build();
+    let cat_a2 = base.builder().add_value(Tenant("a".to_owned())).build();
+    let cat_b = base.builder().add_value(Tenant("b".to_owned())).build();
+
+    let inst_a = cat_a.get_one::<Settings>().unwrap();
+    let inst_b = cat_b.get_one::<Settings>().unwrap();
+    assert_eq!(inst_a.tenant.0, "a");
+    assert_eq!(inst_b.tenant.0, "b");
+    assert!(Arc::ptr_eq(&inst_a, &cat_a2.get_one::<Settings>().unwrap()));
+    assert!(!Arc::ptr_eq(&inst_a, &inst_b));
+
+    cat_a.invalidate::<Settings>();
+    assert!(!Arc::ptr_eq(&inst_a, &cat_a.get_one::<Settings>().unwrap()));
+    assert!(Arc::ptr_eq(&inst_b, &cat_b.get_one::<Settings>().unwrap()));
+}
+
+#[test]
+fn test_invalidate() {
+    use std::sync::Arc;
+
+    trait Credentials: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Credentials)]
+    #[scope(Singleton)]
+    struct Token;
+    impl Credentials for Token {}
+
+    let cat = CatalogBuilder::new().add::<Token>().build();
+
+    let inst1 = cat.get_one::<Token>().unwrap();
+    assert!(Arc::ptr_eq(&inst1, &cat.get_one::<Token>().unwrap()));
+
+    // Invalidating via the interface affects the implementation
+    cat.invalidate::<dyn Credentials>();
+    let inst2 = cat.get_one::<Token>().unwrap();
+    assert!(!Arc::ptr_eq(&inst1, &inst2));
+    assert!(Arc::ptr_eq(&inst2, &cat.get_one::<Token>().unwrap()));
+}
+
+#[test]
+fn test_invalidate_with_dependents() {
+    use std::sync::Arc;
+
+    trait Config: Send + Sync {}
+
+    #[component]
+    #[interface(dyn Config)]
+    #[scope(Singleton)]
+    struct FileConfig;
+    impl Config for FileConfig {}
+
+    #[component]
+    #[scope(Singleton)]
+    struct Repo {
+        _config: Arc<dyn Config>,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Service {
+        _repo: Arc<Repo>,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Unrelated;
+
+    let cat = CatalogBuilder::new()
+        .add::<FileConfig>()
+        .add::<Repo>()
+        .add::<Service>()
+        .add::<Unrelated>()
+        .build();
+
+    let service1 = cat.get_one::<Service>().unwrap();
+    let repo1 = cat.get_one::<Repo>().unwrap();
+    let unrelated1 = cat.get_one::<Unrelated>().unwrap();
+
+    cat.invalidate_with_dependents::<dyn Config>();
+
+    assert!(!Arc::ptr_eq(&service1, &cat.get_one::<Service>().unwrap()));
+    assert!(!Arc::ptr_eq(&repo1, &cat.get_one::<Repo>().unwrap()));
+    assert!(Arc::ptr_eq(
+        &unrelated1,
+        &cat.get_one::<Unrelated>().unwrap()
+    ));
+}
+
+#[test]
+fn test_cached() {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[component]
+    #[scope(Cached(Duration::from_millis(100)))]
+    struct Token {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<Token>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let inst1 = cat.get_one::<Token>().unwrap();
+    assert!(Arc::ptr_eq(&inst1, &cat.get_one::<Token>().unwrap()));
+    assert_eq!(inst1.name, "foo");
+
+    // Expired instance is rebuilt and cached again
+    std::thread::sleep(Duration::from_millis(150));
+    let inst2 = cat.get_one::<Token>().unwrap();
+    assert!(!Arc::ptr_eq(&inst1, &inst2));
+    assert!(Arc::ptr_eq(&inst2, &cat.get_one::<Token>().unwrap()));
+
+    assert_eq!(
+        builder_for::<Token>().scope_name(),
+        std::any::type_name::<Cached>()
+    );
+}
+
+#[test]
+fn test_keyed() {
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct TenantId(u64);
+
+    #[component]
+    #[scope(Keyed<TenantId>)]
+    struct TenantDb {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    #[component]
+    struct Service {
+        db: Arc<TenantDb>,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<TenantDb>()
+        .add::<Service>()
+        .add_value("foo".to_owned())
+        .build();
+
+    // Without a key behaves like a transient
+    let inst1 = cat.get_one::<TenantDb>().unwrap();
+    let inst2 = cat.get_one::<TenantDb>().unwrap();
+    assert!(!Arc::ptr_eq(&inst1, &inst2));
+
+    let tenant1 = cat.with_context(TenantId(1));
+    let tenant2 = cat.with_context(TenantId(2));
+
+    let inst1 = tenant1.get_one::<TenantDb>().unwrap();
+    let inst2 = tenant2.get_one::<TenantDb>().unwrap();
+    assert!(!Arc::ptr_eq(&inst1, &inst2));
+    assert_eq!(inst1.name, "foo");
+
+    // Same key selects the same instance regardless of the handle
+    assert!(Arc::ptr_eq(
+        &inst1,
+        &cat.with_context(TenantId(1)).get_one::<TenantDb>().unwrap()
+    ));
+
+    // Key is visible to the nested resolutions
+    assert!(Arc::ptr_eq(
+        &inst2,
+        &tenant2.get_one::<Service>().unwrap().db
+    ));
+
+    // Most recently attached key wins
+    assert!(Arc::ptr_eq(
+        &inst2,
+        &tenant1
+            .with_context(TenantId(2))
+            .get_one::<TenantDb>()
+            .unwrap()
+    ));
+
+    // Snapshots don't share the instances
+    let snapshot = cat.snapshot().with_context(TenantId(1));
+    assert!(!Arc::ptr_eq(
+        &inst1,
+        &snapshot.get_one::<TenantDb>().unwrap()
+    ));
+
+    tenant1.invalidate::<TenantDb>();
+    assert!(!Arc::ptr_eq(
+        &inst1,
+        &tenant1.get_one::<TenantDb>().unwrap()
+    ));
+    assert!(Arc::ptr_eq(&inst2, &tenant2.get_one::<TenantDb>().unwrap()));
+}
+
+#[test]
+fn test_thread_local() {
+    use std::cell::RefCell;
+    use std::sync::Arc;
+
+    // Send but not Sync
+    struct Conn {
+        queries: RefCell<Vec<String>>,
+    }
+
+    #[component]
+    #[scope(ThreadLocal)]
+    fn conn() -> ThreadBound<Conn> {
+        ThreadBound::new(Conn {
+            queries: RefCell::new(Vec::new()),
+        })
+    }
+
+    #[component]
+    struct Repo {
+        conn: Arc<ThreadBound<Conn>>,
+    }
+
+    impl Repo {
+        fn query(&self, q: &str) -> usize {
+            self.conn.queries.borrow_mut().push(q.to_owned());
+            self.conn.queries.borrow().len()
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(ConnBuilder::new())
+        .add::<Repo>()
+        .build();
+
+    assert_eq!(cat.get_one::<Repo>().unwrap().query("a"), 1);
+    assert_eq!(cat.get_one::<Repo>().unwrap().query("b"), 2);
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let cat = cat.clone();
+            std::thread::spawn(move || {
+                let repo = cat.get_one::<Repo>().unwrap();
+                assert_eq!(repo.query("c"), 1);
+                assert_eq!(cat.get_one::<Repo>().unwrap().query("d"), 2);
+                repo.conn.clone()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        // Instances of other threads can be held, but not used
+        let conn = handle.join().unwrap();
+        assert!(!conn.is_owned());
+        assert!(conn.try_get().is_none());
+    }
+
+    let conn = cat.get_one::<ThreadBound<Conn>>().unwrap();
+    assert!(conn.is_owned());
+    assert_eq!(conn.queries.borrow().len(), 2);
+
+    // Invalidation only affects the calling thread
+    cat.invalidate::<ThreadBound<Conn>>();
+    assert_eq!(cat.get_one::<Repo>().unwrap().query("e"), 1);
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let conn = cat.get_one::<ThreadBound<Conn>>().unwrap();
+        std::thread::spawn(move || conn.queries.borrow().len())
+            .join()
+            .unwrap()
+    }));
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_pooled() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    static OPENED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Conn {
+        id: usize,
+    }
+
+    #[component]
+    #[scope(Pooled(2))]
+    fn conn() -> Conn {
+        Conn {
+            id: OPENED.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_builder(ConnBuilder::new())
+        .build();
+
+    // Instance is reused once checked back in
+    let id = cat.get_one::<Conn>().unwrap().id;
+    assert_eq!(cat.get_one::<Conn>().unwrap().id, id);
+    assert_eq!(OPENED.load(Ordering::SeqCst), 1);
+
+    // Held instances are never handed out twice
+    let c1 = cat.get_one::<Conn>().unwrap();
+    let c2 = cat.get_one::<Conn>().unwrap();
+    assert!(!Arc::ptr_eq(&c1, &c2));
+    assert_eq!(OPENED.load(Ordering::SeqCst), 2);
+
+    let res = cat.get_one::<Conn>();
+    assert!(matches!(&res, Err(InjectionError::PoolExhausted(e)) if e.capacity() == 2));
+    assert_eq!(
+        res.err().unwrap().to_string(),
+        "All 2 pooled instances are checked out"
+    );
+
+    // Clones keep the instance checked out
+    let c2_clone = c2.clone();
+    drop(c2);
+    assert!(cat.get_one::<Conn>().is_err());
+    drop(c2_clone);
+    assert_eq!(cat.get_one::<Conn>().unwrap().id, c1.id + 1);
+    drop(c1);
+
+    // Pools are per-catalog
+    let other = CatalogBuilder::new()
+        .add_builder(ConnBuilder::new())
+        .build();
+    assert_eq!(other.get_one::<Conn>().unwrap().id, 2);
+
+    cat.invalidate::<Conn>();
+    assert_eq!(cat.get_one::<Conn>().unwrap().id, 3);
+}
+
+#[test]
+fn test_cache_limits() {
+    use std::sync::{Arc, Weak};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct TenantId(u64);
+
+    #[component]
+    #[scope(Keyed<TenantId>)]
+    struct TenantDb {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    struct Registry;
+
+    let cat = CatalogBuilder::new()
+        .max_cached_instances(2)
+        .add::<TenantDb>()
+        .add::<Registry>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let registry = Arc::downgrade(&cat.get_one::<Registry>().unwrap());
+    let get = |tenant| -> Weak<TenantDb> {
+        Arc::downgrade(&cat.with_context(TenantId(tenant)).get_one().unwrap())
+    };
+
+    let db1 = get(1);
+    let db2 = get(2);
+    assert!(db1.upgrade().is_some());
+
+    // Accessing the instance makes the other one least recently used
+    assert!(Weak::ptr_eq(&db1, &get(1)));
+    let db3 = get(3);
+    assert!(db1.upgrade().is_some());
+    assert!(db2.upgrade().is_none());
+    assert!(db3.upgrade().is_some());
+
+    // Instances in use are not evicted
+    let held1 = db1.upgrade().unwrap();
+    let held3 = db3.upgrade().unwrap();
+    let db4 = get(4);
+    assert!(Arc::ptr_eq(
+        &held1,
+        &cat.with_context(TenantId(1)).get_one().unwrap()
+    ));
+    assert!(Weak::ptr_eq(&db4, &get(4)));
+    drop((held1, held3));
+
+    // Evicts the ones released meanwhile
+    let db5 = get(5);
+    assert!(db1.upgrade().is_none());
+    assert!(db3.upgrade().is_none());
+    assert!(db4.upgrade().is_some());
+    assert!(db5.upgrade().is_some());
+
+    // Singletons are not affected
+    assert!(registry.upgrade().is_some());
+
+    // Evicted tenants get a new instance that is cached again
+    for tenant in 100..1000 {
+        get(tenant);
+    }
+    let db2 = get(2);
+    assert!(db2.upgrade().is_some());
+    assert!(Weak::ptr_eq(&db2, &get(2)));
+    assert!(db5.upgrade().is_none());
+    assert!(registry.upgrade().is_some());
+}
+
+#[test]
+fn test_scope_guard() {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<&'static str>>);
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Singleton)]
+    struct Pool {
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Pool {
+        fn shutdown(&self) {
+            self.log.0.lock().unwrap().push("pool");
+        }
+    }
+
+    #[component]
+    #[interface(dyn Shutdown)]
+    #[scope(Scoped)]
+    struct Transaction {
+        pool: Arc<Pool>,
+        log: Arc<Log>,
+    }
+
+    impl Shutdown for Transaction {
+        fn shutdown(&self) {
+            self.log.0.lock().unwrap().push("tx");
+        }
+    }
+
+    let cat = CatalogBuilder::new()
+        .add_value(Log::default())
+        .add::<Pool>()
+        .add::<Transaction>()
+        .build();
+    let log = cat.get_one::<Log>().unwrap();
+
+    // Only the instances of the scope are shut down and dropped, singletons are left intact
+    let (tx, scope_cat) = cat.scope(|cat| {
+        let tx = cat.get_one::<Transaction>().unwrap();
+        assert!(Arc::ptr_eq(&tx, &cat.get_one::<Transaction>().unwrap()));
+        (tx, cat.clone())
+    });
+    assert_eq!(*log.0.lock().unwrap(), ["tx"]);
+    assert!(Arc::ptr_eq(&tx.pool, &cat.get_one::<Pool>().unwrap()));
+
+    // Escaped handles resolve new instances
+    assert!(!Arc::ptr_eq(
+        &tx,
+        &scope_cat.get_one::<Transaction>().unwrap()
+    ));
+
+    // Instances are shut down on panic too
+    log.0.lock().unwrap().clear();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cat.scope(|cat| {
+            cat.get_one::<Transaction>().unwrap();
+            panic!("boom");
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(*log.0.lock().unwrap(), ["tx"]);
+}
@@ -0,0 +1,83 @@
+#![cfg(all(feature = "testing", not(feature = "unsync")))]
+
+use std::sync::{Arc, Mutex};
+
+use dill::*;
+
+#[interface]
+trait Mailer: Send + Sync {
+    fn send(&self, to: &str, body: &str) -> bool;
+
+    fn sender(&self) -> String {
+        "noreply@example.com".to_owned()
+    }
+}
+
+#[component]
+#[interface(dyn Mailer)]
+struct SmtpMailer {
+    outbox: Arc<Outbox>,
+}
+
+impl Mailer for SmtpMailer {
+    fn send(&self, to: &str, body: &str) -> bool {
+        self.outbox
+            .0
+            .lock()
+            .unwrap()
+            .push(format!("{}: {}", to, body));
+        true
+    }
+}
+
+#[derive(Default)]
+struct Outbox(Mutex<Vec<String>>);
+
+#[component]
+struct Signup {
+    mailer: Arc<dyn Mailer>,
+}
+
+impl Signup {
+    fn register(&self, email: &str) {
+        self.mailer.send(email, "Welcome!");
+    }
+}
+
+#[test]
+fn test_spy() {
+    let mut b = CatalogBuilder::new();
+    b.add::<SmtpMailer>()
+        .add_value(Outbox::default())
+        .add::<Signup>();
+    let mailer_calls = b.spy::<dyn Mailer>();
+    let cat = b.build();
+
+    cat.get_one::<Signup>()
+        .unwrap()
+        .register("alice@example.com");
+    assert_eq!(mailer_calls.calls(), ["send"]);
+    assert_eq!(mailer_calls.call_count("send"), 1);
+
+    // Calls are delegated to the real component
+    assert_eq!(
+        *cat.get_one::<Outbox>().unwrap().0.lock().unwrap(),
+        ["alice@example.com: Welcome!"]
+    );
+
+    // Recorder is shared by all resolved instances, including the default methods
+    let mailer = cat.get_mailer().unwrap();
+    assert_eq!(mailer.sender(), "noreply@example.com");
+    cat.get_one::<Signup>().unwrap().register("bob@example.com");
+    assert_eq!(mailer_calls.calls(), ["send", "sender", "send"]);
+    assert!(!mailer_calls.was_called("other"));
+
+    mailer_calls.clear();
+    assert!(mailer_calls.calls().is_empty());
+
+    // Component resolved by its own type is not decorated
+    cat.get_one::<SmtpMailer>()
+        .unwrap()
+        .send("eve@example.com", "Hi");
+    assert!(mailer_calls.calls().is_empty());
+}
@@ -1,3 +1,7 @@
+#![cfg(not(feature = "unsync"))]
+
+use std::sync::Arc;
+
 use dill::*;
 
 #[test]
@@ -60,3 +64,147 @@ fn test_clone_to_threads() {
     assert_eq!(res1, "foo");
     assert_eq!(res2, "foo");
 }
+
+#[test]
+fn test_transient_concurrent_resolution() {
+    #[component]
+    struct AImpl {
+        // Needed for compiler not to optimize type out
+        name: String,
+    }
+
+    let cat = CatalogBuilder::new()
+        .add::<AImpl>()
+        .add_value("foo".to_owned())
+        .build();
+
+    let handles: Vec<_> = (0..16)
+        .map(|_| {
+            let cat = cat.clone();
+            std::thread::spawn(move || {
+                (0..100)
+                    .map(|_| cat.get_one::<AImpl>().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    let instances: Vec<_> = handles
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+
+    let distinct: std::collections::HashSet<_> =
+        instances.iter().map(|i| Arc::as_ptr(i) as usize).collect();
+
+    assert_eq!(instances.len(), 1600);
+    assert_eq!(distinct.len(), 1600);
+    assert!(instances.iter().all(|i| i.name == "foo"));
+}
+
+#[test]
+fn test_singleton_constructed_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    #[component]
+    #[scope(Singleton)]
+    struct Dep {}
+
+    struct AImpl {
+        dep: Arc<Dep>,
+    }
+
+    #[component]
+    #[scope(Singleton)]
+    impl AImpl {
+        pub fn new(dep: Arc<Dep>) -> Self {
+            CREATED.fetch_add(1, Ordering::SeqCst);
+            // Widen the window for racing threads
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            Self { dep }
+        }
+    }
+
+    let cat = CatalogBuilder::new().add::<Dep>().add::<AImpl>().build();
+    let barrier = Arc::new(std::sync::Barrier::new(8));
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let cat = cat.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                cat.get_one::<AImpl>().unwrap()
+            })
+        })
+        .collect();
+
+    let instances: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+    assert!(instances.iter().all(|i| Arc::ptr_eq(i, &instances[0])));
+    assert!(Arc::ptr_eq(
+        &instances[0].dep,
+        &cat.get_one::<Dep>().unwrap()
+    ));
+}
+
+#[test]
+fn test_singletons_concurrent_first_resolution() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    macro_rules! singleton {
+        ($name:ident) => {
+            struct $name;
+
+            #[component]
+            #[scope(Singleton)]
+            impl $name {
+                pub fn new() -> Self {
+                    CREATED.fetch_add(1, Ordering::SeqCst);
+                    Self
+                }
+            }
+        };
+    }
+
+    singleton!(A);
+    singleton!(B);
+    singleton!(C);
+    singleton!(D);
+
+    let cat = CatalogBuilder::new()
+        .add::<A>()
+        .add::<B>()
+        .add::<C>()
+        .add::<D>()
+        .build();
+    let barrier = Arc::new(std::sync::Barrier::new(8));
+
+    // Slots of different singletons are created concurrently
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let cat = cat.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..1000 {
+                    cat.get_one::<A>().unwrap();
+                    cat.get_one::<B>().unwrap();
+                    cat.get_one::<C>().unwrap();
+                    cat.get_one::<D>().unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for h in handles {
+        h.join().unwrap();
+    }
+
+    assert_eq!(CREATED.load(Ordering::SeqCst), 4);
+}
@@ -0,0 +1,74 @@
+#![cfg(all(feature = "dill-tokio", not(feature = "unsync")))]
+
+use std::sync::Arc;
+
+use dill::*;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+}
+
+#[component]
+#[scope(Scoped)]
+struct RequestId;
+
+// Deeply nested code that doesn't receive the catalog
+fn request_id() -> Arc<RequestId> {
+    current_catalog().get_one::<RequestId>().unwrap()
+}
+
+#[test]
+fn test_run_scoped() {
+    let cat = CatalogBuilder::new()
+        .add::<RequestId>()
+        .add_value(8080u16)
+        .build();
+
+    assert!(try_current_catalog().is_none());
+
+    let request = cat.enter_scope();
+    let (a, b) = runtime().block_on(request.run_scoped(async {
+        let a = request_id();
+        tokio::task::yield_now().await;
+        (a, request_id())
+    }));
+    assert!(Arc::ptr_eq(&a, &b));
+
+    // Every request gets its own scope
+    let other = runtime().block_on(cat.enter_scope().run_scoped(async { request_id() }));
+    assert!(!Arc::ptr_eq(&a, &other));
+
+    // Innermost catalog wins
+    let child = CatalogBuilder::new_chained(&cat).add_value(9090u16).build();
+    let ports = runtime().block_on(cat.run_scoped(async {
+        let port = || *current_catalog().get_one::<u16>().unwrap();
+        let inner = child.run_scoped(async { port() }).await;
+        (port(), inner, port())
+    }));
+    assert_eq!(ports, (8080, 9090, 8080));
+
+    assert!(try_current_catalog().is_none());
+}
+
+#[test]
+fn test_spawn_scoped() {
+    let cat = CatalogBuilder::new().add::<RequestId>().build();
+
+    let request = cat.enter_scope();
+    let (a, b) = runtime().block_on(request.run_scoped(async {
+        let a = request_id();
+        let b = spawn_scoped(async { request_id() }).await.unwrap();
+        (a, b)
+    }));
+    assert!(Arc::ptr_eq(&a, &b));
+
+    // Without the enclosing scope the task has no catalog either
+    let spawned = runtime().block_on(async {
+        spawn_scoped(async { try_current_catalog().is_none() })
+            .await
+            .unwrap()
+    });
+    assert!(spawned);
+}
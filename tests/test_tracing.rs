@@ -0,0 +1,139 @@
+#![cfg(all(feature = "tracing", not(feature = "unsync")))]
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use dill::*;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records spans and events in a compact form, e.g. `span:A(Singleton)` or `Cache hit:A`
+#[derive(Default, Clone)]
+struct Recorder {
+    log: Arc<Mutex<Vec<String>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+#[derive(Default)]
+struct Fields {
+    message: String,
+    component: Option<String>,
+    scope: Option<String>,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let short = value.rsplit("::").next().unwrap().to_owned();
+        match field.name() {
+            "component" => self.component = Some(short),
+            "scope" => self.scope = Some(short),
+            _ => (),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::default();
+        span.record(&mut fields);
+        self.log.lock().unwrap().push(format!(
+            "span:{}({})",
+            fields.component.unwrap(),
+            fields.scope.unwrap()
+        ));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let entry = match fields.component {
+            Some(component) => format!("{}:{}", fields.message, component),
+            None => fields.message,
+        };
+        self.log.lock().unwrap().push(entry);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+impl Recorder {
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.log.lock().unwrap())
+    }
+}
+
+#[test]
+fn test_construction_spans_and_cache_events() {
+    #[component]
+    struct B;
+
+    #[component]
+    #[scope(Singleton)]
+    struct A {
+        _b: Arc<B>,
+    }
+
+    let cat = CatalogBuilder::new().add::<A>().add::<B>().build();
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        cat.get_one::<A>().unwrap();
+        assert_eq!(
+            recorder.take(),
+            [
+                "span:A(Singleton)",
+                "Cache miss",
+                "span:B(Transient)",
+                "Cache miss",
+                "Constructed instance",
+                "Constructed instance",
+            ]
+        );
+
+        cat.get_one::<A>().unwrap();
+        assert_eq!(recorder.take(), ["Cache hit:A"]);
+    });
+}
+
+#[test]
+fn test_construction_failure_event() {
+    #[component]
+    struct A {
+        _name: Arc<String>,
+    }
+
+    let cat = CatalogBuilder::new().add::<A>().build();
+    let recorder = Recorder::default();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        assert!(cat.get_one::<A>().is_err());
+    });
+
+    assert_eq!(
+        recorder.take(),
+        [
+            "span:A(Transient)",
+            "Cache miss",
+            "Failed to construct instance"
+        ]
+    );
+}
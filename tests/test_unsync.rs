@@ -0,0 +1,83 @@
+#![cfg(feature = "unsync")]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use dill::*;
+
+/////////////////////////////////////////////////////////////////////////////////////////
+
+trait Counter {
+    fn increment(&self) -> u32;
+}
+
+#[component]
+#[scope(Singleton)]
+struct CounterImpl {
+    #[dill::default]
+    count: RefCell<u32>,
+}
+
+impl Counter for CounterImpl {
+    fn increment(&self) -> u32 {
+        let mut count = self.count.borrow_mut();
+        *count += 1;
+        *count
+    }
+}
+
+#[component]
+struct Greeter {
+    counter: Rc<dyn Counter>,
+    log: Rc<RefCell<Vec<String>>>,
+}
+
+impl Greeter {
+    fn greet(&self, name: &str) -> String {
+        let msg = format!("Hello {} #{}", name, self.counter.increment());
+        self.log.borrow_mut().push(msg.clone());
+        msg
+    }
+}
+
+#[test]
+fn test_unsync_components() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let cat = CatalogBuilder::new()
+        .add::<CounterImpl>()
+        .bind::<dyn Counter, CounterImpl>()
+        .add::<Greeter>()
+        .add_builder(Prebuilt::from_shared(log.clone()))
+        .build();
+
+    let greeter: Rc<Greeter> = cat.get_one().unwrap();
+    assert_eq!(greeter.greet("foo"), "Hello foo #1");
+
+    // Singleton counter is shared between the transient greeters
+    let greeter = cat.get_one::<Greeter>().unwrap();
+    assert_eq!(greeter.greet("bar"), "Hello bar #2");
+
+    assert_eq!(
+        *log.borrow(),
+        vec!["Hello foo #1".to_owned(), "Hello bar #2".to_owned()]
+    );
+}
+
+#[test]
+fn test_unsync_builder_overrides() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let log_captured = log.clone();
+
+    // Override closures capturing `Rc`s are not `Send + Sync`
+    let cat = CatalogBuilder::new()
+        .add::<CounterImpl>()
+        .bind::<dyn Counter, CounterImpl>()
+        .add_builder(builder_for::<Greeter>().with_log_fn(move |_| Ok(log_captured.clone())))
+        .build();
+
+    let greeter = cat.get_one::<Greeter>().unwrap();
+
+    assert_eq!(greeter.greet("foo"), "Hello foo #1");
+    assert_eq!(log.borrow().len(), 1);
+}
@@ -0,0 +1,16 @@
+use dill::component;
+
+struct A;
+
+#[component(after_build = "init")]
+impl A {
+    pub fn new() -> std::sync::Arc<Self> {
+        std::sync::Arc::new(Self)
+    }
+
+    fn init(&self, _cat: &dill::Catalog) -> Result<(), dill::InjectionError> {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use dill::component;
+
+struct Parser<'a> {
+    input: &'a str,
+}
+
+#[component]
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+}
+
+fn main() {}
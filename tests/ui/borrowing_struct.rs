@@ -0,0 +1,8 @@
+use dill::component;
+
+#[component]
+struct Parser<'a> {
+    input: &'a str,
+}
+
+fn main() {}
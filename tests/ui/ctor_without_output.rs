@@ -0,0 +1,10 @@
+use dill::component;
+
+struct A;
+
+#[component]
+impl A {
+    pub fn new() {}
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use dill::component;
+
+trait Db: Send + Sync {}
+
+struct A;
+
+#[component]
+impl A {
+    pub fn new<D: Db>(_db: std::sync::Arc<D>) -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use dill::component;
+
+struct Client;
+
+#[component]
+fn client<T>() -> Client {
+    Client
+}
+
+fn main() {}
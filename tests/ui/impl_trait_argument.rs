@@ -0,0 +1,14 @@
+use dill::component;
+
+trait Db: Send + Sync {}
+
+struct A;
+
+#[component]
+impl A {
+    pub fn new(_db: std::sync::Arc<impl Db>) -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use dill::component;
+
+#[component]
+#[scope("singleton")]
+struct A;
+
+fn main() {}
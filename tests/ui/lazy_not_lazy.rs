@@ -0,0 +1,11 @@
+use dill::component;
+
+struct B;
+
+#[component]
+struct A {
+    #[dill(lazy)]
+    b: std::sync::Arc<B>,
+}
+
+fn main() {}
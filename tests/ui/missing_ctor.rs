@@ -0,0 +1,12 @@
+use dill::component;
+
+struct A;
+
+#[component]
+impl A {
+    pub fn create() -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use dill::component;
+
+struct A;
+
+#[component(ctor = "build")]
+impl A {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dill::component;
+
+trait B: Send + Sync {}
+
+#[component]
+struct A {
+    #[named("foo")]
+    b: Vec<std::sync::Arc<dyn B>>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use dill::component;
+
+struct A {
+    b: u32,
+}
+
+#[component]
+impl A {
+    pub fn new((b, _): (u32, u32)) -> Self {
+        Self { b }
+    }
+}
+
+fn main() {}
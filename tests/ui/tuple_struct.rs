@@ -0,0 +1,6 @@
+use dill::component;
+
+#[component]
+struct A(u32);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dill::component;
+
+#[component]
+struct A {
+    #[dill(eager)]
+    b: u32,
+}
+
+fn main() {}